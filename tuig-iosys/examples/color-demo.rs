@@ -32,11 +32,15 @@ fn color_demo(mut sys: Box<dyn IoSystem>) {
     let mut pos = 0;
     let mut moving = true;
     'main: loop {
-        while let Some(action) = sys.poll_input().unwrap() {
+        for action in sys.drain_input().unwrap() {
             match action {
-                Action::Closed | Action::KeyPress { key: Key::Escape } => break 'main,
+                Action::Closed
+                | Action::KeyPress {
+                    key: Key::Escape, ..
+                } => break 'main,
                 Action::KeyPress {
                     key: Key::Char(' '),
+                    ..
                 } => moving = !moving,
                 _ => (),
             }
@@ -1,6 +1,12 @@
 //! The various data types representing different player to the game.
 
-use alloc::string::String;
+use core::{fmt, str::FromStr};
+
+#[cfg(feature = "std")]
+use alloc::{collections::VecDeque, vec::Vec};
+use alloc::{format, string::String};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
 
 use super::xy::XY;
 
@@ -31,6 +37,17 @@ pub enum Key {
     RightAlt,
     LeftSuper,
     RightSuper,
+    CapsLock,
+    NumLock,
+    ScrollLock,
+    PrintScreen,
+    Pause,
+    Menu,
+    /// Some other key, which doesn't have a portable meaning, with the raw scancode the backend got for it.
+    ///
+    /// The scancodes depend on the backend and platform, and even then, aren't necessarily consistent across
+    /// keyboards. They're mostly useful to let players bind unusual keys to things.
+    Unknown(u32),
 }
 
 impl Key {
@@ -52,52 +69,408 @@ impl Key {
     }
 }
 
+/// The names of keys that aren't written as themselves, for [`Key`]'s `Display` and `FromStr`. When there are
+/// several names for one key, the first is the one it's displayed as; the rest are only accepted when parsing.
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("space", Key::Char(' ')),
+    ("plus", Key::Char('+')),
+    ("esc", Key::Escape),
+    ("escape", Key::Escape),
+    ("backspace", Key::Backspace),
+    ("up", Key::Up),
+    ("down", Key::Down),
+    ("left", Key::Left),
+    ("right", Key::Right),
+    ("pageup", Key::PageUp),
+    ("pagedown", Key::PageDown),
+    ("home", Key::Home),
+    ("end", Key::End),
+    ("delete", Key::Delete),
+    ("del", Key::Delete),
+    ("insert", Key::Insert),
+    ("tab", Key::Tab),
+    ("enter", Key::Enter),
+    ("return", Key::Enter),
+    ("lshift", Key::LeftShift),
+    ("rshift", Key::RightShift),
+    ("lctrl", Key::LeftCtrl),
+    ("rctrl", Key::RightCtrl),
+    ("lalt", Key::LeftAlt),
+    ("ralt", Key::RightAlt),
+    ("lsuper", Key::LeftSuper),
+    ("rsuper", Key::RightSuper),
+    ("capslock", Key::CapsLock),
+    ("numlock", Key::NumLock),
+    ("scrolllock", Key::ScrollLock),
+    ("printscreen", Key::PrintScreen),
+    ("pause", Key::Pause),
+    ("menu", Key::Menu),
+];
+
+/// Keys are written the way keybinding config files usually write them: characters as themselves, like `a` or `?`,
+/// except `space` and `plus`; function keys as `f1`, `f2`, etc.; other keys by lowercase names like `enter`, `esc`,
+/// `pageup`, or `lshift`; and [`Key::Unknown`] as `scancode:` and the number.
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((name, _)) = KEY_NAMES.iter().find(|(_, k)| k == self) {
+            return f.write_str(name);
+        }
+        match self {
+            Self::Char(ch) => write!(f, "{}", ch),
+            Self::F(n) => write!(f, "f{}", n),
+            Self::Unknown(code) => write!(f, "scancode:{}", code),
+            // every other key should be in KEY_NAMES, but if one's missed, printing something beats panicking
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Parses keys as they're [displayed](#impl-Display-for-Key). Names are case-insensitive, but characters aren't, so
+/// `Enter` is [`Key::Enter`] but `A` is `Key::Char('A')`.
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (None, _) => return Err(ParseKeyError("empty key name".into())),
+            (Some(ch), None) => return Ok(Self::Char(ch)),
+            _ => (),
+        }
+        let lower = s.to_ascii_lowercase();
+        if let Some((_, key)) = KEY_NAMES.iter().find(|(name, _)| *name == lower) {
+            return Ok(*key);
+        }
+        if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+            return Ok(Self::F(n));
+        }
+        if let Some(code) = lower.strip_prefix("scancode:") {
+            return code
+                .parse()
+                .map(Self::Unknown)
+                .map_err(|_| ParseKeyError(format!("invalid scancode {:?} in key {:?}", code, s)));
+        }
+        Err(ParseKeyError(format!("unknown key name {:?}", s)))
+    }
+}
+
+/// A [`Key`] or [`KeyCombo`] couldn't be parsed from a string, with a description of why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseKeyError(String);
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseKeyError {}
+
+/// Which modifier keys were held down during a [`KeyPress`](Action::KeyPress) or [`KeyRelease`](Action::KeyRelease).
+///
+/// These can be combined with `|`, like bitflags, e.g. `Modifiers::CTRL | Modifiers::SHIFT`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Hash)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// The Windows key, or Command on macOS. (Named `super_` because `super` is a keyword.)
+    pub super_: bool,
+}
+
+impl Modifiers {
+    /// No modifiers held.
+    pub const NONE: Self = Self {
+        ctrl: false,
+        alt: false,
+        shift: false,
+        super_: false,
+    };
+    /// Only Ctrl held.
+    pub const CTRL: Self = Self {
+        ctrl: true,
+        ..Self::NONE
+    };
+    /// Only Alt held.
+    pub const ALT: Self = Self {
+        alt: true,
+        ..Self::NONE
+    };
+    /// Only Shift held.
+    pub const SHIFT: Self = Self {
+        shift: true,
+        ..Self::NONE
+    };
+    /// Only Super held.
+    pub const SUPER: Self = Self {
+        super_: true,
+        ..Self::NONE
+    };
+
+    /// Whether no modifiers at all are held.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+}
+
+impl core::ops::BitOr for Modifiers {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            ctrl: self.ctrl || rhs.ctrl,
+            alt: self.alt || rhs.alt,
+            shift: self.shift || rhs.shift,
+            super_: self.super_ || rhs.super_,
+        }
+    }
+}
+
+impl core::ops::BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+/// A key pressed with some modifiers held, like `ctrl+shift+s`, e.g. for keybindings.
+///
+/// It's displayed and parsed as the modifiers, in the order `ctrl`, `alt`, `shift`, `super`, then the [`Key`], all
+/// joined with `+`. When parsing, the modifiers can come in any order, and are case-insensitive; `control`, `cmd`,
+/// `win`, and `meta` are also accepted.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct KeyCombo {
+    pub key: Key,
+    pub mods: Modifiers,
+}
+
+impl KeyCombo {
+    /// Create a combo of a key and some modifiers.
+    pub fn new(key: Key, mods: Modifiers) -> Self {
+        Self { key, mods }
+    }
+
+    /// Whether an action is a press of this combo, with exactly these modifiers held.
+    pub fn matches(&self, action: &Action) -> bool {
+        match action {
+            Action::KeyPress { key, mods, .. } => *key == self.key && *mods == self.mods,
+            _ => false,
+        }
+    }
+}
+
+impl From<Key> for KeyCombo {
+    fn from(key: Key) -> Self {
+        Self::new(key, Modifiers::NONE)
+    }
+}
+
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (self.mods.ctrl, "ctrl+"),
+            (self.mods.alt, "alt+"),
+            (self.mods.shift, "shift+"),
+            (self.mods.super_, "super+"),
+        ];
+        for (_, name) in names.iter().filter(|(held, _)| *held) {
+            f.write_str(name)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+impl FromStr for KeyCombo {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // `+` on its own can be the key, e.g. `ctrl++`
+        let (mods, key) = match s.strip_suffix("++") {
+            Some(mods) => (Some(mods), "+"),
+            None => match s.rsplit_once('+') {
+                Some((mods, key)) if !key.is_empty() => (Some(mods), key),
+                _ => (None, s),
+            },
+        };
+        let mut res = Self::from(key.parse::<Key>()?);
+        for name in mods.into_iter().flat_map(|m| m.split('+')) {
+            res.mods |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => Modifiers::CTRL,
+                "alt" => Modifiers::ALT,
+                "shift" => Modifiers::SHIFT,
+                "super" | "cmd" | "win" | "meta" => Modifiers::SUPER,
+                _ => {
+                    return Err(ParseKeyError(format!(
+                        "unknown modifier {:?} in {:?}",
+                        name, s
+                    )))
+                }
+            };
+        }
+        Ok(res)
+    }
+}
+
 /// A mouse button which can be pressed or released in an [`Action`].
+///
+/// The scroll wheel is treated as a pair of buttons. Every backend sends each notch scrolled as a
+/// [`Action::MousePress`] immediately followed by a [`Action::MouseRelease`] of [`Self::ScrollUp`] or
+/// [`Self::ScrollDown`], at the mouse's current position. To handle scrolling, match on the presses and ignore the
+/// releases:
+///
+/// ```
+/// # use tuig_iosys::{Action, MouseButton};
+/// # fn scroll_by(action: &Action) -> isize {
+/// match action {
+///     Action::MousePress { button: MouseButton::ScrollUp, .. } => -1,
+///     Action::MousePress { button: MouseButton::ScrollDown, .. } => 1,
+///     _ => 0,
+/// }
+/// # }
+/// ```
+///
+/// Older versions of the crossterm backend sent two presses per notch and no release, so code which matched on
+/// those should count only the presses, as above, and stop halving the count. The GUI backends didn't report
+/// scrolling at all before.
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum MouseButton {
     Left,
     Middle,
     Right,
+    /// One notch of the scroll wheel, away from the user. Always pressed and released together.
     ScrollUp,
+    /// One notch of the scroll wheel, towards the user. Always pressed and released together.
     ScrollDown,
 }
 
+impl MouseButton {
+    /// Whether this is one of the scroll "buttons" rather than one that's actually held down.
+    pub fn is_scroll(&self) -> bool {
+        matches!(self, Self::ScrollUp | Self::ScrollDown)
+    }
+}
+
 /// An action the player has taken in the [`IoSystem`][super::IoSystem].
 #[derive(Clone, PartialEq, Eq, Debug)]
 #[non_exhaustive]
 pub enum Action {
-    /// A key was pressed. Note this theoretically handles modifiers by sending them when they're pressed, but
-    /// depending on the input mechanism it may only be able to send them when a non-modifier key is pressed.
-    KeyPress { key: Key },
-    /// A key was let go. Note this theoretically handles modifiers by sending them when they're let go, but
-    /// depending on the input mechanism it may only be able to send them when a non-modifier key is released.
-    KeyRelease { key: Key },
+    /// A key was pressed, with whatever modifiers were held at the time. Note this theoretically also handles
+    /// modifiers by sending them when they're pressed, but depending on the input mechanism it may only be able to
+    /// send them when a non-modifier key is pressed.
+    ///
+    /// `repeat` is whether this is an automatic repeat from the key being held down, rather than the initial press,
+    /// e.g. so you can implement your own repeat timing. Backends which can't tell always set it to `false`.
+    KeyPress {
+        key: Key,
+        mods: Modifiers,
+        repeat: bool,
+    },
+    /// A key was let go, with whatever modifiers were held at the time. Note this theoretically also handles modifiers
+    /// by sending them when they're let go, but depending on the input mechanism it may only be able to send them when
+    /// a non-modifier key is released.
+    KeyRelease { key: Key, mods: Modifiers },
     /// A mouse button was pressed.
     MousePress { pos: XY, button: MouseButton },
     /// A mouse button was released.
     MouseRelease { pos: XY, button: MouseButton },
     /// The mouse has moved to a new location, possibly while holding a button
     MouseMove { pos: XY },
+    /// Some text was pasted in all at once, e.g. with bracketed paste in a terminal.
+    ///
+    /// This should generally be treated as literal text, even if it contains newlines, tabs, etc.
+    ///
+    /// Only the terminal backend sends this. The GUI backend has no clipboard access, so pasting there isn't
+    /// supported; Ctrl+V just arrives as a keypress.
+    Paste(String),
     /// The render backend requested that a redraw happen, maybe without direct user input.
     ///
     /// In particular, for UI elements, this will be passed along whenever an event occurred, but it's outside the
     /// element's region.
     Redraw,
+    /// The display was resized, and is now `size` characters big.
+    ///
+    /// This is only sent when the size of the character grid actually changes, so it's a good signal for when to
+    /// resize your [`Screen`](crate::Screen). Backends send a [`Redraw`](Self::Redraw) after it, too.
+    Resized { size: XY },
     /// User requested the program end externally, e.g. clicking the X button in a window
     Closed,
-    /// User requested that the program pause temporarily
+    /// The display gained focus, e.g. the user clicked into the window or switched back to the terminal.
+    ///
+    /// Unlike [`Unpaused`][Self::Unpaused], the program may have been visible (and running) the whole time; it just
+    /// wasn't receiving input.
+    FocusGained,
+    /// The display lost focus, e.g. the user clicked another window, but it may well still be visible.
+    ///
+    /// This is a good time to, e.g., pause a game or dim the cursor. It's *not* the same as
+    /// [`Paused`][Self::Paused], which means the program has been suspended or backgrounded entirely.
+    FocusLost,
+    /// User requested that the program pause temporarily, e.g. by suspending it or moving it to the background.
+    ///
+    /// See also [`FocusLost`][Self::FocusLost], for when the program is still visible but not receiving input.
     Paused,
     /// User, having requested that the program pause temporarily, has since requested that it unpause
     ///
     /// This may be fired spuriously, i.e. without an associated [`Paused`][Self::Paused]. These must be ignored.
     Unpaused,
-    /// Some unknown input was received, with a description of what it was
+    /// Some unknown input was received, with a description of what it was, e.g. a key with no [`Key`] and the
+    /// modifiers held with it.
+    ///
+    /// These are meant for debugging, like finding out why a binding doesn't work, so the description isn't stable.
     Unknown(String),
-    /// Trying to read input let to some kind of error, with a description
+    /// Trying to read input led to some kind of error, with a description
+    ///
+    /// Backends keep reading input after an error if they can, so this doesn't necessarily mean input has stopped.
     Error(String),
 }
 
 impl Action {
+    /// A [`KeyPress`](Self::KeyPress) with no modifiers held.
+    pub fn press(key: Key) -> Self {
+        Self::KeyPress {
+            key,
+            mods: Modifiers::NONE,
+            repeat: false,
+        }
+    }
+
+    /// Mark a [`KeyPress`](Self::KeyPress) as a repeat or not. Other kinds of action are returned unchanged.
+    #[must_use]
+    pub fn with_repeat(mut self, new_repeat: bool) -> Self {
+        if let Self::KeyPress { repeat, .. } = &mut self {
+            *repeat = new_repeat;
+        }
+        self
+    }
+
+    /// Whether this is a [`KeyPress`](Self::KeyPress) from a key being held down, rather than the initial press.
+    pub fn is_repeat(&self) -> bool {
+        matches!(self, Self::KeyPress { repeat: true, .. })
+    }
+
+    /// A [`KeyRelease`](Self::KeyRelease) with no modifiers held.
+    pub fn release(key: Key) -> Self {
+        Self::KeyRelease {
+            key,
+            mods: Modifiers::NONE,
+        }
+    }
+
+    /// Replace the modifiers of a key event. Other kinds of action are returned unchanged.
+    #[must_use]
+    pub fn with_mods(mut self, new_mods: Modifiers) -> Self {
+        if let Self::KeyPress { mods, .. } | Self::KeyRelease { mods, .. } = &mut self {
+            *mods = new_mods;
+        }
+        self
+    }
+
+    /// Get the modifiers held during key events, or None for non-key events
+    pub fn mods(&self) -> Option<Modifiers> {
+        match self {
+            Self::KeyPress { mods, .. } | Self::KeyRelease { mods, .. } => Some(*mods),
+            _ => None,
+        }
+    }
+
     /// Get the screen position of mouse events, or None for non-mouse events
     pub fn position(&self) -> Option<XY> {
         match self {
@@ -108,3 +481,502 @@ impl Action {
         }
     }
 }
+
+/// Recognizes sequences of keys, like `g g` in vim, as they're pressed one at a time.
+///
+/// Register the sequences with [`Self::chord`], then [`Self::feed`] it every key pressed. When a whole sequence is
+/// pressed, with no more than the timeout between keys, you get its name back. Pressing a key that doesn't continue
+/// any sequence, or waiting too long, starts over.
+///
+/// When one sequence is a prefix of another, e.g. `g` and `g g`, the shorter one doesn't fire right away. It waits
+/// until either the timeout passes or a key that doesn't continue the longer one is pressed, so the longer one has a
+/// chance. That means you should also call [`Self::poll`] regularly, e.g. once a frame, so those fire when they time
+/// out, rather than the next time a key is pressed.
+///
+/// ```
+/// # use std::time::{Duration, Instant};
+/// # use tuig_iosys::{ChordMatcher, Key};
+/// let mut chords = ChordMatcher::new(Duration::from_secs(1))
+///     .chord("top", [Key::Char('g'), Key::Char('g')])
+///     .chord("down", [Key::Char('j')]);
+/// let now = Instant::now();
+/// assert_eq!(chords.feed(Key::Char('g'), now), None);
+/// assert_eq!(chords.feed(Key::Char('g'), now), Some("top"));
+/// assert_eq!(chords.feed(Key::Char('j'), now), Some("down"));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct ChordMatcher {
+    chords: Vec<(Vec<Key>, String)>,
+    timeout: Duration,
+    /// The keys pressed so far in a sequence that hasn't finished yet
+    pending: Vec<Key>,
+    /// When the last key in `pending` was pressed
+    last: Option<Instant>,
+    /// Chords which have been matched, but not returned yet, by index
+    ready: VecDeque<usize>,
+}
+
+#[cfg(feature = "std")]
+impl ChordMatcher {
+    /// Create a matcher with no sequences, which allows up to `timeout` between keys in a sequence.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            chords: Vec::new(),
+            timeout,
+            pending: Vec::new(),
+            last: None,
+            ready: VecDeque::new(),
+        }
+    }
+
+    /// Register a named sequence of keys.
+    ///
+    /// If the same sequence is registered twice, the first name wins. Empty sequences never match.
+    #[must_use]
+    pub fn chord(mut self, name: impl Into<String>, keys: impl IntoIterator<Item = Key>) -> Self {
+        let keys: Vec<_> = keys.into_iter().collect();
+        if !keys.is_empty() {
+            self.chords.push((keys, name.into()));
+        }
+        self
+    }
+
+    /// Handle a key being pressed at some time, returning the name of the sequence it completed, if any.
+    ///
+    /// This returns at most one name. In the rare case that one key finishes two sequences at once -- e.g. it cut off
+    /// a pending `g`, and is itself a whole sequence -- the second comes out of the next call to this or
+    /// [`Self::poll`].
+    pub fn feed(&mut self, key: Key, now: Instant) -> Option<&str> {
+        self.expire(now);
+        self.pending.push(key);
+        self.last = Some(now);
+        if !self.has_longer(&self.pending) && self.exact(&self.pending).is_none() {
+            // the new key doesn't fit, so finish off what came before it, if that was a whole sequence...
+            self.pending.pop();
+            self.finish();
+            // ...and try again with the new key on its own
+            self.pending.push(key);
+        }
+        if !self.has_longer(&self.pending) {
+            self.finish();
+        }
+        self.pop_ready()
+    }
+
+    /// Check for a sequence finishing by timing out, i.e. one that's the prefix of a longer one, which wasn't
+    /// continued in time.
+    pub fn poll(&mut self, now: Instant) -> Option<&str> {
+        self.expire(now);
+        self.pop_ready()
+    }
+
+    /// Forget about any keys pressed so far, and any matches that haven't been returned yet.
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.last = None;
+        self.ready.clear();
+    }
+
+    fn exact(&self, keys: &[Key]) -> Option<usize> {
+        self.chords.iter().position(|(seq, _)| seq == keys)
+    }
+
+    fn has_longer(&self, keys: &[Key]) -> bool {
+        self.chords
+            .iter()
+            .any(|(seq, _)| seq.len() > keys.len() && seq.starts_with(keys))
+    }
+
+    /// End the pending sequence, queueing it up if it's a whole chord.
+    fn finish(&mut self) {
+        if let Some(idx) = self.exact(&self.pending) {
+            self.ready.push_back(idx);
+        }
+        self.pending.clear();
+        self.last = None;
+    }
+
+    fn expire(&mut self, now: Instant) {
+        if let Some(last) = self.last {
+            if now.saturating_duration_since(last) > self.timeout {
+                self.finish();
+            }
+        }
+    }
+
+    fn pop_ready(&mut self) -> Option<&str> {
+        let idx = self.ready.pop_front()?;
+        Some(&self.chords[idx].1)
+    }
+}
+
+/// One step of a mouse drag, as recognized by a [`DragTracker`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DragEvent {
+    /// A button was pressed at `from`, and the mouse has moved far enough since to count as a drag.
+    Start { button: MouseButton, from: XY },
+    /// The mouse moved to `to` while dragging from `from`.
+    Update { from: XY, to: XY },
+    /// The button was released at `to`, finishing the drag from `from`.
+    End { from: XY, to: XY },
+}
+
+/// Turns the raw mouse [`Action`]s into drags, e.g. for box selection.
+///
+/// [`Self::feed`] it every action. A press followed by movement of at least [`Self::threshold`] cells, in either
+/// direction, starts a drag; every move after that updates it, and releasing the same button ends it. A press and
+/// release that don't move far enough are just a click, and produce nothing. [Scrolling](MouseButton::is_scroll)
+/// never starts a drag.
+///
+/// ```
+/// # use tuig_iosys::{Action, DragEvent, DragTracker, MouseButton, XY};
+/// let mut drags = DragTracker::new();
+/// let button = MouseButton::Left;
+/// assert_eq!(drags.feed(&Action::MousePress { pos: XY(1, 1), button: button.clone() }).count(), 0);
+/// let events: Vec<_> = drags.feed(&Action::MouseMove { pos: XY(3, 2) }).collect();
+/// assert_eq!(events, [
+///     DragEvent::Start { button, from: XY(1, 1) },
+///     DragEvent::Update { from: XY(1, 1), to: XY(3, 2) },
+/// ]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DragTracker {
+    threshold: usize,
+    /// The button held and where it was pressed, if one is
+    held: Option<(MouseButton, XY)>,
+    /// Whether the held button has moved far enough to be dragging
+    dragging: bool,
+}
+
+impl DragTracker {
+    /// Create a tracker which isn't tracking anything yet, with a threshold of 1 cell.
+    pub fn new() -> Self {
+        Self {
+            threshold: 1,
+            held: None,
+            dragging: false,
+        }
+    }
+
+    tuig_pm::setters! {
+        /// How many cells the mouse has to move, horizontally or vertically, from where it was pressed before it
+        /// counts as a drag. No matter what this is set to, the mouse has to move at least a little.
+        ///
+        /// Defaults to 1, i.e. any movement.
+        threshold(v: usize) => threshold = v.max(1),
+    }
+
+    /// Whether a drag is currently happening, i.e. it's started and not ended.
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Handle an action, returning the drag events it caused, if any.
+    ///
+    /// Usually that's at most one event, but the move that starts a drag also produces an update, and a release far
+    /// enough from the press, with no moves reported in between, starts and ends a drag at once.
+    pub fn feed(&mut self, action: &Action) -> impl Iterator<Item = DragEvent> {
+        let (start, step) = match action {
+            Action::MousePress { pos, button } if self.held.is_none() && !button.is_scroll() => {
+                self.held = Some((button.clone(), *pos));
+                (None, None)
+            }
+            Action::MouseMove { pos } => match self.start(*pos) {
+                Some((start, from)) => (start, Some(DragEvent::Update { from, to: *pos })),
+                None => (None, None),
+            },
+            Action::MouseRelease { pos, button }
+                if self.held.as_ref().map(|h| &h.0) == Some(button) =>
+            {
+                let res = match self.start(*pos) {
+                    Some((start, from)) => (start, Some(DragEvent::End { from, to: *pos })),
+                    None => (None, None),
+                };
+                self.held = None;
+                self.dragging = false;
+                res
+            }
+            _ => (None, None),
+        };
+        start.into_iter().chain(step)
+    }
+
+    /// Stop tracking the current press, if any, without ending a drag, e.g. when the window loses focus.
+    pub fn reset(&mut self) {
+        self.held = None;
+        self.dragging = false;
+    }
+
+    /// If a button is held and the mouse is now far enough away from the press, start dragging if that hasn't
+    /// happened yet. Returns the start event, if this started it, and where the drag started from.
+    fn start(&mut self, pos: XY) -> Option<(Option<DragEvent>, XY)> {
+        let (button, from) = self.held.clone()?;
+        if self.dragging {
+            return Some((None, from));
+        }
+        let dist = pos.x().abs_diff(from.x()).max(pos.y().abs_diff(from.y()));
+        if dist < self.threshold {
+            return None;
+        }
+        self.dragging = true;
+        Some((Some(DragEvent::Start { button, from }), from))
+    }
+}
+
+impl Default for DragTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn repeat_helpers() {
+        assert!(!Action::press(Key::Up).is_repeat());
+        assert!(Action::press(Key::Up).with_repeat(true).is_repeat());
+        assert_eq!(
+            Action::release(Key::Up).with_repeat(true),
+            Action::release(Key::Up)
+        );
+        assert!(!Action::release(Key::Up).is_repeat());
+    }
+
+    #[test]
+    fn named_keys_round_trip() {
+        let mut keys: Vec<_> = KEY_NAMES.iter().map(|(_, k)| *k).collect();
+        keys.extend([
+            Key::Char('a'),
+            Key::Char('A'),
+            Key::Char('?'),
+            Key::Char('漢'),
+            Key::F(1),
+            Key::F(12),
+            Key::Unknown(1234),
+        ]);
+        for key in keys {
+            assert_eq!(Key::from_str(&key.to_string()), Ok(key));
+        }
+        assert_eq!(Key::Escape.to_string(), "esc");
+        assert_eq!(Key::Char(' ').to_string(), "space");
+        assert_eq!("Return".parse(), Ok(Key::Enter));
+        assert_eq!("F5".parse(), Ok(Key::F(5)));
+        assert_eq!("f".parse(), Ok(Key::Char('f')));
+    }
+
+    #[test]
+    fn bad_keys_rejected() {
+        for bad in [
+            "",
+            "entr",
+            "fx",
+            "scancode:-1",
+            "ctrl+",
+            "hyper+a",
+            "ctrl+nope",
+        ] {
+            let err = KeyCombo::from_str(bad).expect_err(bad);
+            assert!(!err.to_string().is_empty());
+        }
+        assert_eq!(
+            Key::from_str("entr").unwrap_err().to_string(),
+            "unknown key name \"entr\""
+        );
+    }
+
+    #[test]
+    fn combos_round_trip() {
+        let combo: KeyCombo = "Shift+CTRL+s".parse().unwrap();
+        assert_eq!(
+            combo,
+            KeyCombo::new(Key::Char('s'), Modifiers::CTRL | Modifiers::SHIFT)
+        );
+        assert_eq!(combo.to_string(), "ctrl+shift+s");
+        assert!(combo.matches(&Action::press(Key::Char('s')).with_mods(combo.mods)));
+        assert!(!combo.matches(&Action::press(Key::Char('s'))));
+        for s in [
+            "+",
+            "ctrl++",
+            "alt+plus",
+            "super+f4",
+            "ctrl+alt+shift+super+space",
+            "tab",
+        ] {
+            let combo: KeyCombo = s.parse().unwrap();
+            assert_eq!(combo.to_string().parse(), Ok(combo));
+        }
+        assert_eq!(
+            "ctrl++".parse(),
+            Ok(KeyCombo::new(Key::Char('+'), Modifiers::CTRL))
+        );
+    }
+
+    #[test]
+    fn modifiers_combine() {
+        let both = Modifiers::CTRL | Modifiers::SHIFT;
+        assert!(both.ctrl && both.shift && !both.alt && !both.super_);
+        assert!(Modifiers::NONE.is_empty());
+        assert!(!both.is_empty());
+        let mut m = Modifiers::NONE;
+        m |= Modifiers::SUPER;
+        assert_eq!(m, Modifiers::SUPER);
+    }
+
+    #[test]
+    fn key_helpers_set_mods() {
+        let act = Action::press(Key::Char('s')).with_mods(Modifiers::CTRL);
+        assert_eq!(
+            act,
+            Action::KeyPress {
+                key: Key::Char('s'),
+                mods: Modifiers::CTRL,
+                repeat: false,
+            }
+        );
+        assert_eq!(act.mods(), Some(Modifiers::CTRL));
+        assert_eq!(Action::release(Key::Tab).mods(), Some(Modifiers::NONE));
+        assert_eq!(Action::Redraw.with_mods(Modifiers::ALT), Action::Redraw);
+        assert_eq!(Action::Redraw.mods(), None);
+    }
+
+    #[cfg(feature = "std")]
+    fn chords() -> ChordMatcher {
+        ChordMatcher::new(Duration::from_millis(500))
+            .chord("g", [Key::Char('g')])
+            .chord("gg", [Key::Char('g'), Key::Char('g')])
+            .chord("dw", [Key::Char('d'), Key::Char('w')])
+            .chord("x", [Key::Char('x')])
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn chord_prefix_waits_for_timeout() {
+        let mut c = chords();
+        let start = Instant::now();
+        assert_eq!(c.feed(Key::Char('g'), start), None);
+        assert_eq!(c.poll(start + Duration::from_millis(400)), None);
+        assert_eq!(c.poll(start + Duration::from_millis(600)), Some("g"));
+        assert_eq!(c.poll(start + Duration::from_millis(700)), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn chord_mismatch_finishes_prefix_then_retries() {
+        let mut c = chords();
+        let now = Instant::now();
+        c.feed(Key::Char('g'), now);
+        // x cuts off the g, and is a chord itself
+        assert_eq!(c.feed(Key::Char('x'), now), Some("g"));
+        assert_eq!(c.poll(now), Some("x"));
+        // d isn't anything by itself, so d then x is just x
+        c.feed(Key::Char('d'), now);
+        assert_eq!(c.feed(Key::Char('x'), now), Some("x"));
+        assert_eq!(c.poll(now), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn chord_timeout_resets() {
+        let mut c = chords();
+        let start = Instant::now();
+        c.feed(Key::Char('d'), start);
+        assert_eq!(c.feed(Key::Char('w'), start + Duration::from_secs(1)), None);
+        c.feed(Key::Char('d'), start);
+        assert_eq!(
+            c.feed(Key::Char('w'), start + Duration::from_millis(300)),
+            Some("dw")
+        );
+    }
+
+    fn press(x: usize, y: usize) -> Action {
+        Action::MousePress {
+            pos: XY(x, y),
+            button: MouseButton::Left,
+        }
+    }
+
+    fn moved(x: usize, y: usize) -> Action {
+        Action::MouseMove { pos: XY(x, y) }
+    }
+
+    fn release(x: usize, y: usize) -> Action {
+        Action::MouseRelease {
+            pos: XY(x, y),
+            button: MouseButton::Left,
+        }
+    }
+
+    fn drag(tracker: &mut DragTracker, actions: &[Action]) -> alloc::vec::Vec<DragEvent> {
+        actions.iter().flat_map(|a| tracker.feed(a)).collect()
+    }
+
+    #[test]
+    fn click_is_not_drag() {
+        let mut t = DragTracker::new();
+        assert_eq!(drag(&mut t, &[press(2, 2), release(2, 2)]), []);
+        // moves without a button held don't count either
+        assert_eq!(drag(&mut t, &[moved(5, 5), moved(1, 1)]), []);
+    }
+
+    #[test]
+    fn drag_starts_updates_and_ends() {
+        let mut t = DragTracker::new();
+        let from = XY(1, 1);
+        let events = drag(&mut t, &[press(1, 1), moved(2, 1), moved(4, 3)]);
+        assert!(t.is_dragging());
+        let end = drag(&mut t, &[release(5, 3)]);
+        assert!(!t.is_dragging());
+        assert_eq!(
+            events
+                .into_iter()
+                .chain(end)
+                .collect::<alloc::vec::Vec<_>>(),
+            [
+                DragEvent::Start {
+                    button: MouseButton::Left,
+                    from
+                },
+                DragEvent::Update { from, to: XY(2, 1) },
+                DragEvent::Update { from, to: XY(4, 3) },
+                DragEvent::End { from, to: XY(5, 3) },
+            ]
+        );
+    }
+
+    #[test]
+    fn drag_waits_for_threshold() {
+        let mut t = DragTracker::new().threshold(3);
+        assert_eq!(drag(&mut t, &[press(5, 5), moved(7, 3), release(6, 6)]), []);
+        let events = drag(&mut t, &[press(5, 5), moved(7, 3), moved(5, 8)]);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0],
+            DragEvent::Start {
+                button: MouseButton::Left,
+                from: XY(5, 5)
+            }
+        );
+        // releasing some other button doesn't end it
+        let right = Action::MouseRelease {
+            pos: XY(5, 8),
+            button: MouseButton::Right,
+        };
+        assert_eq!(drag(&mut t, &[right]), []);
+        assert!(t.is_dragging());
+    }
+
+    #[test]
+    fn scrolling_is_not_drag() {
+        let mut t = DragTracker::new();
+        let scroll = Action::MousePress {
+            pos: XY(1, 1),
+            button: MouseButton::ScrollDown,
+        };
+        assert_eq!(drag(&mut t, &[scroll, moved(5, 5)]), []);
+        // and it doesn't get in the way of a real press
+        assert_eq!(drag(&mut t, &[press(5, 5), moved(6, 5)]).len(), 2);
+    }
+}
@@ -1,6 +1,8 @@
 //! The many ways something can go wrong while using an IoSystem.
 
-use alloc::{borrow::Cow, string::String};
+use core::fmt;
+
+use alloc::{borrow::Cow, collections::BTreeMap, string::String, vec::Vec};
 
 /// An error happened while trying to run an IO system.
 #[non_exhaustive]
@@ -14,8 +16,44 @@ pub enum Error {
     Winit(winit::error::ExternalError),
     /// Just directly contains an error message.
     Bare(Cow<'static, str>),
+    /// [`load!`](crate::load!) couldn't load any backend. This has each feature it tried and how loading it failed,
+    /// in the order they were tried.
+    NoBackend(Vec<(&'static str, Error)>),
+}
+
+impl Error {
+    /// If this is a [`NoBackend`](Self::NoBackend), how each backend failed, keyed by feature name.
+    pub fn backend_errors(&self) -> Option<BTreeMap<&'static str, &Error>> {
+        match self {
+            Self::NoBackend(tried) => Some(tried.iter().map(|(name, e)| (*name, e)).collect()),
+            _ => None,
+        }
+    }
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Io(e) => write!(f, "{}", e),
+            #[cfg(feature = "gui")]
+            Self::Winit(e) => write!(f, "{}", e),
+            Self::Bare(msg) => write!(f, "{}", msg),
+            Self::NoBackend(tried) if tried.is_empty() => write!(f, "no backends to try"),
+            Self::NoBackend(tried) => {
+                write!(f, "tried {} backends:", tried.len())?;
+                for (name, e) in tried {
+                    write!(f, "\n  {}: {}", name, e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 #[cfg(feature = "std")]
 impl From<std::io::Error> for Error {
     fn from(value: std::io::Error) -> Self {
@@ -44,3 +82,26 @@ impl From<String> for Error {
 
 /// Alias for [`core::result::Result`] with the error always being [`Error`].
 pub type Result<T> = core::result::Result<T, Error>;
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::ToString, vec};
+
+    use super::*;
+
+    #[test]
+    fn no_backend_displays_in_order() {
+        let err = Error::NoBackend(vec![("b", "bad".into()), ("a", "worse".into())]);
+        assert_eq!(err.to_string(), "tried 2 backends:\n  b: bad\n  a: worse");
+        assert_eq!(Error::NoBackend(vec![]).to_string(), "no backends to try");
+    }
+
+    #[test]
+    fn backend_errors_maps() {
+        let err = Error::NoBackend(vec![("b", "bad".into()), ("a", "worse".into())]);
+        let map = err.backend_errors().unwrap();
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), ["a", "b"]);
+        assert!(matches!(map["b"], Error::Bare(msg) if msg == "bad"));
+        assert!(Error::from("nope").backend_errors().is_none());
+    }
+}
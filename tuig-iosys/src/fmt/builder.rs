@@ -0,0 +1,111 @@
+use alloc::{string::String, vec::Vec};
+
+use super::{Color, Format, Formatted, FormattedExt, Text};
+
+/// What a [`TextBuilder`] with nothing in it reports as its format.
+static EMPTY: Format = Format::NONE;
+
+/// Builds up a `Vec<Text>` piece by piece, for when [`text!`](crate::text!)'s literal format strings don't fit, like
+/// a number colored by its value. Start one with [`Text::builder`].
+///
+/// The builder is [`Formatted`] itself, and the [`FormattedExt`] methods style the last piece added, so each piece
+/// can be styled right after it's added:
+///
+/// ```
+/// # use tuig_iosys::fmt::{Color, FormattedExt, Text};
+/// let hp = 7;
+/// let color = if hp < 10 { Color::Red } else { Color::Green };
+/// let line = Text::builder()
+///     .plain("HP: ")
+///     .colored(hp.to_string(), color)
+///     .bold()
+///     .plain("/20")
+///     .build();
+/// assert_eq!(line[1], Text::plain("7").red().bold());
+/// assert_eq!(line[2], Text::plain("/20"));
+/// ```
+///
+/// Styling a builder with nothing in it yet adds an empty piece to hold the style, which is harmless when it's
+/// drawn, but shows up in the result.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextBuilder {
+    parts: Vec<Text>,
+}
+
+impl TextBuilder {
+    /// Add a piece of text with the default format.
+    pub fn plain(self, text: impl Into<String>) -> Self {
+        self.push(Text::of(text.into()))
+    }
+
+    /// Add a piece of text in some foreground color.
+    pub fn colored(self, text: impl Into<String>, color: Color) -> Self {
+        self.push(Text::of(text.into()).fg(color))
+    }
+
+    /// Add an already-formatted piece of text.
+    pub fn push(mut self, text: Text) -> Self {
+        self.parts.push(text);
+        self
+    }
+
+    /// Add several already-formatted pieces of text, e.g. from [`text!`](crate::text!).
+    pub fn extend(mut self, texts: impl IntoIterator<Item = Text>) -> Self {
+        self.parts.extend(texts);
+        self
+    }
+
+    /// Finish building, getting all the pieces in the order they were added.
+    pub fn build(self) -> Vec<Text> {
+        self.parts
+    }
+}
+
+impl Formatted for TextBuilder {
+    fn get_fmt(&self) -> &Format {
+        self.parts.last().map_or(&EMPTY, |t| t.get_fmt())
+    }
+
+    fn get_fmt_mut(&mut self) -> &mut Format {
+        if self.parts.is_empty() {
+            self.parts.push(Text::plain(""));
+        }
+        self.parts.last_mut().unwrap().get_fmt_mut()
+    }
+}
+
+impl From<TextBuilder> for Vec<Text> {
+    fn from(value: TextBuilder) -> Self {
+        value.build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        fmt::{Color, FormattedExt, Text},
+        text,
+    };
+
+    #[test]
+    fn matches_equivalent_macro() {
+        let hp = 12;
+        let built = Text::builder()
+            .plain("HP: ")
+            .colored(alloc::format!("{}", hp), Color::Green)
+            .underline()
+            .extend(text!(" of ", bold "{}"(20)))
+            .build();
+        assert_eq!(
+            built,
+            text!("HP: ", green underline "{}"(hp), " of ", bold "20")
+        );
+    }
+
+    #[test]
+    fn styling_empty_adds_a_piece() {
+        let built = Text::builder().red().plain("x").build();
+        assert_eq!(built, [Text::plain("").red(), Text::plain("x")]);
+        assert!(Text::builder().build().is_empty());
+    }
+}
@@ -0,0 +1,711 @@
+//! Implements CLI-compatible text formatting.
+//!
+//! This module defaults to a 'lowest common subset' across all render targets. Additional functionality is enabled if
+//! explicitly turned on, but be aware that incompatible render hardware will simply ignore it. To achieve "good" UI
+//! across a variety of render targets, you'll need to write your own code taking maximum advantage of avaliable
+//! features and degrading nicely, for your set of supported backends. See the backends' documentation for supported
+//! feature sets.
+//!
+//! This entire module is `#![no_std]` compatible. See the crate root for more info.
+//!
+//! -   By default:
+//!     -   16 basic [`Color`]s (blue, green, cyan, red, magenta, yellow, black, and the bright equivalents)
+//!     -   Arbitrary RGB colors, which backends that can't show them snap to the nearest basic color
+//!     -   The xterm 256-color palette, which is more widely supported than RGB, and snaps the same way
+//!     -   Setting foreground and background
+//!     -   Underline, bold, and blink
+//!
+//! And uh. Eventually there'll be others!
+
+use core::fmt::Debug;
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+
+mod builder;
+pub use builder::TextBuilder;
+mod width;
+pub use width::{char_width, grapheme_len, graphemes, str_width};
+mod wrap;
+pub use wrap::{expand_tabs, wrap, wrap_indexed, Wrap, WrapOpts, DEFAULT_TAB_WIDTH};
+
+/// The color of a piece of formatted text.
+///
+/// Meant to be used through the [`FormattedExt`] methods. The 16 named colors are the basic ANSI palette, which every
+/// backend supports; see [`Self::ansi_code`] for their codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    /// An arbitrary 24-bit color, as red, green, and blue.
+    ///
+    /// Not every backend can show these. The ones that can't show the [nearest](Self::nearest_palette) of the 16 named
+    /// colors instead, or of the 256 [indexed](Self::Indexed) ones, if they can show those.
+    Rgb(u8, u8, u8),
+    /// One of the xterm 256-color palette: 0-15 are the named colors, 16-231 are a 6x6x6 color cube, and 232-255 are
+    /// a ramp of grays. See [`Self::rgb`] for exactly which colors they are.
+    ///
+    /// More terminals support these than [`Self::Rgb`], especially over SSH. Backends that can't show them show the
+    /// [nearest](Self::nearest_palette) named color instead. To get exactly the named colors for 0-15, rather than an
+    /// `Indexed` that means the same thing, use [`Self::from_indexed`].
+    Indexed(u8),
+}
+
+/// The levels of red, green, and blue in the [indexed](Color::Indexed) color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Color {
+    /// All of the colors supported
+    #[rustfmt::skip]
+    pub fn all() -> [Color; 16] {
+        [
+            Color::Black,   Color::BrightBlack,
+            Color::Red,     Color::BrightRed,
+            Color::Green,   Color::BrightGreen,
+            Color::Yellow,  Color::BrightYellow,
+            Color::Blue,    Color::BrightBlue,
+            Color::Magenta, Color::BrightMagenta,
+            Color::Cyan,    Color::BrightCyan,
+            Color::White,   Color::BrightWhite,
+        ]
+    }
+
+    pub fn count() -> usize {
+        Self::all().len()
+    }
+
+    /// The name of the color as a string
+    #[rustfmt::skip]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Color::Black => "black",        Color::BrightBlack => "bright black",
+            Color::Red => "red",            Color::BrightRed => "bright red",
+            Color::Green => "green",        Color::BrightGreen => "bright green",
+            Color::Yellow => "yellow",      Color::BrightYellow => "bright yellow",
+            Color::Blue => "blue",          Color::BrightBlue => "bright blue",
+            Color::Magenta => "magenta",    Color::BrightMagenta => "bright magenta",
+            Color::Cyan => "cyan",          Color::BrightCyan => "bright cyan",
+            Color::White => "white",        Color::BrightWhite => "bright white",
+            Color::Rgb(..) => "rgb",
+            Color::Indexed(..) => "indexed",
+        }
+    }
+
+    /// The ANSI color code for this color, i.e. 0-7 for the normal colors and 60-67 for the bright ones, or `None`
+    /// for [`Color::Rgb`] and [`Color::Indexed`].
+    ///
+    /// Add 30 for the foreground or 40 for the background to get the SGR parameter.
+    #[rustfmt::skip]
+    pub fn ansi_code(&self) -> Option<u8> {
+        let code = match self {
+            Color::Black => 0,      Color::BrightBlack => 60,
+            Color::Red => 1,        Color::BrightRed => 61,
+            Color::Green => 2,      Color::BrightGreen => 62,
+            Color::Yellow => 3,     Color::BrightYellow => 63,
+            Color::Blue => 4,       Color::BrightBlue => 64,
+            Color::Magenta => 5,    Color::BrightMagenta => 65,
+            Color::Cyan => 6,       Color::BrightCyan => 66,
+            Color::White => 7,      Color::BrightWhite => 67,
+            Color::Rgb(..) | Color::Indexed(..) => return None,
+        };
+        Some(code)
+    }
+
+    /// The color at some index in the xterm 256-color palette: one of the named colors for 0-15, otherwise a
+    /// [`Color::Indexed`].
+    #[rustfmt::skip]
+    pub fn from_indexed(idx: u8) -> Color {
+        const NAMED: [Color; 16] = [
+            Color::Black, Color::Red, Color::Green, Color::Yellow,
+            Color::Blue, Color::Magenta, Color::Cyan, Color::White,
+            Color::BrightBlack, Color::BrightRed, Color::BrightGreen, Color::BrightYellow,
+            Color::BrightBlue, Color::BrightMagenta, Color::BrightCyan, Color::BrightWhite,
+        ];
+        match NAMED.get(idx as usize) {
+            Some(&named) => named,
+            None => Color::Indexed(idx),
+        }
+    }
+
+    /// This color's index in the xterm 256-color palette, e.g. for backends which can show those but not
+    /// [`Color::Rgb`].
+    ///
+    /// Named colors are 0-15, and indexed colors are returned as-is. RGB colors become the nearest color in the cube
+    /// or the gray ramp, but never 0-15, since terminals often let users change those.
+    pub fn to_indexed(self) -> u8 {
+        let (r, g, b) = match self {
+            Color::Indexed(idx) => return idx,
+            Color::Rgb(r, g, b) => (r, g, b),
+            named => {
+                let code = named.ansi_code().unwrap();
+                return if code >= 60 { code - 60 + 8 } else { code };
+            }
+        };
+        let level = |c: u8| {
+            (0..CUBE_LEVELS.len())
+                .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - c as i32).abs())
+                .unwrap() as u8
+        };
+        let cube = 16 + 36 * level(r) + 6 * level(g) + level(b);
+        let avg = (r as u32 + g as u32 + b as u32) / 3;
+        let gray = 232 + ((avg.saturating_sub(3)) / 10).min(23) as u8;
+        let dist = |idx: u8| {
+            let (cr, cg, cb) = Color::Indexed(idx).rgb();
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, cr) + d(g, cg) + d(b, cb)
+        };
+        if dist(gray) < dist(cube) {
+            gray
+        } else {
+            cube
+        }
+    }
+
+    /// The red, green, and blue components of this color.
+    ///
+    /// For the named colors, these are the canonical xterm values. Backends are free to draw them differently, though,
+    /// and terminals often let the user pick their own palette, so don't count on them looking exactly like this.
+    #[rustfmt::skip]
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match *self {
+            Color::Black => (0, 0, 0),          Color::BrightBlack => (127, 127, 127),
+            Color::Red => (205, 0, 0),          Color::BrightRed => (255, 0, 0),
+            Color::Green => (0, 205, 0),        Color::BrightGreen => (0, 255, 0),
+            Color::Yellow => (205, 205, 0),     Color::BrightYellow => (255, 255, 0),
+            Color::Blue => (0, 0, 238),         Color::BrightBlue => (92, 92, 255),
+            Color::Magenta => (205, 0, 205),    Color::BrightMagenta => (255, 0, 255),
+            Color::Cyan => (0, 205, 205),       Color::BrightCyan => (0, 255, 255),
+            Color::White => (229, 229, 229),    Color::BrightWhite => (255, 255, 255),
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Indexed(idx @ 0..=15) => Color::from_indexed(idx).rgb(),
+            Color::Indexed(idx @ 16..=231) => {
+                let idx = idx - 16;
+                let level = |i: u8| CUBE_LEVELS[i as usize];
+                (level(idx / 36), level(idx / 6 % 6), level(idx % 6))
+            }
+            Color::Indexed(idx) => {
+                let v = 8 + 10 * (idx - 232);
+                (v, v, v)
+            }
+        }
+    }
+
+    /// The named color closest to this one, for backends which only support those. Named colors are returned as-is,
+    /// and [indexed](Self::Indexed) colors 0-15 become the named colors they stand for.
+    pub fn nearest_palette(self) -> Color {
+        if let Color::Indexed(idx @ 0..=15) = self {
+            Color::from_indexed(idx)
+        } else if let Color::Rgb(..) | Color::Indexed(..) = self {
+            let (r, g, b) = self.rgb();
+            let dist = |c: &Color| {
+                let (cr, cg, cb) = c.rgb();
+                let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+                d(r, cr) + d(g, cg) + d(b, cb)
+            };
+            Self::all().into_iter().min_by_key(dist).unwrap()
+        } else {
+            self
+        }
+    }
+
+    /// Blend from this color to another in RGB, with `t` going from 0.0 (entirely this one) to 1.0 (entirely
+    /// `other`). It's clamped to that range.
+    ///
+    /// The endpoints are returned exactly, so e.g. `Color::Red.lerp(Color::Blue, 0.0)` is `Color::Red`, not the
+    /// equivalent [`Color::Rgb`]. Everything in between is a [`Color::Rgb`].
+    pub fn lerp(self, other: Color, t: f32) -> Color {
+        if t.is_nan() || t <= 0.0 {
+            return self;
+        } else if t >= 1.0 {
+            return other;
+        }
+        let (fr, fg, fb) = self.rgb();
+        let (tr, tg, tb) = other.rgb();
+        // everything's nonnegative, so adding 0.5 rounds -- and it works without std
+        let mix = |f: u8, t_: u8| (f as f32 + (t_ as f32 - f as f32) * t + 0.5) as u8;
+        Color::Rgb(mix(fr, tr), mix(fg, tg), mix(fb, tb))
+    }
+
+    /// How bright this color looks, from 0.0 (black) to 1.0 (white), based on its [`Self::rgb`].
+    ///
+    /// This is the WCAG relative luminance, except that the sRGB gamma curve is approximated by squaring, so it works
+    /// without `std`. It's plenty accurate for picking readable colors, which is what it's for.
+    pub fn luminance(self) -> f32 {
+        let (r, g, b) = self.rgb();
+        let lin = |c: u8| {
+            let c = c as f32 / 255.0;
+            c * c
+        };
+        0.2126 * lin(r) + 0.7152 * lin(g) + 0.0722 * lin(b)
+    }
+
+    /// The contrast ratio between this color and another, from 1.0 (identical brightness) to 21.0 (black and
+    /// white), per [`Self::luminance`]. It's the same whichever way around the colors are.
+    ///
+    /// For reference, WCAG recommends at least 4.5 for ordinary text.
+    pub fn contrast_with(self, other: Color) -> f32 {
+        let (a, b) = (self.luminance(), other.luminance());
+        let (light, dark) = if a > b { (a, b) } else { (b, a) };
+        (light + 0.05) / (dark + 0.05)
+    }
+
+    /// Of some candidate colors, the one that [contrasts](Self::contrast_with) the most with this one, e.g. to pick a
+    /// foreground for this background. Ties go to the earliest candidate.
+    ///
+    /// If there aren't any candidates, this is the same as [`Self::readable_on`].
+    pub fn best_contrast(&self, candidates: &[Color]) -> Color {
+        let mut best = match candidates.first() {
+            Some(&c) => (c, self.contrast_with(c)),
+            None => return Self::readable_on(*self),
+        };
+        for &c in &candidates[1..] {
+            let contrast = self.contrast_with(c);
+            if contrast > best.1 {
+                best = (c, contrast);
+            }
+        }
+        best.0
+    }
+
+    /// A foreground color that's readable on some background: either [`Color::Black`] or [`Color::BrightWhite`],
+    /// whichever [contrasts](Self::contrast_with) more.
+    ///
+    /// ```
+    /// # use tuig_iosys::fmt::Color;
+    /// assert_eq!(Color::readable_on(Color::Blue), Color::BrightWhite);
+    /// assert_eq!(Color::readable_on(Color::Rgb(250, 220, 180)), Color::Black);
+    /// ```
+    pub fn readable_on(bg: Color) -> Color {
+        bg.best_contrast(&[Color::Black, Color::BrightWhite])
+    }
+}
+
+/// Color each character of some text, stepping the foreground color evenly from `from` at the first character to `to`
+/// at the last.
+///
+/// This returns one [`Text`] per character -- or rather per [grapheme](graphemes), so combining characters stay with
+/// what they're combining with. The colors in between are [`Color::Rgb`], per [`Color::lerp`], so backends that can
+/// only show the named colors will snap them to the nearest one.
+pub fn gradient(text: &str, from: Color, to: Color) -> Vec<Text> {
+    let count = graphemes(text).count();
+    let steps = count.saturating_sub(1).max(1) as f32;
+    graphemes(text)
+        .enumerate()
+        .map(|(i, g)| Text::plain(g).fg(from.lerp(to, i as f32 / steps)))
+        .collect()
+}
+
+/// The format of a single formatted item.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Format {
+    /// The foreground color of the item
+    pub fg: Color,
+    /// The background color of the item
+    pub bg: Color,
+    /// Whether it's bolded or not
+    pub bold: bool,
+    /// Whether it's underlined or not
+    pub underline: bool,
+    /// Whether it blinks or not
+    ///
+    /// Some terminals ignore blinking entirely, and some let the user turn it off, so don't rely on it to convey
+    /// anything important. Where it's not supported, blinking text is just drawn normally.
+    pub blink: bool,
+    /// A URL this item links to, if any.
+    ///
+    /// Terminals which support OSC 8 make linked text clickable; everywhere else it's drawn normally, so check
+    /// [`Capabilities::links`](crate::Capabilities::links) before relying on it. The URL should be plain printable
+    /// ASCII, i.e. percent-encoded, since control characters are stripped when it's written to the terminal.
+    ///
+    /// It's shared, rather than a `String`, since one link usually covers many [`Cell`]s. It's still part of the
+    /// format's equality, so changing only a link counts as a change when diffing screens, at the cost of comparing
+    /// the URLs of linked cells.
+    pub link: Option<Arc<str>>,
+}
+
+impl Format {
+    /// The default formatting Redshell uses
+    pub const NONE: Self = Format {
+        fg: Color::White,
+        bg: Color::Black,
+        bold: false,
+        underline: false,
+        blink: false,
+        link: None,
+    };
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+macro_rules! fmt_fn {
+    ( $(
+        $name:ident
+        $(( $( $arg:ident: $type:ty ),* $(,)? ))?
+        =>
+        $field:ident $(.$subfield:ident)*
+        = $val:expr
+    ),* $(,)? ) => { $(
+        #[must_use]
+        fn $name(mut self $($(, $arg: $type )*)? ) -> Self {
+            self.get_fmt_mut().$field $(.$subfield)* = $val;
+            self
+        }
+    )* };
+}
+
+/// Trait implemented by all formattable items ([`Text`] and [`Cell`]) for common functionality.
+pub trait Formatted {
+    fn get_fmt(&self) -> &Format;
+    fn get_fmt_mut(&mut self) -> &mut Format;
+}
+
+/// Provides common formatting operations on anything implementing [`Formatted`].
+pub trait FormattedExt: Formatted + Sized {
+    /// Directly set the formatting of this item to some [`Format`]
+    #[must_use]
+    fn fmt(mut self, fmt: Format) -> Self {
+        *self.get_fmt_mut() = fmt;
+        self
+    }
+
+    /// Copy another item's formatting into this one.
+    ///
+    /// The two objects don't need to be the same type, e.g. you can copy a [`Text`]'s formatting to a [`Cell`].
+    #[must_use]
+    fn fmt_of(mut self, rhs: &dyn Formatted) -> Self {
+        *self.get_fmt_mut() = rhs.get_fmt().clone();
+        self
+    }
+    fmt_fn! {
+        fg(c: Color) => fg = c,                         bg(c: Color) => bg = c,
+        black => fg = Color::Black,                     on_black => bg = Color::Black,
+        bright_black => fg = Color::BrightBlack,        on_bright_black => bg = Color::BrightBlack,
+        red => fg = Color::Red,                         on_red => bg = Color::Red,
+        bright_red => fg = Color::BrightRed,            on_bright_red => bg = Color::BrightRed,
+        green => fg = Color::Green,                     on_green => bg = Color::Green,
+        bright_green => fg = Color::BrightGreen,        on_bright_green => bg = Color::BrightGreen,
+        yellow => fg = Color::Yellow,                   on_yellow => bg = Color::Yellow,
+        bright_yellow => fg = Color::BrightYellow,      on_bright_yellow => bg = Color::BrightYellow,
+        blue => fg = Color::Blue,                       on_blue => bg = Color::Blue,
+        bright_blue => fg = Color::BrightBlue,          on_bright_blue => bg = Color::BrightBlue,
+        magenta => fg = Color::Magenta,                 on_magenta => bg = Color::Magenta,
+        bright_magenta => fg = Color::BrightMagenta,    on_bright_magenta => bg = Color::BrightMagenta,
+        cyan => fg = Color::Cyan,                       on_cyan => bg = Color::Cyan,
+        bright_cyan => fg = Color::BrightCyan,          on_bright_cyan => bg = Color::BrightCyan,
+        white => fg = Color::White,                     on_white => bg = Color::White,
+        bright_white => fg = Color::BrightWhite,        on_bright_white => bg = Color::BrightWhite,
+        underline => underline = true,
+        bold => bold = true,
+        blink => blink = true,
+        link(url: &str) => link = Some(url.into()),
+    }
+}
+
+impl<F: Formatted> FormattedExt for F {}
+
+macro_rules! fmt_type {
+    (
+        $( #[$($attr:meta),* $(,)?] )*
+        $svis:vis struct $name:ident { $( $fvis:vis $field:ident: $type:ty ),* $(,)? }
+    ) => {
+        $( #[$($attr),*] )*
+        $svis struct $name {
+            $( $fvis $field: $type, )*
+            _fmt: $crate::fmt::Format,
+        }
+        impl $crate::fmt::Formatted for $name {
+            fn get_fmt(&self) -> &$crate::fmt::Format {
+                &self._fmt
+            }
+            fn get_fmt_mut(&mut self) -> &mut $crate::fmt::Format {
+                &mut self._fmt
+            }
+        }
+        impl $name {
+            pub const fn of( $($field: $type),* ) -> Self {
+                Self {
+                    $( $field, )*
+                    _fmt: $crate::fmt::Format::NONE,
+                }
+            }
+            pub fn data(mut self $(, $field: $type)*) -> Self {
+                $(
+                    self.$field = $field;
+                )*
+                self
+            }
+        }
+    };
+}
+
+fmt_type!(
+    /// A single bit of formatted text.
+    ///
+    /// You can use this directly, but most APIs are oriented around `Vec`s of `Text`.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Text {
+        pub text: String,
+    }
+);
+
+impl Text {
+    pub fn plain(s: &str) -> Text {
+        Text::of(s.into())
+    }
+
+    pub fn with_text(&self, new_text: String) -> Text {
+        let mut res = self.clone();
+        res.text = new_text;
+        res
+    }
+
+    /// Start building a `Vec<Text>` piece by piece, e.g. to color values that aren't known until runtime. See
+    /// [`TextBuilder`].
+    pub fn builder() -> TextBuilder {
+        TextBuilder::default()
+    }
+}
+
+/// Create a single [`Text`]. Not recommended to be used directly.
+#[macro_export]
+macro_rules! text1 {
+    [
+        $( $name:ident )*
+        $text:literal
+        $( ( $( $arg:expr ),* $(,)? ) )?
+    ] => {
+        {
+            extern crate alloc;
+            #[allow(unused_imports)]
+            use $crate::fmt::{FormattedExt as _};
+            $crate::fmt::Text::of(
+                alloc::format!( $text $(, $( $arg ),* )? )
+            ) $( . $name () )*
+        }
+    };
+}
+
+/// Create a series of formatted [`Text`]s.
+#[macro_export]
+macro_rules! text {
+    [ $(
+        $( $name:ident )*
+        $text:literal
+        $( ( $( $arg:expr ),* $(,)? ) )?
+    ),* $(,)? ] => {
+        {
+            extern crate alloc;
+            #[allow(unused_imports)]
+            use $crate::fmt::{Text, FormattedExt as _};
+            // enforce the correct type, even for empty text![]
+            alloc::vec![
+                $(
+                    $crate::text1!($( $name )* $text $( ( $( $arg ),* ) )?)
+                ),*
+            ] as Vec<Text>
+        }
+    };
+}
+
+fmt_type! {
+    /// A single character with associated formatting.
+    #[derive(Clone, PartialEq, Eq, Hash)]
+    pub struct Cell { pub ch: char }
+}
+
+impl core::fmt::Debug for Cell {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Cell").field(&self.ch).finish()
+    }
+}
+
+/// Create a formatted [`Cell`].
+#[macro_export]
+macro_rules! cell {
+    [ $( $name:ident )* $( $char:literal )? ] => {
+        {
+            #[allow(unused_imports)]
+            use $crate::fmt::{FormattedExt as _};
+            Cell::of($($char)?) $( .$name() )*
+        }
+    };
+}
+
+impl Cell {
+    /// A blank cell with default formatting.
+    pub const BLANK: Cell = cell!(' ');
+
+    /// The `ch` of a continuation cell, i.e. the right half of a [wide character](char_width).
+    ///
+    /// A wide character is stored in one cell, immediately followed by a continuation cell with the same formatting.
+    /// Renderers draw the wide character across both and skip the continuation itself. A continuation cell that
+    /// *doesn't* follow a wide character (e.g. because the wide character was overwritten) renders as a blank.
+    pub const CONTINUATION: char = '\0';
+
+    /// Whether this cell is the [continuation](Self::CONTINUATION) of the wide character before it.
+    pub fn is_continuation(&self) -> bool {
+        self.ch == Self::CONTINUATION
+    }
+
+    /// How many columns this cell's character takes up, per [`char_width`]. Continuation cells are 0 wide.
+    pub fn width(&self) -> usize {
+        if self.is_continuation() {
+            0
+        } else {
+            char_width(self.ch)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn indexed_palette_rgb() {
+        assert_eq!(Color::Indexed(1).rgb(), Color::Red.rgb());
+        assert_eq!(Color::Indexed(16).rgb(), (0, 0, 0));
+        assert_eq!(Color::Indexed(196).rgb(), (255, 0, 0));
+        assert_eq!(Color::Indexed(110).rgb(), (135, 175, 215));
+        assert_eq!(Color::Indexed(231).rgb(), (255, 255, 255));
+        assert_eq!(Color::Indexed(232).rgb(), (8, 8, 8));
+        assert_eq!(Color::Indexed(255).rgb(), (238, 238, 238));
+    }
+
+    #[test]
+    fn indexed_round_trips() {
+        for idx in 0..=255 {
+            assert_eq!(Color::from_indexed(idx).to_indexed(), idx);
+            assert_eq!(Color::Indexed(idx).to_indexed(), idx);
+            // every cube and gray color is its own nearest
+            if idx >= 16 {
+                let (r, g, b) = Color::Indexed(idx).rgb();
+                assert_eq!(Color::Rgb(r, g, b).to_indexed(), idx, "index {}", idx);
+            }
+        }
+        assert_eq!(Color::from_indexed(9), Color::BrightRed);
+        assert_eq!(Color::from_indexed(100), Color::Indexed(100));
+        assert_eq!(Color::Rgb(250, 10, 10).to_indexed(), 196);
+        assert_eq!(Color::Rgb(100, 101, 99).to_indexed(), 241);
+    }
+
+    #[test]
+    fn indexed_snaps_to_named() {
+        assert_eq!(Color::Indexed(12).nearest_palette(), Color::BrightBlue);
+        assert_eq!(Color::Indexed(196).nearest_palette(), Color::BrightRed);
+        assert_eq!(Color::Indexed(232).nearest_palette(), Color::Black);
+    }
+
+    #[test]
+    fn lerp_endpoints_exact() {
+        assert_eq!(Color::Red.lerp(Color::Blue, 0.0), Color::Red);
+        assert_eq!(Color::Red.lerp(Color::Blue, 1.0), Color::Blue);
+        assert_eq!(Color::Red.lerp(Color::Blue, -3.0), Color::Red);
+        assert_eq!(Color::Red.lerp(Color::Blue, 7.0), Color::Blue);
+        assert_eq!(Color::Red.lerp(Color::Blue, f32::NAN), Color::Red);
+    }
+
+    #[test]
+    fn lerp_blends_in_rgb() {
+        let mid = Color::Rgb(0, 100, 200).lerp(Color::Rgb(100, 100, 0), 0.5);
+        assert_eq!(mid, Color::Rgb(50, 100, 100));
+        assert_eq!(
+            Color::Black.lerp(Color::BrightWhite, 0.25),
+            Color::Rgb(64, 64, 64)
+        );
+    }
+
+    #[test]
+    fn nearest_palette_snaps() {
+        assert_eq!(Color::Rgb(250, 10, 10).nearest_palette(), Color::BrightRed);
+        assert_eq!(Color::Rgb(10, 10, 10).nearest_palette(), Color::Black);
+        assert_eq!(
+            Color::Rgb(140, 130, 120).nearest_palette(),
+            Color::BrightBlack
+        );
+        for c in Color::all() {
+            assert_eq!(c.nearest_palette(), c);
+            let (r, g, b) = c.rgb();
+            assert_eq!(Color::Rgb(r, g, b).nearest_palette(), c);
+        }
+    }
+
+    #[test]
+    fn luminance_and_contrast_extremes() {
+        assert_eq!(Color::Black.luminance(), 0.0);
+        assert_eq!(Color::BrightWhite.luminance(), 1.0);
+        let max = Color::Black.contrast_with(Color::BrightWhite);
+        assert!(max > 20.99 && max < 21.01, "got {}", max);
+        assert_eq!(Color::BrightWhite.contrast_with(Color::Black), max);
+        assert_eq!(Color::Red.contrast_with(Color::Rgb(205, 0, 0)), 1.0);
+        assert!(Color::Green.luminance() > Color::Red.luminance());
+        assert!(Color::Red.luminance() > Color::Blue.luminance());
+    }
+
+    #[test]
+    fn readable_on_picks_black_or_white() {
+        let white_on = [Color::Black, Color::Red, Color::Blue, Color::Rgb(60, 0, 90)];
+        for bg in white_on {
+            assert_eq!(Color::readable_on(bg), Color::BrightWhite, "on {:?}", bg);
+        }
+        let black_on = [
+            Color::Green,
+            Color::Yellow,
+            Color::Cyan,
+            Color::White,
+            Color::BrightBlack,
+            Color::BrightYellow,
+            Color::BrightWhite,
+        ];
+        for bg in black_on {
+            assert_eq!(Color::readable_on(bg), Color::Black, "on {:?}", bg);
+        }
+    }
+
+    #[test]
+    fn best_contrast_picks_from_candidates() {
+        let dims = [Color::Red, Color::Blue, Color::BrightBlack];
+        assert_eq!(Color::BrightWhite.best_contrast(&dims), Color::Blue);
+        assert_eq!(Color::Black.best_contrast(&dims), Color::BrightBlack);
+        assert_eq!(
+            Color::Black.best_contrast(&[Color::Red, Color::Red]),
+            Color::Red
+        );
+        assert_eq!(Color::Black.best_contrast(&[]), Color::BrightWhite);
+    }
+
+    #[test]
+    fn gradient_steps_across_text() {
+        let text = gradient("abcde", Color::Black, Color::BrightWhite);
+        let chars: Vec<_> = text.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(chars, ["a", "b", "c", "d", "e"]);
+        assert_eq!(text[0].get_fmt().fg, Color::Black);
+        assert_eq!(text[2].get_fmt().fg, Color::Rgb(128, 128, 128));
+        assert_eq!(text[4].get_fmt().fg, Color::BrightWhite);
+    }
+
+    #[test]
+    fn gradient_short_text() {
+        assert!(gradient("", Color::Red, Color::Blue).is_empty());
+        let one = gradient("x", Color::Red, Color::Blue);
+        assert_eq!(one.len(), 1);
+        assert_eq!(one[0].get_fmt().fg, Color::Red);
+    }
+}
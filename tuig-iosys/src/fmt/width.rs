@@ -0,0 +1,222 @@
+//! Display width of characters, i.e. how many columns they take up on a character grid.
+
+/// Ranges of characters that don't take up any space of their own, like combining marks and variation selectors.
+///
+/// Checked before [`WIDE`], because a few of these fall in the middle of wide ranges.
+#[rustfmt::skip]
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036F), (0x0483, 0x0489), (0x0591, 0x05BD), (0x05BF, 0x05BF), (0x05C1, 0x05C2), (0x05C4, 0x05C5),
+    (0x05C7, 0x05C7), (0x0610, 0x061A), (0x064B, 0x065F), (0x0670, 0x0670), (0x06D6, 0x06DC), (0x06DF, 0x06E4),
+    (0x06E7, 0x06E8), (0x06EA, 0x06ED), (0x0E31, 0x0E31), (0x0E34, 0x0E3A), (0x0E47, 0x0E4E), (0x1AB0, 0x1AFF),
+    (0x1DC0, 0x1DFF), (0x200B, 0x200F), (0x2060, 0x2064), (0x20D0, 0x20FF), (0x302A, 0x302D), (0x3099, 0x309A),
+    (0xFE00, 0xFE0F), (0xFE20, 0xFE2F), (0xFEFF, 0xFEFF), (0xE0100, 0xE01EF),
+];
+
+/// Ranges of characters that take up two columns: East Asian Wide and Fullwidth characters, and most emoji.
+#[rustfmt::skip]
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F), (0x231A, 0x231B), (0x2329, 0x232A), (0x23E9, 0x23EC), (0x23F0, 0x23F0), (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE), (0x2614, 0x2615), (0x2648, 0x2653), (0x267F, 0x267F), (0x2693, 0x2693), (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB), (0x26BD, 0x26BE), (0x26C4, 0x26C5), (0x26CE, 0x26CE), (0x26D4, 0x26D4), (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3), (0x26F5, 0x26F5), (0x26FA, 0x26FA), (0x26FD, 0x26FD), (0x2705, 0x2705), (0x270A, 0x270B),
+    (0x2728, 0x2728), (0x274C, 0x274C), (0x274E, 0x274E), (0x2753, 0x2755), (0x2757, 0x2757), (0x2795, 0x2797),
+    (0x27B0, 0x27B0), (0x27BF, 0x27BF), (0x2B1B, 0x2B1C), (0x2B50, 0x2B50), (0x2B55, 0x2B55), (0x2E80, 0x303E),
+    (0x3041, 0x33FF), (0x3400, 0x4DBF), (0x4E00, 0x9FFF), (0xA000, 0xA4CF), (0xA960, 0xA97F), (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF), (0xFE10, 0xFE19), (0xFE30, 0xFE6F), (0xFF00, 0xFF60), (0xFFE0, 0xFFE6), (0x16FE0, 0x16FE4),
+    (0x17000, 0x18AFF), (0x1B000, 0x1B2FF), (0x1F004, 0x1F004), (0x1F0CF, 0x1F0CF), (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A), (0x1F200, 0x1F202), (0x1F210, 0x1F23B), (0x1F240, 0x1F248), (0x1F250, 0x1F251),
+    (0x1F260, 0x1F265), (0x1F300, 0x1F64F), (0x1F680, 0x1F6FF), (0x1F7E0, 0x1F7EB), (0x1F900, 0x1F9FF),
+    (0x1FA70, 0x1FAFF), (0x20000, 0x2FFFD), (0x30000, 0x3FFFD),
+];
+
+fn in_table(table: &[(u32, u32)], ch: char) -> bool {
+    let ch = ch as u32;
+    table
+        .binary_search_by(|&(lo, hi)| {
+            if hi < ch {
+                core::cmp::Ordering::Less
+            } else if lo > ch {
+                core::cmp::Ordering::Greater
+            } else {
+                core::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// How many columns a character takes up when displayed on a character grid: 0, 1, or 2.
+///
+/// This is an approximation of the Unicode East Asian Width property, which is what most terminals use. It's not
+/// perfect, and terminals themselves don't all agree, but it's the measure everything in `tuig` uses, so at least it
+/// will be consistently wrong.
+///
+/// - Combining marks and other zero-width characters are 0 wide. Since a cell can only hold one `char`, these are
+///   generally dropped when rendering.
+/// - CJK ideographs, fullwidth forms, Hangul, most emoji, etc. are 2 wide, taking up their own cell plus a following
+///   [continuation](super::Cell::CONTINUATION) cell.
+/// - Everything else, including control characters, is 1 wide.
+pub fn char_width(ch: char) -> usize {
+    if (ch as u32) < 0x300 {
+        // fast path for ASCII and Latin-1, which are never wide or zero-width
+        1
+    } else if in_table(ZERO, ch) {
+        0
+    } else if in_table(WIDE, ch) {
+        2
+    } else {
+        1
+    }
+}
+
+/// How many columns a string takes up when displayed. Just the sum of the [`char_width`]s of its characters.
+pub fn str_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+fn is_regional_indicator(ch: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&ch)
+}
+
+/// How many bytes long the first grapheme cluster in a string is, i.e. the first thing a user would see as a single
+/// character. 0 if and only if the string is empty.
+///
+/// Like [`char_width`], this is an approximation, not a full implementation of the Unicode segmentation rules. It
+/// gets the common cases right:
+///
+/// - A character followed by zero-width ones (combining marks, variation selectors, etc.) is one cluster.
+/// - Characters joined by a zero-width joiner (U+200D), like in most multi-person emoji, are one cluster.
+/// - Pairs of regional indicators, i.e. flags, are one cluster.
+/// - Control characters are always their own cluster, except that `"\r\n"` is one.
+pub fn grapheme_len(s: &str) -> usize {
+    let mut chars = s.char_indices().peekable();
+    let first = match chars.next() {
+        Some((_, ch)) => ch,
+        None => return 0,
+    };
+    let mut end = first.len_utf8();
+    if first.is_control() {
+        if first == '\r' && s[end..].starts_with('\n') {
+            end += 1;
+        }
+        return end;
+    }
+    let mut prev = first;
+    let mut flag_half = is_regional_indicator(first);
+    while let Some(&(idx, ch)) = chars.peek() {
+        let joins = if ch.is_control() {
+            false
+        } else if prev == '\u{200D}' || char_width(ch) == 0 {
+            true
+        } else if flag_half && is_regional_indicator(ch) {
+            flag_half = false;
+            true
+        } else {
+            false
+        };
+        if !joins {
+            break;
+        }
+        chars.next();
+        end = idx + ch.len_utf8();
+        prev = ch;
+    }
+    end
+}
+
+/// Split a string into its grapheme clusters, per [`grapheme_len`].
+pub fn graphemes(s: &str) -> impl Iterator<Item = &str> + '_ {
+    let mut rest = s;
+    core::iter::from_fn(move || {
+        let len = grapheme_len(rest);
+        if len == 0 {
+            return None;
+        }
+        let (head, tail) = rest.split_at(len);
+        rest = tail;
+        Some(head)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tables_sorted() {
+        for table in [ZERO, WIDE] {
+            for pair in table.windows(2) {
+                assert!(pair[0].0 <= pair[0].1, "bad range {:x?}", pair[0]);
+                assert!(pair[0].1 < pair[1].0, "overlap at {:x?}", pair);
+            }
+        }
+    }
+
+    #[test]
+    fn ascii_is_narrow() {
+        for ch in ' '..='~' {
+            assert_eq!(char_width(ch), 1, "{:?}", ch);
+        }
+    }
+
+    #[test]
+    fn cjk_is_wide() {
+        assert_eq!(char_width('漢'), 2);
+        assert_eq!(char_width('あ'), 2);
+        assert_eq!(char_width('한'), 2);
+        assert_eq!(char_width('Ａ'), 2);
+        assert_eq!(char_width('🎉'), 2);
+    }
+
+    #[test]
+    fn combining_is_zero() {
+        assert_eq!(char_width('\u{0301}'), 0);
+        assert_eq!(char_width('\u{200D}'), 0);
+        assert_eq!(char_width('\u{FE0F}'), 0);
+        // in the middle of a wide range, but combining
+        assert_eq!(char_width('\u{3099}'), 0);
+    }
+
+    #[test]
+    fn str_width_sums() {
+        assert_eq!(str_width(""), 0);
+        assert_eq!(str_width("abc"), 3);
+        assert_eq!(str_width("a漢b"), 4);
+        assert_eq!(str_width("e\u{0301}"), 1);
+    }
+
+    #[test]
+    fn grapheme_plain_chars() {
+        assert_eq!(grapheme_len(""), 0);
+        assert_eq!(grapheme_len("abc"), 1);
+        assert_eq!(grapheme_len("漢字"), 3);
+    }
+
+    #[test]
+    fn grapheme_absorbs_combining() {
+        assert_eq!(grapheme_len("e\u{0301}\u{0302}x"), 5);
+        assert_eq!(grapheme_len("\u{2764}\u{FE0F}!"), 6);
+    }
+
+    #[test]
+    fn grapheme_follows_zwj() {
+        let family = "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}";
+        assert_eq!(grapheme_len(family), family.len());
+    }
+
+    #[test]
+    fn grapheme_pairs_flags() {
+        let flags = "\u{1F1EF}\u{1F1F5}\u{1F1FA}\u{1F1F8}";
+        assert_eq!(graphemes(flags).count(), 2);
+        assert_eq!(grapheme_len("\u{1F1EF}x"), 4);
+    }
+
+    #[test]
+    fn grapheme_controls_stand_alone() {
+        assert_eq!(grapheme_len("\n\u{0301}"), 1);
+        assert_eq!(grapheme_len("\r\nx"), 2);
+        assert_eq!(
+            graphemes("a\u{0301}\nb").collect::<alloc::vec::Vec<_>>(),
+            ["a\u{0301}", "\n", "b"]
+        );
+    }
+}
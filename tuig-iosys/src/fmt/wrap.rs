@@ -0,0 +1,412 @@
+//! Wrapping formatted text into lines.
+
+use alloc::{string::String, vec::Vec};
+
+use super::{str_width, Formatted, FormattedExt, Text};
+use crate::text1;
+
+/// Whether a line can be broken at `ch`. That's any whitespace, except the no-break spaces, which are there
+/// specifically to keep the words on either side together.
+fn breakable(ch: char) -> bool {
+    ch.is_whitespace() && !matches!(ch, '\u{a0}' | '\u{2007}' | '\u{202f}')
+}
+
+/// The byte index where the longest prefix of `s` that's at most `width` columns wide ends.
+fn fit(s: &str, width: usize) -> usize {
+    let mut used = 0;
+    for (idx, ch) in s.char_indices() {
+        used += super::char_width(ch);
+        if used > width {
+            return idx;
+        }
+    }
+    s.len()
+}
+
+/// How many columns apart tab stops are, unless set otherwise, e.g. with [`WrapOpts::tab_width`]. Matches most
+/// terminals.
+pub const DEFAULT_TAB_WIDTH: usize = 8;
+
+/// Replace every tab in some text with spaces, up to the next tab stop, i.e. the next multiple of `tab_width`
+/// columns. The text is assumed to start at column `start`, and newlines go back to it.
+///
+/// Each chunk stays one chunk, with its formatting, so indices into the text still line up.
+///
+/// ```
+/// # use tuig_iosys::{fmt, text};
+/// let expanded = fmt::expand_tabs(&text!("a\tb", red "cd\te"), 0, 4);
+/// assert_eq!(expanded, text!("a   b", red "cd e"));
+/// ```
+pub fn expand_tabs(chunks: &[Text], start: usize, tab_width: usize) -> Vec<Text> {
+    let tab_width = tab_width.max(1);
+    let mut col = start;
+    let mut res = Vec::with_capacity(chunks.len());
+    for chunk in chunks {
+        if !chunk.text.contains('\t') {
+            // still have to keep track of where we are
+            for line in chunk.text.split('\n').skip(1) {
+                col = start + str_width(line);
+            }
+            if !chunk.text.contains('\n') {
+                col += str_width(&chunk.text);
+            }
+            res.push(chunk.clone());
+            continue;
+        }
+        let mut text = String::with_capacity(chunk.text.len());
+        for ch in chunk.text.chars() {
+            match ch {
+                '\t' => {
+                    let stop = (col / tab_width + 1) * tab_width;
+                    text.extend(core::iter::repeat(' ').take(stop - col));
+                    col = stop;
+                }
+                '\n' => {
+                    text.push(ch);
+                    col = start;
+                }
+                _ => {
+                    text.push(ch);
+                    col += super::char_width(ch);
+                }
+            }
+        }
+        res.push(chunk.with_text(text));
+    }
+    res
+}
+
+/// How [`wrap`] handles lines that are too long to fit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Wrap {
+    /// Break on whitespace other than no-break spaces, only breaking up a word (with a hyphen, if there's room and
+    /// hyphenation is on) when it's too long to fit on a line by itself.
+    Word,
+    /// Fill each line right to the edge, breaking wherever the edge happens to fall, even mid-word.
+    Char,
+    /// Don't wrap at all; anything past the edge is cut off.
+    None,
+}
+
+/// Options for [`wrap`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct WrapOpts {
+    mode: Wrap,
+    hyphenate: bool,
+    indent: usize,
+    first_indent: Option<usize>,
+    tab_width: usize,
+}
+
+impl WrapOpts {
+    /// The default options: word wrapping, with hyphens, no indent, and [`DEFAULT_TAB_WIDTH`].
+    pub const fn new() -> Self {
+        Self {
+            mode: Wrap::Word,
+            hyphenate: true,
+            indent: 0,
+            first_indent: None,
+            tab_width: DEFAULT_TAB_WIDTH,
+        }
+    }
+
+    tuig_pm::setters! {
+        /// How to break lines that are too long.
+        ///
+        /// Defaults to [`Wrap::Word`].
+        mode(v: Wrap) => mode = v,
+        /// Whether to add a hyphen when breaking up a word that's too long for a line by itself, in [`Wrap::Word`]
+        /// mode.
+        ///
+        /// Defaults to true.
+        hyphenate(v: bool) => hyphenate = v,
+        /// How much to indent every line.
+        ///
+        /// Defaults to 0, i.e. no indent.
+        indent(amt: usize) => indent = amt,
+        /// How much to specifically indent the first line of each paragraph.
+        ///
+        /// Defaults to being the same as the indent.
+        first_indent(amt: usize) => first_indent = Some(amt),
+        /// How many columns apart tab stops are, counting from the left edge, indent included. At least 1.
+        ///
+        /// Tabs are [expanded](expand_tabs) into spaces before wrapping, as though each paragraph were all on its
+        /// first line, so they line up in lines that don't wrap. A tab past the edge wraps like any other spaces.
+        ///
+        /// Defaults to [`DEFAULT_TAB_WIDTH`].
+        tab_width(v: usize) => tab_width = v.max(1),
+    }
+}
+
+impl Default for WrapOpts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wrap formatted text into lines at most `width` columns wide, by [display width](super::str_width).
+///
+/// Each line is a list of chunks, each with its original [`Format`](super::Format), even when a chunk gets split
+/// across lines. Newlines start new paragraphs, which always start on a new line. Indents are included as plain
+/// spaces at the start of each line, and when a chunk is split, the rest of its line is padded with spaces in its
+/// background color, so e.g. a highlight reaches the edge rather than stopping short. There's always at least one
+/// line, even if it's empty.
+///
+/// This panics if the indent or first indent doesn't leave room for any text, i.e. if either is at least `width`.
+///
+/// ```
+/// # use tuig_iosys::{fmt::{self, WrapOpts}, text};
+/// let lines = fmt::wrap(&text!("some words ", red "in red"), 8, WrapOpts::new());
+/// assert_eq!(lines.len(), 3);
+/// ```
+pub fn wrap(chunks: &[Text], width: usize, opts: WrapOpts) -> Vec<Vec<Text>> {
+    wrap_indexed(chunks, width, opts)
+        .into_iter()
+        .map(|line| line.into_iter().map(|(_, chunk)| chunk).collect())
+        .collect()
+}
+
+/// [Wrap](wrap) formatted text exactly the same way, but pair each piece of each line with the index of the chunk in
+/// `chunks` it came from, or `None` for the indents and padding added by wrapping.
+///
+/// This is what lets you map a position in the wrapped text back to the original chunks, e.g. to find what was
+/// clicked on.
+///
+/// ```
+/// # use tuig_iosys::{fmt::{self, WrapOpts}, text};
+/// let lines = fmt::wrap_indexed(&text!("ab ", red "cd"), 4, WrapOpts::new().indent(1));
+/// let sources: Vec<Vec<_>> = lines.iter().map(|l| l.iter().map(|(i, _)| *i).collect()).collect();
+/// assert_eq!(sources, [vec![None, Some(0)], vec![None, Some(1)]]);
+/// ```
+pub fn wrap_indexed(
+    chunks: &[Text],
+    width: usize,
+    opts: WrapOpts,
+) -> Vec<Vec<(Option<usize>, Text)>> {
+    let first_indent = opts.first_indent.unwrap_or(opts.indent);
+
+    assert!(width > opts.indent);
+    assert!(width > first_indent);
+
+    // break the chunks into paragraphs on newlines
+    let chunks = expand_tabs(chunks, first_indent, opts.tab_width);
+    let mut paragraphs = alloc::vec![];
+    let mut cur_para = alloc::vec![];
+    for (idx, chunk) in chunks.iter().enumerate() {
+        let mut chunk = chunk.clone();
+        while let Some((line, rest)) = chunk.text.split_once('\n') {
+            cur_para.push((idx, chunk.with_text(line.into())));
+            paragraphs.push(cur_para);
+            cur_para = alloc::vec![];
+            chunk.text = rest.into();
+        }
+        if !chunk.text.is_empty() {
+            cur_para.push((idx, chunk));
+        }
+    }
+    paragraphs.push(cur_para);
+
+    // space out and word-wrap those paragraphs into lines
+    let mut lines = alloc::vec![];
+    for para in paragraphs {
+        let mut line = alloc::vec![(None, text1!["{0:1$}"("", first_indent)])];
+        let mut pos = first_indent;
+        let mut line_start = true;
+        let mut clipped = false;
+        for (idx, mut chunk) in para {
+            if clipped {
+                break;
+            }
+            // the code flow in this for loop is too complex to add this =false at the end, so we make do
+            let mut was_line_start = line_start;
+            line_start = false;
+            // while there's too much to fit on the next line all at once
+            while pos + str_width(&chunk.text) > width {
+                // how much space can we fit things into?
+                let space_left = width - pos;
+                if opts.mode == Wrap::None {
+                    // keep what fits and drop the rest of the paragraph
+                    chunk.text.truncate(fit(&chunk.text, space_left));
+                    clipped = true;
+                    break;
+                }
+                // the bit of text that will be put at the end of this line
+                let line_end: String;
+                // the rest of the text, which wraps to following lines
+                let rest: String;
+                if opts.mode == Wrap::Char {
+                    // take as much as fits, or move on to the next line if nothing does -- unless we're already
+                    // at the start of one, in which case take one character anyway to make progress
+                    let mut idx = fit(&chunk.text, space_left);
+                    if idx == 0 && was_line_start {
+                        idx = chunk.text.chars().next().map_or(0, char::len_utf8);
+                    }
+                    let (pre, post) = chunk.text.split_at(idx);
+                    line_end = pre.into();
+                    rest = post.into();
+                } else if let Some((idx, ch)) = chunk.text[..fit(&chunk.text, space_left + 1)]
+                    .char_indices()
+                    .rev()
+                    .find(|(_, c)| breakable(*c))
+                {
+                    // we have a breakable character in time; we break there, dropping it however wide it is
+                    let pre = &chunk.text[..idx];
+                    let post = &chunk.text[idx + ch.len_utf8()..];
+                    line_end = pre.into();
+                    rest = post.into();
+                } else if !was_line_start {
+                    // no breakable character, but we're not at the start of the line, so let's try
+                    // ending the line here and getting to the next one
+                    line_end = String::new();
+                    rest = chunk.text;
+                } else if opts.hyphenate && space_left > 1 && fit(&chunk.text, space_left - 1) > 0 {
+                    // break the word with a hyphen, since there's space for it
+                    let (pre, post) = chunk.text.split_at(fit(&chunk.text, space_left - 1));
+                    line_end = alloc::format!("{}-", pre);
+                    rest = post.into();
+                } else if space_left > 0 {
+                    // no room for a hyphen (or we don't want one), so just pull off as much as fits -- and at least
+                    // one character even if it doesn't (e.g. a wide character in a 1-wide box), so we always make
+                    // progress
+                    let mut idx = fit(&chunk.text, space_left);
+                    if idx == 0 {
+                        idx = chunk.text.chars().next().map_or(0, char::len_utf8);
+                    }
+                    let (pre, post) = chunk.text.split_at(idx);
+                    line_end = pre.into();
+                    rest = post.into();
+                } else {
+                    // at the start of a line, but 0 space left -- the asserts above should have
+                    // prevented this!
+                    unreachable!("indent or first indent is larger than width")
+                }
+                // set up the chunk for next iteration
+                chunk.text = rest;
+                // tack on the end of the line, if it's not empty
+                if !line_end.is_empty() {
+                    let rem_space = width.saturating_sub(pos + str_width(&line_end));
+                    line.push((Some(idx), chunk.with_text(line_end)));
+                    // then make sure the formatting continues into the next line
+                    if rem_space > 0 {
+                        let pad = text1!("{0:1$}"("", rem_space)).bg(chunk.get_fmt().bg);
+                        line.push((None, pad));
+                    }
+                }
+                // actually terminate the line and start the next one
+                lines.push(line);
+                line = alloc::vec![(None, text1!["{0:1$}"("", opts.indent)])];
+                pos = opts.indent;
+                line_start = true;
+                was_line_start = true;
+            }
+            // now we can fit the rest on this one line
+            pos += str_width(&chunk.text);
+            line.push((Some(idx), chunk));
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{fmt::Color, text};
+
+    fn strings(lines: &[Vec<Text>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.iter().map(|c| c.text.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn wraps_words_keeping_format() {
+        let lines = wrap(&text!("some words ", red "in red"), 8, WrapOpts::new());
+        // the break eats the space, and the split line is padded out
+        assert_eq!(strings(&lines), ["some    ", "words in", "red"]);
+        let red: Vec<_> = lines[1..]
+            .iter()
+            .flatten()
+            .filter(|c| c.get_fmt().fg == Color::Red)
+            .map(|c| c.text.as_str())
+            .collect();
+        assert_eq!(red, ["in", "red"]);
+    }
+
+    #[test]
+    fn hyphenation_optional() {
+        let chunks = text!("abcdefgh");
+        let hyphen = wrap(&chunks, 5, WrapOpts::new());
+        assert_eq!(strings(&hyphen), ["abcd-", "efgh"]);
+        let bare = wrap(&chunks, 5, WrapOpts::new().hyphenate(false));
+        assert_eq!(strings(&bare), ["abcde", "fgh"]);
+    }
+
+    #[test]
+    fn indents_and_paragraphs() {
+        let opts = WrapOpts::new().indent(1).first_indent(2);
+        let lines = wrap(&text!("aaa bbb\nccc"), 6, opts);
+        assert_eq!(strings(&lines), ["  aaa ", " bbb", "  ccc"]);
+    }
+
+    #[test]
+    fn indexes_follow_split_chunks() {
+        let chunks = text!("aaa bbb\nc", red "cc dd");
+        let lines = wrap_indexed(&chunks, 4, WrapOpts::new());
+        let pieces: Vec<Vec<_>> = lines
+            .iter()
+            .map(|l| l.iter().map(|(i, c)| (*i, c.text.as_str())).collect())
+            .collect();
+        assert_eq!(
+            pieces,
+            [
+                vec![(None, ""), (Some(0), "aaa"), (None, " ")],
+                vec![(None, ""), (Some(0), "bbb")],
+                vec![(None, ""), (Some(0), "c"), (Some(1), "cc"), (None, " ")],
+                vec![(None, ""), (Some(1), "dd")],
+            ]
+        );
+    }
+
+    #[test]
+    fn tabs_align_across_chunks() {
+        let opts = WrapOpts::new().tab_width(4).first_indent(1);
+        let lines = wrap(&text!("a\tbcd\t", red "e\tf\ng\th"), 20, opts);
+        assert_eq!(strings(&lines), [" a  bcd e   f", " g  h"]);
+    }
+
+    #[test]
+    fn tabs_past_the_edge_wrap() {
+        let opts = WrapOpts::new().tab_width(4);
+        let lines = wrap(&text!("ab\tcd\t\tefg"), 8, opts);
+        // the tabs after cd are 2 and 4 spaces, and the break eats one of them
+        assert_eq!(strings(&lines), ["ab  cd  ", "   efg"]);
+    }
+
+    #[test]
+    fn breaks_at_multibyte_whitespace() {
+        let ideographic = wrap(&text!("漢字漢字\u{3000}かなかな"), 9, WrapOpts::new());
+        assert_eq!(strings(&ideographic), ["漢字漢字 ", "かなかな"]);
+    }
+
+    #[test]
+    fn no_break_spaces_dont_break() {
+        for nbsp in ['\u{a0}', '\u{2007}', '\u{202f}'] {
+            let word = format!("cd{}ef", nbsp);
+            let lines = wrap(&[Text::plain(&format!("ab {}", word))], 6, WrapOpts::new());
+            // it breaks at the normal space instead, keeping the words joined by the no-break one together
+            assert_eq!(
+                strings(&lines),
+                [String::from("ab    "), word],
+                "{:?}",
+                nbsp
+            );
+        }
+    }
+
+    #[test]
+    fn always_one_line() {
+        assert_eq!(strings(&wrap(&[], 4, WrapOpts::new())), [""]);
+    }
+}
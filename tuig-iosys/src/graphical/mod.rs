@@ -6,29 +6,33 @@
 compile_error!("enable std to use gui backends");
 
 use std::{
-    io,
+    fs::File,
+    io::{self, BufWriter},
+    path::Path,
     sync::{
-        mpsc::{self, TryRecvError},
-        Arc, Once,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError, TryRecvError},
+        Arc, Mutex, MutexGuard, Once,
     },
     time::{Duration, Instant},
 };
 
 use winit::{
     dpi::LogicalSize,
-    event::{ElementState, Event, VirtualKeyCode, WindowEvent},
+    event::{ElementState, Event, KeyboardInput, MouseScrollDelta, VirtualKeyCode, WindowEvent},
     event_loop::{ControlFlow, EventLoop, EventLoopBuilder},
     platform::run_return::EventLoopExtRunReturn,
     window::{Window, WindowBuilder},
 };
 
 use crate::{
-    action::{Action, Key, MouseButton},
+    action::{Action, Key, Modifiers, MouseButton},
+    fmt::Color,
     screen::Screen,
     xy::XY,
 };
 
-use super::{IoRunner, IoSystem};
+use super::{Capabilities, CursorStyle, IoRunner, IoSystem};
 
 pub mod softbuffer;
 
@@ -41,127 +45,182 @@ pub const REGULAR_TTF: &[u8] = include_bytes!("inconsolata-reg.ttf");
 /// The default font, but bold.
 pub const BOLD_TTF: &[u8] = include_bytes!("inconsolata-bold.ttf");
 
-/// Convert a winit [`VirtualKeyCode`] to a Redshell [`Key`]
-fn key4vkc(vkc: Option<VirtualKeyCode>) -> Option<Key> {
-    match vkc? {
-        VirtualKeyCode::Key1 => Some(Key::Char('1')),
-        VirtualKeyCode::Key2 => Some(Key::Char('2')),
-        VirtualKeyCode::Key3 => Some(Key::Char('3')),
-        VirtualKeyCode::Key4 => Some(Key::Char('4')),
-        VirtualKeyCode::Key5 => Some(Key::Char('5')),
-        VirtualKeyCode::Key6 => Some(Key::Char('6')),
-        VirtualKeyCode::Key7 => Some(Key::Char('7')),
-        VirtualKeyCode::Key8 => Some(Key::Char('8')),
-        VirtualKeyCode::Key9 => Some(Key::Char('9')),
-        VirtualKeyCode::Key0 => Some(Key::Char('0')),
-        VirtualKeyCode::A => Some(Key::Char('a')),
-        VirtualKeyCode::B => Some(Key::Char('b')),
-        VirtualKeyCode::C => Some(Key::Char('c')),
-        VirtualKeyCode::D => Some(Key::Char('d')),
-        VirtualKeyCode::E => Some(Key::Char('e')),
-        VirtualKeyCode::F => Some(Key::Char('f')),
-        VirtualKeyCode::G => Some(Key::Char('g')),
-        VirtualKeyCode::H => Some(Key::Char('h')),
-        VirtualKeyCode::I => Some(Key::Char('i')),
-        VirtualKeyCode::J => Some(Key::Char('j')),
-        VirtualKeyCode::K => Some(Key::Char('k')),
-        VirtualKeyCode::L => Some(Key::Char('l')),
-        VirtualKeyCode::M => Some(Key::Char('m')),
-        VirtualKeyCode::N => Some(Key::Char('n')),
-        VirtualKeyCode::O => Some(Key::Char('o')),
-        VirtualKeyCode::P => Some(Key::Char('p')),
-        VirtualKeyCode::Q => Some(Key::Char('q')),
-        VirtualKeyCode::R => Some(Key::Char('r')),
-        VirtualKeyCode::S => Some(Key::Char('s')),
-        VirtualKeyCode::T => Some(Key::Char('t')),
-        VirtualKeyCode::U => Some(Key::Char('u')),
-        VirtualKeyCode::V => Some(Key::Char('v')),
-        VirtualKeyCode::W => Some(Key::Char('w')),
-        VirtualKeyCode::X => Some(Key::Char('x')),
-        VirtualKeyCode::Y => Some(Key::Char('y')),
-        VirtualKeyCode::Z => Some(Key::Char('z')),
-        VirtualKeyCode::Escape => Some(Key::Escape),
-        VirtualKeyCode::F1 => Some(Key::F(1)),
-        VirtualKeyCode::F2 => Some(Key::F(2)),
-        VirtualKeyCode::F3 => Some(Key::F(3)),
-        VirtualKeyCode::F4 => Some(Key::F(4)),
-        VirtualKeyCode::F5 => Some(Key::F(5)),
-        VirtualKeyCode::F6 => Some(Key::F(6)),
-        VirtualKeyCode::F7 => Some(Key::F(7)),
-        VirtualKeyCode::F8 => Some(Key::F(8)),
-        VirtualKeyCode::F9 => Some(Key::F(9)),
-        VirtualKeyCode::F10 => Some(Key::F(10)),
-        VirtualKeyCode::F11 => Some(Key::F(11)),
-        VirtualKeyCode::F12 => Some(Key::F(12)),
-        VirtualKeyCode::F13 => Some(Key::F(13)),
-        VirtualKeyCode::F14 => Some(Key::F(14)),
-        VirtualKeyCode::F15 => Some(Key::F(15)),
-        VirtualKeyCode::F16 => Some(Key::F(16)),
-        VirtualKeyCode::F17 => Some(Key::F(17)),
-        VirtualKeyCode::F18 => Some(Key::F(18)),
-        VirtualKeyCode::F19 => Some(Key::F(19)),
-        VirtualKeyCode::F20 => Some(Key::F(20)),
-        VirtualKeyCode::F21 => Some(Key::F(21)),
-        VirtualKeyCode::F22 => Some(Key::F(22)),
-        VirtualKeyCode::F23 => Some(Key::F(23)),
-        VirtualKeyCode::F24 => Some(Key::F(24)),
-        VirtualKeyCode::Insert => Some(Key::Insert),
-        VirtualKeyCode::Home => Some(Key::Home),
-        VirtualKeyCode::Delete => Some(Key::Delete),
-        VirtualKeyCode::End => Some(Key::End),
-        VirtualKeyCode::PageDown => Some(Key::PageDown),
-        VirtualKeyCode::PageUp => Some(Key::PageUp),
-        VirtualKeyCode::Left => Some(Key::Left),
-        VirtualKeyCode::Up => Some(Key::Up),
-        VirtualKeyCode::Right => Some(Key::Right),
-        VirtualKeyCode::Down => Some(Key::Down),
-        VirtualKeyCode::Back => Some(Key::Backspace),
-        VirtualKeyCode::Return => Some(Key::Enter),
-        VirtualKeyCode::Space => Some(Key::Char(' ')),
-        VirtualKeyCode::Caret => Some(Key::Char('^')),
-        VirtualKeyCode::Numpad0 => Some(Key::Char('0')),
-        VirtualKeyCode::Numpad1 => Some(Key::Char('1')),
-        VirtualKeyCode::Numpad2 => Some(Key::Char('2')),
-        VirtualKeyCode::Numpad3 => Some(Key::Char('3')),
-        VirtualKeyCode::Numpad4 => Some(Key::Char('4')),
-        VirtualKeyCode::Numpad5 => Some(Key::Char('5')),
-        VirtualKeyCode::Numpad6 => Some(Key::Char('6')),
-        VirtualKeyCode::Numpad7 => Some(Key::Char('7')),
-        VirtualKeyCode::Numpad8 => Some(Key::Char('8')),
-        VirtualKeyCode::Numpad9 => Some(Key::Char('9')),
-        VirtualKeyCode::NumpadAdd => Some(Key::Char('+')),
-        VirtualKeyCode::NumpadDivide => Some(Key::Char('/')),
-        VirtualKeyCode::NumpadDecimal => Some(Key::Char('.')),
-        VirtualKeyCode::NumpadComma => Some(Key::Char(',')),
-        VirtualKeyCode::NumpadEnter => Some(Key::Enter),
-        VirtualKeyCode::NumpadEquals => Some(Key::Char('=')),
-        VirtualKeyCode::NumpadMultiply => Some(Key::Char('*')),
-        VirtualKeyCode::NumpadSubtract => Some(Key::Char('-')),
-        VirtualKeyCode::Apostrophe => Some(Key::Char('\'')),
-        VirtualKeyCode::Asterisk => Some(Key::Char('*')),
-        VirtualKeyCode::At => Some(Key::Char('@')),
-        VirtualKeyCode::Backslash => Some(Key::Char('\\')),
-        VirtualKeyCode::Colon => Some(Key::Char(':')),
-        VirtualKeyCode::Comma => Some(Key::Char(',')),
-        VirtualKeyCode::Equals => Some(Key::Char('=')),
-        VirtualKeyCode::Grave => Some(Key::Char('`')),
-        VirtualKeyCode::LAlt => Some(Key::LeftAlt),
-        VirtualKeyCode::LBracket => Some(Key::Char('[')),
-        VirtualKeyCode::LControl => Some(Key::LeftCtrl),
-        VirtualKeyCode::LShift => Some(Key::LeftShift),
-        VirtualKeyCode::LWin => Some(Key::LeftSuper),
-        VirtualKeyCode::Minus => Some(Key::Char('-')),
-        VirtualKeyCode::Period => Some(Key::Char('.')),
-        VirtualKeyCode::Plus => Some(Key::Char('+')),
-        VirtualKeyCode::RAlt => Some(Key::RightAlt),
-        VirtualKeyCode::RBracket => Some(Key::Char(']')),
-        VirtualKeyCode::RControl => Some(Key::RightCtrl),
-        VirtualKeyCode::RShift => Some(Key::RightShift),
-        VirtualKeyCode::RWin => Some(Key::RightSuper),
-        VirtualKeyCode::Semicolon => Some(Key::Char(';')),
-        VirtualKeyCode::Slash => Some(Key::Char('/')),
-        VirtualKeyCode::Tab => Some(Key::Tab),
-        _ => None,
+/// How long blinking text spends visible, then hidden, for a full blink about once a second.
+const BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// For a blink clock started at `epoch`, whether blinking text is visible at `now`, and when that next changes.
+///
+/// For [`GuiRenderer`]s to use to implement blinking, so they all do it the same way.
+pub(crate) fn blink_phase(epoch: Instant, now: Instant) -> (bool, Instant) {
+    let elapsed = now.saturating_duration_since(epoch).as_millis();
+    let interval = BLINK_INTERVAL.as_millis();
+    let phase = elapsed / interval;
+    let next = epoch + BLINK_INTERVAL * (phase + 1) as u32;
+    (phase % 2 == 0, next)
+}
+
+/// Which [`Key`] each winit [`VirtualKeyCode`] is, for [`key4input`].
+///
+/// Each key code should only be in here once. Several key codes can be the same `Key`, e.g. the numpad digits are
+/// the same as the normal ones, but those need to be listed in the test to make sure it's on purpose.
+const VKC_KEYS: &[(VirtualKeyCode, Key)] = &[
+    (VirtualKeyCode::Key1, Key::Char('1')),
+    (VirtualKeyCode::Key2, Key::Char('2')),
+    (VirtualKeyCode::Key3, Key::Char('3')),
+    (VirtualKeyCode::Key4, Key::Char('4')),
+    (VirtualKeyCode::Key5, Key::Char('5')),
+    (VirtualKeyCode::Key6, Key::Char('6')),
+    (VirtualKeyCode::Key7, Key::Char('7')),
+    (VirtualKeyCode::Key8, Key::Char('8')),
+    (VirtualKeyCode::Key9, Key::Char('9')),
+    (VirtualKeyCode::Key0, Key::Char('0')),
+    (VirtualKeyCode::A, Key::Char('a')),
+    (VirtualKeyCode::B, Key::Char('b')),
+    (VirtualKeyCode::C, Key::Char('c')),
+    (VirtualKeyCode::D, Key::Char('d')),
+    (VirtualKeyCode::E, Key::Char('e')),
+    (VirtualKeyCode::F, Key::Char('f')),
+    (VirtualKeyCode::G, Key::Char('g')),
+    (VirtualKeyCode::H, Key::Char('h')),
+    (VirtualKeyCode::I, Key::Char('i')),
+    (VirtualKeyCode::J, Key::Char('j')),
+    (VirtualKeyCode::K, Key::Char('k')),
+    (VirtualKeyCode::L, Key::Char('l')),
+    (VirtualKeyCode::M, Key::Char('m')),
+    (VirtualKeyCode::N, Key::Char('n')),
+    (VirtualKeyCode::O, Key::Char('o')),
+    (VirtualKeyCode::P, Key::Char('p')),
+    (VirtualKeyCode::Q, Key::Char('q')),
+    (VirtualKeyCode::R, Key::Char('r')),
+    (VirtualKeyCode::S, Key::Char('s')),
+    (VirtualKeyCode::T, Key::Char('t')),
+    (VirtualKeyCode::U, Key::Char('u')),
+    (VirtualKeyCode::V, Key::Char('v')),
+    (VirtualKeyCode::W, Key::Char('w')),
+    (VirtualKeyCode::X, Key::Char('x')),
+    (VirtualKeyCode::Y, Key::Char('y')),
+    (VirtualKeyCode::Z, Key::Char('z')),
+    (VirtualKeyCode::Escape, Key::Escape),
+    (VirtualKeyCode::F1, Key::F(1)),
+    (VirtualKeyCode::F2, Key::F(2)),
+    (VirtualKeyCode::F3, Key::F(3)),
+    (VirtualKeyCode::F4, Key::F(4)),
+    (VirtualKeyCode::F5, Key::F(5)),
+    (VirtualKeyCode::F6, Key::F(6)),
+    (VirtualKeyCode::F7, Key::F(7)),
+    (VirtualKeyCode::F8, Key::F(8)),
+    (VirtualKeyCode::F9, Key::F(9)),
+    (VirtualKeyCode::F10, Key::F(10)),
+    (VirtualKeyCode::F11, Key::F(11)),
+    (VirtualKeyCode::F12, Key::F(12)),
+    (VirtualKeyCode::F13, Key::F(13)),
+    (VirtualKeyCode::F14, Key::F(14)),
+    (VirtualKeyCode::F15, Key::F(15)),
+    (VirtualKeyCode::F16, Key::F(16)),
+    (VirtualKeyCode::F17, Key::F(17)),
+    (VirtualKeyCode::F18, Key::F(18)),
+    (VirtualKeyCode::F19, Key::F(19)),
+    (VirtualKeyCode::F20, Key::F(20)),
+    (VirtualKeyCode::F21, Key::F(21)),
+    (VirtualKeyCode::F22, Key::F(22)),
+    (VirtualKeyCode::F23, Key::F(23)),
+    (VirtualKeyCode::F24, Key::F(24)),
+    (VirtualKeyCode::Insert, Key::Insert),
+    (VirtualKeyCode::Home, Key::Home),
+    (VirtualKeyCode::Delete, Key::Delete),
+    (VirtualKeyCode::End, Key::End),
+    (VirtualKeyCode::PageDown, Key::PageDown),
+    (VirtualKeyCode::PageUp, Key::PageUp),
+    (VirtualKeyCode::Left, Key::Left),
+    (VirtualKeyCode::Up, Key::Up),
+    (VirtualKeyCode::Right, Key::Right),
+    (VirtualKeyCode::Down, Key::Down),
+    (VirtualKeyCode::Back, Key::Backspace),
+    (VirtualKeyCode::Return, Key::Enter),
+    (VirtualKeyCode::Space, Key::Char(' ')),
+    (VirtualKeyCode::Caret, Key::Char('^')),
+    (VirtualKeyCode::Numpad0, Key::Char('0')),
+    (VirtualKeyCode::Numpad1, Key::Char('1')),
+    (VirtualKeyCode::Numpad2, Key::Char('2')),
+    (VirtualKeyCode::Numpad3, Key::Char('3')),
+    (VirtualKeyCode::Numpad4, Key::Char('4')),
+    (VirtualKeyCode::Numpad5, Key::Char('5')),
+    (VirtualKeyCode::Numpad6, Key::Char('6')),
+    (VirtualKeyCode::Numpad7, Key::Char('7')),
+    (VirtualKeyCode::Numpad8, Key::Char('8')),
+    (VirtualKeyCode::Numpad9, Key::Char('9')),
+    (VirtualKeyCode::NumpadAdd, Key::Char('+')),
+    (VirtualKeyCode::NumpadDivide, Key::Char('/')),
+    (VirtualKeyCode::NumpadDecimal, Key::Char('.')),
+    (VirtualKeyCode::NumpadComma, Key::Char(',')),
+    (VirtualKeyCode::NumpadEnter, Key::Enter),
+    (VirtualKeyCode::NumpadEquals, Key::Char('=')),
+    (VirtualKeyCode::NumpadMultiply, Key::Char('*')),
+    (VirtualKeyCode::NumpadSubtract, Key::Char('-')),
+    (VirtualKeyCode::Apostrophe, Key::Char('\'')),
+    (VirtualKeyCode::Asterisk, Key::Char('*')),
+    (VirtualKeyCode::At, Key::Char('@')),
+    (VirtualKeyCode::Backslash, Key::Char('\\')),
+    (VirtualKeyCode::Colon, Key::Char(':')),
+    (VirtualKeyCode::Comma, Key::Char(',')),
+    (VirtualKeyCode::Equals, Key::Char('=')),
+    (VirtualKeyCode::Grave, Key::Char('`')),
+    (VirtualKeyCode::LAlt, Key::LeftAlt),
+    (VirtualKeyCode::LBracket, Key::Char('[')),
+    (VirtualKeyCode::LControl, Key::LeftCtrl),
+    (VirtualKeyCode::LShift, Key::LeftShift),
+    (VirtualKeyCode::LWin, Key::LeftSuper),
+    (VirtualKeyCode::Minus, Key::Char('-')),
+    (VirtualKeyCode::Period, Key::Char('.')),
+    (VirtualKeyCode::Plus, Key::Char('+')),
+    (VirtualKeyCode::RAlt, Key::RightAlt),
+    (VirtualKeyCode::RBracket, Key::Char(']')),
+    (VirtualKeyCode::RControl, Key::RightCtrl),
+    (VirtualKeyCode::RShift, Key::RightShift),
+    (VirtualKeyCode::RWin, Key::RightSuper),
+    (VirtualKeyCode::Semicolon, Key::Char(';')),
+    (VirtualKeyCode::Slash, Key::Char('/')),
+    (VirtualKeyCode::Tab, Key::Tab),
+    (VirtualKeyCode::Capital, Key::CapsLock),
+    (VirtualKeyCode::Snapshot, Key::PrintScreen),
+    (VirtualKeyCode::Scroll, Key::ScrollLock),
+    (VirtualKeyCode::Pause, Key::Pause),
+    (VirtualKeyCode::Apps, Key::Menu),
+    (VirtualKeyCode::Numlock, Key::NumLock),
+];
+
+/// Convert a winit keyboard input to a Redshell [`Key`].
+///
+/// Keys which don't have a `Key` of their own are [`Key::Unknown`], with the raw scancode.
+fn key4input(input: &KeyboardInput) -> Key {
+    input
+        .virtual_keycode
+        .and_then(|vkc| VKC_KEYS.iter().find(|(k, _)| *k == vkc))
+        .map_or(Key::Unknown(input.scancode), |(_, key)| *key)
+}
+
+/// Which keys are currently held down, to tell repeated key presses from new ones, since winit doesn't.
+#[derive(Default)]
+struct HeldKeys(Vec<Key>);
+
+impl HeldKeys {
+    /// A key was pressed; returns whether it's a repeat, i.e. it was already held down.
+    fn press(&mut self, key: Key) -> bool {
+        let repeat = self.0.contains(&key);
+        if !repeat {
+            self.0.push(key);
+        }
+        repeat
+    }
+
+    /// A key was released.
+    fn release(&mut self, key: Key) {
+        self.0.retain(|k| *k != key);
+    }
+
+    /// Forget every key, e.g. because the window lost focus and won't hear about them being released.
+    fn clear(&mut self) {
+        self.0.clear();
     }
 }
 
@@ -175,26 +234,81 @@ fn mb4button(button: winit::event::MouseButton) -> Option<MouseButton> {
     }
 }
 
-/// Convert pxiel position in the window to logical position in the char array
-fn char4pixel_pos(pos: XY, char_size: XY, win_size: XY) -> XY {
-    // buffer around the edges
-    let buf = (win_size % char_size) / 2;
-    let pos = pos.clamp(buf, win_size - char_size + buf);
+/// Add some scrolling, in lines, to the leftover from previous scrolls, and take out as many whole notches as that
+/// adds up to. Positive is up, matching winit.
+///
+/// Touchpads scroll by fractions of a line at a time, which need to add up rather than being rounded away.
+fn scroll_notches(acc: &mut f32, lines: f32) -> i32 {
+    *acc += lines;
+    let notches = acc.trunc();
+    *acc -= notches;
+    notches as i32
+}
+
+/// The empty space around the edges of a window, left over because it isn't an exact multiple of the character size.
+///
+/// The character grid is centered in the window, so this is the offset of the top-left of the grid.
+fn edge_buffer(char_size: XY, win_size: XY) -> XY {
+    (win_size % char_size) / 2
+}
+
+/// Convert a pixel position in the window to the position of the character cell it's in.
+///
+/// Positions in the empty space around the edges are clamped to the nearest cell. This is the inverse of
+/// [`cell_to_pixel_rect`], so you can use the two to line up your own drawing with the character grid.
+pub fn char4pixel_pos(pos: XY, char_size: XY, win_size: XY) -> XY {
+    let buf = edge_buffer(char_size, win_size);
+    // in a window smaller than a character (e.g. mid-resize), everything is in the first row or column
+    let last = XY(
+        win_size.x().saturating_sub(char_size.x()),
+        win_size.y().saturating_sub(char_size.y()),
+    );
+    let pos = pos.clamp(buf, last + buf);
     (pos - buf) / char_size
 }
 
+/// Convert the position of a character cell to the rectangle of pixels it covers in a window, as the top-left corner
+/// and the size.
+///
+/// This is the inverse of [`char4pixel_pos`]: every pixel in the rectangle maps back to `cell`.
+pub fn cell_to_pixel_rect(cell: XY, char_size: XY, win_size: XY) -> (XY, XY) {
+    (
+        cell * char_size + edge_buffer(char_size, win_size),
+        char_size,
+    )
+}
+
+/// Collapse every run of consecutive [`Action::MouseMove`]s into just the last one.
+///
+/// Nothing else is dropped or reordered, so e.g. a press in the middle of a drag still happens between the moves
+/// before and after it.
+fn coalesce_moves(actions: &mut Vec<Action>) {
+    actions.dedup_by(|later, earlier| {
+        let both = matches!(
+            (&*later, &*earlier),
+            (Action::MouseMove { .. }, Action::MouseMove { .. })
+        );
+        if both {
+            core::mem::swap(later, earlier);
+        }
+        both
+    });
+}
+
 struct WindowSpawnOutput {
-    window: Window,
+    window: Arc<Window>,
     action_recv: mpsc::Receiver<Action>,
     kill_send: Arc<Once>,
+    char_size: Arc<Mutex<XY>>,
+    next_change: Arc<Mutex<Option<Instant>>>,
     runner: GuiRunner,
 }
 
-fn spawn_window(char_size: XY, win_size: XY) -> io::Result<WindowSpawnOutput> {
+fn spawn_window(char_size: XY, win_size: XY, title: &str) -> io::Result<WindowSpawnOutput> {
     let el = EventLoopBuilder::<Action>::with_user_event().build();
     let window = WindowBuilder::new()
         .with_inner_size(LogicalSize::new(win_size.x() as u32, win_size.y() as u32))
-        .with_title("redshell")
+        .with_title(title)
         .build(&el)
         .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
     let (act_send, action_recv) = mpsc::channel();
@@ -202,20 +316,33 @@ fn spawn_window(char_size: XY, win_size: XY) -> io::Result<WindowSpawnOutput> {
     let killer = Arc::new(Once::new());
     let kill_recv = killer.clone();
     let kill_send = killer.clone();
+    let grid_size = win_size / char_size;
+    let char_size = Arc::new(Mutex::new(char_size));
+    let next_change = Arc::new(Mutex::new(None));
     let runner = GuiRunner {
         el,
         rest: WrRest {
             act_send,
             kill_recv,
-            char_size,
+            char_size: char_size.clone(),
+            next_change: next_change.clone(),
             win_size,
+            grid_size,
             prev_pos: XY(0, 0),
+            scroll: 0.0,
+            mods: Modifiers::NONE,
+            held: HeldKeys::default(),
+            redraw: RedrawThrottle::new(None),
+            resize: ResizeDebounce::new(Some(ResizeDebounce::DEFAULT)),
+            repaint: None,
         },
     };
     Ok(WindowSpawnOutput {
-        window,
+        window: Arc::new(window),
         action_recv,
         kill_send,
+        char_size,
+        next_change,
         runner,
     })
 }
@@ -236,6 +363,19 @@ pub trait GuiRenderer: Send + Sync + Sized {
     /// any monospace font, that should also be the width of every other character.
     fn new(font_size: f32) -> io::Result<Self>;
 
+    /// Create a new backend with the given font size, using custom fonts instead of the defaults.
+    ///
+    /// `regular` and `bold` are the raw contents of TTF files, and must be monospace, with characters the same width
+    /// in both. Implementations should check that and return an error if they aren't. The default implementation,
+    /// for backends that don't support custom fonts, just returns an `Unsupported` error.
+    fn with_fonts(font_size: f32, regular: &[u8], bold: &[u8]) -> io::Result<Self> {
+        let _ = (font_size, regular, bold);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "this renderer doesn't support custom fonts",
+        ))
+    }
+
     /// Reset the renderer to use a new font size.
     ///
     /// The default implementation simply destroys the old renderer and replaces it in-place with a new one, but there
@@ -254,8 +394,53 @@ pub trait GuiRenderer: Send + Sync + Sized {
     /// it's in the OS's hands.
     fn render(&self, window: &Window, screen: &Screen) -> io::Result<()>;
 
+    /// Render a screen into a buffer of pixels, without needing a window, e.g. for screenshots.
+    ///
+    /// `size` is the size of the imaginary window in pixels. The pixels are returned in row-major order, each encoded
+    /// as `0x00RRGGBB`. If at all possible this should share its code with [`Self::render`], so the result matches
+    /// what's actually shown on screen. The default implementation just returns an `Unsupported` error.
+    fn render_to_buffer(&self, size: XY, screen: &Screen) -> io::Result<Vec<u32>> {
+        let _ = (size, screen);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "this renderer can't render offscreen",
+        ))
+    }
+
     /// Return the bounding box dimensions of the characters being used in the font being used.
     fn char_size(&self) -> XY;
+
+    /// When the rendered `screen` will next look different on its own, e.g. because blinking text turns on or off.
+    ///
+    /// The runner sends an [`Action::Redraw`] at that time, so the game draws again and the change actually shows up.
+    /// Renderers should keep track of time with their own clock, rather than relying on the game's. The default
+    /// implementation returns `None`, i.e. the rendering never changes on its own.
+    fn next_change(&self, screen: &Screen) -> Option<Instant> {
+        let _ = screen;
+        None
+    }
+
+    /// Set where to draw a text cursor, and how, or `None` to stop drawing one.
+    ///
+    /// The cursor should blink, the same way blinking text does, so [`Self::next_change`] should account for it. The
+    /// default implementation, for renderers that don't draw a cursor, ignores it.
+    fn set_cursor(&mut self, cursor: Option<(XY, CursorStyle)>) {
+        let _ = cursor;
+    }
+
+    /// Set the color to fill the window with where there aren't any cells, i.e. the margins around the grid when the
+    /// window isn't an exact multiple of the character size.
+    ///
+    /// Renderers should start with black. The default implementation, for renderers that can't change it, ignores it.
+    fn set_clear_color(&mut self, color: Color) {
+        let _ = color;
+    }
+}
+
+/// The renderer and the last screen it drew, shared between the [`GuiSystem`] and its [`GuiRunner`].
+struct Shown<B: GuiRenderer> {
+    backend: B,
+    screen: Option<Screen>,
 }
 
 /// Provides a winit-based GUI [`IoSystem`].
@@ -267,74 +452,392 @@ pub trait GuiRenderer: Send + Sync + Sized {
 /// - Input handling, i.e. converting `winit`'s events to [`Action`]s
 /// - Closing the window when `stop` is called
 /// - Calling the `GuiBackend` when appropriate
+///
+/// It keeps the last screen drawn, so when the window is resized or zoomed, the runner can immediately show it again,
+/// clipped or padded to the new size, rather than leaving the OS to stretch the old frame until the game draws a new
+/// one.
+///
+/// It doesn't have clipboard access, so it never sends [`Action::Paste`]: Ctrl+V (or Cmd+V) arrives as an ordinary
+/// keypress, and [`clipboard_get`](IoSystem::clipboard_get) always returns `None`.
 pub struct GuiSystem<B: GuiRenderer> {
-    window: Window,
+    window: Arc<Window>,
     inputs: mpsc::Receiver<Action>,
     kill_el: Arc<Once>,
-    backend: B,
+    /// shared with the runner, so it can map mouse positions to cells after zooming
+    char_size: Arc<Mutex<XY>>,
+    /// shared with the runner, so it knows when to send a redraw for animations like blinking
+    next_change: Arc<Mutex<Option<Instant>>>,
+    /// an input taken off the queue while coalescing mouse moves, to be returned next
+    held: Option<Action>,
+    cursor: Option<XY>,
+    cursor_style: CursorStyle,
+    clear_color: Color,
+    /// shared with the runner, so it can repaint the last screen as soon as the window changes
+    shown: Arc<Mutex<Shown<B>>>,
 }
 
-impl<B: GuiRenderer> GuiSystem<B> {
+impl<B: GuiRenderer + 'static> GuiSystem<B> {
+    /// The window title used by [`Self::new`].
+    pub const DEFAULT_TITLE: &'static str = "tuig";
+    /// The size of the window, in characters, used by [`Self::new`].
+    pub const DEFAULT_SIZE: XY = XY(80, 25);
+
     /// Create a new GuiSystem with its chosen GuiRenderer.
+    ///
+    /// The window starts with the title [`Self::DEFAULT_TITLE`]; use [`Self::with_title`] to pick your own.
     pub fn new(font_size: f32) -> crate::Result<(Self, GuiRunner)> {
-        let backend = B::new(font_size)?;
+        Self::with_title(font_size, Self::DEFAULT_TITLE)
+    }
+
+    /// Create a new GuiSystem with its chosen GuiRenderer, and a window with the given title.
+    pub fn with_title(font_size: f32, title: &str) -> crate::Result<(Self, GuiRunner)> {
+        Self::with_backend(B::new(font_size)?, title)
+    }
+
+    /// Create a new GuiSystem with its chosen GuiRenderer, and a window big enough for a grid of `cells` characters.
+    ///
+    /// The OS might not give you a window that big, e.g. if it doesn't fit on the monitor, so you should still check
+    /// [`IoSystem::size`] rather than assuming you got what you asked for.
+    pub fn with_size(font_size: f32, cells: XY) -> crate::Result<(Self, GuiRunner)> {
+        Self::spawn(B::new(font_size)?, Self::DEFAULT_TITLE, cells)
+    }
+
+    /// Create a new GuiSystem with its chosen GuiRenderer, using custom fonts instead of the defaults.
+    ///
+    /// See [`GuiRenderer::with_fonts`] for what's expected of the fonts.
+    pub fn with_fonts(
+        font_size: f32,
+        regular: &[u8],
+        bold: &[u8],
+    ) -> crate::Result<(Self, GuiRunner)> {
+        Self::with_backend(
+            B::with_fonts(font_size, regular, bold)?,
+            Self::DEFAULT_TITLE,
+        )
+    }
+
+    /// Create a new GuiSystem around an already-constructed GuiRenderer, and a window with the given title.
+    ///
+    /// This is useful if the renderer has its own extra configuration, e.g.
+    /// [`SoftbufferBackend::with_fallbacks`](crate::im::SoftbufferBackend::with_fallbacks).
+    pub fn with_backend(backend: B, title: &str) -> crate::Result<(Self, GuiRunner)> {
+        Self::spawn(backend, title, Self::DEFAULT_SIZE)
+    }
+
+    fn spawn(backend: B, title: &str, cells: XY) -> crate::Result<(Self, GuiRunner)> {
         let char_size = backend.char_size();
-        let win_size = char_size * XY(80, 25);
+        let win_size = char_size * cells;
         let WindowSpawnOutput {
             window,
             action_recv: inputs,
             kill_send,
-            runner,
-        } = spawn_window(char_size, win_size)?;
+            char_size,
+            next_change,
+            mut runner,
+        } = spawn_window(char_size, win_size, title)?;
+        let shown = Arc::new(Mutex::new(Shown {
+            backend,
+            screen: None,
+        }));
+        runner.rest.repaint = Some(Box::new({
+            let (window, shown) = (window.clone(), shown.clone());
+            move || {
+                let shown = shown.lock().unwrap();
+                if let Some(screen) = &shown.screen {
+                    // if this fails, the game's next draw will run into the same problem and report it
+                    let _ = shown.backend.render(&window, screen);
+                }
+            }
+        }));
         Ok((
             Self {
                 window,
                 inputs,
                 kill_el: kill_send,
-                backend,
+                char_size,
+                next_change,
+                held: None,
+                cursor: None,
+                cursor_style: CursorStyle::default(),
+                clear_color: Color::Black,
+                shown,
             },
             runner,
         ))
     }
 }
 
-impl<B: GuiRenderer> IoSystem for GuiSystem<B> {
+impl<B: GuiRenderer> GuiSystem<B> {
+    /// Stop the window from being resized smaller than a grid of `cells` characters, e.g. so the game stays playable.
+    ///
+    /// This is only a hint to the OS, which might ignore it, and it's in terms of the current font size, so call it
+    /// again after [zooming](IoSystem::zoom). `None` removes the limit.
+    pub fn set_min_size(&mut self, cells: Option<XY>) {
+        let char_size = self.shown().backend.char_size();
+        let size = cells.map(|cells| {
+            let px = cells * char_size;
+            LogicalSize::new(px.x() as u32, px.y() as u32)
+        });
+        self.window.set_min_inner_size(size);
+    }
+
+    /// Set the color of the window's background where there aren't any cells, e.g. to match the game's palette when
+    /// the window isn't an exact multiple of the character size. It starts out black.
+    ///
+    /// Not every [`GuiRenderer`] supports this; the ones that don't just ignore it.
+    pub fn set_clear_color(&mut self, color: Color) {
+        self.clear_color = color;
+        self.shown().backend.set_clear_color(color);
+        self.window.request_redraw();
+    }
+
+    fn shown(&self) -> MutexGuard<'_, Shown<B>> {
+        self.shown.lock().unwrap()
+    }
+
+    /// Tell the renderer where the cursor is, e.g. after it's been changed or the renderer's been renewed.
+    fn update_cursor(&mut self) {
+        let cursor = self.cursor.map(|pos| (pos, self.cursor_style));
+        self.shown().backend.set_cursor(cursor);
+    }
+
+    /// If `latest` is a mouse move, skip ahead to the last of any mouse moves queued right after it; see
+    /// [`coalesce_moves`].
+    fn latest_move(&mut self, mut latest: Action) -> Action {
+        if !matches!(latest, Action::MouseMove { .. }) {
+            return latest;
+        }
+        while let Ok(next) = self.inputs.try_recv() {
+            if matches!(next, Action::MouseMove { .. }) {
+                latest = next;
+            } else {
+                self.held = Some(next);
+                break;
+            }
+        }
+        latest
+    }
+
+    /// Render a screen exactly the way it would be shown in the window, and save it as a PNG file.
+    ///
+    /// The image is exactly big enough to fit the screen, regardless of the window's actual size.
+    pub fn screenshot(&self, screen: &Screen, path: &Path) -> io::Result<()> {
+        let shown = self.shown();
+        let size = screen.size() * shown.backend.char_size();
+        let pixels = shown.backend.render_to_buffer(size, screen)?;
+        write_png(size, &pixels, BufWriter::new(File::create(path)?))
+    }
+}
+
+/// Write a buffer of `0x00RRGGBB` pixels as an RGB PNG.
+fn write_png(size: XY, pixels: &[u32], out: impl io::Write) -> io::Result<()> {
+    fn ioe4png(e: png::EncodingError) -> io::Error {
+        match e {
+            png::EncodingError::IoError(e) => e,
+            other => io::Error::new(io::ErrorKind::Other, other),
+        }
+    }
+    let mut enc = png::Encoder::new(out, size.x() as u32, size.y() as u32);
+    enc.set_color(png::ColorType::Rgb);
+    enc.set_depth(png::BitDepth::Eight);
+    let mut writer = enc.write_header().map_err(ioe4png)?;
+    let data: Vec<u8> = pixels
+        .iter()
+        .flat_map(|px| [(px >> 16) as u8, (px >> 8) as u8, *px as u8])
+        .collect();
+    writer.write_image_data(&data).map_err(ioe4png)
+}
+
+impl<B: GuiRenderer + 'static> IoSystem for GuiSystem<B> {
     fn draw(&mut self, screen: &Screen) -> crate::Result<()> {
-        self.backend.render(&self.window, screen)?;
+        let mut shown = self.shown();
+        shown.backend.render(&self.window, screen)?;
+        *self.next_change.lock().unwrap() = shown.backend.next_change(screen);
+        match &mut shown.screen {
+            Some(last) => last.clone_from(screen),
+            none => *none = Some(screen.clone()),
+        }
         Ok(())
     }
 
     fn size(&self) -> XY {
         let raw_sz = self.window.inner_size();
-        let char_sz = self.backend.char_size();
+        let char_sz = self.shown().backend.char_size();
         let width = raw_sz.width as usize / char_sz.x();
         let height = raw_sz.height as usize / char_sz.y();
         XY(width, height)
     }
 
     fn input(&mut self) -> crate::Result<Action> {
-        self.inputs.recv().map_err(|_| {
+        if let Some(held) = self.held.take() {
+            return Ok(self.latest_move(held));
+        }
+        let res = self.inputs.recv().map_err(|_| {
             io::Error::new(
                 io::ErrorKind::BrokenPipe,
                 "input loop has terminated unexpectedly",
             )
-            .into()
-        })
+        })?;
+        Ok(self.latest_move(res))
     }
 
     fn poll_input(&mut self) -> crate::Result<Option<Action>> {
+        if let Some(held) = self.held.take() {
+            return Ok(Some(self.latest_move(held)));
+        }
         match self.inputs.try_recv() {
-            Ok(res) => Ok(Some(res)),
+            Ok(res) => Ok(Some(self.latest_move(res))),
             Err(TryRecvError::Disconnected) => panic!("unexpected queue closure"),
             Err(TryRecvError::Empty) => Ok(None),
         }
     }
 
+    fn input_until(&mut self, timeout: Duration) -> crate::Result<Option<Action>> {
+        if let Some(held) = self.held.take() {
+            return Ok(Some(self.latest_move(held)));
+        }
+        match self.inputs.recv_timeout(timeout) {
+            Ok(res) => Ok(Some(self.latest_move(res))),
+            Err(RecvTimeoutError::Disconnected) => panic!("unexpected queue closure"),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+        }
+    }
+
+    fn drain_input(&mut self) -> crate::Result<Vec<Action>> {
+        let mut res: Vec<_> = self.held.take().into_iter().collect();
+        res.extend(self.inputs.try_iter());
+        coalesce_moves(&mut res);
+        Ok(res)
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        // there's nowhere to open links from yet
+        Capabilities::FULL.links(false)
+    }
+
+    fn set_title(&mut self, title: &str) {
+        self.window.set_title(title)
+    }
+
+    fn set_cursor(&mut self, pos: Option<XY>) {
+        self.cursor = pos;
+        self.update_cursor();
+    }
+
+    fn set_cursor_style(&mut self, style: CursorStyle) {
+        self.cursor_style = style;
+        self.update_cursor();
+    }
+
+    fn zoom(&mut self, font_size: f32) -> crate::Result<()> {
+        let char_size = {
+            let mut shown = self.shown();
+            shown.backend.renew(font_size)?;
+            shown.backend.set_clear_color(self.clear_color);
+            shown.backend.char_size()
+        };
+        self.update_cursor();
+        *self.char_size.lock().unwrap() = char_size;
+        // the runner repaints the last screen at the new size when it gets this
+        self.window.request_redraw();
+        Ok(())
+    }
+
     fn stop(&mut self) {
         self.kill_el.call_once(|| {})
     }
 }
 
+/// Coalesces redraw requests so at most one is sent per frame interval.
+struct RedrawThrottle {
+    /// The minimum time between redraws, or `None` for no limit
+    interval: Option<Duration>,
+    /// When the last redraw was sent
+    last: Option<Instant>,
+    /// Whether a redraw was requested but hasn't been sent yet
+    pending: bool,
+}
+
+impl RedrawThrottle {
+    fn new(interval: Option<Duration>) -> Self {
+        Self {
+            interval,
+            last: None,
+            pending: false,
+        }
+    }
+
+    /// A redraw was requested; returns whether it should be sent now. If not, it'll be sent by a later `poll`.
+    fn request(&mut self, now: Instant) -> bool {
+        self.pending = true;
+        self.poll(now)
+    }
+
+    /// Returns whether a pending redraw is due to be sent now.
+    fn poll(&mut self, now: Instant) -> bool {
+        if !self.pending {
+            return false;
+        }
+        match self.deadline() {
+            Some(deadline) if now < deadline => false,
+            _ => {
+                self.pending = false;
+                self.last = Some(now);
+                true
+            }
+        }
+    }
+
+    /// When the next redraw is allowed to happen, if there's a limit and it's not allowed yet.
+    fn deadline(&self) -> Option<Instant> {
+        Some(self.last? + self.interval?)
+    }
+}
+
+/// Holds off on telling the game the window's been resized until it stops changing size for a moment.
+struct ResizeDebounce {
+    /// How long the size has to stay the same, or `None` to report every resize right away
+    interval: Option<Duration>,
+    /// When the window will have settled, if it's been resized since the last report
+    settle_at: Option<Instant>,
+}
+
+impl ResizeDebounce {
+    /// How long the size has to stay the same unless [`GuiRunner::set_resize_debounce`] says otherwise: short enough
+    /// that the pause isn't noticeable, but long enough to catch most of the sizes a window goes through while it's
+    /// being dragged.
+    const DEFAULT: Duration = Duration::from_millis(50);
+
+    fn new(interval: Option<Duration>) -> Self {
+        Self {
+            interval,
+            settle_at: None,
+        }
+    }
+
+    /// The window was resized; returns whether to report it now. If not, it'll be reported by a later `poll`.
+    fn resized(&mut self, now: Instant) -> bool {
+        match self.interval {
+            Some(interval) => {
+                self.settle_at = Some(now + interval);
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Returns whether the window has settled since it was last resized, so it's time to report the new size.
+    fn poll(&mut self, now: Instant) -> bool {
+        match self.settle_at {
+            Some(at) if at <= now => {
+                self.settle_at = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 /// Everything in a `WindowRunner` except the winit `EventLoop`.
 ///
 /// This struct is a little bit of a hack. We want `run_return_cb` to be its own function, so that `IoRunner::step`
@@ -345,27 +848,82 @@ impl<B: GuiRenderer> IoSystem for GuiSystem<B> {
 struct WrRest {
     act_send: mpsc::Sender<Action>,
     kill_recv: Arc<Once>,
-    char_size: XY,
+    char_size: Arc<Mutex<XY>>,
+    next_change: Arc<Mutex<Option<Instant>>>,
     win_size: XY,
+    /// the size of the window in characters, as of the last `Action::Resized`
+    grid_size: XY,
     prev_pos: XY,
+    /// how far the wheel has scrolled, in lines, that hasn't added up to a whole notch yet
+    scroll: f32,
+    mods: Modifiers,
+    held: HeldKeys,
+    redraw: RedrawThrottle,
+    resize: ResizeDebounce,
+    /// immediately show the last screen drawn again, to fit the window's new size
+    repaint: Option<Box<dyn Fn()>>,
 }
 
 impl WrRest {
     const CONTINUE_CODE: i32 = 0;
     const STOP_CODE: i32 = 0;
 
-    fn run_return_cb(&mut self, stepping: bool, ev: Event<'_, Action>, cf: &mut ControlFlow) {
-        if self.kill_recv.is_completed() {
+    /// Check whether the size of the window in characters has changed, and if so, what to.
+    ///
+    /// It can change without the window changing size, e.g. when zooming.
+    fn grid_change(&mut self) -> Option<XY> {
+        let grid_size = self.win_size / *self.char_size.lock().unwrap();
+        if grid_size == self.grid_size {
+            return None;
+        }
+        self.grid_size = grid_size;
+        Some(grid_size)
+    }
+
+    /// What to tell the game about the window's current size: `Resized` if the grid changed, then `Redraw` unless
+    /// it's being throttled.
+    fn settled(&mut self, now: Instant) -> [Option<Action>; 2] {
+        let resized = self.grid_change().map(|size| Action::Resized { size });
+        let redraw = self.redraw.request(now).then_some(Action::Redraw);
+        [resized, redraw]
+    }
+
+    /// Show the last screen again, if there is one, until the game draws a new one.
+    fn repaint(&self) {
+        if let Some(repaint) = &self.repaint {
+            repaint();
+        }
+    }
+
+    /// Handle one event from the event loop, first exiting if the paired system was stopped or `until` is set.
+    fn run_return_cb(
+        &mut self,
+        stepping: bool,
+        until: Option<&AtomicBool>,
+        ev: Event<'_, Action>,
+        cf: &mut ControlFlow,
+    ) {
+        if self.kill_recv.is_completed() || until.map_or(false, |u| u.load(Ordering::Relaxed)) {
             cf.set_exit_with_code(Self::STOP_CODE);
             return;
         }
 
+        let now = Instant::now();
+        let next_change = *self.next_change.lock().unwrap();
         if stepping {
             // exit immediately afterwards with CONTINUE_CODE
             cf.set_exit_with_code(Self::CONTINUE_CODE)
         } else {
-            // ensure that we check at least once a second to see if we should quit
-            cf.set_wait_until(Instant::now() + Duration::from_secs(1));
+            // ensure that we check at least once a second to see if we should quit, and wake up in time to send any
+            // throttled redraw
+            let mut wake = now + Duration::from_secs(1);
+            if self.redraw.pending {
+                wake = wake.min(self.redraw.deadline().unwrap_or(now));
+            }
+            for at in next_change.into_iter().chain(self.resize.settle_at) {
+                wake = wake.min(at);
+            }
+            cf.set_wait_until(wake);
         }
 
         macro_rules! send {
@@ -378,6 +936,19 @@ impl WrRest {
                 )*
             } };
         }
+        if next_change.map_or(false, |at| at <= now) {
+            // the game will redraw, which will update this anyway, but clear it so we don't keep sending redraws
+            *self.next_change.lock().unwrap() = None;
+            self.redraw.pending = true;
+        }
+        if self.redraw.poll(now) {
+            send!(Action::Redraw);
+        }
+        if self.resize.poll(now) {
+            for act in self.settled(now).into_iter().flatten() {
+                send!(act);
+            }
+        }
         match ev {
             Event::UserEvent(a) => send!(a),
             Event::WindowEvent {
@@ -385,9 +956,22 @@ impl WrRest {
                 ..
             } => {
                 self.win_size = XY(sz.width as usize, sz.height as usize);
-                send!(Action::Redraw);
+                self.repaint();
+                if self.resize.resized(now) {
+                    for act in self.settled(now).into_iter().flatten() {
+                        send!(act);
+                    }
+                }
+            }
+            Event::RedrawRequested(_) => {
+                self.repaint();
+                // while the window's still being resized, the repaint is all it gets
+                if self.resize.settle_at.is_none() {
+                    for act in self.settled(now).into_iter().flatten() {
+                        send!(act);
+                    }
+                }
             }
-            Event::RedrawRequested(_) => send!(Action::Redraw),
             Event::WindowEvent {
                 event: WindowEvent::CloseRequested | WindowEvent::Destroyed,
                 ..
@@ -399,19 +983,37 @@ impl WrRest {
                 event: WindowEvent::KeyboardInput { input, .. },
                 ..
             } => {
-                if let Some(key) = key4vkc(input.virtual_keycode) {
-                    match input.state {
-                        ElementState::Pressed => send!(Action::KeyPress { key }),
-                        ElementState::Released => send!(Action::KeyRelease { key }),
+                let key = key4input(&input);
+                let mods = self.mods;
+                match input.state {
+                    ElementState::Pressed => {
+                        let repeat = self.held.press(key);
+                        send!(Action::KeyPress { key, mods, repeat })
+                    }
+                    ElementState::Released => {
+                        self.held.release(key);
+                        send!(Action::KeyRelease { key, mods })
                     }
                 }
             }
+            Event::WindowEvent {
+                event: WindowEvent::ModifiersChanged(state),
+                ..
+            } => {
+                self.mods = Modifiers {
+                    ctrl: state.ctrl(),
+                    alt: state.alt(),
+                    shift: state.shift(),
+                    super_: state.logo(),
+                };
+            }
             Event::WindowEvent {
                 event: WindowEvent::CursorMoved { position, .. },
                 ..
             } => {
                 let pos = XY(position.x as usize, position.y as usize);
-                let pos = char4pixel_pos(pos, self.char_size, self.win_size);
+                let char_size = *self.char_size.lock().unwrap();
+                let pos = char4pixel_pos(pos, char_size, self.win_size);
                 if self.prev_pos != pos {
                     self.prev_pos = pos;
                     send!(Action::MouseMove { pos });
@@ -434,6 +1036,44 @@ impl WrRest {
                     }
                 }
             }
+            Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => y,
+                    MouseScrollDelta::PixelDelta(pos) => {
+                        pos.y as f32 / self.char_size.lock().unwrap().y().max(1) as f32
+                    }
+                };
+                let notches = scroll_notches(&mut self.scroll, lines);
+                let button = match notches > 0 {
+                    true => MouseButton::ScrollUp,
+                    false => MouseButton::ScrollDown,
+                };
+                for _ in 0..notches.unsigned_abs() {
+                    let pos = self.prev_pos;
+                    send!(Action::MousePress {
+                        pos,
+                        button: button.clone()
+                    });
+                    send!(Action::MouseRelease {
+                        pos,
+                        button: button.clone()
+                    });
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Focused(true),
+                ..
+            } => send!(Action::FocusGained),
+            Event::WindowEvent {
+                event: WindowEvent::Focused(false),
+                ..
+            } => {
+                self.held.clear();
+                send!(Action::FocusLost)
+            }
             Event::Suspended => send!(Action::Paused),
             Event::Resumed => send!(Action::Unpaused),
 
@@ -444,20 +1084,408 @@ impl WrRest {
 }
 
 /// Runner for a [`GuiSystem`].
+///
+/// Resizes are [debounced](Self::set_resize_debounce) by default, so [`Action::Resized`] only comes once the window's
+/// held its new size for a moment.
 pub struct GuiRunner {
     el: EventLoop<Action>,
     rest: WrRest,
 }
 
+impl GuiRunner {
+    /// Limit how often [`Action::Redraw`] is sent, to at most `fps` times per second.
+    ///
+    /// Redraw requests that come in too soon after the last one are coalesced and sent once the frame interval has
+    /// passed. Only redraws are affected; keyboard and mouse input is still sent immediately. `None`, the default,
+    /// means there's no limit.
+    pub fn set_max_fps(&mut self, fps: Option<u32>) {
+        let interval = fps.map(|fps| Duration::from_secs(1) / fps.max(1));
+        self.rest.redraw = RedrawThrottle::new(interval);
+    }
+
+    /// Wait until the window stops changing size for `interval` before sending [`Action::Resized`], and the
+    /// [`Action::Redraw`] that goes with it.
+    ///
+    /// Dragging the edge of a window resizes it many times a second, and re-laying out the whole game for each of
+    /// those sizes can make it stutter. With this set, only the final size is sent. In the mean time, the last screen
+    /// drawn is shown again as the window changes, so it's never left blank.
+    ///
+    /// This is on by default, with a 50ms interval, so this is only needed to tune it. `None` sends every resize
+    /// right away.
+    pub fn set_resize_debounce(&mut self, interval: Option<Duration>) {
+        self.rest.resize = ResizeDebounce::new(interval);
+    }
+}
+
 impl IoRunner for GuiRunner {
     fn step(&mut self) -> bool {
         self.el
-            .run_return(|ev, _, cf| self.rest.run_return_cb(true, ev, cf))
+            .run_return(|ev, _, cf| self.rest.run_return_cb(true, None, ev, cf))
             == WrRest::STOP_CODE
     }
 
     fn run(&mut self) {
         self.el
-            .run_return(|ev, _, cf| self.rest.run_return_cb(false, ev, cf));
+            .run_return(|ev, _, cf| self.rest.run_return_cb(false, None, ev, cf));
+    }
+
+    fn run_until(&mut self, stop: &AtomicBool) {
+        self.el
+            .run_return(|ev, _, cf| self.rest.run_return_cb(false, Some(stop), ev, cf));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// A window's worth of state, 80x25 characters of 10x20 pixels each, without a real window behind it
+    fn test_rest(
+        act_send: mpsc::Sender<Action>,
+        resize: ResizeDebounce,
+        repaint: Option<Box<dyn Fn()>>,
+    ) -> WrRest {
+        WrRest {
+            act_send,
+            kill_recv: Arc::new(Once::new()),
+            char_size: Arc::new(Mutex::new(XY(10, 20))),
+            next_change: Arc::new(Mutex::new(None)),
+            win_size: XY(800, 500),
+            grid_size: XY(80, 25),
+            prev_pos: XY(0, 0),
+            scroll: 0.0,
+            mods: Modifiers::NONE,
+            held: HeldKeys::default(),
+            redraw: RedrawThrottle::new(None),
+            resize,
+            repaint,
+        }
+    }
+
+    #[test]
+    fn unthrottled_redraws_always_send() {
+        let mut rt = RedrawThrottle::new(None);
+        let now = Instant::now();
+        assert!(rt.request(now));
+        assert!(rt.request(now));
+        assert!(!rt.poll(now));
+        assert_eq!(rt.deadline(), None);
+    }
+
+    #[test]
+    fn throttled_redraws_coalesce() {
+        let mut rt = RedrawThrottle::new(Some(Duration::from_millis(10)));
+        let start = Instant::now();
+        assert!(rt.request(start));
+        // several requests within the interval only result in one pending redraw
+        assert!(!rt.request(start + Duration::from_millis(2)));
+        assert!(!rt.request(start + Duration::from_millis(5)));
+        assert_eq!(rt.deadline(), Some(start + Duration::from_millis(10)));
+        assert!(!rt.poll(start + Duration::from_millis(9)));
+        assert!(rt.poll(start + Duration::from_millis(10)));
+        // and once it's sent there's nothing left
+        assert!(!rt.poll(start + Duration::from_millis(30)));
+    }
+
+    #[test]
+    fn resizes_settle_after_quiet_period() {
+        let mut rd = ResizeDebounce::new(Some(Duration::from_millis(50)));
+        let start = Instant::now();
+        let at = |ms| start + Duration::from_millis(ms);
+        assert!(!rd.resized(at(0)));
+        assert!(!rd.resized(at(30)));
+        // each resize pushes the deadline back
+        assert!(!rd.poll(at(60)));
+        assert!(rd.poll(at(80)));
+        assert!(!rd.poll(at(200)));
+        let mut immediate = ResizeDebounce::new(None);
+        assert!(immediate.resized(at(0)));
+        assert!(!immediate.poll(at(0)));
+    }
+
+    #[test]
+    fn grid_changes_only_when_chars_do() {
+        let (act_send, _act_recv) = mpsc::channel();
+        let mut rest = test_rest(act_send, ResizeDebounce::new(None), None);
+        assert_eq!(rest.grid_change(), None);
+        // a few pixels doesn't make room for another character
+        rest.win_size = XY(805, 510);
+        assert_eq!(rest.grid_change(), None);
+        rest.win_size = XY(1000, 500);
+        assert_eq!(rest.grid_change(), Some(XY(100, 25)));
+        assert_eq!(rest.grid_change(), None);
+        // zooming changes the grid without resizing the window
+        *rest.char_size.lock().unwrap() = XY(20, 20);
+        assert_eq!(rest.grid_change(), Some(XY(50, 25)));
+    }
+
+    #[test]
+    fn resize_repaints_before_game_redraws() {
+        use std::{cell::RefCell, rc::Rc};
+        use winit::{dpi::PhysicalSize, window::WindowId};
+
+        let (act_send, act_recv) = mpsc::channel();
+        let log = Rc::new(RefCell::new(vec![]));
+        let mut rest = test_rest(
+            act_send,
+            ResizeDebounce::new(None),
+            Some(Box::new({
+                let log = log.clone();
+                move || log.borrow_mut().push("repaint")
+            })),
+        );
+        // SAFETY: it's only compared against, never used to look up a real window
+        let window_id = unsafe { WindowId::dummy() };
+        let mut cf = ControlFlow::Poll;
+        let resized = Event::WindowEvent {
+            window_id,
+            event: WindowEvent::Resized(PhysicalSize::new(1000, 500)),
+        };
+        rest.run_return_cb(true, None, resized, &mut cf);
+        log.borrow_mut()
+            .extend(act_recv.try_iter().map(|a| match a {
+                Action::Resized { .. } => "resized",
+                Action::Redraw => "redraw",
+                _ => "other",
+            }));
+        assert_eq!(*log.borrow(), ["repaint", "resized", "redraw"]);
+        rest.run_return_cb(true, None, Event::RedrawRequested(window_id), &mut cf);
+        assert_eq!(log.borrow().len(), 4);
+    }
+
+    #[test]
+    fn debounced_resize_only_repaints_until_settled() {
+        use std::{cell::RefCell, rc::Rc};
+        use winit::{dpi::PhysicalSize, window::WindowId};
+
+        let (act_send, act_recv) = mpsc::channel();
+        let log = Rc::new(RefCell::new(vec![]));
+        let mut rest = test_rest(
+            act_send,
+            ResizeDebounce::new(Some(Duration::from_secs(3600))),
+            Some(Box::new({
+                let log = log.clone();
+                move || log.borrow_mut().push("repaint")
+            })),
+        );
+        // SAFETY: it's only compared against, never used to look up a real window
+        let window_id = unsafe { WindowId::dummy() };
+        let mut cf = ControlFlow::Poll;
+        let sent = |log: &Rc<RefCell<Vec<_>>>| {
+            log.borrow_mut()
+                .extend(act_recv.try_iter().map(|a| match a {
+                    Action::Resized { size: XY(120, 25) } => "resized",
+                    Action::Redraw => "redraw",
+                    _ => "other",
+                }))
+        };
+        for width in [1000, 1100, 1200] {
+            let resized = Event::WindowEvent {
+                window_id,
+                event: WindowEvent::Resized(PhysicalSize::new(width, 500)),
+            };
+            rest.run_return_cb(true, None, resized, &mut cf);
+        }
+        rest.run_return_cb(true, None, Event::RedrawRequested(window_id), &mut cf);
+        sent(&log);
+        assert_eq!(*log.borrow(), ["repaint"; 4]);
+        // skip ahead to when it settles
+        rest.resize.settle_at = Some(Instant::now());
+        rest.run_return_cb(true, None, Event::MainEventsCleared, &mut cf);
+        sent(&log);
+        assert_eq!(log.borrow()[4..], ["resized", "redraw"]);
+    }
+
+    #[test]
+    fn stop_flag_exits_loop() {
+        let (act_send, act_recv) = mpsc::channel();
+        let mut rest = test_rest(act_send, ResizeDebounce::new(None), None);
+        let flag = AtomicBool::new(false);
+        let mut cf = ControlFlow::Poll;
+        rest.run_return_cb(false, Some(&flag), Event::Resumed, &mut cf);
+        assert!(!matches!(cf, ControlFlow::ExitWithCode(_)));
+        act_recv.try_iter().for_each(drop);
+        flag.store(true, Ordering::Relaxed);
+        let redraw = Event::UserEvent(Action::Redraw);
+        rest.run_return_cb(false, Some(&flag), redraw, &mut cf);
+        assert_eq!(cf, ControlFlow::ExitWithCode(WrRest::STOP_CODE));
+        // and the event it was handling gets dropped
+        assert_eq!(act_recv.try_iter().count(), 0);
+    }
+
+    #[test]
+    fn vkc_table_has_no_accidental_duplicates() {
+        // key codes which are deliberately the same key as another one
+        const ALIASES: &[VirtualKeyCode] = &[
+            VirtualKeyCode::Numpad0,
+            VirtualKeyCode::Numpad1,
+            VirtualKeyCode::Numpad2,
+            VirtualKeyCode::Numpad3,
+            VirtualKeyCode::Numpad4,
+            VirtualKeyCode::Numpad5,
+            VirtualKeyCode::Numpad6,
+            VirtualKeyCode::Numpad7,
+            VirtualKeyCode::Numpad8,
+            VirtualKeyCode::Numpad9,
+            VirtualKeyCode::NumpadAdd,
+            VirtualKeyCode::NumpadDivide,
+            VirtualKeyCode::NumpadDecimal,
+            VirtualKeyCode::NumpadComma,
+            VirtualKeyCode::NumpadEnter,
+            VirtualKeyCode::NumpadEquals,
+            VirtualKeyCode::NumpadMultiply,
+            VirtualKeyCode::NumpadSubtract,
+            VirtualKeyCode::Asterisk,
+            VirtualKeyCode::Plus,
+        ];
+        for (i, (vkc, key)) in VKC_KEYS.iter().enumerate() {
+            for (other_vkc, other_key) in &VKC_KEYS[i + 1..] {
+                assert_ne!(vkc, other_vkc, "{:?} is in the table twice", vkc);
+                if key == other_key {
+                    assert!(
+                        ALIASES.contains(vkc) || ALIASES.contains(other_vkc),
+                        "{:?} and {:?} are both {:?}",
+                        vkc,
+                        other_vkc,
+                        key
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn partial_scrolls_add_up() {
+        let mut acc = 0.0;
+        assert_eq!(scroll_notches(&mut acc, 2.0), 2);
+        assert_eq!(scroll_notches(&mut acc, -1.0), -1);
+        assert_eq!(scroll_notches(&mut acc, 0.5), 0);
+        assert_eq!(scroll_notches(&mut acc, 0.75), 1);
+        assert_eq!(scroll_notches(&mut acc, -0.5), 0);
+        assert_eq!(scroll_notches(&mut acc, -0.75), -1);
+    }
+
+    #[test]
+    fn held_keys_repeat() {
+        let mut held = HeldKeys::default();
+        assert!(!held.press(Key::Char('w')));
+        assert!(held.press(Key::Char('w')));
+        assert!(!held.press(Key::Char('a')));
+        held.release(Key::Char('w'));
+        assert!(!held.press(Key::Char('w')));
+        held.clear();
+        assert!(!held.press(Key::Char('a')));
+    }
+
+    #[test]
+    fn moves_coalesce_around_buttons() {
+        let mv = |x| Action::MouseMove { pos: XY(x, 0) };
+        let press = Action::MousePress {
+            pos: XY(3, 0),
+            button: MouseButton::Left,
+        };
+        let release = Action::MouseRelease {
+            pos: XY(6, 0),
+            button: MouseButton::Left,
+        };
+        let mut actions = vec![
+            mv(1),
+            mv(2),
+            mv(3),
+            press.clone(),
+            mv(4),
+            mv(5),
+            Action::press(Key::Char('a')),
+            mv(6),
+            release.clone(),
+            mv(7),
+        ];
+        coalesce_moves(&mut actions);
+        assert_eq!(
+            actions,
+            [
+                mv(3),
+                press,
+                mv(5),
+                Action::press(Key::Char('a')),
+                mv(6),
+                release,
+                mv(7)
+            ]
+        );
+    }
+
+    #[test]
+    fn coalescing_nothing_is_fine() {
+        let mut actions = vec![];
+        coalesce_moves(&mut actions);
+        assert!(actions.is_empty());
+        let mut actions = vec![Action::Redraw, Action::Redraw];
+        coalesce_moves(&mut actions);
+        assert_eq!(actions, [Action::Redraw, Action::Redraw]);
+    }
+
+    #[test]
+    fn pixel_cell_roundtrip() {
+        let (char_size, win_size) = (XY(10, 20), XY(805, 513));
+        assert_eq!(edge_buffer(char_size, win_size), XY(2, 6));
+        for cell in [XY(0, 0), XY(3, 7), XY(79, 24)] {
+            let (top_left, size) = cell_to_pixel_rect(cell, char_size, win_size);
+            assert_eq!(size, char_size);
+            let bottom_right = top_left + size - XY(1, 1);
+            assert_eq!(char4pixel_pos(top_left, char_size, win_size), cell);
+            assert_eq!(char4pixel_pos(bottom_right, char_size, win_size), cell);
+        }
+        // the edges clamp to the nearest cell
+        assert_eq!(char4pixel_pos(XY(0, 0), char_size, win_size), XY(0, 0));
+        assert_eq!(
+            char4pixel_pos(XY(804, 512), char_size, win_size),
+            XY(79, 24)
+        );
+    }
+
+    #[test]
+    fn pixel_to_cell_in_tiny_window() {
+        let char_size = XY(10, 20);
+        for win_size in [XY(5, 8), XY(0, 0)] {
+            for pos in [XY(0, 0), XY(3, 7), XY(900, 900)] {
+                assert_eq!(char4pixel_pos(pos, char_size, win_size), XY(0, 0));
+            }
+        }
+        // too short, but wide enough for plenty of columns
+        let win_size = XY(805, 8);
+        assert_eq!(char4pixel_pos(XY(0, 7), char_size, win_size), XY(0, 0));
+        assert_eq!(char4pixel_pos(XY(804, 3), char_size, win_size), XY(79, 0));
+    }
+
+    #[test]
+    fn blink_alternates() {
+        let epoch = Instant::now();
+        let at = |ms| epoch + Duration::from_millis(ms);
+        assert_eq!(blink_phase(epoch, epoch), (true, at(500)));
+        assert_eq!(blink_phase(epoch, at(499)), (true, at(500)));
+        assert_eq!(blink_phase(epoch, at(500)), (false, at(1000)));
+        assert_eq!(blink_phase(epoch, at(1250)), (true, at(1500)));
+    }
+
+    #[test]
+    fn png_roundtrips_pixels() {
+        let pixels = [
+            0x00ff0000, 0x0000ff00, 0x000000ff, 0x00123456, 0x00000000, 0x00ffffff,
+        ];
+        let mut out = vec![];
+        write_png(XY(3, 2), &pixels, &mut out).unwrap();
+
+        let dec = png::Decoder::new(out.as_slice());
+        let mut reader = dec.read_info().unwrap();
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).unwrap();
+        assert_eq!((info.width, info.height), (3, 2));
+        assert_eq!(info.color_type, png::ColorType::Rgb);
+        let decoded: Vec<u32> = buf[..info.buffer_size()]
+            .chunks(3)
+            .map(|px| (px[0] as u32) << 16 | (px[1] as u32) << 8 | px[2] as u32)
+            .collect();
+        assert_eq!(decoded, pixels);
     }
 }
@@ -7,9 +7,9 @@
 #[cfg(not(feature = "std"))]
 compile_error!("enable std to use cli_crossterm");
 
-use std::io;
+use std::{collections::HashMap, io, sync::RwLock, time::Instant};
 
-use fontdue::{Font, FontSettings};
+use fontdue::{Font, FontSettings, Metrics};
 use rayon::prelude::*;
 use winit::window::Window;
 
@@ -17,9 +17,10 @@ use crate::{
     fmt::{Color, Formatted},
     screen::Screen,
     xy::XY,
+    CursorStyle,
 };
 
-use super::GuiRenderer;
+use super::{blink_phase, GuiRenderer};
 
 fn ioe4fe(e: &'static str) -> io::Error {
     io::Error::new(io::ErrorKind::Other, e)
@@ -27,6 +28,11 @@ fn ioe4fe(e: &'static str) -> io::Error {
 
 fn color_f32(c: Color) -> (f32, f32, f32) {
     let (h, s, v): (f32, f32, f32) = match c {
+        Color::Indexed(idx @ 0..=15) => return color_f32(Color::from_indexed(idx)),
+        Color::Rgb(..) | Color::Indexed(..) => {
+            let (r, g, b) = c.rgb();
+            return (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+        }
         Color::Black => (000.0, 0.0, 0.05),
         Color::Red => (000.0, 1.0, 0.75),
         Color::Green => (120.0, 1.0, 0.75),
@@ -98,25 +104,153 @@ fn color_of(fg: Color, bg: Color, opacity: f32) -> u32 {
     (r_b << 16) | (g_b << 8) | (b_b << 0)
 }
 
+/// Make sure a font is monospace, by checking that every printable ASCII character is as wide as every other.
+fn check_monospace(font: &Font, scale: f32, which: &str) -> io::Result<f32> {
+    let width = font.metrics('m', scale).advance_width;
+    for ch in ' '..='~' {
+        let this = font.metrics(ch, scale).advance_width;
+        if (this - width).abs() > 0.01 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} font isn't monospace: {:?} is {} wide, but 'm' is {}",
+                    which, ch, this, width
+                ),
+            ));
+        }
+    }
+    Ok(width)
+}
+
 pub struct SoftbufferBackend {
+    /// the raw TTF data for the regular font, kept around for `renew`
+    regular_ttf: Vec<u8>,
+    /// the raw TTF data for the bold font, kept around for `renew`
+    bold_ttf: Vec<u8>,
     /// the font size, in whatever units fontdue likes
     scale: f32,
     /// the unbolded font
     regular: Font,
     /// the bolded font (all the metrics are based on unbolded)
     bold: Font,
-    /// the total size of one character in the font
+    /// the total size of one character cell, including the padding
     ch_sz: XY,
+    /// extra pixels added to each cell, split evenly on either side of the glyph
+    pad: XY,
     /// how many pixels down from the top the character baseline is
     line_baseline: usize,
     /// how thick the underline should be, in fractions of a pixel
     underline_top: usize,
+    /// the row just under the bottom of the underline, i.e. the top of the bottom padding
+    underline_bottom: usize,
+    /// the raw TTF data for the fallback fonts, kept around for `renew`
+    fallback_ttfs: Vec<Vec<u8>>,
+    /// fonts to try, in order, if the main ones don't have a character
+    fallbacks: Vec<Font>,
+    /// which font to draw each character with: `None` for the main font, `Some(i)` for `fallbacks[i]`
+    font_cache: RwLock<HashMap<char, Option<usize>>>,
+    /// when the clock for blinking text started
+    blink_epoch: Instant,
+    /// where to draw the cursor, and how, if anywhere; it blinks along with the text
+    cursor: Option<(XY, CursorStyle)>,
+    /// what to fill the margins around the grid with
+    clear: Color,
+}
+
+impl SoftbufferBackend {
+    /// Create a new backend like [`GuiRenderer::new`], with `pad` extra pixels of space in each cell, horizontally
+    /// and vertically, e.g. to make text less cramped on high-DPI displays.
+    ///
+    /// The padding is part of the [character size](GuiRenderer::char_size), so everything measured in cells, like
+    /// the size of the grid and where the mouse is, takes it into account. Each glyph is centered in its padded cell,
+    /// with the odd pixel, if there is one, going on the right or bottom.
+    pub fn with_spacing(font_size: f32, pad: XY) -> io::Result<Self> {
+        Ok(Self::new(font_size)?.padded(pad))
+    }
+
+    /// Add padding to a backend that doesn't have any yet.
+    fn padded(mut self, pad: XY) -> Self {
+        let before = pad / XY(2, 2);
+        self.pad = pad;
+        self.ch_sz += pad;
+        self.line_baseline += before.y();
+        self.underline_top += before.y();
+        self.underline_bottom += before.y();
+        self
+    }
+
+    /// Add fallback fonts, to be used (in order) for any characters the main fonts don't have, e.g. emoji.
+    ///
+    /// Which font each character uses is looked up once and then cached. Fallback fonts don't need to be monospace:
+    /// their glyphs are shrunk to fit in the cell if they're too wide, and centered in it. They also don't have bold
+    /// variants, so bold text in a fallback font is drawn normally.
+    pub fn with_fallbacks(mut self, fonts: Vec<&[u8]>) -> io::Result<Self> {
+        for ttf in fonts {
+            let font = Font::from_bytes(
+                ttf,
+                FontSettings {
+                    scale: self.scale,
+                    ..Default::default()
+                },
+            )
+            .map_err(ioe4fe)?;
+            self.fallback_ttfs.push(ttf.to_vec());
+            self.fallbacks.push(font);
+        }
+        self.font_cache.get_mut().unwrap().clear();
+        Ok(self)
+    }
+
+    /// Figure out which font to draw a character with; see `font_cache`.
+    fn font_for(&self, ch: char) -> Option<usize> {
+        if let Some(res) = self.font_cache.read().unwrap().get(&ch) {
+            return *res;
+        }
+        let res = if self.regular.lookup_glyph_index(ch) != 0 {
+            None
+        } else {
+            self.fallbacks
+                .iter()
+                .position(|f| f.lookup_glyph_index(ch) != 0)
+        };
+        self.font_cache.write().unwrap().insert(ch, res);
+        res
+    }
+
+    /// Rasterize a character to fit into a cell `cell_width` pixels wide, picking the right font for it.
+    ///
+    /// Also returns whether the glyph should be centered in the cell, rather than positioned by its own metrics.
+    fn rasterize(&self, ch: char, bold: bool, cell_width: usize) -> (Metrics, Vec<u8>, bool) {
+        match self.font_for(ch) {
+            None => {
+                // select bold or normal font (this is really how we do bold, it bugs me too)
+                let font = if bold { &self.bold } else { &self.regular };
+                let (metrics, buf) = font.rasterize(ch, self.scale);
+                (metrics, buf, false)
+            }
+            Some(idx) => {
+                let font = &self.fallbacks[idx];
+                let width = font.metrics(ch, self.scale).width;
+                let scale = if width > cell_width {
+                    self.scale * cell_width as f32 / width as f32
+                } else {
+                    self.scale
+                };
+                let (metrics, buf) = font.rasterize(ch, scale);
+                (metrics, buf, true)
+            }
+        }
+    }
 }
 
 impl GuiRenderer for SoftbufferBackend {
     fn new(scale: f32) -> io::Result<Self> {
+        Self::with_fonts(scale, super::REGULAR_TTF, super::BOLD_TTF)
+    }
+
+    fn with_fonts(scale: f32, regular_ttf: &[u8], bold_ttf: &[u8]) -> io::Result<Self> {
         let regular = Font::from_bytes(
-            super::REGULAR_TTF,
+            regular_ttf,
             FontSettings {
                 scale,
                 ..Default::default()
@@ -124,13 +258,26 @@ impl GuiRenderer for SoftbufferBackend {
         )
         .map_err(ioe4fe)?;
         let bold = Font::from_bytes(
-            super::BOLD_TTF,
+            bold_ttf,
             FontSettings {
                 scale,
                 ..Default::default()
             },
         )
         .map_err(ioe4fe)?;
+        let regular_width = check_monospace(&regular, scale, "regular")?;
+        let bold_width = check_monospace(&bold, scale, "bold")?;
+        // bold text is drawn in the same grid, so its characters have to be the same width too; fallbacks are exempt
+        // because their glyphs get scaled to fit the cell instead
+        if (regular_width - bold_width).abs() > 0.01 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "bold font's characters are {} wide, but the regular font's are {}",
+                    bold_width, regular_width
+                ),
+            ));
+        }
 
         let line_met = regular
             .horizontal_line_metrics(scale)
@@ -144,17 +291,43 @@ impl GuiRenderer for SoftbufferBackend {
         let line_baseline = line_met.ascent as usize + 1;
 
         let underline_top = height - regular.metrics('_', scale).height;
+        let underline_bottom = height;
 
         Ok(Self {
+            regular_ttf: regular_ttf.to_vec(),
+            bold_ttf: bold_ttf.to_vec(),
             scale,
             regular,
             bold,
             ch_sz,
+            pad: XY(0, 0),
             line_baseline,
             underline_top,
+            underline_bottom,
+            fallback_ttfs: vec![],
+            fallbacks: vec![],
+            font_cache: RwLock::new(HashMap::new()),
+            blink_epoch: Instant::now(),
+            cursor: None,
+            clear: Color::Black,
         })
     }
 
+    fn renew(&mut self, scale: f32) -> io::Result<()> {
+        let fallbacks = self.fallback_ttfs.iter().map(|f| f.as_slice()).collect();
+        let new = Self::with_fonts(scale, &self.regular_ttf, &self.bold_ttf)?
+            .with_fallbacks(fallbacks)?
+            .padded(self.pad);
+        // keep blinking in step across zooms
+        *self = Self {
+            blink_epoch: self.blink_epoch,
+            cursor: self.cursor,
+            clear: self.clear,
+            ..new
+        };
+        Ok(())
+    }
+
     fn char_size(&self) -> XY {
         self.ch_sz
     }
@@ -164,26 +337,78 @@ impl GuiRenderer for SoftbufferBackend {
             window.inner_size().width as usize,
             window.inner_size().height as usize,
         );
+        let (blink_on, _) = blink_phase(self.blink_epoch, Instant::now());
+        let screen_buf = self.draw_buffer(window_sz, screen, blink_on);
+
+        // SAFETY: if winit betrays us we have no recourse
+        let mut wh = unsafe { softbuffer::GraphicsContext::new(window, window) }
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{}", e)))?;
+
+        wh.set_buffer(&screen_buf, window_sz.x() as u16, window_sz.y() as u16);
+
+        Ok(())
+    }
+
+    fn render_to_buffer(&self, window_sz: XY, screen: &Screen) -> io::Result<Vec<u32>> {
+        Ok(self.draw_buffer(window_sz, screen, true))
+    }
+
+    fn next_change(&self, screen: &Screen) -> Option<Instant> {
+        if self.cursor.is_none() && !screen.cells().iter().any(|c| c.get_fmt().blink) {
+            return None;
+        }
+        let (_, next) = blink_phase(self.blink_epoch, Instant::now());
+        Some(next)
+    }
+
+    fn set_cursor(&mut self, cursor: Option<(XY, CursorStyle)>) {
+        self.cursor = cursor;
+    }
+
+    fn set_clear_color(&mut self, color: Color) {
+        self.clear = color;
+    }
+}
+
+impl SoftbufferBackend {
+    /// Draw a screen into a buffer of pixels, as for [`GuiRenderer::render_to_buffer`].
+    ///
+    /// `blink_on` is whether blinking text is currently visible; if not, it's drawn as a blank. The cursor blinks
+    /// along with it.
+    fn draw_buffer(&self, window_sz: XY, screen: &Screen, blink_on: bool) -> Vec<u32> {
         let bounded_sz = {
             let max = window_sz / self.ch_sz;
             let sz = screen.size();
             XY(sz.x().min(max.x()), sz.y().min(max.y()))
         };
         let buffer_sz = (window_sz % self.ch_sz) / 2;
+        let clear = color_u32(self.clear);
+        let pad_left = (self.pad.x() / 2) as i32;
 
         let char_rows = (0..bounded_sz.y()).into_par_iter().flat_map(|y| {
             // how many pixels down from the top this starts
-            let mut row_buf = vec![color_u32(Color::Black); window_sz.x() * self.ch_sz.y()];
-            for x in 0..bounded_sz.x() {
+            let mut row_buf = vec![clear; window_sz.x() * self.ch_sz.y()];
+            let mut x = 0;
+            while x < bounded_sz.x() {
                 // how many pixels right from the left this starts
                 let col = x * self.ch_sz.x() + buffer_sz.x();
 
                 let cell = &screen[y][x];
                 let fmt = cell.get_fmt();
 
-                // select bold or normal font (this is really how we do bold, it bugs me too)
-                let font = if fmt.bold { &self.bold } else { &self.regular };
-                let (metrics, char_buf) = font.rasterize(cell.ch, self.scale);
+                // wide characters are drawn across their own cell and the continuation after it; anything that can't
+                // be drawn properly (orphaned continuations, zero-width characters) is drawn as a blank
+                let (ch, span) = match cell.width() {
+                    1 => (cell.ch, 1),
+                    2 if x + 1 < bounded_sz.x() && screen[y][x + 1].is_continuation() => {
+                        (cell.ch, 2)
+                    }
+                    _ => (' ', 1),
+                };
+                let ch = if fmt.blink && !blink_on { ' ' } else { ch };
+                let cell_width = span * self.ch_sz.x();
+
+                let (metrics, char_buf, centered) = self.rasterize(ch, fmt.bold, cell_width);
 
                 let ch_bottom = metrics.height as i32;
                 // + because the axes are inverted (so really it's - (-metrics.ymin))
@@ -203,22 +428,27 @@ impl GuiRenderer for SoftbufferBackend {
                 // ditto for the x offset but that's easier because the "line baseline" is at 0
                 let x_offset;
                 let x_cutoff;
-                if metrics.xmin >= 0 {
-                    x_offset = metrics.xmin as usize;
+                if centered {
+                    x_offset = cell_width.saturating_sub(metrics.width) / 2;
+                    x_cutoff = 0;
+                } else if metrics.xmin + pad_left >= 0 {
+                    x_offset = (metrics.xmin + pad_left) as usize;
                     x_cutoff = 0;
                 } else {
                     x_offset = 0;
-                    x_cutoff = -metrics.xmin as usize;
+                    x_cutoff = -(metrics.xmin + pad_left) as usize;
                 }
 
                 // now we can actually move the rasterized character onto the screen!
                 for line_row in 0..self.ch_sz.y() {
-                    let dest_row = line_row - y_cutoff;
-                    let dest_start = (dest_row * window_sz.x()) + col - x_cutoff;
-                    let dest_end = dest_start + self.ch_sz.x();
+                    let dest_start = (line_row * window_sz.x()) + col;
+                    let dest_end = dest_start + cell_width;
                     let dest = &mut row_buf[dest_start..dest_end];
 
-                    if fmt.underline && line_row > self.underline_top {
+                    if fmt.underline
+                        && line_row > self.underline_top
+                        && line_row < self.underline_bottom
+                    {
                         dest.fill(color_u32(fmt.fg));
                         continue;
                     }
@@ -228,7 +458,7 @@ impl GuiRenderer for SoftbufferBackend {
                         continue;
                     }
 
-                    for line_col in 0..self.ch_sz.x() {
+                    for line_col in 0..cell_width {
                         if line_col < x_offset || line_col >= metrics.width + x_offset - x_cutoff {
                             dest[line_col] = color_u32(fmt.bg);
                             continue;
@@ -242,20 +472,279 @@ impl GuiRenderer for SoftbufferBackend {
                         dest[line_col] = color;
                     }
                 }
+
+                x += span;
             }
             row_buf
         });
         let mut screen_buf = Vec::with_capacity(window_sz.x() * window_sz.y());
-        screen_buf.resize(window_sz.x() * buffer_sz.y(), color_u32(Color::Black));
+        screen_buf.resize(window_sz.x() * buffer_sz.y(), clear);
         screen_buf.par_extend(char_rows);
-        screen_buf.resize(window_sz.x() * window_sz.y(), color_u32(Color::Black));
+        screen_buf.resize(window_sz.x() * window_sz.y(), clear);
+        match self.cursor {
+            Some((pos, style))
+                if blink_on && pos.x() < bounded_sz.x() && pos.y() < bounded_sz.y() =>
+            {
+                let fg = color_u32(screen[pos.y()][pos.x()].get_fmt().fg);
+                let top_left = pos * self.ch_sz + buffer_sz;
+                self.draw_cursor(&mut screen_buf, window_sz, top_left, style, fg);
+            }
+            _ => (),
+        }
+        screen_buf
+    }
 
-        // SAFETY: if winit betrays us we have no recourse
-        let mut wh = unsafe { softbuffer::GraphicsContext::new(window, window) }
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, format!("{}", e)))?;
+    /// Draw the cursor over the cell whose top-left pixel is `top_left`, in the color `fg` if it's not a block.
+    fn draw_cursor(
+        &self,
+        buf: &mut [u32],
+        window_sz: XY,
+        top_left: XY,
+        style: CursorStyle,
+        fg: u32,
+    ) {
+        let XY(w, h) = self.ch_sz;
+        let (rows, cols) = match style {
+            CursorStyle::Block => (0..h, 0..w),
+            // the same rows as an underline
+            CursorStyle::Underline => {
+                let bottom = self.underline_bottom;
+                ((self.underline_top + 1).min(bottom - 1)..bottom, 0..w)
+            }
+            CursorStyle::Bar => (0..h, 0..(w / 8).max(1)),
+        };
+        for row in rows {
+            let start = (top_left.y() + row) * window_sz.x() + top_left.x();
+            for px in &mut buf[start + cols.start..start + cols.end] {
+                *px = match style {
+                    CursorStyle::Block => *px ^ 0xff_ff_ff,
+                    _ => fg,
+                };
+            }
+        }
+    }
+}
 
-        wh.set_buffer(&screen_buf, window_sz.x() as u16, window_sz.y() as u16);
+#[cfg(test)]
+mod test {
+    use crate::{graphical::char4pixel_pos, text};
 
-        Ok(())
+    use super::*;
+
+    #[test]
+    fn default_fonts_are_monospace() {
+        let reg = Font::from_bytes(crate::im::REGULAR_TTF, FontSettings::default()).unwrap();
+        check_monospace(&reg, 20.0, "regular").unwrap();
+        let bold = Font::from_bytes(crate::im::BOLD_TTF, FontSettings::default()).unwrap();
+        check_monospace(&bold, 20.0, "bold").unwrap();
+    }
+
+    #[test]
+    fn bad_fonts_error() {
+        let res = SoftbufferBackend::with_fonts(20.0, b"not a font", crate::im::BOLD_TTF);
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn mismatched_font_widths_error() {
+        // monospace on its own, since every character is the same missing glyph, but not as wide as the regular font
+        let err =
+            match SoftbufferBackend::with_fonts(20.0, crate::im::REGULAR_TTF, FALLBACK_TEST_TTF) {
+                Ok(_) => panic!("fonts of different widths were accepted"),
+                Err(e) => e,
+            };
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert!(
+            err.to_string().contains("the regular font's are"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn renew_keeps_fonts() {
+        let mut backend = SoftbufferBackend::new(20.0).unwrap();
+        let small = backend.char_size();
+        backend.renew(40.0).unwrap();
+        assert!(backend.char_size().x() > small.x());
+        assert_eq!(backend.regular_ttf, crate::im::REGULAR_TTF);
+    }
+
+    /// A font with nothing in it but a box for `漢`, which inconsolata doesn't have, wider than a cell.
+    const FALLBACK_TEST_TTF: &[u8] = include_bytes!("fallback-test.ttf");
+
+    #[test]
+    fn fallbacks_are_used_and_cached() {
+        // the bold font doesn't have 漢 either, so it gets skipped over
+        let backend = SoftbufferBackend::new(20.0)
+            .unwrap()
+            .with_fallbacks(vec![crate::im::BOLD_TTF, FALLBACK_TEST_TTF])
+            .unwrap();
+        let cell_width = backend.char_size().x();
+        assert_eq!(backend.font_for('a'), None);
+        assert_eq!(backend.font_cache.read().unwrap().get(&'a'), Some(&None));
+        let (_, _, centered) = backend.rasterize('a', false, cell_width);
+        assert!(!centered);
+
+        assert_eq!(backend.font_for('漢'), Some(1));
+        assert_eq!(
+            backend.font_cache.read().unwrap().get(&'漢'),
+            Some(&Some(1))
+        );
+        let (metrics, _, centered) = backend.rasterize('漢', false, cell_width);
+        assert!(centered);
+        // and it's too wide for the cell, so it gets shrunk
+        let full = backend.fallbacks[1].metrics('漢', backend.scale);
+        assert!(full.width > cell_width);
+        assert!(metrics.width < full.width);
+
+        // nothing has this one, so it's drawn with the main font's missing glyph
+        assert_eq!(backend.font_for('字'), None);
+        assert_eq!(backend.font_cache.read().unwrap().get(&'字'), Some(&None));
+    }
+
+    #[test]
+    fn renew_keeps_fallbacks() {
+        let mut backend = SoftbufferBackend::new(20.0)
+            .unwrap()
+            .with_fallbacks(vec![crate::im::BOLD_TTF])
+            .unwrap();
+        backend.renew(30.0).unwrap();
+        assert_eq!(backend.fallbacks.len(), 1);
+    }
+
+    #[test]
+    fn renders_offscreen() {
+        let backend = SoftbufferBackend::new(20.0).unwrap();
+        let mut screen = Screen::new(XY(4, 2));
+        let size = screen.size() * backend.char_size();
+        let blank = backend.render_to_buffer(size, &screen).unwrap();
+        assert_eq!(blank.len(), size.x() * size.y());
+        assert!(blank.iter().all(|px| *px == color_u32(Color::Black)));
+
+        screen.write(XY(0, 0), text!["ab"]);
+        let written = backend.render_to_buffer(size, &screen).unwrap();
+        assert_eq!(written.len(), blank.len());
+        assert_ne!(written, blank);
+    }
+
+    #[test]
+    fn overhanging_glyphs_clip() {
+        // at this size, `─` sticks out past the left of its cell and `Ǻ` past the top
+        let backend = SoftbufferBackend::new(20.0).unwrap();
+        let mut screen = Screen::new(XY(2, 1));
+        screen.write(XY(0, 0), text!["─Ǻ"]);
+        let size = screen.size() * backend.char_size();
+        let buf = backend.render_to_buffer(size, &screen).unwrap();
+        assert_eq!(buf.len(), size.x() * size.y());
+        // both still get drawn, each in its own cell
+        let cell_width = backend.char_size().x();
+        for x in 0..2 {
+            let drawn = buf
+                .chunks(size.x())
+                .flat_map(|row| &row[x * cell_width..(x + 1) * cell_width])
+                .any(|px| *px != color_u32(Color::Black));
+            assert!(drawn, "cell {} is blank", x);
+        }
+    }
+
+    #[test]
+    fn spacing_pads_cells() {
+        let plain = SoftbufferBackend::new(20.0).unwrap();
+        let mut padded = SoftbufferBackend::with_spacing(20.0, XY(4, 6)).unwrap();
+        assert_eq!(padded.char_size(), plain.char_size() + XY(4, 6));
+        padded.renew(20.0).unwrap();
+        assert_eq!(padded.char_size(), plain.char_size() + XY(4, 6));
+
+        let mut screen = Screen::new(XY(1, 1));
+        screen.write(XY(0, 0), text![underline "W"]);
+        let size = padded.char_size();
+        let buf = padded.render_to_buffer(size, &screen).unwrap();
+        let black = color_u32(Color::Black);
+        let row = |y: usize| &buf[y * size.x()..(y + 1) * size.x()];
+        // the padding above and below is blank, even under the underline
+        for y in (0..3).chain(size.y() - 3..size.y()) {
+            assert!(row(y).iter().all(|px| *px == black), "row {} drawn", y);
+        }
+        // and so is the padding on either side, though underlines go all the way across so they join up
+        screen.write(XY(0, 0), text!["W"]);
+        let buf = padded.render_to_buffer(size, &screen).unwrap();
+        let row = |y: usize| &buf[y * size.x()..(y + 1) * size.x()];
+        for y in 0..size.y() {
+            assert_eq!(row(y)[..2], [black, black], "row {} drawn", y);
+            assert_eq!(row(y)[size.x() - 2..], [black, black], "row {} drawn", y);
+        }
+        // but the glyph and its underline are in between
+        assert!(buf.iter().any(|px| *px != black));
+
+        // mouse positions map to the padded cells
+        let win = size * XY(3, 2);
+        assert_eq!(char4pixel_pos(XY(size.x(), 0), size, win), XY(1, 0));
+        assert_eq!(
+            char4pixel_pos(XY(size.x() - 1, size.y()), size, win),
+            XY(0, 1)
+        );
+    }
+
+    #[test]
+    fn margins_use_clear_color() {
+        let mut backend = SoftbufferBackend::new(20.0).unwrap();
+        backend.set_clear_color(Color::Rgb(10, 20, 30));
+        backend.renew(20.0).unwrap();
+        let screen = Screen::new(XY(2, 1));
+        let grid = screen.size() * backend.char_size();
+        // 3 pixels of margin on each side
+        let size = grid + XY(6, 6);
+        let buf = backend.render_to_buffer(size, &screen).unwrap();
+        let px = |x: usize, y: usize| buf[y * size.x() + x];
+        let clear = color_u32(Color::Rgb(10, 20, 30));
+        for x in 0..size.x() {
+            assert_eq!((px(x, 0), px(x, size.y() - 1)), (clear, clear));
+        }
+        for y in 0..size.y() {
+            assert_eq!((px(0, y), px(size.x() - 1, y)), (clear, clear));
+        }
+        // but the cells themselves are still their own background
+        assert_eq!(px(3, 3), color_u32(Color::Black));
+        assert_eq!(px(size.x() - 4, size.y() - 4), color_u32(Color::Black));
+    }
+
+    #[test]
+    fn blinking_text_hides() {
+        let backend = SoftbufferBackend::new(20.0).unwrap();
+        let mut screen = Screen::new(XY(2, 1));
+        let size = screen.size() * backend.char_size();
+        assert_eq!(backend.next_change(&screen), None);
+        let blank = backend.draw_buffer(size, &screen, true);
+        screen.write(XY(0, 0), text![blink "a"]);
+        assert_ne!(backend.draw_buffer(size, &screen, true), blank);
+        assert_eq!(backend.draw_buffer(size, &screen, false), blank);
+        assert!(backend.next_change(&screen).is_some());
+    }
+
+    #[test]
+    fn cursor_blinks_over_cell() {
+        let mut backend = SoftbufferBackend::new(20.0).unwrap();
+        let screen = Screen::new(XY(2, 1));
+        let size = screen.size() * backend.char_size();
+        let blank = backend.draw_buffer(size, &screen, true);
+        for style in [CursorStyle::Block, CursorStyle::Underline, CursorStyle::Bar] {
+            backend.set_cursor(Some((XY(1, 0), style)));
+            let shown = backend.draw_buffer(size, &screen, true);
+            assert_ne!(shown, blank);
+            // only the cursor's cell is touched
+            for row in shown.chunks(size.x()).zip(blank.chunks(size.x())) {
+                let half = backend.char_size().x();
+                assert_eq!(row.0[..half], row.1[..half]);
+            }
+            assert_eq!(backend.draw_buffer(size, &screen, false), blank);
+            assert!(backend.next_change(&screen).is_some());
+        }
+        backend.set_cursor(Some((XY(0, 0), CursorStyle::Block)));
+        let inverted = backend.draw_buffer(size, &screen, true);
+        assert_eq!(inverted[0], color_u32(Color::Black) ^ 0xff_ff_ff);
+        backend.set_cursor(None);
+        assert_eq!(backend.draw_buffer(size, &screen, true), blank);
+        assert_eq!(backend.next_change(&screen), None);
     }
 }
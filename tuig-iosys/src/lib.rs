@@ -9,9 +9,9 @@
 //! designed for chargrids that renders straight to it, consider [`tuig-ui`](https://crates.io/crates/tuig-ui)!)
 //!
 //! The second is [`IoSystem`]. You can use `load!` to pick one based on available features and which one succeeds
-//! first, or you can load them yourself. The `IoSystem` can be passed around wherever you like, but its associated
-//! [`IoRunner`] must be run on the main thread, to ensure things work as expected on Windows GUIs. Builtin backends
-//! are enabled by features and available in [`backends`].
+//! first, `load_ordered!` to pick the order they're tried in, or you can load them yourself. The `IoSystem` can be
+//! passed around wherever you like, but its associated [`IoRunner`] must be run on the main thread, to ensure things
+//! work as expected on Windows GUIs. Builtin backends are enabled by features and available in [`backends`].
 //!
 //! # Features
 //!
@@ -38,7 +38,7 @@
 
 /// Re-exported for the [`load!`] macro.
 #[doc(hidden)]
-pub use alloc::{boxed::Box, collections::BTreeMap, string::String};
+pub use alloc::{boxed::Box, string::String, vec::Vec};
 
 extern crate alloc;
 
@@ -54,18 +54,27 @@ pub mod fmt;
 mod screen;
 mod xy;
 
+#[cfg(feature = "std")]
+pub use crate::action::ChordMatcher;
 pub use crate::{
-    action::{Action, Key, MouseButton},
+    action::{
+        Action, DragEvent, DragTracker, Key, KeyCombo, Modifiers, MouseButton, ParseKeyError,
+    },
     error::{Error, Result},
-    screen::Screen,
-    traits::{IoRunner, IoSystem},
+    screen::{Screen, ScreenPatch, ScreenView, SliceScreen},
+    traits::{Capabilities, ColorDepth, CursorStyle, IoRunner, IoSystem},
     xy::XY,
 };
 
 /// Helper types for implementing your own (primarily graphical) IO systems.
 #[cfg(feature = "gui")]
 pub mod im {
-    pub use super::graphical::{GuiRenderer, GuiRunner, GuiSystem, BOLD_TTF, REGULAR_TTF};
+    #[cfg(feature = "gui_softbuffer")]
+    pub use super::graphical::softbuffer::SoftbufferBackend;
+    pub use super::graphical::{
+        cell_to_pixel_rect, char4pixel_pos, GuiRenderer, GuiRunner, GuiSystem, BOLD_TTF,
+        REGULAR_TTF,
+    };
 }
 
 /// Available rendering backends. See the [`IoSystem`] and [`IoRunner`] docs for more information.
@@ -106,23 +115,97 @@ pub mod backends {
         /// updates to the dependencies.
         "gui_softbuffer", Softbuffer => graphical::GuiSystem<graphical::softbuffer::SoftbufferBackend>, graphical::GuiRunner;
     }
+
+    /// An [`IoSystem`] wrapper that records every frame drawn and plays back scripted input, for testing.
+    #[cfg(feature = "nop")]
+    pub use super::misc::record::Record;
+
+    /// One of the backends [`load!`] knows how to load, whether or not it's compiled in.
+    ///
+    /// These parse from strings case-insensitively, either by feature name, like `cli_crossterm`, or by a shorter
+    /// name, like `crossterm`, e.g. so users can pick a backend in a config file or environment variable. Pass the
+    /// [features](Self::feature) to [`load_ordered!`] to try them in a particular order.
+    #[non_exhaustive]
+    #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+    pub enum BackendKind {
+        /// `NopSystem`, from feature `nop`.
+        Nop,
+        /// `CrosstermSystem`, from feature `cli_crossterm`.
+        Crossterm,
+        /// `SoftbufferSystem`, from feature `gui_softbuffer`.
+        Softbuffer,
+    }
+
+    impl BackendKind {
+        /// Every kind of backend, in the order [`load!`] tries them.
+        pub const ALL: &'static [Self] = &[Self::Nop, Self::Softbuffer, Self::Crossterm];
+
+        /// The name of the feature which enables this backend.
+        pub fn feature(self) -> &'static str {
+            match self {
+                Self::Nop => "nop",
+                Self::Crossterm => "cli_crossterm",
+                Self::Softbuffer => "gui_softbuffer",
+            }
+        }
+
+        /// Whether this backend's feature is turned on, i.e. whether [`load!`] can try it at all.
+        pub fn is_compiled(self) -> bool {
+            match self {
+                Self::Nop => cfg!(feature = "nop"),
+                Self::Crossterm => cfg!(feature = "cli_crossterm"),
+                Self::Softbuffer => cfg!(feature = "gui_softbuffer"),
+            }
+        }
+    }
+
+    impl core::str::FromStr for BackendKind {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            match s.trim().to_ascii_lowercase().as_str() {
+                "nop" => Ok(Self::Nop),
+                "crossterm" | "cli_crossterm" => Ok(Self::Crossterm),
+                "softbuffer" | "gui_softbuffer" => Ok(Self::Softbuffer),
+                _ => Err(alloc::format!(
+                    "unknown backend {:?}; expected one of nop, crossterm, softbuffer",
+                    s
+                )
+                .into()),
+            }
+        }
+    }
 }
 
-type LoadError =
-    core::result::Result<(Box<dyn IoSystem>, Box<dyn IoRunner>), BTreeMap<&'static str, Error>>;
+type LoadError = Result<(Box<dyn IoSystem>, Box<dyn IoRunner>)>;
 /// Based on IO system features enabled, attempt to initialize an IO system, in the same manner as [`load!`].
 ///
 /// This returns things boxed so they can be used as trait objects, which provides nicer ergonomics at the
 /// cost of slightly lower max performance.
 pub fn load() -> LoadError {
-    #[allow(unused)]
-    fn cb(
-        sys: impl IoSystem + 'static,
-        run: impl IoRunner + 'static,
-    ) -> (Box<dyn IoSystem>, Box<dyn IoRunner>) {
-        (Box::new(sys), Box::new(run))
-    }
-    load!(cb)
+    load!(boxed)
+}
+
+/// Like [`load()`], but trying the backends in the given order, in the same manner as [`load_ordered!`].
+pub fn load_ordered(order: &[&str]) -> LoadError {
+    load_ordered!(order, boxed)
+}
+
+#[allow(unused)]
+fn boxed(
+    sys: impl IoSystem + 'static,
+    run: impl IoRunner + 'static,
+) -> (Box<dyn IoSystem>, Box<dyn IoRunner>) {
+    (Box::new(sys), Box::new(run))
+}
+
+/// Used by [`load_ordered!`] to complain about backend names it doesn't recognize.
+#[doc(hidden)]
+pub fn warn_unknown_backend(name: &str) {
+    #[cfg(feature = "std")]
+    eprintln!("tuig-iosys: ignoring unknown backend {:?}", name);
+    #[cfg(not(feature = "std"))]
+    let _ = name;
 }
 
 tuig_pm::make_load! {
@@ -140,8 +223,43 @@ tuig_pm::make_load! {
     ///
     /// The callback can be any "function call", up to the parens, e.g. `run` or `self.start`. It will be called as
     /// `$thing(iosys, iorun)`. If it's called, this macro "returns" `Ok(())`. Otherwise, all attempted loads failed,
-    /// and this macro "returns" `Err(map)`, where `map` maps `&'static str` feature name to `io::Error` failure.
+    /// and this macro "returns" an [`Error::NoBackend`] listing each feature tried and how it failed, in order.
     "nop" => $crate::backends::NopSystem::new(),
     "gui_softbuffer" => $crate::backends::SoftbufferSystem::new(20.0),
     "cli_crossterm" => $crate::backends::CrosstermSystem::new(),
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn backend_kinds_parse_by_either_name() {
+        use backends::BackendKind;
+        for &kind in BackendKind::ALL {
+            assert_eq!(kind.feature().parse::<BackendKind>().unwrap(), kind);
+        }
+        assert_eq!(
+            " CrossTerm".parse::<BackendKind>().unwrap(),
+            BackendKind::Crossterm
+        );
+        assert_eq!(
+            "softbuffer".parse::<BackendKind>().unwrap(),
+            BackendKind::Softbuffer
+        );
+        assert!("vulkan".parse::<BackendKind>().is_err());
+    }
+
+    #[test]
+    fn ordered_ignores_unknown() {
+        let err = load_ordered(&["bogus", "also bogus"]).err().unwrap();
+        assert!(matches!(&err, Error::NoBackend(tried) if tried.is_empty()));
+        assert!(err.backend_errors().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "nop")]
+    #[test]
+    fn ordered_follows_order() {
+        assert!(load_ordered(&["bogus", "nop", "cli_crossterm"]).is_ok());
+    }
+}
@@ -1,3 +1,4 @@
 //! Miscellaneous backends that didn't neatly categorize elsewhere.
 
 pub mod nop;
+pub mod record;
@@ -6,7 +6,10 @@
 compile_error!("enable std to use nop");
 
 use std::{
-    sync::{Arc, Condvar, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
     time::Duration,
 };
 
@@ -33,6 +36,10 @@ impl IoSystem for NopSystem {
     fn poll_input(&mut self) -> crate::Result<Option<Action>> {
         Ok(None)
     }
+    fn input_until(&mut self, timeout: Duration) -> crate::Result<Option<Action>> {
+        std::thread::sleep(timeout);
+        Ok(None)
+    }
     fn size(&self) -> XY {
         XY(80, 24)
     }
@@ -70,4 +77,46 @@ impl IoRunner for NopRunner {
             .wait_while(self.0 .0.lock().unwrap(), |b| !*b)
             .unwrap();
     }
+
+    fn run_until(&mut self, stop: &AtomicBool) {
+        let mut stopped = self.0 .0.lock().unwrap();
+        while !*stopped && !stop.load(Ordering::Relaxed) {
+            // nothing notifies us when the flag changes, so check it every so often
+            let timeout = Duration::from_millis(10);
+            stopped = self.0 .1.wait_timeout(stopped, timeout).unwrap().0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{atomic::AtomicBool, Arc},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn run_until_stops_on_either() {
+        let (mut system, mut runner) = NopSystem::new().unwrap();
+        let flag = Arc::new(AtomicBool::new(false));
+        let setter = {
+            let flag = flag.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(20));
+                flag.store(true, Ordering::Relaxed);
+            })
+        };
+        runner.run_until(&flag);
+        setter.join().unwrap();
+
+        let stopper = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            system.stop();
+        });
+        runner.run_until(&AtomicBool::new(false));
+        stopper.join().unwrap();
+    }
 }
@@ -0,0 +1,231 @@
+//! A backend that remembers everything drawn to it, for golden-frame tests.
+
+#![cfg(feature = "nop")]
+
+#[cfg(not(feature = "std"))]
+compile_error!("enable std to use nop");
+
+use std::{collections::VecDeque, time::Duration};
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{action::Action, screen::Screen, xy::XY, Capabilities, CursorStyle, IoSystem};
+
+/// Wraps another [`IoSystem`] and records every [`Screen`] drawn to it.
+///
+/// Input comes from a pre-seeded script first, then from the inner system once the script runs out. If the inner
+/// system is a [`NopSystem`](crate::backends::NopSystem) that means `input` blocks forever once the script is done, so you
+/// probably want to end your scripts with [`Action::Closed`].
+pub struct Record<S: IoSystem> {
+    inner: S,
+    script: VecDeque<Action>,
+    frames: Vec<Screen>,
+}
+
+impl<S: IoSystem> Record<S> {
+    /// Record frames going to `inner`, feeding it the given `script` of inputs before any of its own.
+    pub fn new(inner: S, script: impl Into<VecDeque<Action>>) -> Self {
+        Self {
+            inner,
+            script: script.into(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Every frame that's been drawn so far, oldest first.
+    pub fn frames(&self) -> &[Screen] {
+        &self.frames
+    }
+
+    /// The remaining scripted input, which hasn't been consumed yet.
+    pub fn script(&self) -> &VecDeque<Action> {
+        &self.script
+    }
+
+    /// Stop recording and get back the inner system and the frames drawn to it.
+    pub fn into_inner(self) -> (S, Vec<Screen>) {
+        (self.inner, self.frames)
+    }
+}
+
+impl<S: IoSystem> IoSystem for Record<S> {
+    fn draw(&mut self, screen: &Screen) -> crate::Result<()> {
+        self.frames.push(screen.clone());
+        self.inner.draw(screen)
+    }
+    fn size(&self) -> XY {
+        self.inner.size()
+    }
+    fn input(&mut self) -> crate::Result<Action> {
+        match self.script.pop_front() {
+            Some(action) => Ok(action),
+            None => self.inner.input(),
+        }
+    }
+    fn poll_input(&mut self) -> crate::Result<Option<Action>> {
+        match self.script.pop_front() {
+            Some(action) => Ok(Some(action)),
+            None => self.inner.poll_input(),
+        }
+    }
+    fn input_until(&mut self, timeout: Duration) -> crate::Result<Option<Action>> {
+        match self.script.pop_front() {
+            Some(action) => Ok(Some(action)),
+            None => self.inner.input_until(timeout),
+        }
+    }
+    fn drain_input(&mut self) -> crate::Result<Vec<Action>> {
+        let mut res: Vec<_> = self.script.drain(..).collect();
+        res.extend(self.inner.drain_input()?);
+        Ok(res)
+    }
+    fn clipboard_get(&mut self) -> crate::Result<Option<String>> {
+        self.inner.clipboard_get()
+    }
+    fn clipboard_set(&mut self, text: &str) -> crate::Result<()> {
+        self.inner.clipboard_set(text)
+    }
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+    fn set_title(&mut self, title: &str) {
+        self.inner.set_title(title)
+    }
+    fn set_cursor(&mut self, pos: Option<XY>) {
+        self.inner.set_cursor(pos)
+    }
+    fn set_cursor_style(&mut self, style: CursorStyle) {
+        self.inner.set_cursor_style(style)
+    }
+    fn zoom(&mut self, font_size: f32) -> crate::Result<()> {
+        self.inner.zoom(font_size)
+    }
+    fn suspend(&mut self) -> crate::Result<()> {
+        self.inner.suspend()
+    }
+    fn resume(&mut self) -> crate::Result<()> {
+        self.inner.resume()
+    }
+    fn stop(&mut self) {
+        self.inner.stop()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{backends::NopSystem, text, Key};
+
+    use super::*;
+
+    fn record(script: Vec<Action>) -> Record<NopSystem> {
+        let (nop, _run) = NopSystem::new().unwrap();
+        Record::new(nop, script)
+    }
+
+    /// Notes down the calls that have no visible effect on a [`NopSystem`]
+    #[derive(Default)]
+    struct Probe(Vec<String>);
+
+    impl IoSystem for Probe {
+        fn draw(&mut self, _screen: &Screen) -> crate::Result<()> {
+            Ok(())
+        }
+        fn size(&self) -> XY {
+            XY(0, 0)
+        }
+        fn input(&mut self) -> crate::Result<Action> {
+            Ok(Action::Closed)
+        }
+        fn poll_input(&mut self) -> crate::Result<Option<Action>> {
+            Ok(None)
+        }
+        fn zoom(&mut self, font_size: f32) -> crate::Result<()> {
+            self.0.push(alloc::format!("zoom {}", font_size));
+            Ok(())
+        }
+        fn suspend(&mut self) -> crate::Result<()> {
+            self.0.push("suspend".into());
+            Ok(())
+        }
+        fn resume(&mut self) -> crate::Result<()> {
+            self.0.push("resume".into());
+            Ok(())
+        }
+        fn stop(&mut self) {}
+    }
+
+    #[test]
+    fn records_frames_in_order() {
+        let mut rec = record(alloc::vec![]);
+        let mut screen = Screen::new(XY(5, 1));
+        rec.draw(&screen).unwrap();
+        screen.write(XY(0, 0), text!["hi"]);
+        rec.draw(&screen).unwrap();
+        assert_eq!(rec.frames().len(), 2);
+        assert!(rec.frames()[0] == Screen::new(XY(5, 1)));
+        assert!(rec.frames()[1] == screen);
+    }
+
+    #[test]
+    fn script_comes_first() {
+        let mut rec = record(alloc::vec![Action::press(Key::Enter), Action::Closed]);
+        assert_eq!(rec.poll_input().unwrap(), Some(Action::press(Key::Enter)));
+        assert_eq!(rec.input().unwrap(), Action::Closed);
+        assert!(rec.script().is_empty());
+        // then it falls back to nop, which has nothing
+        assert_eq!(rec.poll_input().unwrap(), None);
+    }
+
+    #[test]
+    fn input_until_script_then_timeout() {
+        let mut rec = record(alloc::vec![Action::press(Key::Enter)]);
+        let wait = Duration::from_millis(5);
+        assert_eq!(
+            rec.input_until(wait).unwrap(),
+            Some(Action::press(Key::Enter))
+        );
+        let start = std::time::Instant::now();
+        assert_eq!(rec.input_until(wait).unwrap(), None);
+        assert!(start.elapsed() >= wait);
+    }
+
+    #[test]
+    fn drain_takes_whole_script() {
+        let mut rec = record(alloc::vec![Action::press(Key::Enter), Action::Closed]);
+        assert_eq!(
+            rec.drain_input().unwrap(),
+            [Action::press(Key::Enter), Action::Closed]
+        );
+        assert!(rec.script().is_empty());
+        assert_eq!(rec.drain_input().unwrap(), []);
+    }
+
+    #[test]
+    fn delegates_size() {
+        let rec = record(alloc::vec![]);
+        assert_eq!(rec.size(), XY(80, 24));
+    }
+
+    #[test]
+    fn delegates_capabilities() {
+        let rec = record(alloc::vec![]);
+        // nop doesn't override it, so it's the conservative default
+        assert_eq!(rec.capabilities(), Capabilities::BASIC);
+        assert!(Capabilities::FULL.color > crate::ColorDepth::Ansi256);
+    }
+
+    #[test]
+    fn delegates_suspend_and_resume() {
+        let mut rec = Record::new(Probe::default(), alloc::vec![]);
+        rec.suspend().unwrap();
+        rec.resume().unwrap();
+        assert_eq!(rec.into_inner().0 .0, ["suspend", "resume"]);
+    }
+
+    #[test]
+    fn delegates_zoom() {
+        let mut rec = Record::new(Probe::default(), alloc::vec![]);
+        rec.zoom(18.5).unwrap();
+        assert_eq!(rec.into_inner().0 .0, ["zoom 18.5"]);
+    }
+}
@@ -1,14 +1,18 @@
 //! Contains the miscellaneous types that `Screen` uses.
 
 use core::{
+    fmt,
+    hash::{Hash, Hasher},
     iter::FusedIterator,
     ops::{self, Range},
 };
 
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 
+#[cfg(feature = "std")]
+use crate::fmt::Format;
 use crate::{
-    fmt::{Cell, FormattedExt, Text},
+    fmt::{char_width, Cell, Color, Formatted, FormattedExt, Text, DEFAULT_TAB_WIDTH},
     xy::XY,
 };
 
@@ -64,14 +68,123 @@ impl<'s> DoubleEndedIterator for ScreenRows<'s> {
 impl<'s> ExactSizeIterator for ScreenRows<'s> {}
 impl<'s> FusedIterator for ScreenRows<'s> {}
 
+/// A read-only view into a rectangle of a [`Screen`], e.g. for a backend to render just part of one.
+///
+/// Get one with [`Screen::view`]. Positions are relative to the top-left of the view, and everything is
+/// bounds-checked against the view, not the whole screen. It's just a shared borrow of the screen, so you can have as
+/// many as you like, overlapping or not, as long as nothing's modifying the screen.
+///
+/// [`tuig-ui`](https://crates.io/crates/tuig-ui) has a mutable `ScreenView` for drawing, with the same indexing.
+#[derive(Clone, Copy)]
+pub struct ScreenView<'s> {
+    screen: &'s Screen,
+    pos: XY,
+    size: XY,
+}
+
+impl<'s> ScreenView<'s> {
+    /// Get the size of this view.
+    pub fn size(&self) -> XY {
+        self.size
+    }
+
+    /// Where the top-left of this view is on the original screen.
+    pub fn pos(&self) -> XY {
+        self.pos
+    }
+
+    /// Get a single cell in this view.
+    ///
+    /// This returns `None` if the index is out of bounds.
+    pub fn cell(&self, pos: XY) -> Option<&'s Cell> {
+        self.row(pos.y())?.get(pos.x())
+    }
+
+    /// Get an entire row of cells in this view as a slice.
+    ///
+    /// This returns `None` if the index is out of bounds.
+    pub fn row(&self, idx: usize) -> Option<&'s [Cell]> {
+        if idx >= self.size.y() {
+            return None;
+        }
+        let row = &self.screen[self.pos.y() + idx];
+        Some(&row[self.pos.x()..self.pos.x() + self.size.x()])
+    }
+
+    /// Returns an iterator over the rows in this view.
+    pub fn rows(&self) -> impl Iterator<Item = &'s [Cell]> {
+        let this = *self;
+        (0..self.size.y()).filter_map(move |y| this.row(y))
+    }
+
+    /// Get a smaller view within this one, clipped the same way as [`Screen::view`].
+    pub fn view(&self, rect: (XY, XY)) -> ScreenView<'s> {
+        let (XY(x, y), XY(w, h)) = rect;
+        let x = x.min(self.size.x());
+        let y = y.min(self.size.y());
+        ScreenView {
+            screen: self.screen,
+            pos: self.pos + XY(x, y),
+            size: XY(w.min(self.size.x() - x), h.min(self.size.y() - y)),
+        }
+    }
+}
+
+impl<'s> ops::Index<usize> for ScreenView<'s> {
+    type Output = [Cell];
+    fn index(&self, index: usize) -> &Self::Output {
+        self.row(index).expect("row index is out of bounds")
+    }
+}
+
+impl<'s> ops::Index<XY> for ScreenView<'s> {
+    type Output = Cell;
+    fn index(&self, index: XY) -> &Self::Output {
+        self.cell(index).expect("cell index is out of bounds")
+    }
+}
+
 /// A text framebuffer.
 ///
 /// Allows you to render things onto it, then can be rendered onto the screen. This strategy avoids flickering,
 /// partial renders, etc. and helps deduplicate rendering effort.
-#[derive(Clone, PartialEq, Eq)]
+///
+/// It also keeps track of roughly which cells have changed since [`Self::mark_clean`] was last called, which you can
+/// get with [`Self::dirty_bounds`], so e.g. a backend can skip redrawing parts of the screen it knows are unchanged.
+///
+/// Screens compare and hash by their size and cells, but not that bookkeeping, so two screens with the same contents
+/// are equal however they were drawn. That makes them easy to check against a golden frame in tests, or dedupe in a
+/// recording. Screens of different sizes are simply unequal. Their `Debug` output is the size and [`Self::to_text`].
+#[derive(Clone, Eq)]
 pub struct Screen {
     size: XY,
     pub(crate) cells: Vec<Cell>,
+    /// The top-left and (exclusive) bottom-right corners of the area that might have changed, if any.
+    dirty: Option<(XY, XY)>,
+}
+
+impl PartialEq for Screen {
+    fn eq(&self, other: &Self) -> bool {
+        // dirtiness is bookkeeping, not content
+        self.size == other.size && self.cells == other.cells
+    }
+}
+
+impl Hash for Screen {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // has to match `eq`, so again, not the dirtiness
+        self.size.hash(state);
+        self.cells.hash(state);
+    }
+}
+
+impl fmt::Debug for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Screen")
+            .field("size", &self.size)
+            .field("text", &self.to_text())
+            .finish()
+    }
 }
 
 impl Screen {
@@ -80,6 +193,7 @@ impl Screen {
         let mut res = Self {
             cells: alloc::vec![],
             size: XY(0, 0),
+            dirty: None,
         };
         res.resize(size);
         res
@@ -105,15 +219,111 @@ impl Screen {
     }
 
     /// All of the cells of this screen, in row-major order, mutably.
+    ///
+    /// Since there's no telling what you'll change, this marks the whole screen dirty.
     pub fn cells_mut(&mut self) -> &mut [Cell] {
+        self.touch_all();
         &mut self.cells
     }
 
+    /// Returns an iterator over every cell in the screen, in row-major order, along with its position.
+    pub fn iter_cells(&self) -> impl Iterator<Item = (XY, &Cell)> {
+        let width = self.size.x();
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (XY(i % width, i / width), cell))
+    }
+
+    /// Returns an iterator over every cell in the screen, in row-major order, along with its position, mutably.
+    pub fn iter_cells_mut(&mut self) -> impl Iterator<Item = (XY, &mut Cell)> {
+        self.touch_all();
+        let width = self.size.x();
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .map(move |(i, cell)| (XY(i % width, i / width), cell))
+    }
+
     /// Returns an iterator over the rows in a screen.
     pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
         ScreenRows::new(self)
     }
 
+    /// Every cell that's different from `prev`, with its position, in row-major order, e.g. to send a compact update
+    /// to a remote display.
+    ///
+    /// If the screens are different sizes, there's no meaningful per-cell difference, so this returns nothing at
+    /// all. Check the sizes yourself first, and send the whole screen if they don't match.
+    pub fn diff(&self, prev: &Screen) -> Vec<(XY, Cell)> {
+        if self.size != prev.size {
+            return Vec::new();
+        }
+        let width = self.size.x();
+        self.cells
+            .iter()
+            .zip(&prev.cells)
+            .enumerate()
+            .filter(|(_, (new, old))| new != old)
+            .map(|(i, (new, _))| (XY(i % width, i / width), new.clone()))
+            .collect()
+    }
+
+    /// Write every cell from a [`Self::diff`], e.g. to rebuild a remote display from a base frame plus deltas.
+    ///
+    /// Positions outside the screen are skipped, and this returns how many there were, so you can tell if the delta
+    /// was meant for a screen of a different size.
+    pub fn apply_diff(&mut self, delta: &[(XY, Cell)]) -> usize {
+        let mut skipped = 0;
+        for (pos, cell) in delta {
+            if pos.x() >= self.size.x() || pos.y() >= self.size.y() {
+                skipped += 1;
+                continue;
+            }
+            self.cells[pos.y() * self.size.x() + pos.x()] = cell.clone();
+            self.touch(*pos, *pos + XY(1, 1));
+        }
+        skipped
+    }
+
+    /// The visible text on the screen, with all the formatting stripped, e.g. for screen readers, logging, or tests.
+    ///
+    /// Each row becomes one line, with trailing blanks trimmed off, and the lines are joined with `\n`. Wide
+    /// characters only appear once, since their [continuation](Cell::CONTINUATION) cells are skipped, and a stray
+    /// continuation is a blank, just like it's rendered.
+    pub fn to_text(&self) -> String {
+        let mut res = String::with_capacity(self.cells.len() + self.size.y());
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                res.push('\n');
+            }
+            let mut prev_wide = false;
+            for cell in row {
+                match cell.ch {
+                    Cell::CONTINUATION if prev_wide => (),
+                    Cell::CONTINUATION => res.push(' '),
+                    ch => res.push(ch),
+                }
+                prev_wide = char_width(cell.ch) == 2;
+            }
+            res.truncate(res.trim_end_matches(' ').len());
+        }
+        res
+    }
+
+    /// Get a read-only view of a rectangle of this screen, given as its top-left corner and size.
+    ///
+    /// Anything past the edge of the screen is clipped, rather than panicking, so the view might be smaller than
+    /// requested, or even empty.
+    pub fn view(&self, rect: (XY, XY)) -> ScreenView<'_> {
+        ScreenView {
+            screen: self,
+            pos: XY(0, 0),
+            size: self.size,
+        }
+        .view(rect)
+    }
+
     /// Clear this screen's contents, resetting it to the default and filling it with blank cells.
     pub fn clear(&mut self) {
         self.resize(self.size())
@@ -124,22 +334,598 @@ impl Screen {
         self.cells.truncate(0);
         self.cells.resize(size.x() * size.y(), Cell::BLANK);
         self.size = size;
+        self.touch_all();
+    }
+
+    /// Mark the whole screen as unchanged, so [`Self::dirty_bounds`] only reports changes made after this.
+    ///
+    /// Typically you'd call this right after rendering the screen.
+    pub fn mark_clean(&mut self) {
+        self.dirty = None;
+    }
+
+    /// The area of the screen that's changed since the last [`Self::mark_clean`], as its top-left corner and size, or
+    /// `None` if nothing has.
+    ///
+    /// This is a bounding box, and it's conservative: every changed cell is inside it, but not every cell inside it
+    /// necessarily changed. Methods that draw a specific area, like [`Self::write`] or [`Self::fill`], only mark that
+    /// area; indexing mutably marks the whole row, and methods like [`Self::cells_mut`] mark everything. A screen
+    /// that's never been marked clean is entirely dirty.
+    pub fn dirty_bounds(&self) -> Option<(XY, XY)> {
+        let (start, end) = self.dirty?;
+        Some((start, end - start))
+    }
+
+    /// Expand the dirty area to include the rectangle from `start` to `end`, exclusive.
+    fn touch(&mut self, start: XY, end: XY) {
+        if start.x() >= end.x() || start.y() >= end.y() {
+            return;
+        }
+        self.dirty = Some(match self.dirty {
+            None => (start, end),
+            Some((s, e)) => (
+                XY(s.x().min(start.x()), s.y().min(start.y())),
+                XY(e.x().max(end.x()), e.y().max(end.y())),
+            ),
+        });
+    }
+
+    fn touch_all(&mut self) {
+        self.touch(XY(0, 0), self.size);
     }
 
     /// Write some formatted text to the position on screen.
     ///
     /// This **does not** handle newlines or anything else. If you want that, use a UI widget.
+    ///
+    /// Characters are placed according to their [display width](crate::fmt::char_width): wide characters take up two
+    /// cells (the second being a [continuation](Cell::CONTINUATION)), and zero-width characters are dropped. A wide
+    /// character that would be cut off by the right edge of the screen is replaced with a space.
+    ///
+    /// Tabs move on to the next [tab stop](crate::fmt::DEFAULT_TAB_WIDTH), counting from the left edge, filling the
+    /// gap with spaces in the tab's format. To use a different tab width, [expand](crate::fmt::expand_tabs) the
+    /// tabs first.
     pub fn write(&mut self, pos: XY, text: Vec<Text>) {
-        let XY(mut x, y) = pos;
-        for chunk in text {
-            for char in chunk.text.chars() {
-                self[y][x] = Cell::of(char).fmt_of(&chunk);
-                x += 1;
+        let width = self.size.x();
+        let start = pos.y() * width;
+        let end = write_row(&mut self.cells[start..start + width], pos.x(), text);
+        self.touch(pos, XY(end.min(width), pos.y() + 1));
+    }
+
+    /// Fill a rectangle of this screen with copies of one cell.
+    ///
+    /// Anything past the edge of the screen is clipped, rather than panicking.
+    pub fn fill(&mut self, top_left: XY, size: XY, cell: Cell) {
+        let size = fill_rect(&mut self.cells, self.size, top_left, size, cell);
+        self.touch(top_left, top_left + size);
+    }
+
+    /// Fill the entire screen with copies of one cell.
+    pub fn fill_all(&mut self, cell: Cell) {
+        self.cells.fill(cell);
+        self.touch_all();
+    }
+
+    /// Change the background color of a rectangle of this screen, given as its top-left corner and size, leaving
+    /// the characters and the rest of their formatting alone, e.g. to highlight a selection.
+    ///
+    /// Anything past the edge of the screen is clipped, rather than panicking.
+    pub fn set_bg(&mut self, rect: (XY, XY), color: Color) {
+        let size = edit_rect(&mut self.cells, self.size, rect.0, rect.1, |row| {
+            recolor(row, color, true)
+        });
+        self.touch(rect.0, rect.0 + size);
+    }
+
+    /// Change the foreground color of a rectangle of this screen, like [`Self::set_bg`] does the background.
+    pub fn set_fg(&mut self, rect: (XY, XY), color: Color) {
+        let size = edit_rect(&mut self.cells, self.size, rect.0, rect.1, |row| {
+            recolor(row, color, false)
+        });
+        self.touch(rect.0, rect.0 + size);
+    }
+
+    /// Move the whole contents of the screen over by `dx` columns and `dy` rows, filling the space left behind with
+    /// copies of `fill`. Anything moved past the edge is dropped.
+    ///
+    /// Positive numbers move content right or down; negative, left or up. Shifting by at least the width or height
+    /// fills the whole screen. This happens in place, without reallocating, so it's cheap enough to scroll a log one
+    /// line at a time. See also [`Self::scroll_up`] and friends, which fill with [`Cell::BLANK`].
+    pub fn shift(&mut self, dx: isize, dy: isize, fill: Cell) {
+        let XY(w, h) = self.size;
+        let (ax, ay) = (dx.unsigned_abs(), dy.unsigned_abs());
+        if ax >= w || ay >= h {
+            self.fill_all(fill);
+            return;
+        }
+        if ay != 0 {
+            let n = ay * w;
+            let len = self.cells.len();
+            if dy < 0 {
+                self.cells.rotate_left(n);
+                self.cells[len - n..].fill(fill.clone());
+            } else {
+                self.cells.rotate_right(n);
+                self.cells[..n].fill(fill.clone());
+            }
+        }
+        if ax != 0 {
+            for row in self.cells.chunks_exact_mut(w) {
+                if dx < 0 {
+                    row.rotate_left(ax);
+                    row[w - ax..].fill(fill.clone());
+                } else {
+                    row.rotate_right(ax);
+                    row[..ax].fill(fill.clone());
+                }
+            }
+        }
+        if ax != 0 || ay != 0 {
+            self.touch_all();
+        }
+    }
+
+    /// Move everything on screen up by some number of rows, blanking the rows at the bottom. See [`Self::shift`].
+    pub fn scroll_up(&mut self, rows: usize) {
+        self.shift(0, -(rows.min(self.size.y()) as isize), Cell::BLANK)
+    }
+
+    /// Move everything on screen down by some number of rows, blanking the rows at the top. See [`Self::shift`].
+    pub fn scroll_down(&mut self, rows: usize) {
+        self.shift(0, rows.min(self.size.y()) as isize, Cell::BLANK)
+    }
+
+    /// Move everything on screen left by some number of columns, blanking the columns at the right. See
+    /// [`Self::shift`].
+    pub fn scroll_left(&mut self, cols: usize) {
+        self.shift(-(cols.min(self.size.x()) as isize), 0, Cell::BLANK)
+    }
+
+    /// Move everything on screen right by some number of columns, blanking the columns at the left. See
+    /// [`Self::shift`].
+    pub fn scroll_right(&mut self, cols: usize) {
+        self.shift(cols.min(self.size.x()) as isize, 0, Cell::BLANK)
+    }
+
+    /// Copy a rectangle of cells from another screen onto this one.
+    ///
+    /// `src_rect` is the top-left corner and size of the area in `src` to copy, and `dst` is where its top-left corner
+    /// ends up on this screen. Anything that falls outside of either screen is clipped, rather than panicking, so
+    /// e.g. a `dst` entirely off this screen just does nothing.
+    pub fn blit(&mut self, src: &Screen, src_rect: (XY, XY), dst: XY) {
+        let (XY(sx, sy), XY(w, h)) = src_rect;
+        // clip against the source...
+        let w = w.min(src.size.x().saturating_sub(sx));
+        let h = h.min(src.size.y().saturating_sub(sy));
+        // ...then against the destination
+        let w = w.min(self.size.x().saturating_sub(dst.x()));
+        let h = h.min(self.size.y().saturating_sub(dst.y()));
+        if w == 0 {
+            return;
+        }
+        for row in 0..h {
+            let from = &src[sy + row][sx..sx + w];
+            let start = (dst.y() + row) * self.size.x() + dst.x();
+            self.cells[start..start + w].clone_from_slice(from);
+        }
+        self.touch(dst, dst + XY(w, h));
+    }
+
+    /// Copy out a rectangle of this screen, given as its top-left corner and size, to [`Self::restore`] later.
+    ///
+    /// This is meant for transient overlays, like popups: save what's under one before drawing it, then put it back
+    /// when it closes, without redrawing everything behind it. Anything past the edge of the screen is clipped, so
+    /// the patch might be smaller than requested, or even empty.
+    pub fn snapshot(&self, rect: (XY, XY)) -> ScreenPatch {
+        let (pos, XY(w, h)) = rect;
+        let w = w.min(self.size.x().saturating_sub(pos.x()));
+        let h = h.min(self.size.y().saturating_sub(pos.y()));
+        let mut cells = Vec::with_capacity(w * h);
+        // a rectangle entirely off to the right can't be sliced out of the rows at all
+        if w > 0 {
+            for row in pos.y()..pos.y() + h {
+                cells.extend_from_slice(&self[row][pos.x()..pos.x() + w]);
             }
         }
+        ScreenPatch {
+            pos,
+            size: XY(w, h),
+            cells,
+        }
+    }
+
+    /// Write a [`Self::snapshot`] back where it was taken from.
+    ///
+    /// If the screen has shrunk since, the parts of the patch that no longer fit are clipped, rather than panicking.
+    pub fn restore(&mut self, patch: &ScreenPatch) {
+        let XY(x, y) = patch.pos;
+        let w = patch.size.x().min(self.size.x().saturating_sub(x));
+        let h = patch.size.y().min(self.size.y().saturating_sub(y));
+        if w == 0 {
+            return;
+        }
+        for (row, from) in patch.cells.chunks_exact(patch.size.x()).take(h).enumerate() {
+            let start = (y + row) * self.size.x() + x;
+            self.cells[start..start + w].clone_from_slice(&from[..w]);
+        }
+        self.touch(patch.pos, patch.pos + XY(w, h));
     }
 }
 
+/// The cells of a rectangle of a [`Screen`], copied out with [`Screen::snapshot`] to be put back with
+/// [`Screen::restore`].
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ScreenPatch {
+    pos: XY,
+    size: XY,
+    cells: Vec<Cell>,
+}
+
+impl ScreenPatch {
+    /// Where the patch's top-left corner was on the screen.
+    pub fn pos(&self) -> XY {
+        self.pos
+    }
+
+    /// How big the patch is, after clipping to the screen it was taken from.
+    pub fn size(&self) -> XY {
+        self.size
+    }
+
+    /// The patch's cells, in row-major order.
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+}
+
+/// Write formatted text into a row of cells, starting at `x`, and return where it ended. See [`Screen::write`] for
+/// the details.
+fn write_row(row: &mut [Cell], mut x: usize, text: Vec<Text>) -> usize {
+    for chunk in text {
+        for char in chunk.text.chars() {
+            if char == '\t' {
+                let stop = (x / DEFAULT_TAB_WIDTH + 1) * DEFAULT_TAB_WIDTH;
+                // clipped at the edge, like the tab stop is past it
+                let end = stop.min(row.len());
+                if x < end {
+                    row[x..end].fill(Cell::of(' ').fmt_of(&chunk));
+                }
+                x = stop;
+                continue;
+            }
+            match char_width(char) {
+                0 => (),
+                2 if x + 1 < row.len() => {
+                    row[x] = Cell::of(char).fmt_of(&chunk);
+                    row[x + 1] = Cell::of(Cell::CONTINUATION).fmt_of(&chunk);
+                    x += 2;
+                }
+                2 => {
+                    row[x] = Cell::of(' ').fmt_of(&chunk);
+                    x += 2;
+                }
+                _ => {
+                    row[x] = Cell::of(char).fmt_of(&chunk);
+                    x += 1;
+                }
+            }
+        }
+    }
+    x
+}
+
+/// Fill a clipped rectangle of a row-major grid of cells, and return the clipped size. See [`Screen::fill`] for the
+/// details.
+fn fill_rect(cells: &mut [Cell], grid: XY, top_left: XY, size: XY, cell: Cell) -> XY {
+    edit_rect(cells, grid, top_left, size, |row| row.fill(cell.clone()))
+}
+
+/// Run something on each row of a clipped rectangle of a row-major grid of cells, and return the clipped size.
+fn edit_rect(
+    cells: &mut [Cell],
+    grid: XY,
+    top_left: XY,
+    size: XY,
+    mut edit: impl FnMut(&mut [Cell]),
+) -> XY {
+    let w = size.x().min(grid.x().saturating_sub(top_left.x()));
+    let h = size.y().min(grid.y().saturating_sub(top_left.y()));
+    for row in top_left.y()..top_left.y() + h {
+        let start = row * grid.x() + top_left.x();
+        edit(&mut cells[start..start + w]);
+    }
+    XY(w, h)
+}
+
+/// Set the foreground or background color of every cell in a row.
+fn recolor(row: &mut [Cell], color: Color, bg: bool) {
+    for cell in row {
+        let fmt = cell.get_fmt_mut();
+        match bg {
+            true => fmt.bg = color,
+            false => fmt.fg = color,
+        }
+    }
+}
+
+/// A text framebuffer like [`Screen`], but with its cells stored in a slice you provide, rather than on the heap.
+///
+/// This is meant for constrained environments which can't (or would rather not) allocate the whole grid. It uses the
+/// same [`Cell`]s and [`fmt`](crate::fmt) types, and has the same indexing and drawing API, so code written against
+/// one is easy to point at the other. [`Screen`] is still the one to use if you have an allocator to spare.
+///
+/// ```
+/// # use tuig_iosys::{fmt::Cell, text, SliceScreen, XY};
+/// let mut cells = [Cell::BLANK; 12];
+/// let mut screen = SliceScreen::new(&mut cells, XY(4, 3));
+/// screen.write(XY(1, 1), text!("hi"));
+/// assert_eq!(screen[1][2].ch, 'i');
+/// ```
+pub struct SliceScreen<'c> {
+    size: XY,
+    cells: &'c mut [Cell],
+}
+
+impl<'c> SliceScreen<'c> {
+    /// Wrap a slice of cells, in row-major order, as a screen of the given size.
+    ///
+    /// The existing contents are kept, so [`Self::clear`] it first if you want a blank screen. This panics if the
+    /// slice isn't exactly `size.x() * size.y()` cells long.
+    pub fn new(cells: &'c mut [Cell], size: XY) -> Self {
+        assert_eq!(
+            cells.len(),
+            size.x() * size.y(),
+            "slice should have exactly one cell per position"
+        );
+        Self { size, cells }
+    }
+
+    /// How big this screen is, in characters.
+    pub fn size(&self) -> XY {
+        self.size
+    }
+
+    /// All of the cells of this screen, in row-major order, like [`Screen::cells`].
+    pub fn cells(&self) -> &[Cell] {
+        self.cells
+    }
+
+    /// All of the cells of this screen, in row-major order, mutably.
+    pub fn cells_mut(&mut self) -> &mut [Cell] {
+        self.cells
+    }
+
+    /// Give back the slice this screen was made from.
+    pub fn into_cells(self) -> &'c mut [Cell] {
+        self.cells
+    }
+
+    /// Returns an iterator over every cell in the screen, in row-major order, along with its position.
+    pub fn iter_cells(&self) -> impl Iterator<Item = (XY, &Cell)> {
+        let width = self.size.x();
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| (XY(i % width, i / width), cell))
+    }
+
+    /// Returns an iterator over the rows in the screen.
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        (0..self.size.y()).map(move |y| &self[y])
+    }
+
+    /// Clear this screen's contents, filling it with blank cells.
+    pub fn clear(&mut self) {
+        self.fill_all(Cell::BLANK)
+    }
+
+    /// Write some formatted text to the position on screen, exactly like [`Screen::write`], except that tab stops are
+    /// counted from this view's left edge.
+    pub fn write(&mut self, pos: XY, text: Vec<Text>) {
+        write_row(&mut self[pos.y()], pos.x(), text);
+    }
+
+    /// Fill a rectangle of this screen with copies of one cell, clipped like [`Screen::fill`].
+    pub fn fill(&mut self, top_left: XY, size: XY, cell: Cell) {
+        fill_rect(self.cells, self.size, top_left, size, cell);
+    }
+
+    /// Fill the entire screen with copies of one cell.
+    pub fn fill_all(&mut self, cell: Cell) {
+        self.cells.fill(cell);
+    }
+
+    /// Change the background color of a rectangle of this screen, like [`Screen::set_bg`].
+    pub fn set_bg(&mut self, rect: (XY, XY), color: Color) {
+        edit_rect(self.cells, self.size, rect.0, rect.1, |row| {
+            recolor(row, color, true)
+        });
+    }
+
+    /// Change the foreground color of a rectangle of this screen, like [`Screen::set_fg`].
+    pub fn set_fg(&mut self, rect: (XY, XY), color: Color) {
+        edit_rect(self.cells, self.size, rect.0, rect.1, |row| {
+            recolor(row, color, false)
+        });
+    }
+}
+
+impl<'c> ops::Index<usize> for SliceScreen<'c> {
+    type Output = [Cell];
+    fn index(&self, row: usize) -> &Self::Output {
+        let start = row * self.size.x();
+        let end = start + self.size.x();
+        &self.cells[start..end]
+    }
+}
+
+impl<'c> ops::IndexMut<usize> for SliceScreen<'c> {
+    fn index_mut(&mut self, row: usize) -> &mut Self::Output {
+        let start = row * self.size.x();
+        let end = start + self.size.x();
+        &mut self.cells[start..end]
+    }
+}
+
+/// The magic bytes at the start of a saved [`Screen`].
+#[cfg(feature = "std")]
+const SAVE_MAGIC: &[u8; 4] = b"TUIG";
+/// The current version of the saved [`Screen`] format.
+///
+/// Version 2 added RGB colors, version 3 added indexed colors, and version 4 added links. Otherwise they're identical
+/// to version 1, so they all load the same way.
+#[cfg(feature = "std")]
+const SAVE_VERSION: u8 = 4;
+/// The color code marking an RGB color in a saved [`Screen`]. It's followed by the red, green, and blue bytes.
+#[cfg(feature = "std")]
+const SAVE_RGB: u8 = 0xff;
+/// The color code marking an indexed color in a saved [`Screen`]. It's followed by the index.
+#[cfg(feature = "std")]
+const SAVE_INDEXED: u8 = 0xfe;
+/// The flag bit marking a linked cell in a saved [`Screen`]. The flags are followed by the URL's length and bytes.
+#[cfg(feature = "std")]
+const SAVE_LINK: u8 = 8;
+
+#[cfg(feature = "std")]
+impl Screen {
+    /// Save this screen to a simple, portable binary format, which can be read back with [`Screen::load`].
+    ///
+    /// The format is the magic bytes `TUIG`, a version byte, the width and height as little-endian `u32`s, then each
+    /// cell in row-major order: its character as a little-endian `u32`, the foreground and background [`Color`]s,
+    /// and a byte of flags (bit 0 is bold, bit 1 is underline, bit 2 is blink, bit 3 is having a
+    /// [link](crate::fmt::Format::link)). Each color is its [ANSI code](Color::ansi_code), or for [`Color::Rgb`],
+    /// `0xff` followed by the red, green, and blue bytes, or for [`Color::Indexed`], `0xfe` followed by the index. A
+    /// linked cell's flags are followed by the URL's length in bytes, as a little-endian `u32`, and then the URL.
+    ///
+    /// [`Color`]: crate::fmt::Color
+    pub fn save(&self, w: &mut impl std::io::Write) -> std::io::Result<()> {
+        w.write_all(SAVE_MAGIC)?;
+        w.write_all(&[SAVE_VERSION])?;
+        for dim in [self.size.x(), self.size.y()] {
+            let dim = u32::try_from(dim).map_err(|_| invalid("screen too big to save"))?;
+            w.write_all(&dim.to_le_bytes())?;
+        }
+        for cell in &self.cells {
+            let fmt = cell.get_fmt();
+            let flags = fmt.bold as u8
+                | (fmt.underline as u8) << 1
+                | (fmt.blink as u8) << 2
+                | if fmt.link.is_some() { SAVE_LINK } else { 0 };
+            w.write_all(&(cell.ch as u32).to_le_bytes())?;
+            for color in [fmt.fg, fmt.bg] {
+                match color {
+                    Color::Rgb(r, g, b) => w.write_all(&[SAVE_RGB, r, g, b])?,
+                    Color::Indexed(idx) => w.write_all(&[SAVE_INDEXED, idx])?,
+                    // every other color is a named one
+                    named => w.write_all(&[named.ansi_code().unwrap()])?,
+                }
+            }
+            w.write_all(&[flags])?;
+            if let Some(url) = &fmt.link {
+                let len = u32::try_from(url.len()).map_err(|_| invalid("link too long to save"))?;
+                w.write_all(&len.to_le_bytes())?;
+                w.write_all(url.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load a screen saved with [`Screen::save`].
+    ///
+    /// If the data isn't a valid saved screen, this returns an error of kind [`InvalidData`].
+    ///
+    /// [`InvalidData`]: std::io::ErrorKind::InvalidData
+    pub fn load(r: &mut impl std::io::Read) -> std::io::Result<Screen> {
+        let mut header = [0; 13];
+        r.read_exact(&mut header)?;
+        if &header[..4] != SAVE_MAGIC {
+            return Err(invalid("not a saved screen"));
+        }
+        if !(1..=SAVE_VERSION).contains(&header[4]) {
+            return Err(invalid("unsupported saved screen version"));
+        }
+        let dim = |i: usize| {
+            let bytes = [header[i], header[i + 1], header[i + 2], header[i + 3]];
+            usize::try_from(u32::from_le_bytes(bytes))
+                .map_err(|_| invalid("screen too big to load"))
+        };
+        let size = XY(dim(5)?, dim(9)?);
+        let count = size
+            .x()
+            .checked_mul(size.y())
+            .ok_or_else(|| invalid("screen too big to load"))?;
+        // don't trust the header enough to preallocate everything it claims
+        let mut cells = Vec::with_capacity(count.min(1 << 16));
+        let color = |src: &mut dyn std::io::Read| -> std::io::Result<Color> {
+            let mut code = [0; 1];
+            src.read_exact(&mut code)?;
+            if code[0] == SAVE_RGB {
+                let mut rgb = [0; 3];
+                src.read_exact(&mut rgb)?;
+                return Ok(Color::Rgb(rgb[0], rgb[1], rgb[2]));
+            }
+            if code[0] == SAVE_INDEXED {
+                src.read_exact(&mut code)?;
+                return Ok(Color::Indexed(code[0]));
+            }
+            Color::all()
+                .into_iter()
+                .find(|c| c.ansi_code() == Some(code[0]))
+                .ok_or_else(|| invalid("invalid color in saved screen"))
+        };
+        // consecutive cells usually share a link, so they can share the allocation too
+        let mut last_link: Option<alloc::sync::Arc<str>> = None;
+        for _ in 0..count {
+            let mut buf = [0; 4];
+            r.read_exact(&mut buf)?;
+            let ch = char::from_u32(u32::from_le_bytes(buf))
+                .ok_or_else(|| invalid("invalid character in saved screen"))?;
+            let fg = color(r)?;
+            let bg = color(r)?;
+            let mut flags = [0; 1];
+            r.read_exact(&mut flags)?;
+            let mut link = None;
+            if flags[0] & SAVE_LINK != 0 {
+                r.read_exact(&mut buf)?;
+                let len = u32::from_le_bytes(buf) as u64;
+                let mut bytes = Vec::new();
+                // again, don't trust the length enough to allocate it all up front
+                let read =
+                    std::io::Read::read_to_end(&mut std::io::Read::take(&mut *r, len), &mut bytes)?;
+                if read as u64 != len {
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                }
+                let url = String::from_utf8(bytes)
+                    .map_err(|_| invalid("invalid link in saved screen"))?;
+                match &last_link {
+                    Some(last) if **last == *url => (),
+                    _ => last_link = Some(url.into()),
+                }
+                link = last_link.clone();
+            }
+            let fmt = Format {
+                fg,
+                bg,
+                bold: flags[0] & 1 != 0,
+                underline: flags[0] & 2 != 0,
+                blink: flags[0] & 4 != 0,
+                link,
+            };
+            cells.push(Cell::of(ch).fmt(fmt));
+        }
+        Ok(Screen {
+            size,
+            cells,
+            dirty: Some((XY(0, 0), size)),
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+fn invalid(msg: &'static str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
 impl ops::Index<usize> for Screen {
     type Output = [Cell];
     fn index(&self, row: usize) -> &Self::Output {
@@ -151,6 +937,7 @@ impl ops::Index<usize> for Screen {
 
 impl ops::IndexMut<usize> for Screen {
     fn index_mut(&mut self, row: usize) -> &mut Self::Output {
+        self.touch(XY(0, row), XY(self.size.x(), row + 1));
         let start = row * self.size.x();
         let end = start + self.size.x();
         &mut self.cells[start..end]
@@ -162,3 +949,531 @@ impl Default for Screen {
         Self::new(XY(0, 0))
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{cell, text};
+
+    use super::*;
+
+    fn chars(row: &[Cell]) -> alloc::string::String {
+        row.iter().map(|c| c.ch).collect()
+    }
+
+    fn numbered(size: XY) -> Screen {
+        let mut screen = Screen::new(size);
+        for (i, cell) in screen.cells_mut().iter_mut().enumerate() {
+            cell.ch = char::from_digit(i as u32 % 36, 36).unwrap();
+        }
+        screen
+    }
+
+    #[test]
+    fn iter_cells_row_major() {
+        let screen = numbered(XY(3, 2));
+        let cells: Vec<_> = screen.iter_cells().map(|(pos, c)| (pos, c.ch)).collect();
+        assert_eq!(
+            cells,
+            [
+                (XY(0, 0), '0'),
+                (XY(1, 0), '1'),
+                (XY(2, 0), '2'),
+                (XY(0, 1), '3'),
+                (XY(1, 1), '4'),
+                (XY(2, 1), '5'),
+            ]
+        );
+        assert_eq!(Screen::new(XY(0, 0)).iter_cells().count(), 0);
+    }
+
+    #[test]
+    fn iter_cells_mut_edits() {
+        let mut screen = Screen::new(XY(3, 2));
+        for (pos, cell) in screen.iter_cells_mut() {
+            if pos.x() == pos.y() {
+                cell.ch = '#';
+            }
+        }
+        assert_eq!(chars(&screen[0]), "#  ");
+        assert_eq!(chars(&screen[1]), " # ");
+    }
+
+    #[test]
+    fn view_is_offset() {
+        let screen = numbered(XY(4, 4));
+        let view = screen.view((XY(1, 2), XY(2, 2)));
+        assert_eq!(view.size(), XY(2, 2));
+        assert_eq!(view.pos(), XY(1, 2));
+        assert_eq!(chars(&view[0]), "9a");
+        assert_eq!(chars(&view[1]), "de");
+        assert_eq!(view[XY(1, 1)].ch, 'e');
+        assert_eq!(view.cell(XY(2, 0)), None);
+        assert_eq!(view.row(2), None);
+        let rows: Vec<_> = view.rows().map(chars).collect();
+        assert_eq!(rows, ["9a", "de"]);
+    }
+
+    #[test]
+    fn view_clips() {
+        let screen = numbered(XY(4, 4));
+        let view = screen.view((XY(2, 3), XY(10, 10)));
+        assert_eq!(view.size(), XY(2, 1));
+        assert_eq!(chars(&view[0]), "ef");
+        assert_eq!(screen.view((XY(7, 7), XY(1, 1))).size(), XY(0, 0));
+        let inner = view.view((XY(1, 0), XY(5, 5)));
+        assert_eq!(inner.pos(), XY(3, 3));
+        assert_eq!(chars(&inner[0]), "f");
+    }
+
+    #[test]
+    fn fill_paints_rect() {
+        let mut screen = Screen::new(XY(4, 3));
+        screen.fill(XY(1, 1), XY(2, 2), cell!(on_red '#'));
+        assert_eq!(chars(&screen[0]), "    ");
+        assert_eq!(chars(&screen[1]), " ## ");
+        assert_eq!(chars(&screen[2]), " ## ");
+        assert_eq!(screen[1][1], cell!(on_red '#'));
+    }
+
+    #[test]
+    fn fill_clips() {
+        let mut screen = Screen::new(XY(4, 3));
+        screen.fill(XY(2, 1), XY(10, 10), cell!('#'));
+        assert_eq!(chars(&screen[0]), "    ");
+        assert_eq!(chars(&screen[1]), "  ##");
+        assert_eq!(chars(&screen[2]), "  ##");
+        screen.fill(XY(4, 0), XY(1, 1), cell!('!'));
+        screen.fill(XY(0, 3), XY(1, 1), cell!('!'));
+        assert_eq!(chars(&screen[0]), "    ");
+    }
+
+    #[test]
+    fn fill_zero_size_is_noop() {
+        let mut screen = Screen::new(XY(4, 3));
+        screen.fill(XY(1, 1), XY(0, 2), cell!('#'));
+        screen.fill(XY(1, 1), XY(2, 0), cell!('#'));
+        assert!(screen == Screen::new(XY(4, 3)));
+    }
+
+    #[test]
+    fn fill_all_fills_everything() {
+        let mut screen = Screen::new(XY(4, 3));
+        screen.fill_all(cell!(blue 'x'));
+        assert!(screen.cells().iter().all(|c| *c == cell!(blue 'x')));
+    }
+
+    #[test]
+    fn blit_copies_rect() {
+        let src = numbered(XY(4, 4));
+        let mut dst = Screen::new(XY(5, 3));
+        dst.blit(&src, (XY(1, 1), XY(2, 2)), XY(2, 1));
+        assert_eq!(chars(&dst[0]), "     ");
+        assert_eq!(chars(&dst[1]), "  56 ");
+        assert_eq!(chars(&dst[2]), "  9a ");
+    }
+
+    #[test]
+    fn blit_clips_right_and_bottom_of_dst() {
+        let src = numbered(XY(4, 4));
+        let mut dst = Screen::new(XY(3, 3));
+        dst.blit(&src, (XY(0, 0), XY(4, 4)), XY(1, 1));
+        assert_eq!(chars(&dst[0]), "   ");
+        assert_eq!(chars(&dst[1]), " 01");
+        assert_eq!(chars(&dst[2]), " 45");
+    }
+
+    #[test]
+    fn blit_clips_right_and_bottom_of_src() {
+        let src = numbered(XY(4, 4));
+        let mut dst = Screen::new(XY(4, 4));
+        dst.blit(&src, (XY(2, 2), XY(10, 10)), XY(0, 0));
+        assert_eq!(chars(&dst[0]), "ab  ");
+        assert_eq!(chars(&dst[1]), "ef  ");
+        assert_eq!(chars(&dst[2]), "    ");
+    }
+
+    #[test]
+    fn blit_src_rect_outside_src_is_noop() {
+        let src = numbered(XY(4, 4));
+        let mut dst = Screen::new(XY(4, 4));
+        dst.blit(&src, (XY(4, 0), XY(2, 2)), XY(0, 0));
+        dst.blit(&src, (XY(0, 7), XY(2, 2)), XY(0, 0));
+        assert!(dst == Screen::new(XY(4, 4)));
+    }
+
+    #[test]
+    fn blit_dst_outside_is_noop() {
+        let src = numbered(XY(4, 4));
+        let mut dst = Screen::new(XY(4, 4));
+        dst.blit(&src, (XY(0, 0), XY(4, 4)), XY(4, 0));
+        dst.blit(&src, (XY(0, 0), XY(4, 4)), XY(0, 4));
+        dst.blit(&src, (XY(0, 0), XY(4, 4)), XY(100, 100));
+        assert!(dst == Screen::new(XY(4, 4)));
+    }
+
+    #[test]
+    fn snapshot_restores_exactly() {
+        let mut screen = numbered(XY(5, 4));
+        screen.write(XY(0, 2), text!(red "漢"));
+        let before = screen.clone();
+        let patch = screen.snapshot((XY(0, 1), XY(3, 2)));
+        assert_eq!((patch.pos(), patch.size()), (XY(0, 1), XY(3, 2)));
+        screen.fill(XY(0, 1), XY(3, 2), cell!(blue '#'));
+        screen.mark_clean();
+        screen.restore(&patch);
+        assert_eq!(screen, before);
+        assert_eq!(screen.dirty_bounds(), Some((XY(0, 1), XY(3, 2))));
+    }
+
+    #[test]
+    fn snapshot_and_restore_clip() {
+        let mut screen = numbered(XY(4, 3));
+        let patch = screen.snapshot((XY(2, 1), XY(10, 10)));
+        assert_eq!(patch.size(), XY(2, 2));
+        assert_eq!(
+            patch.cells().iter().map(|c| c.ch).collect::<String>(),
+            "67ab"
+        );
+        assert_eq!(screen.snapshot((XY(4, 0), XY(2, 2))).size(), XY(0, 2));
+        // entirely off the screen, in either direction
+        let right = screen.snapshot((XY(10, 0), XY(2, 2)));
+        assert_eq!(right.size(), XY(0, 2));
+        assert!(right.cells().is_empty());
+        let below = screen.snapshot((XY(0, 10), XY(2, 2)));
+        assert_eq!(below.size(), XY(2, 0));
+        assert!(below.cells().is_empty());
+        screen.restore(&right);
+        screen.restore(&below);
+        screen.resize(XY(3, 2));
+        screen.restore(&patch);
+        assert_eq!(chars(&screen[1]), "  6");
+    }
+
+    #[test]
+    fn blit_zero_size_is_noop() {
+        let src = numbered(XY(4, 4));
+        let mut dst = Screen::new(XY(4, 4));
+        dst.blit(&src, (XY(1, 1), XY(0, 3)), XY(0, 0));
+        dst.blit(&src, (XY(1, 1), XY(3, 0)), XY(0, 0));
+        assert!(dst == Screen::new(XY(4, 4)));
+    }
+
+    #[test]
+    fn write_narrow() {
+        let mut screen = Screen::new(XY(5, 1));
+        screen.write(XY(1, 0), text!["abc"]);
+        assert_eq!(chars(&screen[0]), " abc ");
+    }
+
+    #[test]
+    fn write_wide_adds_continuation() {
+        let mut screen = Screen::new(XY(6, 1));
+        screen.write(XY(0, 0), text!["a漢b"]);
+        assert_eq!(chars(&screen[0]), "a漢\0b  ");
+        assert!(screen[0][2].is_continuation());
+        assert_eq!(screen[0][1].width(), 2);
+        assert_eq!(screen[0][2].width(), 0);
+    }
+
+    #[test]
+    fn write_wide_keeps_format() {
+        let mut screen = Screen::new(XY(2, 1));
+        screen.write(XY(0, 0), text![red "漢"]);
+        assert_eq!(screen[0][0], cell!(red '漢'));
+        assert_eq!(screen[0][1], cell!(red '\0'));
+    }
+
+    #[test]
+    fn write_aligns_tabs() {
+        let mut screen = Screen::new(XY(26, 3));
+        screen.write(XY(0, 0), text!["a\tbc\td"]);
+        // a tab right on a stop still goes to the next one
+        screen.write(XY(3, 1), text!["abcde\t", red "\tx"]);
+        // and one past the edge is clipped
+        screen.write(XY(22, 2), text![on_red "ab\t"]);
+        assert_eq!(chars(&screen[0]), "a       bc      d         ");
+        assert_eq!(chars(&screen[1]), "   abcde                x ");
+        assert_eq!(screen[1][16].get_fmt().fg, Color::Red);
+        assert_eq!(screen[1][15].get_fmt().fg, Color::White);
+        assert_eq!(screen[2][25].get_fmt().bg, Color::Red);
+    }
+
+    #[test]
+    fn write_wide_at_edge_is_blank() {
+        let mut screen = Screen::new(XY(3, 1));
+        screen.write(XY(0, 0), text!["ab漢"]);
+        assert_eq!(chars(&screen[0]), "ab ");
+    }
+
+    #[test]
+    fn diff_identical_is_empty() {
+        let screen = numbered(XY(4, 3));
+        assert_eq!(screen.diff(&screen.clone()), []);
+    }
+
+    #[test]
+    fn diff_finds_changed_cell() {
+        let prev = numbered(XY(4, 3));
+        let mut screen = prev.clone();
+        screen[2][1] = cell!(red 'x');
+        assert_eq!(screen.diff(&prev), [(XY(1, 2), cell!(red 'x'))]);
+    }
+
+    #[test]
+    fn diff_mismatched_sizes_is_empty() {
+        assert_eq!(numbered(XY(4, 3)).diff(&numbered(XY(3, 4))), []);
+    }
+
+    #[test]
+    fn apply_diff_reconstructs() {
+        let base = numbered(XY(5, 3));
+        let mut next = base.clone();
+        next.write(XY(1, 1), text![red "ab", "漢"]);
+        next[0][4] = cell!(on_blue ' ');
+        let mut rebuilt = base.clone();
+        rebuilt.mark_clean();
+        assert_eq!(rebuilt.apply_diff(&next.diff(&base)), 0);
+        assert!(rebuilt == next);
+        assert_eq!(rebuilt.dirty_bounds(), Some((XY(1, 0), XY(4, 2))));
+    }
+
+    #[test]
+    fn apply_diff_skips_out_of_bounds() {
+        let mut screen = Screen::new(XY(2, 2));
+        let delta = [
+            (XY(1, 1), cell!('a')),
+            (XY(2, 0), cell!('b')),
+            (XY(0, 5), cell!('c')),
+        ];
+        assert_eq!(screen.apply_diff(&delta), 2);
+        assert_eq!(screen.to_text(), "\n a");
+    }
+
+    #[test]
+    fn set_bg_and_fg_keep_the_rest() {
+        let mut screen = Screen::new(XY(4, 3));
+        screen.write(XY(0, 1), text![bold underline red "abcd"]);
+        screen.mark_clean();
+        screen.set_bg((XY(1, 1), XY(10, 10)), Color::Blue);
+        screen.set_fg((XY(3, 0), XY(1, 2)), Color::Green);
+        assert_eq!(screen.to_text(), "\nabcd\n");
+        assert_eq!(screen[1][0], cell!(bold underline red 'a'));
+        assert_eq!(screen[1][1], cell!(bold underline red on_blue 'b'));
+        assert_eq!(screen[1][3], cell!(bold underline green on_blue 'd'));
+        assert_eq!(screen[0][3], cell!(green ' '));
+        assert_eq!(screen[2][2], cell!(on_blue ' '));
+        assert_eq!(screen.dirty_bounds(), Some((XY(1, 0), XY(3, 3))));
+    }
+
+    #[test]
+    fn shift_moves_and_fills() {
+        let mut screen = numbered(XY(3, 3));
+        screen.mark_clean();
+        screen.scroll_up(1);
+        assert_eq!(screen.to_text(), "345\n678\n");
+        assert_eq!(screen.dirty_bounds(), Some((XY(0, 0), XY(3, 3))));
+        screen.scroll_right(2);
+        assert_eq!(screen.to_text(), "  3\n  6\n");
+        let mut screen = numbered(XY(3, 3));
+        screen.shift(-1, 1, cell!('.'));
+        assert_eq!(screen.to_text(), "...\n12.\n45.");
+        screen.scroll_down(0);
+        screen.scroll_left(0);
+        assert_eq!(screen.to_text(), "...\n12.\n45.");
+    }
+
+    #[test]
+    fn shift_past_edge_blanks_everything() {
+        for (dx, dy) in [(3, 0), (-3, 0), (0, 2), (0, -5), (1, isize::MIN)] {
+            let mut screen = numbered(XY(3, 2));
+            screen.shift(dx, dy, Cell::BLANK);
+            assert!(screen == Screen::new(XY(3, 2)));
+        }
+        let mut screen = numbered(XY(3, 2));
+        screen.scroll_up(usize::MAX);
+        assert!(screen == Screen::new(XY(3, 2)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn independently_built_screens_equal() {
+        use std::collections::hash_map::DefaultHasher;
+
+        fn hash(screen: &Screen) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            screen.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut written = Screen::new(XY(6, 2));
+        written.write(XY(1, 1), text!["a", red "bc"]);
+        let mut by_hand = Screen::new(XY(6, 2));
+        by_hand[1][1] = cell!('a');
+        by_hand[1][2] = cell!(red 'b');
+        by_hand[1][3] = cell!(red 'c');
+        by_hand.mark_clean();
+        assert_eq!(written, by_hand);
+        assert_eq!(hash(&written), hash(&by_hand));
+
+        by_hand[0][0].get_fmt_mut().bold = true;
+        assert_ne!(written, by_hand);
+        // same cells, different shape
+        assert_ne!(Screen::new(XY(6, 2)), Screen::new(XY(2, 6)));
+        assert_ne!(hash(&Screen::new(XY(6, 2))), hash(&Screen::new(XY(2, 6))));
+    }
+
+    #[test]
+    fn to_text_strips_and_trims() {
+        let mut screen = Screen::new(XY(6, 3));
+        screen.write(XY(1, 0), text![red "a", " ", bright_blue "b"]);
+        screen.write(XY(0, 2), text!["c"]);
+        assert_eq!(screen.to_text(), " a b\n\nc");
+    }
+
+    #[test]
+    fn to_text_wide_chars_once() {
+        let mut screen = Screen::new(XY(6, 1));
+        screen.write(XY(0, 0), text!["漢a漢"]);
+        assert_eq!(screen.to_text(), "漢a漢");
+        // overwriting the first halves leaves stray continuations, which are blank
+        screen.write(XY(0, 0), text!["b"]);
+        screen[0][3].ch = 'c';
+        screen[0][5].ch = 'd';
+        assert_eq!(screen.to_text(), "b ac d");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn save_load_round_trips() {
+        let mut screen = Screen::new(XY(6, 2));
+        screen.write(
+            XY(0, 0),
+            text![red "a", bold on_blue "漢", bright_cyan underline "b"],
+        );
+        screen.write(
+            XY(1, 1),
+            text![bright_white on_bright_magenta bold underline "xy", blink "z"],
+        );
+        screen[1][5] = cell!('r')
+            .fg(Color::Rgb(1, 2, 3))
+            .bg(Color::Rgb(255, 0, 128));
+        screen[1][0] = cell!('i').fg(Color::Indexed(0)).bg(Color::Indexed(254));
+        screen[0][3] = cell!('l').link("https://example.com/漢");
+        screen[0][4] = cell!('m').link("https://example.com/漢");
+        screen[0][5] = cell!('n').bold().link("https://example.org");
+        let mut buf = vec![];
+        screen.save(&mut buf).unwrap();
+        let loaded = Screen::load(&mut buf.as_slice()).unwrap();
+        assert!(loaded == screen);
+        // and neighbors with the same link share it
+        let link = |x: usize| loaded[0][x].get_fmt().link.clone().unwrap();
+        assert!(alloc::sync::Arc::ptr_eq(&link(3), &link(4)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn load_rejects_garbage() {
+        let mut buf = vec![];
+        Screen::new(XY(1, 1)).save(&mut buf).unwrap();
+        let err = |bytes: &[u8]| Screen::load(&mut &bytes[..]).err().unwrap().kind();
+        let mut bad_magic = buf.clone();
+        bad_magic[0] = b'X';
+        assert_eq!(err(&bad_magic), std::io::ErrorKind::InvalidData);
+        let mut bad_color = buf.clone();
+        bad_color[17] = 200;
+        assert_eq!(err(&bad_color), std::io::ErrorKind::InvalidData);
+        assert_eq!(err(&buf[..15]), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn load_reads_version_1() {
+        let mut buf = vec![];
+        let screen = Screen::new(XY(2, 1));
+        screen.save(&mut buf).unwrap();
+        // no RGB colors means the data's the same as version 1
+        buf[4] = 1;
+        assert!(Screen::load(&mut buf.as_slice()).unwrap() == screen);
+        buf[4] = SAVE_VERSION + 1;
+        let err = Screen::load(&mut buf.as_slice()).err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn write_skips_zero_width() {
+        let mut screen = Screen::new(XY(3, 1));
+        screen.write(XY(0, 0), text!["e\u{0301}x"]);
+        assert_eq!(chars(&screen[0]), "ex ");
+    }
+
+    #[test]
+    fn new_screen_is_dirty() {
+        let mut screen = Screen::new(XY(4, 3));
+        assert_eq!(screen.dirty_bounds(), Some((XY(0, 0), XY(4, 3))));
+        screen.mark_clean();
+        assert_eq!(screen.dirty_bounds(), None);
+        screen.clear();
+        assert_eq!(screen.dirty_bounds(), Some((XY(0, 0), XY(4, 3))));
+    }
+
+    #[test]
+    fn dirty_bounds_grow_with_draws() {
+        let mut screen = Screen::new(XY(10, 5));
+        screen.mark_clean();
+        screen.write(XY(2, 1), text!("ab漢"));
+        assert_eq!(screen.dirty_bounds(), Some((XY(2, 1), XY(4, 1))));
+        screen.fill(XY(8, 3), XY(5, 5), cell!('#'));
+        assert_eq!(screen.dirty_bounds(), Some((XY(2, 1), XY(8, 4))));
+        screen.mark_clean();
+        screen[2][0].ch = '!';
+        assert_eq!(screen.dirty_bounds(), Some((XY(0, 2), XY(10, 1))));
+        screen.mark_clean();
+        let src = numbered(XY(3, 3));
+        screen.blit(&src, (XY(0, 0), XY(3, 3)), XY(1, 3));
+        assert_eq!(screen.dirty_bounds(), Some((XY(1, 3), XY(3, 2))));
+    }
+
+    #[test]
+    fn empty_draws_stay_clean() {
+        let mut screen = Screen::new(XY(4, 3));
+        screen.mark_clean();
+        screen.fill(XY(5, 5), XY(2, 2), cell!('#'));
+        screen.fill(XY(1, 1), XY(0, 2), cell!('#'));
+        screen.write(XY(1, 1), text!(""));
+        assert_eq!(screen.dirty_bounds(), None);
+    }
+
+    #[test]
+    fn dirtiness_ignored_by_eq() {
+        let mut a = Screen::new(XY(2, 2));
+        let b = Screen::new(XY(2, 2));
+        a.mark_clean();
+        assert!(a == b);
+    }
+
+    #[test]
+    fn slice_screen_matches_screen() {
+        let mut screen = Screen::new(XY(5, 3));
+        let mut cells = [Cell::BLANK; 15];
+        let mut slice = SliceScreen::new(&mut cells, XY(5, 3));
+        screen.write(XY(1, 0), text!("a漢", red "b"));
+        slice.write(XY(1, 0), text!("a漢", red "b"));
+        screen.fill(XY(3, 1), XY(9, 9), cell!(on_blue '#'));
+        slice.fill(XY(3, 1), XY(9, 9), cell!(on_blue '#'));
+        screen[2][0] = cell!('!');
+        slice[2][0] = cell!('!');
+        assert_eq!(slice.size(), screen.size());
+        assert_eq!(slice.cells(), screen.cells());
+        let rows: Vec<_> = slice.rows().map(chars).collect();
+        assert_eq!(rows, [" a漢\0b", "   ##", "!  ##"]);
+        slice.clear();
+        assert!(slice.into_cells().iter().all(|c| *c == Cell::BLANK));
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_screen_checks_length() {
+        let mut cells = [Cell::BLANK; 5];
+        SliceScreen::new(&mut cells, XY(2, 3));
+    }
+}
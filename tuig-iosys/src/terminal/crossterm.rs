@@ -7,16 +7,20 @@ compile_error!("enable std to use cli_crossterm");
 
 use std::{
     io::Write,
+    ops::Range,
     sync::{
-        atomic::{AtomicBool, Ordering},
-        mpsc, Arc,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+        mpsc, Arc, Mutex, Once,
     },
     time::Duration,
 };
 
 use crossterm::{
-    cursor::{Hide, MoveDown, MoveTo, MoveToColumn, Show},
-    event::{self as ct, DisableMouseCapture, EnableMouseCapture},
+    cursor::{Hide, MoveDown, MoveTo, MoveToColumn, SetCursorStyle, Show},
+    event::{
+        self as ct, DisableBracketedPaste, DisableFocusChange, DisableMouseCapture,
+        EnableBracketedPaste, EnableFocusChange, EnableMouseCapture,
+    },
     execute,
     style::{
         Attribute, Color as CrosstermColor, ResetColor, SetAttribute, SetAttributes,
@@ -24,16 +28,16 @@ use crossterm::{
     },
     terminal::{
         self, Clear, ClearType, DisableLineWrap, EnableLineWrap, EnterAlternateScreen,
-        LeaveAlternateScreen,
+        LeaveAlternateScreen, SetTitle,
     },
 };
 
 use crate::{
-    action::{Action, Key, MouseButton},
+    action::{Action, Key, Modifiers, MouseButton},
     fmt::{Cell, Color as RsColor, Formatted},
     screen::Screen,
     xy::XY,
-    IoRunner, IoSystem,
+    Capabilities, ColorDepth, CursorStyle, IoRunner, IoSystem,
 };
 
 fn io4ct_btn(ct: ct::MouseButton) -> MouseButton {
@@ -44,9 +48,93 @@ fn io4ct_btn(ct: ct::MouseButton) -> MouseButton {
     }
 }
 
+/// Convert a crossterm key code to a Redshell [`Key`], if there's an equivalent.
+///
+/// `BackTab` and `Null` are handled separately, since they don't correspond to a single key.
+fn key4ct(code: ct::KeyCode) -> Option<Key> {
+    let key = match code {
+        ct::KeyCode::Char(c) => Key::Char(c),
+        ct::KeyCode::F(c) => Key::F(c as usize),
+        ct::KeyCode::Backspace => Key::Backspace,
+        ct::KeyCode::Enter => Key::Enter,
+        ct::KeyCode::Left => Key::Left,
+        ct::KeyCode::Right => Key::Right,
+        ct::KeyCode::Up => Key::Up,
+        ct::KeyCode::Down => Key::Down,
+        ct::KeyCode::Home => Key::Home,
+        ct::KeyCode::End => Key::End,
+        ct::KeyCode::PageUp => Key::PageUp,
+        ct::KeyCode::PageDown => Key::PageDown,
+        ct::KeyCode::Tab => Key::Tab,
+        ct::KeyCode::Delete => Key::Delete,
+        ct::KeyCode::Insert => Key::Insert,
+        ct::KeyCode::Esc => Key::Escape,
+        ct::KeyCode::CapsLock => Key::CapsLock,
+        ct::KeyCode::NumLock => Key::NumLock,
+        ct::KeyCode::ScrollLock => Key::ScrollLock,
+        ct::KeyCode::PrintScreen => Key::PrintScreen,
+        ct::KeyCode::Pause => Key::Pause,
+        ct::KeyCode::Menu => Key::Menu,
+        _ => return None,
+    };
+    Some(key)
+}
+
+fn io4ct_mods(ct: ct::KeyModifiers) -> Modifiers {
+    Modifiers {
+        ctrl: ct.contains(ct::KeyModifiers::CONTROL),
+        alt: ct.contains(ct::KeyModifiers::ALT),
+        shift: ct.contains(ct::KeyModifiers::SHIFT),
+        super_: ct.contains(ct::KeyModifiers::SUPER),
+    }
+}
+
+/// Describe a key that doesn't have a [`Key`], for an [`Action::Unknown`].
+fn unknown_key(code: ct::KeyCode, mods: Modifiers) -> String {
+    let mut desc = format!("key {:?}", code);
+    let names = [
+        (mods.ctrl, "ctrl"),
+        (mods.alt, "alt"),
+        (mods.shift, "shift"),
+        (mods.super_, "super"),
+    ];
+    let held: Vec<_> = names.iter().filter(|(h, _)| *h).map(|(_, n)| *n).collect();
+    if !held.is_empty() {
+        desc.push_str(" with ");
+        desc.push_str(&held.join("+"));
+    }
+    desc
+}
+
+/// How many times in a row reading input can fail before the input loop gives up.
+const MAX_ERRORS: usize = 16;
+
+/// Whether the game has [suspended](IoSystem::suspend) the terminal.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Suspension {
+    Running,
+    Suspended,
+    /// Resumed, but the runner hasn't noticed yet
+    Resumed,
+}
+
 pub struct CtRunner {
     actions: mpsc::Sender<Action>,
     stop: Arc<AtomicBool>,
+    /// Held while reading input, so the terminal can't be suspended in the middle
+    suspension: Arc<Mutex<Suspension>>,
+    /// How many times reading input has failed since it last worked
+    errors: usize,
+    /// The size last sent in an [`Action::Resized`], or the one at startup, so repeats can be skipped
+    size: Option<XY>,
+}
+
+/// Record the terminal's new size, returning whether it's actually changed from `last`.
+///
+/// Terminals often report the same size several times over while they're being resized, and each one would make the
+/// game lay itself out again for nothing.
+fn size_changed(last: &mut Option<XY>, size: XY) -> bool {
+    last.replace(size) != Some(size)
 }
 
 impl CtRunner {
@@ -55,6 +143,8 @@ impl CtRunner {
         execute!(
             std::io::stdout(),
             EnableMouseCapture,
+            EnableBracketedPaste,
+            EnableFocusChange,
             EnterAlternateScreen,
             DisableLineWrap,
             Hide,
@@ -67,16 +157,23 @@ impl CtRunner {
         execute!(
             std::io::stdout(),
             Clear(ClearType::All),
+            SetCursorStyle::DefaultUserShape,
             Show,
             EnableLineWrap,
             LeaveAlternateScreen,
+            DisableFocusChange,
+            DisableBracketedPaste,
             DisableMouseCapture,
         )?;
         terminal::disable_raw_mode()?;
         Ok(())
     }
 
-    fn new(actions: mpsc::Sender<Action>, stop: Arc<AtomicBool>) -> crate::Result<Self> {
+    fn new(
+        actions: mpsc::Sender<Action>,
+        stop: Arc<AtomicBool>,
+        suspension: Arc<Mutex<Suspension>>,
+    ) -> crate::Result<Self> {
         Self::init_term()?;
         std::panic::set_hook(Box::new(|i| {
             let _ = Self::clean_term();
@@ -85,7 +182,15 @@ impl CtRunner {
             #[cfg(panic = "unwind")]
             let _ = Self::init_term();
         }));
-        Ok(Self { actions, stop })
+        Ok(Self {
+            actions,
+            stop,
+            suspension,
+            errors: 0,
+            size: terminal::size()
+                .ok()
+                .map(|(w, h)| XY(w as usize, h as usize)),
+        })
     }
 }
 
@@ -104,17 +209,34 @@ impl IoRunner for CtRunner {
         }
 
         macro_rules! mods {
-            ( $mods:ident, $action:ident ) => {
-                if $mods.contains(ct::KeyModifiers::SHIFT) {
+            ( $mods:ident, $action:ident $( , $($extra:tt)* )? ) => {
+                if $mods.shift {
+                    try_send!($action {
+                        key: Key::LeftShift,
+                        mods: $mods,
+                        $( $($extra)* )?
+                    });
+                }
+                if $mods.ctrl {
                     try_send!($action {
-                        key: Key::LeftShift
+                        key: Key::LeftCtrl,
+                        mods: $mods,
+                        $( $($extra)* )?
                     });
                 }
-                if $mods.contains(ct::KeyModifiers::CONTROL) {
-                    try_send!($action { key: Key::LeftCtrl });
+                if $mods.alt {
+                    try_send!($action {
+                        key: Key::LeftAlt,
+                        mods: $mods,
+                        $( $($extra)* )?
+                    });
                 }
-                if $mods.contains(ct::KeyModifiers::ALT) {
-                    try_send!($action { key: Key::LeftAlt });
+                if $mods.super_ {
+                    try_send!($action {
+                        key: Key::LeftSuper,
+                        mods: $mods,
+                        $( $($extra)* )?
+                    });
                 }
             };
         }
@@ -126,74 +248,108 @@ impl IoRunner for CtRunner {
                 }
             }
         }
+        // read errors are reported, but only stop the loop if they keep happening, since they're often transient
+        macro_rules! try_read {
+            ( $what:literal, $read:expr ) => {
+                match $read {
+                    Ok(v) => v,
+                    Err(e) => {
+                        self.errors += 1;
+                        try_send!(Error(format!("{}: {}", $what, e)));
+                        return self.errors >= MAX_ERRORS;
+                    }
+                }
+            };
+        }
+        // while suspended, the terminal belongs to someone else, so don't steal its input
+        let mut suspension = self.suspension.lock().unwrap();
+        match *suspension {
+            Suspension::Running => (),
+            Suspension::Suspended => return false,
+            Suspension::Resumed => {
+                *suspension = Suspension::Running;
+                let (w, h) = try_read!("resuming", terminal::size());
+                let size = XY(w as usize, h as usize);
+                if size_changed(&mut self.size, size) {
+                    try_send!(Resized { size });
+                }
+                try_send!(Redraw);
+            }
+        }
         // get an event from the terminal
         // (zero timeout to avoid blocking in `step`)
-        match crossterm::event::poll(Duration::ZERO) {
-            Ok(false) => return false,
-            Ok(true) => (),
-            Err(e) => {
-                try_send!(Error(format!("polling: {}", e)));
-                return true;
-            }
+        if !try_read!("polling", crossterm::event::poll(Duration::ZERO)) {
+            self.errors = 0;
+            return false;
         }
         // we have an event, so get it
-        let ev = match crossterm::event::read() {
-            Ok(ev) => ev,
-            Err(e) => {
-                try_send!(Error(format!("polling: {}", e)));
-                return true;
-            }
-        };
+        let ev = try_read!("reading", crossterm::event::read());
+        self.errors = 0;
         // process the event into a redshell `Event`
         match ev {
             ct::Event::Key(ct::KeyEvent {
-                code, modifiers, ..
+                code,
+                modifiers,
+                kind,
+                ..
             }) => {
-                mods!(modifiers, KeyPress);
+                let mods = io4ct_mods(modifiers);
+                // only reported if the terminal supports keyboard enhancement, otherwise every press is `Press`
+                let repeat = kind == ct::KeyEventKind::Repeat;
+                mods!(mods, KeyPress, repeat: false);
                 if code == ct::KeyCode::BackTab {
+                    let mods = mods | Modifiers::SHIFT;
+                    try_send!(KeyPress {
+                        key: Key::LeftShift,
+                        mods,
+                        repeat: false,
+                    });
                     try_send!(KeyPress {
-                        key: Key::LeftShift
+                        key: Key::Tab,
+                        mods,
+                        repeat,
                     });
-                    try_send!(KeyPress { key: Key::Tab });
-                    try_send!(KeyRelease { key: Key::Tab });
                     try_send!(KeyRelease {
-                        key: Key::LeftShift
+                        key: Key::Tab,
+                        mods,
+                    });
+                    try_send!(KeyRelease {
+                        key: Key::LeftShift,
+                        mods,
                     });
                 } else if code == ct::KeyCode::Null {
-                    try_send!(Unknown("null character".into()));
+                    try_send!(Unknown(unknown_key(code, mods)));
+                } else if let Some(action_code) = key4ct(code) {
+                    try_send!(KeyPress {
+                        key: action_code,
+                        mods,
+                        repeat,
+                    });
+                    try_send!(KeyRelease {
+                        key: action_code,
+                        mods,
+                    });
                 } else {
-                    let action_code = match code {
-                        ct::KeyCode::Char(c) => Key::Char(c),
-                        ct::KeyCode::F(c) => Key::F(c as usize),
-                        ct::KeyCode::Backspace => Key::Backspace,
-                        ct::KeyCode::Enter => Key::Enter,
-                        ct::KeyCode::Left => Key::Left,
-                        ct::KeyCode::Right => Key::Right,
-                        ct::KeyCode::Up => Key::Up,
-                        ct::KeyCode::Down => Key::Down,
-                        ct::KeyCode::Home => Key::Home,
-                        ct::KeyCode::End => Key::End,
-                        ct::KeyCode::PageUp => Key::PageUp,
-                        ct::KeyCode::PageDown => Key::PageDown,
-                        ct::KeyCode::Tab => Key::Tab,
-                        ct::KeyCode::Delete => Key::Delete,
-                        ct::KeyCode::Insert => Key::Insert,
-                        ct::KeyCode::Esc => Key::Escape,
-                        kc => unreachable!("unhandled keycode {:?}; should be handled earlier", kc),
-                    };
-                    try_send!(KeyPress { key: action_code });
-                    try_send!(KeyRelease { key: action_code });
+                    // crossterm doesn't give us scancodes, so these can't be a `Key::Unknown`
+                    try_send!(Unknown(unknown_key(code, mods)));
+                }
+                mods!(mods, KeyRelease);
+            }
+            ct::Event::Resize(w, h) => {
+                let size = XY(w as usize, h as usize);
+                if size_changed(&mut self.size, size) {
+                    try_send!(Resized { size });
+                    try_send!(Redraw);
                 }
-                mods!(modifiers, KeyRelease);
             }
-            ct::Event::Resize(..) => try_send!(Redraw),
             ct::Event::Mouse(ct::MouseEvent {
                 row,
                 column: col,
                 kind,
                 modifiers,
             }) => {
-                mods!(modifiers, KeyPress);
+                let mods = io4ct_mods(modifiers);
+                mods!(mods, KeyPress, repeat: false);
                 let pos = XY(col as usize, row as usize);
                 match kind {
                     ct::MouseEventKind::Up(btn) => try_send!(MouseRelease {
@@ -211,7 +367,7 @@ impl IoRunner for CtRunner {
                             pos,
                             button: MouseButton::ScrollUp
                         });
-                        try_send!(MousePress {
+                        try_send!(MouseRelease {
                             pos,
                             button: MouseButton::ScrollUp
                         });
@@ -221,26 +377,67 @@ impl IoRunner for CtRunner {
                             pos,
                             button: MouseButton::ScrollDown
                         });
-                        try_send!(MousePress {
+                        try_send!(MouseRelease {
                             pos,
                             button: MouseButton::ScrollDown
                         });
                     }
                 }
-                mods!(modifiers, KeyRelease);
+                mods!(mods, KeyRelease);
             }
-            ct::Event::FocusGained => try_send!(Redraw),
-            ct::Event::FocusLost => (),
-            ct::Event::Paste(_) => unreachable!("bracketed paste not enabled yet"), // TODO: #40
+            ct::Event::FocusGained => {
+                try_send!(FocusGained);
+                try_send!(Redraw);
+            }
+            ct::Event::FocusLost => try_send!(FocusLost),
+            ct::Event::Paste(text) => try_send!(Paste(text)),
         };
 
         false
     }
 }
 
-/// Crossterm color for Redshell colors
+/// How many colors the terminal claims to support, per the de facto standard `COLORTERM` variable for 24-bit color,
+/// or a `TERM` ending in `256color` for the 256-color palette.
+fn color_depth() -> ColorDepth {
+    const DEPTHS: [ColorDepth; 3] = [
+        ColorDepth::Ansi16,
+        ColorDepth::Ansi256,
+        ColorDepth::TrueColor,
+    ];
+    static CHECK: Once = Once::new();
+    static DEPTH: AtomicU8 = AtomicU8::new(0);
+    CHECK.call_once(|| {
+        let depth = if matches!(
+            std::env::var("COLORTERM").as_deref(),
+            Ok("truecolor") | Ok("24bit")
+        ) {
+            ColorDepth::TrueColor
+        } else if std::env::var("TERM").map_or(false, |t| t.contains("256color")) {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+        let idx = DEPTHS.iter().position(|&d| d == depth).unwrap();
+        DEPTH.store(idx as u8, Ordering::Relaxed);
+    });
+    DEPTHS[DEPTH.load(Ordering::Relaxed) as usize]
+}
+
+/// Crossterm color for Redshell colors, on the terminal's color depth
 fn ct4rs_color(rs: RsColor) -> CrosstermColor {
+    ct_color(rs, color_depth())
+}
+
+/// Crossterm color for Redshell colors, degrading to what a terminal with some color depth can show: RGB becomes
+/// the nearest indexed color, and indexed colors become the nearest named one.
+fn ct_color(rs: RsColor, depth: ColorDepth) -> CrosstermColor {
     match rs {
+        RsColor::Rgb(r, g, b) if depth == ColorDepth::TrueColor => CrosstermColor::Rgb { r, g, b },
+        RsColor::Rgb(..) | RsColor::Indexed(..) if depth >= ColorDepth::Ansi256 => {
+            CrosstermColor::AnsiValue(rs.to_indexed())
+        }
+        RsColor::Rgb(..) | RsColor::Indexed(..) => ct_color(rs.nearest_palette(), depth),
         RsColor::BrightBlack => CrosstermColor::DarkGrey,
         RsColor::Black => CrosstermColor::Black,
         RsColor::BrightRed => CrosstermColor::Red,
@@ -260,24 +457,67 @@ fn ct4rs_color(rs: RsColor) -> CrosstermColor {
     }
 }
 
-/// Render a single row of cells into a `Vec<u8>` that can be printed
-fn render_row(row: &[Cell], out: &mut Vec<u8>) {
+/// Figure out what character to actually print for `row[idx]`, based on its display width.
+///
+/// Returns `None` if nothing should be printed, because this is the continuation of a wide character the terminal has
+/// already advanced past. Anything that would otherwise misalign the row (orphaned continuations, wide characters
+/// without their continuation, zero-width characters) is printed as a space instead.
+fn printable(row: &[Cell], idx: usize) -> Option<char> {
+    let cell = &row[idx];
+    if cell.is_continuation() {
+        if idx > 0 && row[idx - 1].width() == 2 {
+            None
+        } else {
+            Some(' ')
+        }
+    } else {
+        match cell.width() {
+            1 => Some(cell.ch),
+            2 if row.get(idx + 1).map_or(false, Cell::is_continuation) => Some(cell.ch),
+            _ => Some(' '),
+        }
+    }
+}
+
+/// Start (or, with `None`, end) an OSC 8 hyperlink, leaving out any control characters that would end it early.
+fn queue_link(link: Option<&str>, out: &mut Vec<u8>) {
+    out.extend_from_slice(b"\x1b]8;;");
+    for ch in link.unwrap_or("").chars().filter(|c| !c.is_control()) {
+        out.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+    out.extend_from_slice(b"\x1b\\");
+}
+
+/// Render part of a row of cells into a `Vec<u8>` that can be printed, starting wherever the cursor currently is.
+///
+/// Links are closed at the end, so they don't leak into whatever's written next.
+fn render_cells(row: &[Cell], cols: Range<usize>, out: &mut Vec<u8>) {
     // `unwrap` is sprinkled throughout this code, and is safe because we're queueing/writing into a `Vec`,
     // which is an infallible destination for bytes. (barring allocation failure but that's not handled rn anyway.)
 
     let mut ch_b = [0u8; 4];
 
-    let mut fg = row[0].get_fmt().fg;
-    let mut bg = row[0].get_fmt().bg;
-    let mut bold = row[0].get_fmt().bold;
-    let mut underline = row[0].get_fmt().underline;
-    let mut attrs = [Attribute::NormalIntensity, Attribute::NoUnderline];
+    let first = &row[cols.start];
+    let mut fg = first.get_fmt().fg;
+    let mut bg = first.get_fmt().bg;
+    let mut bold = first.get_fmt().bold;
+    let mut underline = first.get_fmt().underline;
+    let mut blink = first.get_fmt().blink;
+    let mut link = first.get_fmt().link.as_deref();
+    let mut attrs = [
+        Attribute::NormalIntensity,
+        Attribute::NoUnderline,
+        Attribute::NoBlink,
+    ];
     if bold {
         attrs[0] = Attribute::Bold;
     }
     if underline {
         attrs[1] = Attribute::Underlined;
     }
+    if blink {
+        attrs[2] = Attribute::SlowBlink;
+    }
     crossterm::queue!(
         out,
         ResetColor,
@@ -287,16 +527,19 @@ fn render_row(row: &[Cell], out: &mut Vec<u8>) {
         SetAttributes(attrs.as_ref().into()),
     )
     .unwrap();
-    out.extend_from_slice(row[0].ch.encode_utf8(&mut ch_b).as_bytes());
+    if link.is_some() {
+        queue_link(link, out);
+    }
 
-    for cell in &row[1..] {
+    for idx in cols {
+        let cell = &row[idx];
         if cell.get_fmt().fg != fg {
             fg = cell.get_fmt().fg;
-            crossterm::execute!(out, SetForegroundColor(ct4rs_color(fg))).unwrap();
+            crossterm::queue!(out, SetForegroundColor(ct4rs_color(fg))).unwrap();
         }
         if cell.get_fmt().bg != bg {
             bg = cell.get_fmt().bg;
-            crossterm::execute!(out, SetBackgroundColor(ct4rs_color(bg))).unwrap();
+            crossterm::queue!(out, SetBackgroundColor(ct4rs_color(bg))).unwrap();
         }
         if cell.get_fmt().bold != bold {
             bold = cell.get_fmt().bold;
@@ -305,7 +548,7 @@ fn render_row(row: &[Cell], out: &mut Vec<u8>) {
             } else {
                 Attribute::NormalIntensity
             };
-            crossterm::execute!(out, SetAttribute(attr)).unwrap();
+            crossterm::queue!(out, SetAttribute(attr)).unwrap();
         }
         if cell.get_fmt().underline != underline {
             underline = cell.get_fmt().underline;
@@ -314,27 +557,147 @@ fn render_row(row: &[Cell], out: &mut Vec<u8>) {
             } else {
                 Attribute::NoUnderline
             };
-            crossterm::execute!(out, SetAttribute(attr)).unwrap();
+            crossterm::queue!(out, SetAttribute(attr)).unwrap();
+        }
+        if cell.get_fmt().blink != blink {
+            blink = cell.get_fmt().blink;
+            let attr = if blink {
+                Attribute::SlowBlink
+            } else {
+                Attribute::NoBlink
+            };
+            crossterm::queue!(out, SetAttribute(attr)).unwrap();
+        }
+        if cell.get_fmt().link.as_deref() != link {
+            link = cell.get_fmt().link.as_deref();
+            queue_link(link, out);
+        }
+        if let Some(ch) = printable(row, idx) {
+            out.extend_from_slice(ch.encode_utf8(&mut ch_b).as_bytes());
+        }
+    }
+    if link.is_some() {
+        queue_link(None, out);
+    }
+}
+
+/// Render an entire screen from scratch, clearing whatever was there before.
+fn render_full(screen: &Screen, out: &mut Vec<u8>) {
+    crossterm::queue!(out, MoveTo(0, 0), Clear(ClearType::All)).unwrap();
+    for row in screen.rows() {
+        if !row.is_empty() {
+            render_cells(row, 0..row.len(), out);
+        }
+        crossterm::queue!(out, MoveDown(1), MoveToColumn(0)).unwrap();
+    }
+}
+
+/// How many unchanged cells can separate two changed runs before it's cheaper to move the cursor than to just rewrite
+/// the cells in between. A cursor move is `ESC [ row ; col H`, so roughly 6-10 bytes.
+const MERGE_GAP: usize = 6;
+
+/// Find the runs of cells in `new` that differ from `old`, merging runs that are close enough together.
+///
+/// Runs are widened to never split a wide character from its continuation, so they can be rendered independently.
+fn changed_runs(old: &[Cell], new: &[Cell]) -> Vec<Range<usize>> {
+    let mut runs: Vec<Range<usize>> = vec![];
+    let mut idx = 0;
+    while idx < new.len() {
+        if old[idx] == new[idx] {
+            idx += 1;
+            continue;
+        }
+        let mut start = idx;
+        let mut end = idx + 1;
+        while end < new.len() && old[end] != new[end] {
+            end += 1;
+        }
+        if start > 0 && (new[start].is_continuation() || old[start].is_continuation()) {
+            start -= 1;
+        }
+        if end < new.len() && (new[end].is_continuation() || old[end].is_continuation()) {
+            end += 1;
         }
-        out.extend_from_slice(cell.ch.encode_utf8(&mut ch_b).as_bytes());
+        idx = end;
+        match runs.last_mut() {
+            Some(prev) if start <= prev.end + MERGE_GAP => prev.end = end,
+            _ => runs.push(start..end),
+        }
+    }
+    runs
+}
+
+/// Render only the cells that changed between `old` and `new`, which must be the same size.
+fn render_diff(old: &Screen, new: &Screen, out: &mut Vec<u8>) {
+    for (y, (old_row, new_row)) in old.rows().zip(new.rows()).enumerate() {
+        for run in changed_runs(old_row, new_row) {
+            crossterm::queue!(out, MoveTo(run.start as u16, y as u16)).unwrap();
+            render_cells(new_row, run, out);
+        }
+    }
+}
+
+/// The DECSCUSR sequence for a cursor style. They always blink, to match the GUI.
+fn ct4cursor(style: CursorStyle) -> SetCursorStyle {
+    match style {
+        CursorStyle::Block => SetCursorStyle::BlinkingBlock,
+        CursorStyle::Underline => SetCursorStyle::BlinkingUnderScore,
+        CursorStyle::Bar => SetCursorStyle::BlinkingBar,
+    }
+}
+
+/// Base64-encode some bytes, for OSC 52.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut res = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize;
+        res.push(ALPHABET[n >> 18 & 63] as char);
+        res.push(ALPHABET[n >> 12 & 63] as char);
+        res.push(if chunk.len() > 1 {
+            ALPHABET[n >> 6 & 63] as char
+        } else {
+            '='
+        });
+        res.push(if chunk.len() > 2 {
+            ALPHABET[n & 63] as char
+        } else {
+            '='
+        });
     }
-    crossterm::execute!(out, MoveDown(1), MoveToColumn(0)).unwrap();
+    res
 }
 
 pub struct CtSystem {
     queue: mpsc::Receiver<Action>,
     stop: Arc<AtomicBool>,
+    suspension: Arc<Mutex<Suspension>>,
+    /// What's currently on the terminal, so we only need to redraw what changed
+    last: Option<Screen>,
+    /// Where to put the terminal's cursor after drawing, if anywhere
+    cursor: Option<XY>,
+    cursor_style: CursorStyle,
 }
 
 impl CtSystem {
     pub fn new() -> crate::Result<(Self, CtRunner)> {
         let (queue_s, queue_r) = mpsc::channel();
         let stop = Arc::new(AtomicBool::new(false));
-        let runner = CtRunner::new(queue_s, stop.clone())?;
+        let suspension = Arc::new(Mutex::new(Suspension::Running));
+        let runner = CtRunner::new(queue_s, stop.clone(), suspension.clone())?;
         Ok((
             Self {
                 queue: queue_r,
                 stop: stop.clone(),
+                suspension,
+                last: None,
+                cursor: None,
+                cursor_style: CursorStyle::default(),
             },
             runner,
         ))
@@ -349,9 +712,26 @@ impl IoSystem for CtSystem {
 
     fn draw(&mut self, screen: &Screen) -> crate::Result<()> {
         let mut out = vec![];
-        crossterm::queue!(&mut out, MoveTo(0, 0), Clear(ClearType::All)).unwrap();
-        for row in screen.rows() {
-            render_row(row, &mut out);
+        match &mut self.last {
+            Some(last) if last.size() == screen.size() => {
+                render_diff(last, screen, &mut out);
+                last.clone_from(screen);
+            }
+            _ => {
+                render_full(screen, &mut out);
+                self.last = Some(screen.clone());
+            }
+        }
+        // drawing moves the cursor around, so it always needs to be put back
+        match self.cursor {
+            Some(pos) => crossterm::queue!(
+                out,
+                MoveTo(pos.x() as u16, pos.y() as u16),
+                ct4cursor(self.cursor_style),
+                Show,
+            )
+            .unwrap(),
+            None => crossterm::queue!(out, Hide).unwrap(),
         }
         let stdout = std::io::stdout();
         let mut stdout = stdout.lock();
@@ -372,8 +752,256 @@ impl IoSystem for CtSystem {
         }
     }
 
+    fn input_until(&mut self, timeout: Duration) -> crate::Result<Option<Action>> {
+        match self.queue.recv_timeout(timeout) {
+            Ok(res) => Ok(Some(res)),
+            Err(mpsc::RecvTimeoutError::Disconnected) => panic!("unexpected queue closure"),
+            Err(mpsc::RecvTimeoutError::Timeout) => Ok(None),
+        }
+    }
+
+    fn drain_input(&mut self) -> crate::Result<Vec<Action>> {
+        Ok(self.queue.try_iter().collect())
+    }
+
+    /// Reading the clipboard over OSC 52 would mean racing the input thread for the terminal's reply, and most
+    /// terminals disable it anyway, so this always returns `Ok(None)`.
+    fn clipboard_get(&mut self) -> crate::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Sets the clipboard with OSC 52. Terminals that don't support it will just ignore the sequence.
+    fn clipboard_set(&mut self, text: &str) -> crate::Result<()> {
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        write!(stdout, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+        stdout.flush()?;
+        Ok(())
+    }
+
+    /// Guesses the color depth from `COLORTERM` and `TERM`, since terminals can't reliably be asked. Everything else
+    /// is supported by basically every terminal crossterm works with, though some let the user turn off blinking.
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::FULL.color(color_depth())
+    }
+
+    /// Sets the terminal title with OSC 0. Terminals that don't support it will just ignore the sequence.
+    fn set_title(&mut self, title: &str) {
+        // there's nothing useful to do if this fails, and it's purely cosmetic anyway
+        let _ = execute!(std::io::stdout(), SetTitle(title));
+    }
+
+    /// Uses the terminal's own cursor. Its blink rate is up to the terminal.
+    fn set_cursor(&mut self, pos: Option<XY>) {
+        self.cursor = pos;
+    }
+
+    /// Sets the terminal's cursor style with DECSCUSR. Terminals that don't support it will just ignore the sequence.
+    fn set_cursor_style(&mut self, style: CursorStyle) {
+        self.cursor_style = style;
+    }
+
+    /// Leaves raw mode and the alternate screen, putting the terminal back the way it was before the game started,
+    /// and stops reading input from it.
+    fn suspend(&mut self) -> crate::Result<()> {
+        // waits for the runner to finish reading, if it's in the middle of it
+        let mut suspension = self.suspension.lock().unwrap();
+        if *suspension != Suspension::Suspended {
+            CtRunner::clean_term()?;
+            *suspension = Suspension::Suspended;
+        }
+        Ok(())
+    }
+
+    /// Re-enters raw mode and the alternate screen, and starts reading input again.
+    fn resume(&mut self) -> crate::Result<()> {
+        let mut suspension = self.suspension.lock().unwrap();
+        if *suspension == Suspension::Suspended {
+            CtRunner::init_term()?;
+            // whatever was on the alternate screen is gone now
+            self.last = None;
+            *suspension = Suspension::Resumed;
+        }
+        Ok(())
+    }
+
     fn stop(&mut self) {
         eprintln!("stopping");
         self.stop.store(true, Ordering::Relaxed);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::text;
+
+    use super::*;
+
+    #[test]
+    fn colors_degrade_with_depth() {
+        let rgb = RsColor::Rgb(250, 10, 10);
+        let cases = [
+            (
+                rgb,
+                ColorDepth::TrueColor,
+                CrosstermColor::Rgb {
+                    r: 250,
+                    g: 10,
+                    b: 10,
+                },
+            ),
+            (rgb, ColorDepth::Ansi256, CrosstermColor::AnsiValue(196)),
+            (rgb, ColorDepth::Ansi16, CrosstermColor::Red),
+            (
+                RsColor::Indexed(110),
+                ColorDepth::TrueColor,
+                CrosstermColor::AnsiValue(110),
+            ),
+            (
+                RsColor::Indexed(110),
+                ColorDepth::Ansi256,
+                CrosstermColor::AnsiValue(110),
+            ),
+            (
+                RsColor::Indexed(226),
+                ColorDepth::Ansi16,
+                CrosstermColor::Yellow,
+            ),
+            (RsColor::Red, ColorDepth::Ansi256, CrosstermColor::DarkRed),
+        ];
+        for (rs, depth, ct) in cases {
+            assert_eq!(ct_color(rs, depth), ct, "{:?} at {:?}", rs, depth);
+        }
+    }
+
+    fn busy_screen() -> Screen {
+        let mut screen = Screen::new(XY(80, 24));
+        for y in 0..24 {
+            screen.write(
+                XY(0, y),
+                text!["status line ", red "{}"(y), " of some mostly static output"],
+            );
+        }
+        screen
+    }
+
+    #[test]
+    fn repeated_sizes_skipped() {
+        let mut last = Some(XY(80, 24));
+        assert!(!size_changed(&mut last, XY(80, 24)));
+        assert!(size_changed(&mut last, XY(100, 30)));
+        assert!(!size_changed(&mut last, XY(100, 30)));
+        assert!(size_changed(&mut last, XY(80, 24)));
+        assert!(size_changed(&mut None, XY(80, 24)));
+    }
+
+    #[test]
+    fn unknown_keys_described() {
+        let code = ct::KeyCode::Media(ct::MediaKeyCode::Play);
+        assert_eq!(unknown_key(code, Modifiers::NONE), "key Media(Play)");
+        let mods = Modifiers::CTRL | Modifiers::SHIFT;
+        assert_eq!(unknown_key(code, mods), "key Media(Play) with ctrl+shift");
+        assert_eq!(
+            unknown_key(ct::KeyCode::Null, Modifiers::ALT),
+            "key Null with alt"
+        );
+    }
+
+    #[test]
+    fn base64_pads_right() {
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"f"), "Zg==");
+        assert_eq!(base64(b"fo"), "Zm8=");
+        assert_eq!(base64(b"foo"), "Zm9v");
+        assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn links_wrap_their_run() {
+        let mut row = vec![Cell::of('a'); 4];
+        for cell in &mut row[1..3] {
+            cell.get_fmt_mut().link = Some("https://x.y/\x1bz".into());
+        }
+        let mut out = vec![];
+        render_cells(&row, 0..4, &mut out);
+        let out = String::from_utf8(out).unwrap();
+        let open = out
+            .find("\x1b]8;;https://x.y/z\x1b\\")
+            .expect("link should open");
+        let close = out.rfind("\x1b]8;;\x1b\\").expect("link should close");
+        assert!(open < close);
+        assert_eq!(out[open..close].matches('a').count(), 2);
+        // a run that ends inside a link still closes it
+        let mut out = vec![];
+        render_cells(&row, 0..2, &mut out);
+        assert!(String::from_utf8(out).unwrap().ends_with("\x1b]8;;\x1b\\"));
+    }
+
+    #[test]
+    fn unchanged_screen_writes_nothing() {
+        let screen = busy_screen();
+        let mut out = vec![];
+        render_diff(&screen, &screen.clone(), &mut out);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn small_change_writes_much_less() {
+        let old = busy_screen();
+        let mut new = old.clone();
+        new.write(XY(40, 3), text![green "!!"]);
+
+        let mut full = vec![];
+        render_full(&new, &mut full);
+        let mut diff = vec![];
+        render_diff(&old, &new, &mut diff);
+        assert!(
+            diff.len() * 50 < full.len(),
+            "diff {} bytes, full {} bytes",
+            diff.len(),
+            full.len()
+        );
+    }
+
+    #[test]
+    fn nearby_changes_share_a_run() {
+        let old = vec![Cell::BLANK; 30];
+        let mut new = old.clone();
+        new[2].ch = 'a';
+        new[5].ch = 'b';
+        new[20].ch = 'c';
+        assert_eq!(changed_runs(&old, &new), vec![2..6, 20..21]);
+    }
+
+    #[test]
+    fn runs_dont_split_wide_chars() {
+        let mut old = Screen::new(XY(6, 1));
+        old.write(XY(0, 0), text!["a漢b"]);
+        let mut new = old.clone();
+        new[0][2].ch = 'x';
+        assert_eq!(changed_runs(&old[0], &new[0]), vec![1..3]);
+        let mut new = old.clone();
+        new[0][1].ch = 'x';
+        assert_eq!(changed_runs(&old[0], &new[0]), vec![1..3]);
+    }
+
+    #[test]
+    fn blink_sets_attribute() {
+        fn has(haystack: &[u8], needle: &[u8]) -> bool {
+            haystack.windows(needle.len()).any(|w| w == needle)
+        }
+        let mut screen = Screen::new(XY(4, 1));
+        screen.write(XY(0, 0), text![blink "ab", "cd"]);
+        let mut out = vec![];
+        render_cells(&screen[0], 0..4, &mut out);
+        assert!(has(&out, b"\x1b[5m"));
+        assert!(has(&out, b"\x1b[25m"));
+    }
+
+    #[test]
+    fn lock_keys_translate() {
+        assert_eq!(key4ct(ct::KeyCode::CapsLock), Some(Key::CapsLock));
+        assert_eq!(key4ct(ct::KeyCode::Esc), Some(Key::Escape));
+        assert_eq!(key4ct(ct::KeyCode::KeypadBegin), None);
+    }
+}
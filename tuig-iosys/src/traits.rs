@@ -1,7 +1,116 @@
 //! The IO system/backend traits themselves.
 
+use alloc::{string::String, vec::Vec};
+use core::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
 use crate::{Action, Result, Screen, XY};
 
+/// How a text cursor is drawn; see [`IoSystem::set_cursor`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CursorStyle {
+    /// Inverts the whole cell.
+    #[default]
+    Block,
+    /// A line along the bottom of the cell.
+    Underline,
+    /// A thin vertical line at the left of the cell.
+    Bar,
+}
+
+/// How many colors a display can show; see [`Capabilities::color`].
+///
+/// These are ordered from fewest colors to most, so you can compare them, e.g. `caps.color >= ColorDepth::Ansi256`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorDepth {
+    /// Only the 16 named [`Color`](crate::fmt::Color)s. [RGB](crate::fmt::Color::Rgb) and
+    /// [indexed](crate::fmt::Color::Indexed) colors are shown as the [nearest](crate::fmt::Color::nearest_palette)
+    /// one.
+    #[default]
+    Ansi16,
+    /// The 256-color xterm palette, i.e. [indexed](crate::fmt::Color::Indexed) colors. RGB colors are shown as the
+    /// [nearest](crate::fmt::Color::to_indexed) indexed one.
+    Ansi256,
+    /// Full 24-bit color.
+    TrueColor,
+}
+
+/// What an [`IoSystem`]'s display can actually do, at runtime; see [`IoSystem::capabilities`].
+///
+/// Features turn backends on and off at compile time, but can't tell you what the terminal the game ended up in
+/// supports, so this is for UI code to degrade gracefully, e.g. by picking a theme that doesn't rely on truecolor.
+///
+/// More fields might be added later, so to make one, start from [`Self::BASIC`] and use the setters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// How many colors can be shown.
+    pub color: ColorDepth,
+    /// Whether [bold](crate::fmt::Format::bold) text looks different.
+    pub bold: bool,
+    /// Whether [underlined](crate::fmt::Format::underline) text looks different.
+    pub underline: bool,
+    /// Whether [blinking](crate::fmt::Format::blink) text actually blinks. Terminals may still ignore it.
+    pub blink: bool,
+    /// Whether there's mouse input.
+    pub mouse: bool,
+    /// Whether [`IoSystem::set_cursor`] shows a cursor.
+    pub cursor: bool,
+    /// Whether [linked](crate::fmt::Format::link) text can be clicked to open the link.
+    ///
+    /// Terminals are assumed to support it, since they generally ignore the sequence if they don't.
+    pub links: bool,
+}
+
+impl Capabilities {
+    /// The bare minimum: 16 colors, and nothing else.
+    pub const BASIC: Self = Self {
+        color: ColorDepth::Ansi16,
+        bold: false,
+        underline: false,
+        blink: false,
+        mouse: false,
+        cursor: false,
+        links: false,
+    };
+
+    /// Everything supported: truecolor, all the formatting, the mouse, the cursor, and links.
+    pub const FULL: Self = Self {
+        color: ColorDepth::TrueColor,
+        bold: true,
+        underline: true,
+        blink: true,
+        mouse: true,
+        cursor: true,
+        links: true,
+    };
+
+    tuig_pm::setters! {
+        /// Set [`Self::color`].
+        color(v: ColorDepth) => color = v,
+        /// Set [`Self::bold`].
+        bold(v: bool) => bold = v,
+        /// Set [`Self::underline`].
+        underline(v: bool) => underline = v,
+        /// Set [`Self::blink`].
+        blink(v: bool) => blink = v,
+        /// Set [`Self::mouse`].
+        mouse(v: bool) => mouse = v,
+        /// Set [`Self::cursor`].
+        cursor(v: bool) => cursor = v,
+        /// Set [`Self::links`].
+        links(v: bool) => links = v,
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::BASIC
+    }
+}
+
 /// An input/output system.
 ///
 /// This object is meant to be associated with a [`IoRunner`], which will run infinitely on the main thread while this
@@ -39,6 +148,147 @@ pub trait IoSystem: Send {
     ///
     /// Basically a non-blocking [`Self::input`].
     fn poll_input(&mut self) -> Result<Option<Action>>;
+    /// Wait for the next user input, but only up to `timeout`, e.g. to handle input until it's time for the next
+    /// frame. Returns `Ok(None)` if nothing came in.
+    ///
+    /// It's allowed to return `Ok(None)` early, before the timeout is up, so if you need to wait the whole time, loop
+    /// until your own deadline. The default implementation checks [`Self::poll_input`] about once a millisecond, or
+    /// without `std`, where it can't tell the time, just checks once.
+    fn input_until(&mut self, timeout: Duration) -> Result<Option<Action>> {
+        #[cfg(feature = "std")]
+        {
+            let deadline = std::time::Instant::now() + timeout;
+            loop {
+                if let Some(action) = self.poll_input()? {
+                    return Ok(Some(action));
+                }
+                let left = deadline.saturating_duration_since(std::time::Instant::now());
+                if left.is_zero() {
+                    return Ok(None);
+                }
+                std::thread::sleep(left.min(Duration::from_millis(1)));
+            }
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            let _ = timeout;
+            self.poll_input()
+        }
+    }
+    /// Get every user input that's available right now, oldest first, e.g. to handle all of them once per frame.
+    ///
+    /// This never blocks. If there's nothing pending, it returns an empty `Vec`. The default implementation just calls
+    /// [`Self::poll_input`] until it returns `None`, but backends can usually do it more efficiently.
+    fn drain_input(&mut self) -> Result<Vec<Action>> {
+        let mut res = Vec::new();
+        while let Some(action) = self.poll_input()? {
+            res.push(action);
+        }
+        Ok(res)
+    }
+
+    /// Get the current contents of the system clipboard, if there's text in it.
+    ///
+    /// Not every display has access to a clipboard, or can read it if it does. The default implementation, for those,
+    /// always returns `Ok(None)`.
+    fn clipboard_get(&mut self) -> Result<Option<String>> {
+        Ok(None)
+    }
+    /// Put some text into the system clipboard.
+    ///
+    /// Not every display has access to a clipboard. The default implementation, for those, silently does nothing.
+    fn clipboard_set(&mut self, text: &str) -> Result<()> {
+        let _ = text;
+        Ok(())
+    }
+
+    /// What the display can do, e.g. how many colors it can show, as best the backend can tell at runtime.
+    ///
+    /// The default implementation conservatively reports [`Capabilities::BASIC`].
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::BASIC
+    }
+
+    /// Set the title of the display, e.g. the window title or the terminal's tab title.
+    ///
+    /// The default implementation, for displays that don't have a title, does nothing.
+    fn set_title(&mut self, title: &str) {
+        let _ = title;
+    }
+
+    /// Show a blinking text cursor in a cell of the display, or hide it again with `None`.
+    ///
+    /// The cursor is drawn over whatever screen is drawn next, so this takes effect at the next [`Self::draw`], and
+    /// it's drawn in the style set by [`Self::set_cursor_style`]. The default implementation, for displays that can't
+    /// show a cursor, does nothing.
+    fn set_cursor(&mut self, pos: Option<XY>) {
+        let _ = pos;
+    }
+    /// Change how the cursor from [`Self::set_cursor`] is drawn. It starts as [`CursorStyle::Block`].
+    ///
+    /// Like `set_cursor`, this takes effect at the next [`Self::draw`], and the default implementation does nothing.
+    fn set_cursor_style(&mut self, style: CursorStyle) {
+        let _ = style;
+    }
+
+    /// Change the font size of the display, if it has one.
+    ///
+    /// Afterwards, [`Self::size`] immediately reports the new size of the character grid, so the next frame can be
+    /// laid out to match. The default implementation, for displays that can't change their font size (e.g.
+    /// terminals, where that's up to the user), does nothing.
+    ///
+    /// For example, to zoom in and out with Ctrl+Plus and Ctrl+Minus:
+    ///
+    /// ```no_run
+    /// # use tuig_iosys::{Action, IoSystem, Key};
+    /// # fn handle(sys: &mut dyn IoSystem, font_size: &mut f32) -> tuig_iosys::Result<()> {
+    /// match sys.input()? {
+    ///     Action::KeyPress { key: Key::Char('+' | '='), mods, .. } if mods.ctrl => {
+    ///         *font_size += 2.0;
+    ///         sys.zoom(*font_size)?;
+    ///     }
+    ///     Action::KeyPress { key: Key::Char('-'), mods, .. } if mods.ctrl => {
+    ///         *font_size = (*font_size - 2.0).max(6.0);
+    ///         sys.zoom(*font_size)?;
+    ///     }
+    ///     _ => (),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn zoom(&mut self, font_size: f32) -> Result<()> {
+        let _ = font_size;
+        Ok(())
+    }
+
+    /// Hand the display over to something else for a while, e.g. to run `$EDITOR` in the terminal, until
+    /// [`Self::resume`] is called.
+    ///
+    /// While suspended, don't [draw](Self::draw); no input comes in, either. On resuming, backends that actually
+    /// suspend send an [`Action::Resized`] with the current size, since it might have changed in the meantime, and
+    /// the next `draw` redraws everything. Calling either method twice in a row is harmless.
+    ///
+    /// The default implementation, for displays that don't need to be handed over (e.g. windows), does nothing.
+    ///
+    /// ```no_run
+    /// # use tuig_iosys::IoSystem;
+    /// # fn edit(sys: &mut dyn IoSystem, path: &str) -> tuig_iosys::Result<()> {
+    /// sys.suspend()?;
+    /// let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".into());
+    /// let status = std::process::Command::new(editor).arg(path).status();
+    /// sys.resume()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn suspend(&mut self) -> Result<()> {
+        Ok(())
+    }
+    /// Take the display back after [`Self::suspend`].
+    ///
+    /// The default implementation does nothing.
+    fn resume(&mut self) -> Result<()> {
+        Ok(())
+    }
 
     /// Tells the associated [`IoRunner`] to stop and return control of the main thread, and tell the [`IoSystem`] to
     /// dispose of any resources it's handling.
@@ -75,4 +325,31 @@ pub trait IoRunner {
     fn run(&mut self) {
         while !self.step() {}
     }
+
+    /// Run until the paired [`IoSystem`] says to [stop](IoSystem::stop), or until `stop` is set, whichever comes
+    /// first.
+    ///
+    /// This lets the main thread be told to wind down from anywhere, e.g. a Ctrl+C handler, without going through
+    /// the `IoSystem`. Setting the flag only stops the runner; the `IoSystem` should still be stopped, and whatever's
+    /// using it should still be told to quit. The builtin backends notice the flag within about a second, usually
+    /// much sooner.
+    ///
+    /// Will always be called on the main thread.
+    ///
+    /// The default implementation just runs `while !stop.load(Ordering::Relaxed) && !self.step() { }`.
+    ///
+    /// ```no_run
+    /// # use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
+    /// # use tuig_iosys::IoRunner;
+    /// # fn go(mut runner: impl IoRunner) {
+    /// let quit = Arc::new(AtomicBool::new(false));
+    /// let handler_quit = quit.clone();
+    /// // from a signal handler, another thread, etc.
+    /// std::thread::spawn(move || handler_quit.store(true, Ordering::Relaxed));
+    /// runner.run_until(&quit);
+    /// # }
+    /// ```
+    fn run_until(&mut self, stop: &AtomicBool) {
+        while !stop.load(Ordering::Relaxed) && !self.step() {}
+    }
 }
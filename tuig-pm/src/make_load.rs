@@ -39,7 +39,7 @@ pub fn make_load(input: TokenStream) -> TokenStream {
         Ok(li) => li,
         Err(e) => return e.to_compile_error(),
     };
-    // figure out the individual `match` chunks for each feature
+    // figure out the individual `match` chunks for each feature, for `load!` then `load_ordered!`
     let chunks = arms
         .iter()
         .map(|(feat, init)| {
@@ -48,24 +48,43 @@ pub fn make_load(input: TokenStream) -> TokenStream {
                 quote::quote! {
                     match ( #( #init )* ) {
                         Ok((iosys, iorun)) => break Ok($($callback)* (iosys, iorun)),
-                        Err(e) => { errs.insert(#feat, e); }
+                        Err(e) => { errs.push((#feat, e)); }
                     }
                 },
+                quote::quote! {
+                    #feat => match ( #( #init )* ) {
+                        Ok((iosys, iorun)) => break 'load Ok($($callback)* (iosys, iorun)),
+                        Err(e) => { errs.push((#feat, e)); }
+                    },
+                },
             )
         })
         .collect::<Vec<_>>();
+    let ordered_doc = "Like `load!`, but tries the backends named by feature in the given order, e.g. \
+        `load_ordered!(&[\"cli_crossterm\"], run)`.\n\n\
+        The order is anything which can be iterated over to get `&&str`s, like a `&[&str]`. Backends which aren't \
+        compiled in are skipped, and unknown names are ignored with a warning printed to stderr (if `std` is \
+        enabled). Otherwise this behaves just like `load!`.";
     // generate each combination of 0 to n features
     (0..=chunks.len()).flat_map(|n| {
         chunks.iter().combinations(n).map(|c| {
-            let features = c.iter().map(|(f, _)| f).collect::<Vec<_>>();
+            let features = c.iter().map(|(f, _, _)| f).collect::<Vec<_>>();
             let antifeatures = chunks
                 .iter()
-                .map(|(f, _)| f)
-                .filter(|f| !features.contains(f));
+                .map(|(f, _, _)| f)
+                .filter(|f| !features.contains(f))
+                .collect::<Vec<_>>();
             let cfgs = quote::quote! {
                 #[cfg(all(not(any( #( feature = #antifeatures ),* )), #( feature = #features ),* ))]
             };
-            let tokens = c.iter().map(|(_, ts)| ts);
+            let tokens = c.iter().map(|(_, ts, _)| ts);
+            let ordered = c.iter().map(|(_, _, ts)| ts);
+            // features that exist but aren't compiled in are skipped silently
+            let skipped = if antifeatures.is_empty() {
+                quote::quote! {}
+            } else {
+                quote::quote! { #( #antifeatures )|* => (), }
+            };
             quote::quote! {
                 #cfgs
                 #( #attrs )*
@@ -73,9 +92,32 @@ pub fn make_load(input: TokenStream) -> TokenStream {
                 macro_rules! load {
                     ($($callback:tt)*) => { loop {
                         #[allow(unused)]
-                        let mut errs = $crate::BTreeMap::<&'static str, $crate::Error>::new();
+                        let mut errs = $crate::Vec::<(&'static str, $crate::Error)>::new();
                         #( #tokens )*
-                        break Err(errs);
+                        break Err($crate::Error::NoBackend(errs));
+                    } }
+                }
+
+                #cfgs
+                #[doc = #ordered_doc]
+                #[macro_export]
+                macro_rules! load_ordered {
+                    // the label goes unused if no backends are compiled in
+                    ($order:expr, $($callback:tt)*) => { {
+                        #[allow(unused_labels)]
+                        let res = 'load: loop {
+                        #[allow(unused)]
+                        let mut errs = $crate::Vec::<(&'static str, $crate::Error)>::new();
+                        for name in $order {
+                            match *name {
+                                #( #ordered )*
+                                #skipped
+                                other => $crate::warn_unknown_backend(other),
+                            }
+                        }
+                        break Err($crate::Error::NoBackend(errs));
+                        };
+                        res
                     } }
                 }
             }
@@ -1,4 +1,4 @@
-use proc_macro2::{Span, TokenStream, TokenTree};
+use proc_macro2::{TokenStream, TokenTree};
 use syn::{
     parse::{ParseStream, Parser},
     LitInt, LitStr, Token,
@@ -9,6 +9,7 @@ use syn::{
 pub struct SplitSpec {
     base: TokenStream,
     sizes: Vec<usize>,
+    weights: Vec<usize>,
     // TODO: &'static [fmt::Cell] separators?
     presep: String,
     seps: Vec<String>,
@@ -31,15 +32,25 @@ fn parse_sep(input: ParseStream) -> syn::Result<String> {
     }
 }
 
-fn parse_size(input: ParseStream) -> syn::Result<(Span, usize)> {
+/// Parse a size, returning `(size, weight)`: fixed sizes have a weight of 0, stars have no size.
+fn parse_size(input: ParseStream) -> syn::Result<(usize, usize)> {
     let lh = input.lookahead1();
     if lh.peek(LitInt) {
         input
             .parse::<LitInt>()
-            .and_then(|i| i.base10_parse().map(|n| (i.span(), n)))
+            .and_then(|i| i.base10_parse().map(|n| (n, 0)))
     } else if lh.peek(syn::Token!(*)) {
-        let t = input.parse::<syn::Token!(*)>()?;
-        Ok((t.span, usize::MAX))
+        let _ = input.parse::<syn::Token!(*)>()?;
+        if !input.peek(syn::token::Paren) {
+            return Ok((0, 1));
+        }
+        let content;
+        syn::parenthesized!(content in input);
+        let weight = content.parse::<LitInt>()?;
+        match weight.base10_parse()? {
+            0 => Err(syn::Error::new(weight.span(), "star weights must be positive")),
+            w => Ok((0, w)),
+        }
     } else {
         Err(lh.error())
     }
@@ -51,17 +62,10 @@ fn parse_splitspec(input: ParseStream) -> syn::Result<SplitSpec> {
         presep: input.call(parse_sep)?,
         ..Default::default()
     };
-    let mut has_star = false;
     while !input.is_empty() {
-        let (t, w) = input.call(parse_size)?;
-        res.sizes.push(w);
-        if w == usize::MAX {
-            if !has_star {
-                has_star = true;
-            } else {
-                return Err(syn::Error::new(t, "maximum one * per split spec"));
-            }
-        }
+        let (size, weight) = input.call(parse_size)?;
+        res.sizes.push(size);
+        res.weights.push(weight);
         res.seps.push(input.call(parse_sep)?);
     }
     Ok(res)
@@ -71,6 +75,7 @@ pub fn splitter(input: TokenStream) -> TokenStream {
     let SplitSpec {
         base,
         sizes,
+        weights,
         presep,
         seps,
     } = match parse_splitspec.parse2(input) {
@@ -85,6 +90,7 @@ pub fn splitter(input: TokenStream) -> TokenStream {
         #[allow(deprecated)]
         #base::new(
             [ #(#sizes),* ],
+            [ #(#weights),* ],
             #presep,
             [ #(#seps),* ],
         )
@@ -47,7 +47,10 @@ fn run(mut iosys: Box<dyn IoSystem>) {
     let mut screen = Screen::new(iosys.size());
     let mut input = Action::Redraw;
     loop {
-        screen.resize(iosys.size());
+        match input {
+            Action::Resized { size } => screen.resize(size),
+            _ => screen.clear(),
+        }
         let root = Region::new(&mut screen, input);
         if !tui(root) {
             break;
@@ -56,7 +59,11 @@ fn run(mut iosys: Box<dyn IoSystem>) {
         input = iosys.input().expect("failed to get input");
         if matches!(
             input,
-            Action::Closed | Action::KeyPress { key: Key::Escape }
+            Action::Closed
+                | Action::KeyPress {
+                    key: Key::Escape,
+                    ..
+                }
         ) {
             break;
         }
@@ -2,7 +2,7 @@ use std::time::{Duration, Instant};
 
 use tuig_iosys::{Action, IoSystem, Result, Screen, XY};
 
-use crate::{attachments::Attachment, Region};
+use crate::{attachments::Attachment, FocusState, InputState, Layers, Region, Theme};
 
 /// A convenience wrapper to make it easier to manage screens, actions, and input in simple cases.
 ///
@@ -15,6 +15,10 @@ use crate::{attachments::Attachment, Region};
 ///
 /// - Only rendering if the screen has changed
 /// - Capping framerates, optionally
+/// - Compositing [layers](Region::layer) onto the screen
+/// - Tracking [keyboard focus](FocusState) across frames
+/// - Tracking the mouse position across inputs, for [hovering](Region::is_hovered)
+/// - Applying a [`Theme`] to the whole UI
 ///
 /// The biggest benefit is that this API will stay far more stable than the "lower level" ones, even during this early
 /// alpha phase, incorporating lots of [planned] [features] more or less seamlessly. The biggest drawback is that it
@@ -24,6 +28,12 @@ pub struct Adapter<IO: IoSystem> {
     io: IO,
     old: Screen,
     current: Screen,
+    layers: Layers,
+    /// `current` with the `layers` composited on, if there are any
+    composed: Screen,
+    focus: FocusState,
+    state: InputState,
+    theme: Theme,
     fps: Option<(Duration, Instant)>,
 }
 
@@ -34,6 +44,11 @@ impl<IO: IoSystem> Adapter<IO> {
             io,
             old: Screen::new(XY(0, 0)),
             current: Screen::new(XY(0, 0)),
+            layers: Layers::new(),
+            composed: Screen::new(XY(0, 0)),
+            focus: FocusState::new(),
+            state: InputState::new(),
+            theme: Theme::DEFAULT,
             fps: None,
         }
     }
@@ -53,6 +68,12 @@ impl<IO: IoSystem> Adapter<IO> {
         self
     }
 
+    /// Set the [`Theme`] the UI is drawn with. By default, it's [`Theme::DEFAULT`].
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
     /// Wait for one input like [`IoSystem::input`], then use it to render the attachment to the stored screen.
     ///
     /// This returns an error if the `IoSystem` did, or otherwise whatever the root attachment does.
@@ -80,11 +101,49 @@ impl<IO: IoSystem> Adapter<IO> {
     ///
     /// This is probably most obviously useful for `adapter.feed(&mut attachment, Action::Redraw)`. But that shouldn't
     /// be necessary if you're handling inputs immediately before drawing. Window resizes, etc. will send an
-    /// [`Action::Redraw`] that'll trigger a rerender anyway.
+    /// [`Action::Resized`] and [`Action::Redraw`] that'll trigger a rerender anyway.
     pub fn feed<'s, A: Attachment<'s>>(&'s mut self, root: A, input: Action) -> A::Output {
+        self.region(input).attach(root)
+    }
+
+    /// Feed in several actions, one after another, as though each was taken from the `IoSystem`, and return what the
+    /// UI returned for each.
+    ///
+    /// Every action gets its own pass through the whole UI, in order, so none of them are lost even if they all came
+    /// in during one frame. For example, a [`TextInput`](crate::attachments::TextInput) sees every character typed,
+    /// rather than just the last one. Only the screen left by the last pass is kept, ready for [`Self::draw`].
+    ///
+    /// Because the attachments are remade for every pass, this takes a function that builds and attaches them, rather
+    /// than the attachment itself. To handle everything the `IoSystem` has queued up, see [`Self::drain_input`].
+    pub fn feed_many<O>(
+        &mut self,
+        actions: impl IntoIterator<Item = Action>,
+        mut root: impl FnMut(Region<'_>) -> O,
+    ) -> Vec<O> {
+        actions
+            .into_iter()
+            .map(|input| root(self.region(input)))
+            .collect()
+    }
+
+    /// Take every input that's available right now, with [`IoSystem::drain_input`], and [feed](Self::feed_many) them
+    /// all in, in order.
+    ///
+    /// This never blocks. If nothing's come in, the UI isn't rendered at all and this returns an empty `Vec`, so you
+    /// may want to [`Self::refresh`] in that case.
+    pub fn drain_input<O>(&mut self, root: impl FnMut(Region<'_>) -> O) -> Result<Vec<O>> {
+        let actions = self.io.drain_input()?;
+        Ok(self.feed_many(actions, root))
+    }
+
+    /// Set up the root region for handling one input.
+    fn region(&mut self, input: Action) -> Region<'_> {
         self.current.resize(self.io.size());
-        let region = Region::new(&mut self.current, input);
-        region.attach(root)
+        self.state.action(&input);
+        Region::layered(&mut self.current, &mut self.layers, input)
+            .with_focus(&mut self.focus)
+            .with_mouse(self.state.mouse)
+            .with_theme(&self.theme)
     }
 
     /// Draw the stored screen to the display.
@@ -92,7 +151,17 @@ impl<IO: IoSystem> Adapter<IO> {
     /// This will **not** re-render anything if the screen isn't the right size -- it'll just try to draw. See the
     /// caveats in [`IoSystem::draw`] for more info.
     pub fn draw(&mut self) -> Result<()> {
-        if self.current == self.old {
+        let layered = !self.layers.is_empty();
+        if layered {
+            self.composed.clone_from(&self.current);
+            self.layers.composite(&mut self.composed);
+        }
+        let frame = if layered {
+            &mut self.composed
+        } else {
+            &mut self.current
+        };
+        if *frame == self.old {
             return Ok(());
         }
         if let Some((delta, ref mut next_draw)) = self.fps {
@@ -102,14 +171,97 @@ impl<IO: IoSystem> Adapter<IO> {
             }
             *next_draw = now + delta;
         }
-        self.io.draw(&self.current)?;
+        self.io.draw(frame)?;
         // preserve the screen we just drew as the old one, start rendering to the old old one
-        std::mem::swap(&mut self.old, &mut self.current);
+        std::mem::swap(&mut self.old, frame);
         Ok(())
     }
 
+    /// The focus state used for the UI, e.g. to [focus](FocusState::focus) a particular element.
+    pub fn focus(&mut self) -> &mut FocusState {
+        &mut self.focus
+    }
+
+    /// The theme used for the UI, e.g. to switch to a different one.
+    pub fn theme(&mut self) -> &mut Theme {
+        &mut self.theme
+    }
+
+    /// The state of the inputs so far, i.e. which modifiers are held and where the mouse is.
+    pub fn input_state(&self) -> &InputState {
+        &self.state
+    }
+
+    /// [Set the title](IoSystem::set_title) of the `IoSystem`'s display.
+    pub fn set_title(&mut self, title: &str) {
+        self.io.set_title(title)
+    }
+
+    /// [Suspend](IoSystem::suspend) the `IoSystem`, handing its display over to something else until
+    /// [`Self::resume`].
+    pub fn suspend(&mut self) -> Result<()> {
+        self.io.suspend()
+    }
+
+    /// [Resume](IoSystem::resume) the `IoSystem` after [`Self::suspend`].
+    pub fn resume(&mut self) -> Result<()> {
+        self.io.resume()
+    }
+
     /// [Stop](IoSystem::stop) the `IoSystem`.
     pub fn stop(&mut self) {
         self.io.stop()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use tuig_iosys::{
+        backends::{NopSystem, Record},
+        Action, Key,
+    };
+
+    use crate::{
+        attachments::{TextInput, TextInputResult},
+        Adapter,
+    };
+
+    /// An `IoSystem` with a queue of inputs ready to go, all at once
+    fn queued(inputs: impl IntoIterator<Item = Action>) -> Record<NopSystem> {
+        let (nop, _) = NopSystem::new().unwrap();
+        Record::new(nop, inputs.into_iter().collect::<VecDeque<_>>())
+    }
+
+    #[test]
+    fn drain_input_handles_every_action() {
+        let typed = "hi!".chars().map(|c| Action::press(Key::Char(c)));
+        let mut adapter = Adapter::new(queued(typed));
+        let mut ti = TextInput::new("> ", 0);
+        let mut passes = 0;
+        let res = adapter
+            .drain_input(|r| {
+                passes += 1;
+                r.attach(&mut ti);
+                passes
+            })
+            .unwrap();
+        assert_eq!(res, [1, 2, 3]);
+        assert_eq!(ti.line, "hi!");
+        assert_eq!(adapter.drain_input(|_| ()).unwrap(), []);
+    }
+
+    #[test]
+    fn feed_many_keeps_last_screen() {
+        let mut adapter = Adapter::new(queued([]));
+        let actions = [Action::press(Key::Char('a')), Action::Redraw];
+        let seen = adapter.feed_many(actions.clone(), |r| r.attach(|input, _| input));
+        assert_eq!(seen, actions);
+        let mut ti = TextInput::new("", 0);
+        adapter.feed_many([Action::press(Key::Char('z'))], |r| {
+            r.attach(&mut ti) == TextInputResult::Nothing
+        });
+        assert_eq!(adapter.current[0][0].ch, 'z');
+    }
+}
@@ -0,0 +1,252 @@
+use alloc::vec::Vec;
+use tuig_iosys::fmt::{char_width, str_width, Cell};
+
+use crate::{Region, ScreenView};
+
+use super::Attachment;
+
+/// The characters used to draw a [`Border`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BorderStyle {
+    /// Thin lines, with square corners: `┌─┐`
+    Single,
+    /// Doubled lines: `╔═╗`
+    Double,
+    /// Thin lines, with rounded corners: `╭─╮`
+    Rounded,
+    /// Plain ASCII, for when box drawing characters aren't available: `+-+`
+    Ascii,
+}
+
+impl BorderStyle {
+    /// The characters for the top left, top right, bottom left, and bottom right corners, then horizontal and
+    /// vertical edges.
+    fn chars(self) -> [char; 6] {
+        match self {
+            Self::Single => ['┌', '┐', '└', '┘', '─', '│'],
+            Self::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            Self::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+            Self::Ascii => ['+', '+', '+', '+', '-', '|'],
+        }
+    }
+}
+
+/// A box drawn around the edge of a region, optionally with a title.
+///
+/// Attaching a `Border` draws it, and returns the [`Region`] inside it, so you can keep laying things out:
+///
+/// ```no_run
+/// # use tuig_ui::{Region, attachments::Border};
+/// # let region = Region::empty(tuig_iosys::Action::Redraw);
+/// let inner = region.attach(Border::rounded().title("Logs"));
+/// inner.text(vec![]);
+/// ```
+///
+/// If the region is too small to fit a border (i.e. less than 2 cells in either direction) then none is drawn, and
+/// the whole region is returned. If the title is too wide to fit in the top edge, it's cut off with a `…`.
+pub struct Border<'t> {
+    style: BorderStyle,
+    title: Option<&'t str>,
+}
+
+impl<'t> Border<'t> {
+    /// Create a border with a particular style.
+    pub fn new(style: BorderStyle) -> Self {
+        Self { style, title: None }
+    }
+
+    /// Create a border with [`BorderStyle::Single`].
+    pub fn single() -> Self {
+        Self::new(BorderStyle::Single)
+    }
+
+    /// Create a border with [`BorderStyle::Double`].
+    pub fn double() -> Self {
+        Self::new(BorderStyle::Double)
+    }
+
+    /// Create a border with [`BorderStyle::Rounded`].
+    pub fn rounded() -> Self {
+        Self::new(BorderStyle::Rounded)
+    }
+
+    /// Create a border with [`BorderStyle::Ascii`].
+    pub fn ascii() -> Self {
+        Self::new(BorderStyle::Ascii)
+    }
+
+    tuig_pm::setters! {
+        /// Set the title, shown at the left of the top edge.
+        ///
+        /// Defaults to no title.
+        title(text: &'t str) => title = Some(text),
+    }
+
+    /// The cells of the top edge, between the corners, including the title if there is one.
+    fn top_edge(&self, width: usize, horiz: char) -> Vec<Cell> {
+        let mut cells = Vec::with_capacity(width);
+        let title = self.title.unwrap_or("");
+        let cut = str_width(title) > width;
+        let limit = if cut { width.saturating_sub(1) } else { width };
+        for ch in title.chars() {
+            let cw = char_width(ch);
+            if cells.len() + cw > limit {
+                break;
+            }
+            match cw {
+                0 => (),
+                1 => cells.push(Cell::of(ch)),
+                _ => {
+                    cells.push(Cell::of(ch));
+                    cells.push(Cell::of(Cell::CONTINUATION));
+                }
+            }
+        }
+        if cut && width > 0 {
+            cells.push(Cell::of('…'));
+        }
+        cells.resize(width, Cell::of(horiz));
+        cells
+    }
+}
+
+impl<'s, 't> Attachment<'s> for Border<'t> {
+    type Output = Region<'s>;
+
+    fn attach(self, mut region: Region<'s>) -> Self::Output {
+        let size = region.size();
+        if size.x() < 2 || size.y() < 2 {
+            return region;
+        }
+        let [tl, tr, bl, br, horiz, vert] = self.style.chars();
+        let top = self.top_edge(size.x() - 2, horiz);
+        region.split_top_mut(1).attach(|_, mut sv: ScreenView| {
+            let row = &mut sv[0];
+            row[0] = Cell::of(tl);
+            row[1..size.x() - 1].clone_from_slice(&top);
+            row[size.x() - 1] = Cell::of(tr);
+        });
+        // splitting off the *whole* remaining region isn't allowed, so the 2-wide and 2-tall cases need care
+        let empty = Region::empty(region.input.clone());
+        let (bottom, mut inner) = if region.size().y() > 1 {
+            (region.split_bottom_mut(1), region)
+        } else {
+            (region, empty)
+        };
+        bottom.attach(|_, mut sv: ScreenView| {
+            let row = &mut sv[0];
+            row.fill(Cell::of(horiz));
+            row[0] = Cell::of(bl);
+            row[size.x() - 1] = Cell::of(br);
+        });
+        if inner.size().y() == 0 {
+            return inner;
+        }
+        inner.split_left_mut(1).fill(Cell::of(vert));
+        if inner.size().x() == 1 {
+            let input = inner.input.clone();
+            inner.fill(Cell::of(vert));
+            return Region::empty(input);
+        }
+        inner.split_right_mut(1).fill(Cell::of(vert));
+        inner
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::String;
+    use tuig_iosys::{fmt::Cell, Action, Screen, XY};
+
+    use crate::Region;
+
+    use super::{Border, BorderStyle};
+
+    use alloc::vec::Vec;
+
+    fn rows(screen: &Screen) -> Vec<String> {
+        screen
+            .rows()
+            .map(|r| {
+                r.iter()
+                    .filter(|c| !c.is_continuation())
+                    .map(|c| c.ch)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn draws_box_and_returns_inside() {
+        let mut s = Screen::new(XY(5, 3));
+        let r = Region::new(&mut s, Action::Redraw);
+        let inner = r.attach(Border::single());
+        assert_eq!(inner.size(), XY(3, 1));
+        inner.fill(Cell::of('.'));
+        assert_eq!(rows(&s), ["┌───┐", "│...│", "└───┘"]);
+    }
+
+    #[test]
+    fn styles_use_their_chars() {
+        for (style, expected) in [
+            (BorderStyle::Double, ["╔╗", "╚╝"]),
+            (BorderStyle::Rounded, ["╭╮", "╰╯"]),
+            (BorderStyle::Ascii, ["++", "++"]),
+        ] {
+            let mut s = Screen::new(XY(2, 2));
+            let r = Region::new(&mut s, Action::Redraw);
+            assert_eq!(r.attach(Border::new(style)).size(), XY(0, 0));
+            assert_eq!(rows(&s), expected);
+        }
+    }
+
+    #[test]
+    fn title_shown_in_top_edge() {
+        let mut s = Screen::new(XY(8, 2));
+        let r = Region::new(&mut s, Action::Redraw);
+        let _ = r.attach(Border::ascii().title("Logs"));
+        assert_eq!(rows(&s)[0], "+Logs--+");
+    }
+
+    #[test]
+    fn title_exactly_fits() {
+        let mut s = Screen::new(XY(6, 2));
+        let r = Region::new(&mut s, Action::Redraw);
+        let _ = r.attach(Border::ascii().title("Logs"));
+        assert_eq!(rows(&s)[0], "+Logs+");
+    }
+
+    #[test]
+    fn long_title_truncated() {
+        let mut s = Screen::new(XY(6, 2));
+        let r = Region::new(&mut s, Action::Redraw);
+        let _ = r.attach(Border::ascii().title("Logfile"));
+        assert_eq!(rows(&s)[0], "+Log…+");
+    }
+
+    #[test]
+    fn wide_title_truncated() {
+        let mut s = Screen::new(XY(6, 2));
+        let r = Region::new(&mut s, Action::Redraw);
+        let _ = r.attach(Border::ascii().title("日本語"));
+        assert_eq!(rows(&s)[0], "+日…-+");
+    }
+
+    #[test]
+    fn too_small_skips_border() {
+        let mut s = Screen::new(XY(5, 1));
+        let r = Region::new(&mut s, Action::Redraw);
+        let inner = r.attach(Border::single().title("hi"));
+        assert_eq!(inner.size(), XY(5, 1));
+        inner.fill(Cell::of('.'));
+        assert_eq!(rows(&s), ["....."]);
+    }
+
+    #[test]
+    fn narrow_border_has_empty_inside() {
+        let mut s = Screen::new(XY(2, 3));
+        let r = Region::new(&mut s, Action::Redraw);
+        assert_eq!(r.attach(Border::ascii()).size(), XY(0, 0));
+        assert_eq!(rows(&s), ["++", "||", "++"]);
+    }
+}
@@ -1,15 +1,20 @@
 use tuig_iosys::{
-    fmt::{Cell, Color, FormattedExt},
+    fmt::{Cell, Formatted},
     Action, Key, MouseButton,
 };
 
-use crate::ScreenView;
+use crate::Region;
 
-use super::RawAttachment;
+use super::Attachment;
 
 /// A clickable button.
 ///
-/// This renders as some text in the center of the region. When the mouse is over it,
+/// This renders as some text in the center of the region. When the mouse is over it, or when it's focused and there's
+/// something else that could be, it's highlighted, and it's highlighted differently on the frame it gets clicked.
+///
+/// The button is [focusable](Region::focusable), with its label as the ID. When it's focused, pressing Enter clicks it.
+/// If focus isn't being [tracked](Region::with_focus) at all, Enter does nothing, since there's no telling which of
+/// the buttons on screen it's meant for.
 pub struct Button<'l>(pub &'l str);
 
 impl<'l> Button<'l> {
@@ -18,11 +23,11 @@ impl<'l> Button<'l> {
     }
 }
 
-impl<'l, 's> RawAttachment<'s> for Button<'l> {
-    type Output = <ButtonWithHotkey<'l> as RawAttachment<'s>>::Output;
+impl<'l, 's> Attachment<'s> for Button<'l> {
+    type Output = <ButtonWithHotkey<'l> as Attachment<'s>>::Output;
 
-    fn raw_attach(self, input: Action, screen: ScreenView<'s>) -> Self::Output {
-        ButtonWithHotkey(self.0, None).raw_attach(input, screen)
+    fn attach(self, region: Region<'s>) -> Self::Output {
+        ButtonWithHotkey(self.0, None).attach(region)
     }
 }
 
@@ -39,24 +44,37 @@ impl<'l> ButtonWithHotkey<'l> {
     }
 }
 
-impl<'l, 's> RawAttachment<'s> for ButtonWithHotkey<'l> {
+impl<'l, 's> Attachment<'s> for ButtonWithHotkey<'l> {
     type Output = bool;
 
-    fn raw_attach(self, input: Action, mut screen: ScreenView<'s>) -> Self::Output {
+    fn attach(self, mut region: Region<'s>) -> Self::Output {
+        if region.is_empty() {
+            return false;
+        }
+        let focused = region.focusable(self.0);
+        let enter_clicks = focused && region.focus_tracked();
+        let show_focus = focused && region.focus_visible();
+        let hovered = region.is_hovered();
+        let theme = region.theme();
+        let (input, mut screen) = region.raw_pieces();
         let (highlight, click) = match input {
-            Action::MouseMove { .. } => (true, false),
             Action::MousePress { button, .. } => (true, button == MouseButton::Left),
             Action::MouseRelease { .. } => (true, false),
-            Action::KeyPress { key } if self.is_hotkey(key) => (true, true),
-            Action::KeyRelease { key } if self.is_hotkey(key) => (true, false),
-            _ => (false, false),
+            Action::KeyPress { key, .. } if self.is_hotkey(key) => (true, true),
+            Action::KeyRelease { key, .. } if self.is_hotkey(key) => (true, false),
+            Action::KeyPress {
+                key: Key::Enter, ..
+            } if enter_clicks => (true, true),
+            _ => (show_focus || hovered, false),
         };
         let (fg, bg) = match (highlight, click) {
-            (.., true) => (Color::Black, Color::White),
-            (true, false) => (Color::Black, Color::BrightWhite),
-            (false, false) => (Color::White, Color::Black),
+            (.., true) => (&theme.selection, &theme.selection),
+            (true, false) => (&theme.accent, &theme.accent),
+            (false, false) => (&theme.fg, &theme.bg),
         };
-        screen.fill(Cell::of(' ').fg(fg).bg(bg));
+        let mut blank = Cell::of(' ');
+        *blank.get_fmt_mut() = bg.clone();
+        screen.fill(blank);
         let row = screen.size().y() / 2;
         let offset = screen.size().x().saturating_sub(self.0.len()) / 2;
         for (i, ch) in self.0.chars().enumerate() {
@@ -65,7 +83,93 @@ impl<'l, 's> RawAttachment<'s> for ButtonWithHotkey<'l> {
                 break;
             }
             screen[row][x].ch = ch;
+            *screen[row][x].get_fmt_mut() = fg.clone();
         }
         click
     }
 }
+
+#[cfg(test)]
+mod test {
+    use tuig_iosys::{
+        fmt::{Color, Format, Formatted},
+        Action, Key, Screen, XY,
+    };
+
+    use crate::{Region, Theme};
+
+    use super::Button;
+
+    fn bg(screen: &Screen) -> Color {
+        screen[0][0].get_fmt().bg
+    }
+
+    #[test]
+    fn hover_highlights_without_mouse_input() {
+        let mut s = Screen::new(XY(4, 1));
+        let r = Region::new(&mut s, Action::press(Key::Char('x'))).with_mouse(Some(XY(1, 0)));
+        assert!(!r.attach(Button("ok")));
+        assert_eq!(bg(&s), Color::BrightWhite);
+    }
+
+    #[test]
+    fn mouse_elsewhere_doesnt_highlight() {
+        let mut s = Screen::new(XY(4, 2));
+        let r = Region::new(&mut s, Action::Redraw).with_mouse(Some(XY(1, 1)));
+        let (top, _) = r.split_top(1);
+        top.attach(Button("ok"));
+        assert_eq!(bg(&s), Color::Black);
+    }
+
+    #[test]
+    fn highlight_uses_active_theme() {
+        let plain = Theme::DEFAULT;
+        let green = Theme::DEFAULT.accent(Format {
+            fg: Color::Black,
+            bg: Color::Green,
+            ..Format::NONE
+        });
+        for (theme, color) in [(&plain, Color::BrightWhite), (&green, Color::Green)] {
+            let mut s = Screen::new(XY(4, 1));
+            let r = Region::new(&mut s, Action::Redraw)
+                .with_mouse(Some(XY(1, 0)))
+                .with_theme(theme);
+            r.attach(Button("ok"));
+            assert_eq!(bg(&s), color);
+            assert_eq!(s[0][1].ch, 'o');
+            assert_eq!(s[0][1].get_fmt().bg, color);
+        }
+        // and the rest of the theme is used when it's not highlighted
+        let mut s = Screen::new(XY(4, 1));
+        let blue = Theme::DEFAULT.fg(Format {
+            fg: Color::Blue,
+            ..Format::NONE
+        });
+        Region::new(&mut s, Action::Redraw)
+            .with_theme(&blue)
+            .attach(Button("ok"));
+        assert_eq!(s[0][0].get_fmt().fg, Color::White);
+        assert_eq!(s[0][1].get_fmt().fg, Color::Blue);
+    }
+
+    #[test]
+    fn enter_needs_focus_tracking() {
+        let mut s = Screen::new(XY(6, 1));
+        let r = Region::new(&mut s, Action::press(Key::Enter));
+        let (left, right) = r.split_left(3);
+        assert!(!left.attach(Button("ok")));
+        assert!(!right.attach(Button("no")));
+    }
+
+    #[test]
+    fn empty_region_never_clicks() {
+        for size in [XY(0, 3), XY(3, 0), XY(0, 0)] {
+            let mut s = Screen::new(size);
+            let r = Region::new(&mut s, Action::press(Key::Char('k')));
+            assert!(!r.attach(Button("ok").hotkey('k')));
+            let mut s = Screen::new(size);
+            let r = Region::new(&mut s, Action::press(Key::Enter));
+            assert!(!r.attach(Button("ok")));
+        }
+    }
+}
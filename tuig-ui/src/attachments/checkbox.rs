@@ -0,0 +1,193 @@
+use tuig_iosys::{
+    fmt::{char_width, Cell, Formatted},
+    Action, Key, MouseButton,
+};
+
+use crate::{Region, ScreenView, Theme};
+
+use super::Attachment;
+
+/// Draw a line of text at the start of a one-row view, highlighted or not, clearing the rest of the row.
+pub(super) fn draw_row(mut sv: ScreenView, parts: &[&str], theme: &Theme, highlight: bool) {
+    let (fg, bg) = match highlight {
+        true => (&theme.accent, &theme.accent),
+        false => (&theme.fg, &theme.bg),
+    };
+    let mut blank = Cell::of(' ');
+    *blank.get_fmt_mut() = bg.clone();
+    sv.fill(blank);
+    let width = sv.size().x();
+    let row = match sv.row_mut(0) {
+        Some(row) => row,
+        None => return,
+    };
+    let mut x = 0;
+    for ch in parts.iter().flat_map(|p| p.chars()) {
+        let cw = char_width(ch);
+        if cw == 0 {
+            continue;
+        }
+        if x + cw > width {
+            break;
+        }
+        row[x].ch = ch;
+        *row[x].get_fmt_mut() = fg.clone();
+        if cw == 2 {
+            row[x + 1].ch = Cell::CONTINUATION;
+            *row[x + 1].get_fmt_mut() = fg.clone();
+        }
+        x += cw;
+    }
+}
+
+/// Whether an input should toggle a checkbox or select a radio button.
+pub(super) fn is_activation(input: &Action, focused: bool) -> bool {
+    match input {
+        Action::MousePress {
+            button: MouseButton::Left,
+            ..
+        } => true,
+        Action::KeyPress {
+            key: Key::Char(' '),
+            ..
+        } => focused,
+        _ => false,
+    }
+}
+
+/// A checkbox with a label, like `[x] label`.
+///
+/// It's drawn on the first row of the region. Clicking anywhere in the region toggles it, as does pressing Space when
+/// it's focused. Attaching it returns whether it's checked *after* handling the input, so typical use looks like:
+///
+/// ```no_run
+/// # use tuig_ui::{Region, attachments::Checkbox};
+/// # let region = Region::empty(tuig_iosys::Action::Redraw);
+/// # let mut sound = true;
+/// sound = region.attach(Checkbox::new("Sound", sound));
+/// ```
+///
+/// The checkbox is [focusable](Region::focusable), with its label as the ID. If focus isn't being
+/// [tracked](Region::with_focus), Space does nothing, so one keypress doesn't toggle every checkbox on screen.
+pub struct Checkbox<'l> {
+    label: &'l str,
+    checked: bool,
+    unicode: bool,
+}
+
+impl<'l> Checkbox<'l> {
+    /// Create a checkbox with a label and current state.
+    pub fn new(label: &'l str, checked: bool) -> Self {
+        Self {
+            label,
+            checked,
+            unicode: false,
+        }
+    }
+
+    tuig_pm::setters! {
+        /// Whether to draw the box with Unicode glyphs, `☑` and `☐`, rather than `[x]` and `[ ]`.
+        ///
+        /// Defaults to false, since not every font has those glyphs.
+        unicode(v: bool) => unicode = v,
+    }
+}
+
+impl<'s, 'l> Attachment<'s> for Checkbox<'l> {
+    type Output = bool;
+
+    fn attach(self, mut region: Region<'s>) -> Self::Output {
+        if region.is_empty() {
+            return self.checked;
+        }
+        // without focus tracking, every checkbox would think it's focused
+        let focused = region.focusable(self.label) && region.focus_tracked();
+        let show_focus = focused && region.focus_visible();
+        let checked = self.checked ^ is_activation(&region.input, focused);
+        let mark = match (self.unicode, checked) {
+            (false, true) => "[x] ",
+            (false, false) => "[ ] ",
+            (true, true) => "☑ ",
+            (true, false) => "☐ ",
+        };
+        if region.size().y() > 1 {
+            region = region.split_top_mut(1);
+        }
+        let theme = region.theme();
+        let (_, sv) = region.raw_pieces();
+        draw_row(sv, &[mark, self.label], theme, show_focus);
+        checked
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::String;
+    use tuig_iosys::{Action, Key, MouseButton, Screen, XY};
+
+    use crate::{FocusState, Region};
+
+    use super::Checkbox;
+
+    fn row(screen: &Screen, y: usize) -> String {
+        screen[y].iter().map(|c| c.ch).collect()
+    }
+
+    fn click() -> Action {
+        Action::MousePress {
+            pos: XY(3, 0),
+            button: MouseButton::Left,
+        }
+    }
+
+    #[test]
+    fn renders_state() {
+        let mut s = Screen::new(XY(8, 1));
+        assert!(!Region::new(&mut s, Action::Redraw).attach(Checkbox::new("abc", false)));
+        assert_eq!(row(&s, 0), "[ ] abc ");
+        assert!(Region::new(&mut s, Action::Redraw).attach(Checkbox::new("abc", true)));
+        assert_eq!(row(&s, 0), "[x] abc ");
+        Region::new(&mut s, Action::Redraw).attach(Checkbox::new("abc", true).unicode(true));
+        assert_eq!(row(&s, 0), "☑ abc   ");
+    }
+
+    #[test]
+    fn click_toggles() {
+        let mut s = Screen::new(XY(8, 1));
+        assert!(Region::new(&mut s, click()).attach(Checkbox::new("abc", false)));
+        assert_eq!(row(&s, 0), "[x] abc ");
+        assert!(!Region::new(&mut s, click()).attach(Checkbox::new("abc", true)));
+    }
+
+    #[test]
+    fn space_toggles_only_when_focused() {
+        let mut s = Screen::new(XY(8, 1));
+        let space = Action::press(Key::Char(' '));
+        let mut focus = FocusState::new();
+        let r = Region::new(&mut s, space.clone()).with_focus(&mut focus);
+        assert!(r.attach(Checkbox::new("abc", false)));
+        let mut focus = FocusState::new();
+        focus.focus("elsewhere");
+        let r = Region::new(&mut s, space).with_focus(&mut focus);
+        assert!(!r.attach(Checkbox::new("abc", false)));
+    }
+
+    #[test]
+    fn empty_region_never_toggles() {
+        for size in [XY(0, 1), XY(8, 0)] {
+            let mut s = Screen::new(size);
+            let mut focus = FocusState::new();
+            let r = Region::new(&mut s, Action::press(Key::Char(' '))).with_focus(&mut focus);
+            assert!(!r.attach(Checkbox::new("abc", false)));
+        }
+    }
+
+    #[test]
+    fn space_ignored_without_focus_tracking() {
+        let mut s = Screen::new(XY(8, 2));
+        let r = Region::new(&mut s, Action::press(Key::Char(' ')));
+        let (top, bottom) = r.split_top(1);
+        assert!(!top.attach(Checkbox::new("abc", false)));
+        assert!(!bottom.attach(Checkbox::new("def", false)));
+    }
+}
@@ -4,10 +4,23 @@
 //! types directly. That said, all those convenience methods do is call `Region::attach` on an object in this module,
 //! and if you need more control you might need to do the same.
 
+mod border;
+pub use border::{Border, BorderStyle};
 mod button;
 pub use button::Button;
+mod checkbox;
+pub use checkbox::Checkbox;
+mod progress_bar;
+pub use progress_bar::ProgressBar;
+mod radio_group;
+pub use radio_group::RadioGroup;
+mod spinner;
+pub use spinner::Spinner;
+mod table;
+pub use table::{ColWidth, Table};
 mod textbox;
-pub use textbox::{Textbox, TextboxData};
+pub use textbox::{Align, SpanId, Textbox, TextboxData, VAlign};
+pub use tuig_iosys::fmt::Wrap;
 mod text_input;
 pub use text_input::{TextInput, TextInputResult};
 use tuig_iosys::Action;
@@ -23,6 +36,13 @@ use super::{Region, ScreenView};
 /// Implementing this trait directly will let you fiddle with `Region`s and, generally, is the high-level interface.
 /// If you need the lower-level interface to actually handle input and draw to the character grid, you'll need to impl
 /// [`RawAttachment`], or make use of `FnOnce(Action, ScreenView)` implementing `RawAttachment`.
+///
+/// Attachments should handle being attached to an [empty](Region::is_empty) region by doing nothing and quickly
+/// returning whatever output makes sense for that, like a button that isn't clicked. The ones in this crate do.
+///
+/// An attachment can never draw outside of the region it's attached to, since [`ScreenView`] only exposes the cells
+/// in its bounds. If it has more to show than fits, it's clipped, and some attachments say so in their output, like
+/// [`TextboxData::overflow`].
 pub trait Attachment<'s> {
     type Output;
     fn attach(self, region: Region<'s>) -> Self::Output;
@@ -186,3 +206,57 @@ pub(crate) mod test_utils {
         __screen_assert as screen_assert,
     };
 }
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+    use tuig_iosys::{
+        fmt::{Cell, FormattedExt},
+        text, Action, MouseButton, Screen, XY,
+    };
+
+    use crate::Region;
+
+    use super::{
+        test_utils::{assert_area_blank, charat, make_region, make_screen},
+        *,
+    };
+
+    #[test]
+    fn attachments_stay_inside_region() {
+        let long = "far too much text to ever fit in here ".repeat(4);
+        let labels: Vec<&str> = long.split(' ').collect();
+        let click = Action::MousePress {
+            pos: XY(5, 3),
+            button: MouseButton::Left,
+        };
+        let mut input = TextInput::new(long.clone(), 4);
+        input.multiline = true;
+        for action in [Action::Redraw, click] {
+            macro_rules! check {
+                ($attachment:expr) => {{
+                    make_screen!(sc(12, 8), r(3, 2, 5, 3, action.clone()));
+                    r.attach($attachment);
+                    assert_area_blank(&sc, .., ..2);
+                    assert_area_blank(&sc, .., 5..);
+                    assert_area_blank(&sc, ..3, ..);
+                    assert_area_blank(&sc, 8.., ..);
+                }};
+            }
+            check!(Textbox::new(text!("{}"(long))).wrap(Wrap::None));
+            check!(Textbox::new(text!("{}"(long)))
+                .scroll(3)
+                .align(Align::Right));
+            check!(Button(&long));
+            check!(Checkbox::new(&long, true));
+            check!(RadioGroup::new(&labels, 4));
+            check!(ProgressBar::new(0.7).label(&long));
+            check!(Spinner::new(3).label(&long));
+            check!(&mut input);
+            check!(|_, mut sv: ScreenView| sv.fill(Cell::of('#')));
+            check!(Border::double().title(&long));
+            let row = || labels.iter().map(|l| text!("{}"(l))).collect();
+            check!(Table::new(alloc::vec![row(), row(), row(), row()]).headers(row()));
+        }
+    }
+}
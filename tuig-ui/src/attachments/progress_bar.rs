@@ -0,0 +1,298 @@
+use tuig_iosys::fmt::{char_width, str_width, Cell, Formatted, FormattedExt};
+
+use crate::Region;
+
+use super::Attachment;
+
+/// Left-aligned blocks from 1/8 to 7/8 of a cell wide, for drawing the partially filled cell at the end of the bar.
+const PARTIALS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// The full block character, which is the default fill.
+const FULL: char = '█';
+
+enum Progress {
+    Fraction(f32),
+    Marquee(usize),
+}
+
+/// A horizontal progress bar, filling its region from left to right.
+///
+/// Every row of the region gets the same bar, and the label, if there is one, is centered over the middle row. With
+/// the default fill, the end of the bar is drawn with partial block characters, for 1/8th-cell precision; with a
+/// custom fill, it's rounded down to the nearest cell. Unless you pick your own, it's drawn in the region's
+/// [`Theme`](crate::Theme).
+///
+/// If you don't know how far along things are, [`Self::indeterminate`] shows a block bouncing back and forth instead.
+pub struct ProgressBar<'l> {
+    progress: Progress,
+    label: Option<&'l str>,
+    fill: Option<Cell>,
+    empty: Option<Cell>,
+}
+
+impl<'l> ProgressBar<'l> {
+    /// Create a progress bar showing some fraction complete, from 0.0 to 1.0. Anything outside that range is clamped.
+    pub fn new(progress: f32) -> Self {
+        // NaN fails both comparisons in clamp and would stay NaN, so it gets caught separately
+        let progress = if progress.is_nan() {
+            0.0
+        } else {
+            progress.clamp(0.0, 1.0)
+        };
+        Self::with_progress(Progress::Fraction(progress))
+    }
+
+    /// Create a progress bar which doesn't know how far along it is, and shows a marquee instead.
+    ///
+    /// `frame` should go up by one every time you render it, to animate the marquee.
+    pub fn indeterminate(frame: usize) -> Self {
+        Self::with_progress(Progress::Marquee(frame))
+    }
+
+    fn with_progress(progress: Progress) -> Self {
+        Self {
+            progress,
+            label: None,
+            fill: None,
+            empty: None,
+        }
+    }
+
+    tuig_pm::setters! {
+        /// Set the label, drawn centered over the bar.
+        ///
+        /// Defaults to no label.
+        label(text: &'l str) => label = Some(text),
+        /// Set the cell used for the filled part of the bar.
+        ///
+        /// Defaults to a full block, `█`, in the theme's [`fg`](crate::Theme::fg).
+        fill(cell: Cell) => fill = Some(cell),
+        /// Set the cell used for the unfilled part of the bar.
+        ///
+        /// Defaults to a space in the theme's [`bg`](crate::Theme::bg).
+        empty(cell: Cell) => empty = Some(cell),
+    }
+
+    /// Which cells of a `width`-wide bar are filled, as a range, and the partial block to put after it, if any.
+    fn filled(&self, width: usize, fill: &Cell) -> (usize, usize, Option<char>) {
+        match self.progress {
+            Progress::Fraction(frac) => {
+                let eighths = (frac * (width * 8) as f32) as usize;
+                let (full, part) = (eighths / 8, eighths % 8);
+                let partial = if part > 0 && fill.ch == FULL {
+                    Some(PARTIALS[part - 1])
+                } else {
+                    None
+                };
+                (0, full, partial)
+            }
+            Progress::Marquee(frame) => {
+                let len = (width / 4).max(1);
+                let travel = width - len;
+                if travel == 0 {
+                    return (0, width, None);
+                }
+                // bounce back and forth instead of wrapping around
+                let step = frame % (travel * 2);
+                let start = if step > travel {
+                    travel * 2 - step
+                } else {
+                    step
+                };
+                (start, start + len, None)
+            }
+        }
+    }
+
+    /// The cell to draw a label character over some bar cell with.
+    ///
+    /// Over the fill, the colors are swapped if it's a full block, since the color of a block is its foreground.
+    fn label_cell(&self, ch: char, under: &Cell) -> Cell {
+        let cell = Cell::of(ch).fmt_of(under);
+        if under.ch == FULL {
+            let fmt = under.get_fmt();
+            cell.fg(fmt.bg).bg(fmt.fg)
+        } else {
+            cell
+        }
+    }
+}
+
+impl<'s, 'l> Attachment<'s> for ProgressBar<'l> {
+    type Output = ();
+
+    fn attach(self, region: Region<'s>) {
+        if region.is_empty() {
+            return;
+        }
+        let theme = region.theme();
+        let fill = self
+            .fill
+            .clone()
+            .unwrap_or_else(|| Cell::of(FULL).fmt(theme.fg.clone()));
+        let empty = self
+            .empty
+            .clone()
+            .unwrap_or_else(|| Cell::of(' ').fmt(theme.bg.clone()));
+        let (_, mut screen) = region.raw_pieces();
+        let size = screen.size();
+        let (start, end, partial) = self.filled(size.x(), &fill);
+        for y in 0..size.y() {
+            let row = &mut screen[y];
+            row.fill(empty.clone());
+            row[start..end].fill(fill.clone());
+            if let (Some(ch), Some(cell)) = (partial, row.get_mut(end)) {
+                *cell = Cell::of(ch).fmt_of(&fill);
+            }
+        }
+
+        let label = match self.label {
+            Some(l) => l,
+            None => return,
+        };
+        let row = &mut screen[size.y() / 2];
+        let mut x = size.x().saturating_sub(str_width(label)) / 2;
+        for ch in label.chars() {
+            let cw = char_width(ch);
+            if cw == 0 {
+                continue;
+            }
+            if x + cw > size.x() {
+                break;
+            }
+            row[x] = self.label_cell(ch, &row[x]);
+            if cw == 2 {
+                row[x + 1] = self.label_cell(Cell::CONTINUATION, &row[x + 1]);
+            }
+            x += cw;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::String, vec::Vec};
+    use tuig_iosys::{
+        fmt::{Cell, Color, Format, Formatted, FormattedExt},
+        Action, Screen, XY,
+    };
+
+    use crate::{Region, Theme};
+
+    use super::ProgressBar;
+
+    fn render(width: usize, bar: ProgressBar) -> Screen {
+        let mut s = Screen::new(XY(width, 1));
+        Region::new(&mut s, Action::Redraw).attach(bar);
+        s
+    }
+
+    fn row(screen: &Screen) -> String {
+        screen[0].iter().map(|c| c.ch).collect()
+    }
+
+    #[test]
+    fn fills_proportionally() {
+        assert_eq!(row(&render(4, ProgressBar::new(0.5))), "██  ");
+        assert_eq!(row(&render(4, ProgressBar::new(0.0))), "    ");
+        assert_eq!(row(&render(4, ProgressBar::new(1.0))), "████");
+    }
+
+    #[test]
+    fn partial_blocks_for_precision() {
+        assert_eq!(row(&render(4, ProgressBar::new(0.125))), "▌   ");
+        assert_eq!(row(&render(2, ProgressBar::new(0.9375))), "█▉");
+    }
+
+    #[test]
+    fn out_of_range_is_clamped() {
+        assert_eq!(row(&render(3, ProgressBar::new(7.0))), "███");
+        assert_eq!(row(&render(3, ProgressBar::new(-1.0))), "   ");
+        assert_eq!(row(&render(3, ProgressBar::new(f32::NAN))), "   ");
+    }
+
+    #[test]
+    fn custom_cells_round_down() {
+        let bar = ProgressBar::new(0.6)
+            .fill(Cell::of('#'))
+            .empty(Cell::of('-'));
+        assert_eq!(row(&render(4, bar)), "##--");
+    }
+
+    #[test]
+    fn label_centered_and_inverted_over_fill() {
+        let s = render(8, ProgressBar::new(0.5).label("50%"));
+        assert_eq!(row(&s), "██50%   ");
+        let fmt = s[0][2].get_fmt();
+        assert_eq!((fmt.fg, fmt.bg), (Color::Black, Color::White));
+        let fmt = s[0][4].get_fmt();
+        assert_eq!((fmt.fg, fmt.bg), (Color::White, Color::Black));
+    }
+
+    #[test]
+    fn label_keeps_custom_fill_format() {
+        let bar = ProgressBar::new(1.0)
+            .fill(Cell::of('=').on_blue())
+            .label("ok");
+        let s = render(4, bar);
+        assert_eq!(row(&s), "=ok=");
+        assert_eq!(s[0][1].get_fmt().bg, Color::Blue);
+    }
+
+    #[test]
+    fn marquee_bounces() {
+        let frames: Vec<_> = (0..10)
+            .map(|f| row(&render(4, ProgressBar::indeterminate(f))))
+            .collect();
+        assert_eq!(
+            frames,
+            [
+                "█   ", " █  ", "  █ ", "   █", "  █ ", " █  ", "█   ", " █  ", "  █ ", "   █"
+            ]
+        );
+    }
+
+    #[test]
+    fn empty_region_draws_nothing() {
+        for size in [XY(0, 1), XY(4, 0)] {
+            let mut s = Screen::new(size);
+            let r = Region::new(&mut s, Action::Redraw);
+            assert!(r.is_empty());
+            r.attach(ProgressBar::indeterminate(3).label("loading"));
+            assert!(s == Screen::new(size));
+        }
+    }
+
+    #[test]
+    fn default_cells_use_theme() {
+        let theme = Theme::DEFAULT
+            .fg(Format {
+                fg: Color::Green,
+                ..Format::NONE
+            })
+            .bg(Format {
+                bg: Color::Blue,
+                ..Format::NONE
+            });
+        let mut s = Screen::new(XY(4, 1));
+        Region::new(&mut s, Action::Redraw)
+            .with_theme(&theme)
+            .attach(ProgressBar::new(0.5));
+        assert_eq!(row(&s), "██  ");
+        assert_eq!(s[0][0].get_fmt().fg, Color::Green);
+        assert_eq!(s[0][3].get_fmt().bg, Color::Blue);
+        // and custom cells override it
+        Region::new(&mut s, Action::Redraw)
+            .with_theme(&theme)
+            .attach(ProgressBar::new(0.5).fill(Cell::of('#')));
+        assert_eq!(s[0][0].get_fmt().fg, Cell::of('#').get_fmt().fg);
+    }
+
+    #[test]
+    fn fills_every_row() {
+        let mut s = Screen::new(XY(2, 3));
+        Region::new(&mut s, Action::Redraw).attach(ProgressBar::new(0.5).label("x"));
+        let rows: Vec<String> = s.rows().map(|r| r.iter().map(|c| c.ch).collect()).collect();
+        assert_eq!(rows, ["█ ", "x ", "█ "]);
+    }
+}
@@ -0,0 +1,183 @@
+use alloc::vec::Vec;
+use tuig_iosys::{Action, Key};
+
+use crate::Region;
+
+use super::{
+    checkbox::{draw_row, is_activation},
+    Attachment,
+};
+
+/// A list of mutually exclusive options, one per row, like `(*) label`.
+///
+/// Clicking on an option selects it. The group as a whole is [focusable](Region::focusable), with its slice of labels
+/// as the ID, and when it's focused, Up and Down move the selection. (If focus isn't being
+/// [tracked](Region::with_focus), they do nothing, so one keypress doesn't move every radio group on screen.) Attaching
+/// it returns the selected index *after* handling the input:
+///
+/// ```no_run
+/// # use tuig_ui::{Region, attachments::RadioGroup};
+/// # let region = Region::empty(tuig_iosys::Action::Redraw);
+/// # let mut difficulty = 1;
+/// difficulty = region.attach(RadioGroup::new(&["Easy", "Normal", "Hard"], difficulty));
+/// ```
+///
+/// If there are more options than rows in the region, the ones that don't fit aren't drawn, and can only be selected
+/// with the keyboard.
+pub struct RadioGroup<'l> {
+    labels: &'l [&'l str],
+    selected: usize,
+    unicode: bool,
+}
+
+impl<'l> RadioGroup<'l> {
+    /// Create a radio group with some options and the index of the currently selected option.
+    pub fn new(labels: &'l [&'l str], selected: usize) -> Self {
+        Self {
+            labels,
+            selected,
+            unicode: false,
+        }
+    }
+
+    tuig_pm::setters! {
+        /// Whether to draw the buttons with Unicode glyphs, `◉` and `○`, rather than `(*)` and `( )`.
+        ///
+        /// Defaults to false, since not every font has those glyphs.
+        unicode(v: bool) => unicode = v,
+    }
+}
+
+impl<'s, 'l> Attachment<'s> for RadioGroup<'l> {
+    type Output = usize;
+
+    fn attach(self, mut region: Region<'s>) -> Self::Output {
+        if region.is_empty() {
+            return self.selected;
+        }
+        // without focus tracking, every radio group would think it's focused
+        let focused = region.focusable(self.labels) && region.focus_tracked();
+        let show_focus = focused && region.focus_visible();
+        let last = self.labels.len().saturating_sub(1);
+        let mut selected = match region.input {
+            Action::KeyPress { key: Key::Up, .. } if focused => self.selected.saturating_sub(1),
+            Action::KeyPress { key: Key::Down, .. } if focused => self.selected + 1,
+            _ => self.selected,
+        }
+        .min(last);
+
+        let mut rows = Vec::with_capacity(self.labels.len());
+        while rows.len() < self.labels.len() && region.size().y() > 0 {
+            rows.push(region.split_top_mut(1));
+        }
+        for (i, row) in rows.iter().enumerate() {
+            if is_activation(&row.input, false) {
+                selected = i;
+            }
+        }
+        let theme = region.theme();
+        for (i, (row, label)) in rows.into_iter().zip(self.labels).enumerate() {
+            let mark = match (self.unicode, i == selected) {
+                (false, true) => "(*) ",
+                (false, false) => "( ) ",
+                (true, true) => "◉ ",
+                (true, false) => "○ ",
+            };
+            let (_, sv) = row.raw_pieces();
+            draw_row(sv, &[mark, label], theme, show_focus && i == selected);
+        }
+        selected
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::String, vec::Vec};
+    use tuig_iosys::{Action, Key, MouseButton, Screen, XY};
+
+    use crate::{FocusState, Region};
+
+    use super::RadioGroup;
+
+    const OPTS: &[&str] = &["a", "b", "c"];
+
+    fn rows(screen: &Screen) -> Vec<String> {
+        screen
+            .rows()
+            .map(|r| r.iter().map(|c| c.ch).collect())
+            .collect()
+    }
+
+    #[test]
+    fn renders_selection() {
+        let mut s = Screen::new(XY(5, 3));
+        let r = Region::new(&mut s, Action::Redraw);
+        assert_eq!(r.attach(RadioGroup::new(OPTS, 1)), 1);
+        assert_eq!(rows(&s), ["( ) a", "(*) b", "( ) c"]);
+        let r = Region::new(&mut s, Action::Redraw);
+        r.attach(RadioGroup::new(OPTS, 2).unicode(true));
+        assert_eq!(rows(&s), ["○ a  ", "○ b  ", "◉ c  "]);
+    }
+
+    #[test]
+    fn click_selects_row() {
+        let mut s = Screen::new(XY(5, 3));
+        let click = Action::MousePress {
+            pos: XY(4, 2),
+            button: MouseButton::Left,
+        };
+        let r = Region::new(&mut s, click);
+        assert_eq!(r.attach(RadioGroup::new(OPTS, 0)), 2);
+        assert_eq!(rows(&s), ["( ) a", "( ) b", "(*) c"]);
+    }
+
+    #[test]
+    fn arrows_move_and_clamp_when_focused() {
+        let mut s = Screen::new(XY(5, 3));
+        let mut focus = FocusState::new();
+        let mut run = |input, sel| {
+            let r = Region::new(&mut s, input).with_focus(&mut focus);
+            r.attach(RadioGroup::new(OPTS, sel))
+        };
+        assert_eq!(run(Action::press(Key::Down), 0), 1);
+        assert_eq!(run(Action::press(Key::Down), 2), 2);
+        assert_eq!(run(Action::press(Key::Up), 0), 0);
+        assert_eq!(run(Action::press(Key::Up), 2), 1);
+    }
+
+    #[test]
+    fn arrows_ignored_when_unfocused() {
+        let mut s = Screen::new(XY(5, 3));
+        let mut focus = FocusState::new();
+        focus.focus("elsewhere");
+        let r = Region::new(&mut s, Action::press(Key::Down)).with_focus(&mut focus);
+        assert_eq!(r.attach(RadioGroup::new(OPTS, 0)), 0);
+    }
+
+    #[test]
+    fn empty_region_never_selects() {
+        for size in [XY(0, 3), XY(5, 0)] {
+            let mut s = Screen::new(size);
+            let mut focus = FocusState::new();
+            let r = Region::new(&mut s, Action::press(Key::Down)).with_focus(&mut focus);
+            assert_eq!(r.attach(RadioGroup::new(OPTS, 0)), 0);
+        }
+    }
+
+    #[test]
+    fn arrows_ignored_without_focus_tracking() {
+        let mut s = Screen::new(XY(5, 6));
+        let r = Region::new(&mut s, Action::press(Key::Down));
+        let (top, bottom) = r.split_top(3);
+        assert_eq!(top.attach(RadioGroup::new(OPTS, 0)), 0);
+        assert_eq!(bottom.attach(RadioGroup::new(&["x", "y"], 0)), 0);
+    }
+
+    #[test]
+    fn extra_options_not_drawn() {
+        let mut s = Screen::new(XY(5, 2));
+        let r = Region::new(&mut s, Action::Redraw);
+        assert_eq!(r.attach(RadioGroup::new(OPTS, 2)), 2);
+        assert_eq!(rows(&s), ["( ) a", "( ) b"]);
+    }
+}
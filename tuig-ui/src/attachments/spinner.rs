@@ -0,0 +1,114 @@
+use crate::Region;
+
+use super::{checkbox::draw_row, Attachment};
+
+/// A little animated indicator, for when something's happening but there's no telling how far along it is.
+///
+/// It's drawn on the first row of the region, as the current frame followed by the label, if there is one. Which frame
+/// is current is up to you: pass in a counter that goes up by one every time you render, and the spinner cycles
+/// through its frames with it:
+///
+/// ```no_run
+/// # use tuig_ui::{Region, attachments::Spinner};
+/// # let region = Region::empty(tuig_iosys::Action::Redraw);
+/// # let mut frame = 0;
+/// region.attach(Spinner::new(frame).label("Loading..."));
+/// frame += 1;
+/// ```
+///
+/// If you need a progress bar instead, see [`ProgressBar`](super::ProgressBar).
+pub struct Spinner<'l> {
+    frame: usize,
+    frames: &'l [&'l str],
+    label: Option<&'l str>,
+}
+
+impl<'l> Spinner<'l> {
+    /// A line spinning in place: `|/-\`. This is the default, since every font has these.
+    pub const ASCII: &'static [&'static str] = &["|", "/", "-", "\\"];
+
+    /// A dot circling around a Braille cell: `⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏`.
+    pub const BRAILLE: &'static [&'static str] =
+        &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+    /// Create a spinner showing some frame. Any number works; it wraps around to the start of the frames.
+    pub fn new(frame: usize) -> Self {
+        Self {
+            frame,
+            frames: Self::ASCII,
+            label: None,
+        }
+    }
+
+    tuig_pm::setters! {
+        /// Set the frames to cycle through, e.g. [`Self::BRAILLE`], or your own. Each should be the same width, so the
+        /// label doesn't jiggle around.
+        ///
+        /// Defaults to [`Self::ASCII`]. If there are no frames, only the label is drawn.
+        frames(set: &'l [&'l str]) => frames = set,
+        /// Set the label, drawn after the spinner.
+        ///
+        /// Defaults to no label.
+        label(text: &'l str) => label = Some(text),
+    }
+}
+
+impl<'s, 'l> Attachment<'s> for Spinner<'l> {
+    type Output = ();
+
+    fn attach(self, mut region: Region<'s>) -> Self::Output {
+        if region.is_empty() {
+            return;
+        }
+        let glyph = match self.frames.len() {
+            0 => "",
+            n => self.frames[self.frame % n],
+        };
+        let (sep, label) = match self.label {
+            Some(l) if !glyph.is_empty() => (" ", l),
+            Some(l) => ("", l),
+            None => ("", ""),
+        };
+        if region.size().y() > 1 {
+            region = region.split_top_mut(1);
+        }
+        let theme = region.theme();
+        let (_, sv) = region.raw_pieces();
+        draw_row(sv, &[glyph, sep, label], theme, false);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::String, vec::Vec};
+    use tuig_iosys::{Action, Screen, XY};
+
+    use crate::Region;
+
+    use super::Spinner;
+
+    fn render(width: usize, spinner: Spinner) -> String {
+        let mut s = Screen::new(XY(width, 2));
+        Region::new(&mut s, Action::Redraw).attach(spinner);
+        assert!(s[1].iter().all(|c| c.ch == ' '), "drew past the first row");
+        s[0].iter().map(|c| c.ch).collect()
+    }
+
+    #[test]
+    fn cycles_through_frames() {
+        let frames: Vec<_> = (0..6).map(|f| render(1, Spinner::new(f))).collect();
+        assert_eq!(frames, ["|", "/", "-", "\\", "|", "/"]);
+        assert_eq!(render(1, Spinner::new(12).frames(Spinner::BRAILLE)), "⠹");
+        assert_eq!(render(1, Spinner::new(usize::MAX).frames(&["a", "b"])), "b");
+    }
+
+    #[test]
+    fn label_follows_glyph() {
+        assert_eq!(render(8, Spinner::new(1).label("wait")), "/ wait  ");
+        assert_eq!(render(4, Spinner::new(0).label("loading")), "| lo");
+        assert_eq!(
+            render(5, Spinner::new(0).frames(&[]).label("wait")),
+            "wait "
+        );
+    }
+}
@@ -0,0 +1,349 @@
+use alloc::vec::Vec;
+use tuig_iosys::{
+    fmt::{char_width, str_width, Cell, FormattedExt, Text},
+    MouseButton,
+};
+
+use crate::Region;
+
+use super::{Align, Attachment};
+
+/// How wide a [`Table`] column is.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ColWidth {
+    /// As wide as the widest cell in the column, header included.
+    #[default]
+    Auto,
+    /// Exactly this many columns wide, truncating anything wider.
+    Fixed(usize),
+}
+
+#[derive(Clone, Copy, Default)]
+struct ColOpts {
+    width: ColWidth,
+    align: Align,
+}
+
+/// Rows of data, lined up in columns, with an optional header.
+///
+/// Each cell is a `Vec<Text>`, like a [`Textbox`](super::Textbox)'s contents, but all on one line. Rows don't need to
+/// have the same number of cells; missing ones are left blank. Columns are separated by a [gap](Self::gap), and each
+/// one is as wide as its widest cell unless [set otherwise](Self::width). Cells too wide for their column, or cut off
+/// by the edge of the region, are truncated with a `…`.
+///
+/// Attaching it returns the index of the row that was left clicked, if any. Clicks on the header don't count.
+///
+/// ```no_run
+/// # use tuig_iosys::text;
+/// # use tuig_ui::{Region, attachments::{Align, Table}};
+/// # let region = Region::empty(tuig_iosys::Action::Redraw);
+/// let table = Table::new(vec![
+///     vec![text!("init"), text!("1")],
+///     vec![text!("bash"), text!("4512")],
+/// ])
+/// .headers(vec![text!("name"), text!("pid")])
+/// .align(1, Align::Right);
+/// if let Some(row) = region.attach(table) {
+///     // show details for that process
+/// }
+/// ```
+///
+/// Rows that don't fit in the region aren't drawn, and can't be clicked; [`Self::scroll`] to show them.
+pub struct Table {
+    headers: Option<Vec<Vec<Text>>>,
+    rows: Vec<Vec<Vec<Text>>>,
+    cols: Vec<ColOpts>,
+    gap: usize,
+    separator: Option<char>,
+    scroll: usize,
+}
+
+impl Table {
+    /// Create a table with some rows of cells, and no header.
+    pub fn new(rows: Vec<Vec<Vec<Text>>>) -> Self {
+        Self {
+            headers: None,
+            rows,
+            cols: Vec::new(),
+            gap: 1,
+            separator: Some('-'),
+            scroll: 0,
+        }
+    }
+
+    tuig_pm::setters! {
+        /// Set the header, drawn above the rows and never scrolled away.
+        ///
+        /// Defaults to no header.
+        headers(cells: Vec<Vec<Text>>) => headers = Some(cells),
+        /// How many blank columns go between each column of the table.
+        ///
+        /// Defaults to 1.
+        gap(amt: usize) => gap = amt,
+        /// The character to draw a line under the header with, or `None` for no line. Without a header, there's no
+        /// line either.
+        ///
+        /// Defaults to `-`, since not every font has box drawing characters like `─`.
+        separator(ch: Option<char>) => separator = ch,
+        /// How many rows to hide from the top, e.g. to show the rest of a long table.
+        ///
+        /// Defaults to 0, i.e. starting from the first row.
+        scroll(amt: usize) => scroll = amt,
+    }
+
+    /// The options for a column, making them if they don't exist yet.
+    fn col_mut(&mut self, col: usize) -> &mut ColOpts {
+        if self.cols.len() <= col {
+            self.cols.resize(col + 1, ColOpts::default());
+        }
+        &mut self.cols[col]
+    }
+
+    /// Set how wide a column is, counting from 0.
+    ///
+    /// Defaults to [`ColWidth::Auto`].
+    pub fn width(mut self, col: usize, width: ColWidth) -> Self {
+        self.col_mut(col).width = width;
+        self
+    }
+
+    /// Set how the cells in a column are positioned within its width, counting from 0, e.g. [`Align::Right`] for
+    /// numbers. The header is aligned the same way.
+    ///
+    /// Defaults to [`Align::Left`].
+    pub fn align(mut self, col: usize, align: Align) -> Self {
+        self.col_mut(col).align = align;
+        self
+    }
+
+    /// How wide each column is, accounting for every cell, whether or not it's visible.
+    fn column_widths(&self) -> Vec<usize> {
+        let count = self
+            .rows
+            .iter()
+            .chain(&self.headers)
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0);
+        let mut widths = alloc::vec![0; count];
+        for (col, width) in widths.iter_mut().enumerate() {
+            *width = match self.cols.get(col).map(|c| c.width).unwrap_or_default() {
+                ColWidth::Fixed(w) => w,
+                ColWidth::Auto => self
+                    .rows
+                    .iter()
+                    .chain(&self.headers)
+                    .filter_map(|row| row.get(col))
+                    .map(|cell| cell.iter().map(|c| str_width(&c.text)).sum())
+                    .max()
+                    .unwrap_or(0),
+            };
+        }
+        widths
+    }
+
+    /// Draw a row of cells into a row of the screen.
+    fn draw_row(&self, cells: &[Vec<Text>], widths: &[usize], out: &mut [Cell]) {
+        let mut x = 0;
+        for (col, (cell, &width)) in cells.iter().zip(widths).enumerate() {
+            if x >= out.len() {
+                break;
+            }
+            let end = out.len().min(x + width);
+            let align = self.cols.get(col).map(|c| c.align).unwrap_or_default();
+            draw_cell(cell, align, &mut out[x..end]);
+            x += width + self.gap;
+        }
+    }
+}
+
+/// Draw one cell of a table, truncating it with a `…` if it doesn't fit.
+fn draw_cell(chunks: &[Text], align: Align, out: &mut [Cell]) {
+    let width = out.len();
+    let mut cells = Vec::with_capacity(width);
+    'chunks: for chunk in chunks {
+        for ch in chunk.text.chars() {
+            let cw = char_width(ch);
+            if cw == 0 {
+                continue;
+            }
+            cells.push(Cell::of(ch).fmt_of(chunk));
+            if cw == 2 {
+                cells.push(Cell::of(Cell::CONTINUATION).fmt_of(chunk));
+            }
+            if cells.len() > width {
+                break 'chunks;
+            }
+        }
+    }
+    if cells.len() > width {
+        // make room for the ellipsis, without leaving half a wide character behind
+        cells.truncate(width.saturating_sub(1));
+        if cells.last().map_or(false, |c| c.width() == 2) {
+            cells.pop();
+        }
+        if let Some(last) = cells.last().cloned() {
+            cells.push(Cell::of('…').fmt_of(&last));
+        } else if width > 0 {
+            cells.push(Cell::of('…').fmt_of(&chunks[0]));
+        }
+    }
+    let slack = width - cells.len();
+    let x = match align {
+        Align::Left => 0,
+        Align::Center => slack / 2,
+        Align::Right => slack,
+    };
+    out[x..x + cells.len()].clone_from_slice(&cells);
+}
+
+impl<'s> Attachment<'s> for Table {
+    type Output = Option<usize>;
+
+    fn attach(self, mut region: Region<'s>) -> Self::Output {
+        if region.is_empty() {
+            return None;
+        }
+        let click = region.clicked(MouseButton::Left);
+        let theme = region.theme();
+        let (_, mut sv) = region.raw_pieces();
+        sv.fill(Cell::of(' ').fmt(theme.bg.clone()));
+
+        let widths = self.column_widths();
+        let mut y = 0;
+        if let Some(headers) = &self.headers {
+            if let Some(row) = sv.row_mut(y) {
+                self.draw_row(headers, &widths, row);
+                y += 1;
+            }
+            if let (Some(ch), Some(row)) = (self.separator, sv.row_mut(y)) {
+                let total =
+                    widths.iter().sum::<usize>() + self.gap * widths.len().saturating_sub(1);
+                let line = Cell::of(ch).fmt(theme.fg.clone());
+                let end = total.min(row.len());
+                row[..end].fill(line);
+                y += 1;
+            }
+        }
+        let body = y;
+        for cells in self.rows.iter().skip(self.scroll) {
+            match sv.row_mut(y) {
+                Some(row) => self.draw_row(cells, &widths, row),
+                None => break,
+            }
+            y += 1;
+        }
+
+        match click {
+            Some(pos) if pos.y() >= body && pos.y() < y => Some(self.scroll + pos.y() - body),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{string::String, vec::Vec};
+    use tuig_iosys::{
+        fmt::{Color, Formatted},
+        text, Action, MouseButton, Screen, XY,
+    };
+
+    use crate::{attachments::Align, Region};
+
+    use super::{ColWidth, Table};
+
+    fn rows(screen: &Screen) -> Vec<String> {
+        screen
+            .rows()
+            .map(|r| {
+                r.iter()
+                    .filter(|c| !c.is_continuation())
+                    .map(|c| c.ch)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn click(y: usize) -> Action {
+        Action::MousePress {
+            pos: XY(0, y),
+            button: MouseButton::Left,
+        }
+    }
+
+    #[test]
+    fn widths_cover_ragged_rows_and_wide_chars() {
+        let table = Table::new(alloc::vec![
+            alloc::vec![text!("漢字"), text!("10")],
+            alloc::vec![text!("a")],
+            alloc::vec![text!("abc"), text!("1", red "23"), text!("extra")],
+        ])
+        .headers(alloc::vec![text!("id"), text!("size")]);
+        assert_eq!(table.column_widths(), [4, 4, 5]);
+        let fixed = table.width(0, ColWidth::Fixed(2));
+        assert_eq!(fixed.column_widths(), [2, 4, 5]);
+        assert_eq!(Table::new(alloc::vec![]).column_widths(), [0; 0]);
+    }
+
+    #[test]
+    fn renders_aligned_with_header() {
+        let mut s = Screen::new(XY(12, 5));
+        let table = Table::new(alloc::vec![
+            alloc::vec![text!("init"), text!("1")],
+            alloc::vec![text!("sh"), text!(red "4512")],
+            alloc::vec![text!("漢")],
+        ])
+        .headers(alloc::vec![text!("name"), text!("pid")])
+        .align(1, Align::Right)
+        .gap(2);
+        assert_eq!(Region::new(&mut s, Action::Redraw).attach(table), None);
+        assert_eq!(
+            rows(&s),
+            [
+                "name   pid  ",
+                "----------  ",
+                "init     1  ",
+                "sh    4512  ",
+                "漢          ",
+            ]
+        );
+        assert_eq!(s[3][6].get_fmt().fg, Color::Red);
+    }
+
+    #[test]
+    fn overwide_cells_truncate() {
+        let mut s = Screen::new(XY(7, 2));
+        let table = Table::new(alloc::vec![
+            alloc::vec![text!("abcdef"), text!("xyz")],
+            alloc::vec![text!("漢字漢"), text!("q")],
+        ])
+        .width(0, ColWidth::Fixed(4));
+        Region::new(&mut s, Action::Redraw).attach(table);
+        // the second column is cut off by the edge of the region, too
+        assert_eq!(rows(&s), ["abc… x…", "漢…  q "]);
+    }
+
+    #[test]
+    fn clicks_pick_rows_below_header() {
+        let table = |scroll| {
+            Table::new((0..5).map(|i| alloc::vec![text!("{}"(i))]).collect())
+                .headers(alloc::vec![text!("n")])
+                .scroll(scroll)
+        };
+        let cases = [
+            (1, 0, None),
+            (1, 1, None),
+            (1, 2, Some(1)),
+            (1, 4, Some(3)),
+            (3, 3, Some(4)),
+            // past the last row
+            (3, 4, None),
+        ];
+        for (scroll, y, row) in cases {
+            let mut s = Screen::new(XY(3, 5));
+            let r = Region::new(&mut s, click(y));
+            assert_eq!(r.attach(table(scroll)), row, "clicking row {}", y);
+        }
+    }
+}
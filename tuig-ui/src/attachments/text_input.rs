@@ -2,13 +2,13 @@ use core::{iter, mem};
 
 use alloc::{collections::VecDeque, string::String};
 use tuig_iosys::{
-    fmt::{Cell, Format, Formatted, FormattedExt, Text},
-    text1, Action, Key,
+    fmt::{grapheme_len, graphemes, Cell, FormattedExt},
+    Action, Key, Modifiers, XY,
 };
 
-use crate::ScreenView;
+use crate::{Region, ScreenView, Theme};
 
-use super::RawAttachment;
+use super::Attachment;
 
 /// Takes text input, analogous to `<input type="text">`, with hooks for autocompletion, history, etc.
 ///
@@ -16,18 +16,20 @@ use super::RawAttachment;
 /// it's attached to. If the input is longer than the space is wide, the input scrolls left and right to keep the
 /// cursor in view, biasing towards the end, and cutting off the ends with a `…`.
 ///
+/// In [multiline](Self::multiline) mode, it instead shows one line of text per row of its region, and scrolls up and
+/// down to keep the cursor in view. Lines too long to fit are cut off with a `…`.
+///
 /// You'll need to keep the actual element around, because it tracks some pieces of state:
 /// - Currently in-progress input
 /// - Cursor position within the line
 /// - Autocomplete-related things
 ///
-/// Accordingly, [`Attachment`](super::Attachment) (through [`RawAttachment`]) is implemented for `&mut TextInput`,
-/// not `TextInput` itself, so you'll use it like:
+/// Accordingly, [`Attachment`] is implemented for `&mut TextInput`, not `TextInput` itself, so you'll use it like:
 ///
 /// ```no_run
 /// # use tuig_ui::{Region, attachments::{TextInput, TextInputResult}};
 /// let region = //...
-/// # Region::empty();
+/// # Region::empty(tuig_iosys::Action::Redraw);
 /// let mut text_input = // ...
 /// # TextInput::new("", 0);
 /// match region.attach(&mut text_input) {
@@ -39,13 +41,19 @@ use super::RawAttachment;
 ///
 /// [`Region::attach`](super::Region::attach)ing this will return a [`TextInputResult`], which is how you'll interact
 /// with autocomplete. To use the history features, see [`TextInput::store`].
+///
+/// The input is [focusable](Region::focusable), with its prompt as the ID, and it ignores keyboard input unless it's
+/// focused. If you have several in a form, give them different prompts.
+///
+/// It's drawn with the region's [`Theme`], with autocompletion shown as [`disabled`](Theme::disabled) until it's
+/// accepted.
 pub struct TextInput {
     /// A bit of fixed, uneditable text at the beginning of the text input, to signal the user to type.
     pub prompt: String,
 
     /// The current line of text being edited
     pub line: String,
-    /// Which character index the cursor is just before (so `cursor == line.len()` means the cursor is at the end)
+    /// Which byte index the cursor is just before (so `cursor == line.len()` means the cursor is at the end)
     pub cursor: usize,
 
     /// The caller-specified autocomplete text
@@ -60,6 +68,20 @@ pub struct TextInput {
     pub histpos: usize,
     /// Maximum number of history elements
     pub histcap: usize,
+
+    /// Whether Enter inserts a newline rather than submitting
+    pub multiline: bool,
+    /// The key and modifiers which submit the text in multiline mode
+    pub submit_key: (Key, Modifiers),
+    /// The column the cursor is trying to stay in while moving up and down, in multiline mode
+    goal_col: Option<usize>,
+    /// How many lines are scrolled off the top, in multiline mode
+    scroll: usize,
+
+    /// The character shown in place of each character of the text, if any, e.g. for passwords
+    pub mask: Option<char>,
+    /// Whether to temporarily show the real text despite the `mask`
+    pub revealed: bool,
 }
 
 impl TextInput {
@@ -78,9 +100,47 @@ impl TextInput {
             history: VecDeque::new(),
             histpos: 0,
             histcap: history_cap,
+            multiline: false,
+            submit_key: (Key::Enter, Modifiers::CTRL),
+            goal_col: None,
+            scroll: 0,
+            mask: None,
+            revealed: false,
         }
     }
 
+    tuig_pm::setters! {
+        /// Set whether this takes multiple lines of input.
+        ///
+        /// In multiline mode, Enter inserts a newline, and the [submit key](Self::submit_on) submits. Up and down move
+        /// between lines, only going through history from the first or last line.
+        ///
+        /// Defaults to false, i.e. single-line input.
+        multiline(v: bool) => multiline = v,
+        /// Set the key which submits the text in multiline mode.
+        ///
+        /// Defaults to Ctrl+Enter. In single-line mode, Enter always submits.
+        submit_on(key: Key, mods: Modifiers) => submit_key = (key, mods),
+        /// Hide the text, e.g. for passwords, by displaying each character as `mask` instead.
+        ///
+        /// This only affects how it's displayed; results still have the actual text. [`Self::MASK`] is a reasonable
+        /// default, if you don't have a particular preference.
+        ///
+        /// Defaults to no mask, i.e. showing the text as-is.
+        masked(mask: char) => mask = Some(mask),
+    }
+
+    /// A reasonable default mask character for [`Self::masked`].
+    pub const MASK: char = '•';
+
+    /// Toggle whether the real text is shown despite the [mask](Self::masked), returning whether it now is.
+    ///
+    /// This is meant for a "show password" button. It doesn't do anything if there's no mask.
+    pub fn toggle_reveal(&mut self) -> bool {
+        self.revealed = !self.revealed;
+        self.revealed
+    }
+
     /// Store a line in the history, usually one you just got from [`TextInputResult::Submit`]. (But that isn't
     /// required or enforced.)
     ///
@@ -120,6 +180,60 @@ impl TextInput {
         }
     }
 
+    /// The byte indices of the start and end of the line containing byte index `at` in the current text.
+    fn line_bounds(&self, at: usize) -> (usize, usize) {
+        let text = self.cur_line();
+        let start = text[..at].rfind('\n').map_or(0, |i| i + 1);
+        let end = text[at..].find('\n').map_or(text.len(), |i| at + i);
+        (start, end)
+    }
+
+    /// Which line of the current text the cursor is on, and whether that's the last.
+    fn cursor_row(&self) -> (usize, bool) {
+        let text = self.cur_line();
+        let row = text[..self.cursor].matches('\n').count();
+        (row, !text[self.cursor..].contains('\n'))
+    }
+
+    /// The byte index of the grapheme boundary just before the cursor, or 0 if it's at the start.
+    fn prev_boundary(&self) -> usize {
+        let before = &self.cur_line()[..self.cursor];
+        let mut prev = 0;
+        for g in graphemes(before) {
+            if prev + g.len() >= self.cursor {
+                break;
+            }
+            prev += g.len();
+        }
+        prev
+    }
+
+    /// The byte index of the grapheme boundary just after the cursor, or the cursor if it's at the end.
+    fn next_boundary(&self) -> usize {
+        self.cursor + grapheme_len(&self.cur_line()[self.cursor..])
+    }
+
+    /// Move the cursor up or down a line, trying to stay in the same column.
+    fn move_vertical(&mut self, up: bool) {
+        let (start, end) = self.line_bounds(self.cursor);
+        let col = match self.goal_col {
+            Some(c) => c,
+            None => self.cur_line()[start..self.cursor].chars().count(),
+        };
+        self.goal_col = Some(col);
+        let (to_start, to_end) = if up {
+            self.line_bounds(start - 1)
+        } else {
+            self.line_bounds(end + 1)
+        };
+        let target = &self.cur_line()[to_start..to_end];
+        self.cursor = to_start
+            + target
+                .char_indices()
+                .nth(col)
+                .map_or(target.len(), |(i, _)| i);
+    }
+
     fn sel_line(&mut self) {
         if self.histpos < self.history.len() {
             self.line = self.history[self.histpos].clone();
@@ -128,39 +242,82 @@ impl TextInput {
     }
 
     fn input(&mut self, input: Action) -> Option<TextInputResult<'static>> {
+        match &input {
+            Action::KeyPress {
+                key: Key::Up | Key::Down,
+                ..
+            } => (),
+            Action::KeyPress { .. } | Action::Paste(_) => self.goal_col = None,
+            _ => (),
+        }
         match input {
-            Action::KeyPress { key: Key::Char(ch) } => {
+            Action::KeyPress { key, mods, .. }
+                if self.multiline && (key, mods) == self.submit_key =>
+            {
+                self.sel_line();
+                self.cursor = 0;
+                self.scroll = 0;
+                self.autocomplete.clear();
+                Some(TextInputResult::Submit(mem::take(&mut self.line)))
+            }
+            Action::KeyPress {
+                key: Key::Char(ch), ..
+            } => {
                 self.sel_line();
                 self.line.insert(self.cursor, ch);
-                self.cursor += 1;
+                self.cursor += ch.len_utf8();
                 self.autocomplete.clear();
                 Some(TextInputResult::Nothing)
             }
-            Action::KeyPress { key: Key::Home } => {
-                self.cursor = 0;
+            Action::Paste(text) => {
+                // inserted literally, so pasting newlines doesn't submit
+                self.sel_line();
+                self.line.insert_str(self.cursor, &text);
+                self.cursor += text.len();
                 self.autocomplete.clear();
                 Some(TextInputResult::Nothing)
             }
-            Action::KeyPress { key: Key::End } => {
-                self.cursor = self.line.len();
+            Action::KeyPress { key: Key::Home, .. } => {
+                self.cursor = if self.multiline {
+                    self.line_bounds(self.cursor).0
+                } else {
+                    0
+                };
                 self.autocomplete.clear();
                 Some(TextInputResult::Nothing)
             }
-            Action::KeyPress { key: Key::Left } => {
-                if self.cursor > 0 {
-                    self.cursor -= 1;
-                }
+            Action::KeyPress { key: Key::End, .. } => {
+                self.cursor = if self.multiline {
+                    self.line_bounds(self.cursor).1
+                } else {
+                    self.line.len()
+                };
                 self.autocomplete.clear();
                 Some(TextInputResult::Nothing)
             }
-            Action::KeyPress { key: Key::Right } => {
-                if self.cursor < self.cur_line().len() {
-                    self.cursor += 1;
-                }
+            Action::KeyPress { key: Key::Left, .. } => {
+                self.cursor = self.prev_boundary();
                 self.autocomplete.clear();
                 Some(TextInputResult::Nothing)
             }
-            Action::KeyPress { key: Key::Up } => {
+            Action::KeyPress {
+                key: Key::Right, ..
+            } => {
+                self.cursor = self.next_boundary();
+                self.autocomplete.clear();
+                Some(TextInputResult::Nothing)
+            }
+            Action::KeyPress { key: Key::Up, .. } if self.multiline && self.cursor_row().0 > 0 => {
+                self.move_vertical(true);
+                self.autocomplete.clear();
+                Some(TextInputResult::Nothing)
+            }
+            Action::KeyPress { key: Key::Down, .. } if self.multiline && !self.cursor_row().1 => {
+                self.move_vertical(false);
+                self.autocomplete.clear();
+                Some(TextInputResult::Nothing)
+            }
+            Action::KeyPress { key: Key::Up, .. } => {
                 if self.histpos > 0 {
                     self.histpos -= 1;
                     self.cursor = self.cur_line().len();
@@ -168,7 +325,7 @@ impl TextInput {
                 self.autocomplete.clear();
                 Some(TextInputResult::Nothing)
             }
-            Action::KeyPress { key: Key::Down } => {
+            Action::KeyPress { key: Key::Down, .. } => {
                 if self.histpos < self.history.len() {
                     self.histpos += 1;
                     self.cursor = self.cur_line().len();
@@ -178,29 +335,45 @@ impl TextInput {
             }
             Action::KeyPress {
                 key: Key::Backspace,
+                ..
             } => {
                 self.sel_line();
                 if self.cursor > 0 {
-                    self.cursor -= 1;
-                    self.line.remove(self.cursor);
+                    let end = self.cursor;
+                    self.cursor = self.prev_boundary();
+                    self.line.replace_range(self.cursor..end, "");
                 }
                 self.autocomplete.clear();
                 Some(TextInputResult::Nothing)
             }
-            Action::KeyPress { key: Key::Delete } => {
+            Action::KeyPress {
+                key: Key::Delete, ..
+            } => {
                 self.sel_line();
                 if self.cursor < self.line.len() {
-                    self.line.remove(self.cursor);
+                    let end = self.next_boundary();
+                    self.line.replace_range(self.cursor..end, "");
                 }
                 self.autocomplete.clear();
                 Some(TextInputResult::Nothing)
             }
-            Action::KeyPress { key: Key::Tab } => {
+            Action::KeyPress { key: Key::Tab, .. } => {
                 self.sel_line();
                 self.autocomplete.clear();
                 None
             }
-            Action::KeyPress { key: Key::Enter } => {
+            Action::KeyPress {
+                key: Key::Enter, ..
+            } if self.multiline => {
+                self.sel_line();
+                self.line.insert(self.cursor, '\n');
+                self.cursor += 1;
+                self.autocomplete.clear();
+                Some(TextInputResult::Nothing)
+            }
+            Action::KeyPress {
+                key: Key::Enter, ..
+            } => {
                 self.sel_line();
                 self.cursor = 0;
                 self.autocomplete.clear();
@@ -210,14 +383,110 @@ impl TextInput {
         }
     }
 
-    fn render(&self, mut screen: ScreenView) {
-        // TODO: Rewrite like. all of this once #32 lands. it's so bad,,,
+    fn render_multiline(&mut self, mut screen: ScreenView, theme: &Theme) {
+        let XY(width, height) = screen.size();
+        let blank = Cell::of(' ').fmt(theme.bg.clone());
+        let (row, _) = self.cursor_row();
+        if row < self.scroll {
+            self.scroll = row;
+        } else if row >= self.scroll + height {
+            self.scroll = row + 1 - height;
+        }
+
+        let indent = self.prompt.chars().count();
+        let mut cells = alloc::vec::Vec::with_capacity(width);
+        let mut line_start = 0;
+        for (i, line) in self.cur_line().split('\n').enumerate() {
+            if i >= self.scroll + height {
+                break;
+            }
+            let line_end = line_start + line.len();
+            if i >= self.scroll {
+                if i == 0 {
+                    cells.extend(
+                        self.prompt
+                            .chars()
+                            .map(|c| Cell::of(c).fmt(theme.fg.clone())),
+                    );
+                } else {
+                    cells.extend(iter::repeat(blank.clone()).take(indent));
+                }
+                let printable = |c: char| self.display(c, theme);
+                if (line_start..=line_end).contains(&self.cursor) {
+                    let (pre, post) = line.split_at(self.cursor - line_start);
+                    cells.extend(pre.chars().map(printable));
+                    let mut post = post.chars();
+                    let mut auto = self.autocomplete.chars();
+                    let under = auto
+                        .next()
+                        .or_else(|| post.next())
+                        .map_or(blank.clone(), printable);
+                    let under = if self.autocomplete.is_empty() {
+                        under
+                    } else {
+                        under.fmt(theme.disabled.clone())
+                    };
+                    cells.push(under.underline());
+                    cells.extend(auto.map(|c| printable(c).fmt(theme.disabled.clone())));
+                    cells.extend(post.map(printable));
+                } else {
+                    cells.extend(line.chars().map(printable));
+                }
+                if cells.len() > width {
+                    cells.truncate(width);
+                    if let Some(last) = cells.last_mut() {
+                        *last = Cell::of('…').fmt(theme.fg.clone());
+                    }
+                }
+                cells.resize(width, blank.clone());
+                screen[i - self.scroll].clone_from_slice(&cells);
+                cells.clear();
+            }
+            line_start = line_end + 1;
+        }
+        let drawn = (self.cur_line().matches('\n').count() + 1 - self.scroll).min(height);
+        for y in drawn..height {
+            screen[y].fill(blank.clone());
+        }
+    }
+
+    /// Turn a character of the input into a cell to display, masking it if necessary.
+    fn display(&self, c: char, theme: &Theme) -> Cell {
+        let ch = match self.mask {
+            Some(m) if !self.revealed => m,
+            // control characters (e.g. pasted newlines) would break the layout, so show them as spaces
+            _ if c.is_control() => ' ',
+            _ => c,
+        };
+        Cell::of(ch).fmt(theme.fg.clone())
+    }
+
+    fn render(&self, mut screen: ScreenView, theme: &Theme) {
+        let blank = Cell::of(' ').fmt(theme.bg.clone());
+        let prompt = self
+            .prompt
+            .chars()
+            .map(|c| Cell::of(c).fmt(theme.fg.clone()));
+        let text = self.cur_line();
+        let prompt_len = self.prompt.chars().count();
+        let cursor = text[..self.cursor].chars().count();
+        let post_len = text[self.cursor..].chars().count();
+        let auto_len = self.autocomplete.chars().count();
 
         // calculate how wide the right should be
-        let width = screen.size().x() - self.prompt.len();
-        let min_space_left = usize::min(1 + width / 8, self.cursor);
+        let width = screen.size().x().saturating_sub(prompt_len);
+        if width < 3 {
+            // not enough room to show any text around the cursor, so just show as much of the prompt as fits
+            let cells = prompt.chain(iter::once(blank.clone().underline()));
+            screen[0]
+                .iter_mut()
+                .zip(cells.chain(iter::repeat(blank)))
+                .for_each(|(cell, char)| *cell = char);
+            return;
+        }
+        let min_space_left = usize::min(1 + width / 8, cursor);
         let max_space_right = width - min_space_left;
-        let all_right = self.cur_line().len() - self.cursor + self.autocomplete.len();
+        let all_right = post_len + auto_len;
         let (len_right, cut_right) = if all_right == 0 {
             (1, false)
         } else if all_right <= max_space_right {
@@ -228,60 +497,42 @@ impl TextInput {
 
         // calculate left side space
         let max_space_left = width - (len_right + cut_right as usize);
-        let all_left = self.cursor;
-        let (len_left, cut_left) = if all_left <= max_space_left {
-            (all_left, false)
+        let (len_left, cut_left) = if cursor <= max_space_left {
+            (cursor, false)
         } else {
             (max_space_left - 1, true)
         };
 
-        // 5 chunks: prompt, precursor, cursor, autocomplete, postcursor
-        let mut line = alloc::vec::Vec::with_capacity(5);
-        line.push(text1!("{}"(self.prompt)));
-        line.push(text1!("{}"(&self.cur_line()[..self.cursor])));
-        line.push(text1!("")); // cursor, eventually
-        if !self.autocomplete.is_empty() {
-            line.push(text1!(bright_black "{}"(self.autocomplete)));
-        }
-        if self.cursor < self.cur_line().len() {
-            line.push(text1!("{}"(&self.cur_line()[self.cursor..])));
-        }
-
-        // insert the cursor
-        let ch = line.get_mut(3).map(|t| t.text.remove(0)).unwrap_or(' ');
-        let fmt = line
-            .get(3)
-            .map(|t| t.get_fmt().clone())
-            .unwrap_or(Format::default());
-        line[2] = Text::of(ch.into()).fmt(fmt).underline();
-
-        // trim the leftmost bits off the left side off
-        line[1]
-            .text
-            .replace_range(..(self.cursor - len_left), if cut_left { "…" } else { "" });
-        // rim the rightmost bits of the right side off (-1 to account for cursor)
-        let mut trim = len_right - 1;
-        let mut trimmed = false;
-        for chunk in &mut line[3..] {
-            if trim >= chunk.text.len() {
-                trim -= chunk.text.len();
-            } else if !trimmed {
-                chunk
-                    .text
-                    .replace_range(trim.., if cut_right { "…" } else { "" });
-                trimmed = true;
-            } else {
-                chunk.text.clear();
-            }
+        let mut cells = alloc::vec::Vec::with_capacity(screen.size().x());
+        cells.extend(prompt);
+        if cut_left {
+            cells.push(Cell::of('…').fmt(theme.fg.clone()));
+        }
+        cells.extend(
+            text[..self.cursor]
+                .chars()
+                .skip(cursor - len_left)
+                .map(|c| self.display(c, theme)),
+        );
+
+        // everything right of the cursor: the autocomplete (which the cursor sits on, if there is one), then the rest
+        let mut right = self
+            .autocomplete
+            .chars()
+            .map(|c| Cell::of(c).fmt(theme.disabled.clone()))
+            .chain(text[self.cursor..].chars().map(|c| self.display(c, theme)));
+        cells.push(right.next().unwrap_or_else(|| blank.clone()).underline());
+        cells.extend(right.by_ref().take(len_right - 1));
+        if cut_right {
+            // UNWRAP: we only cut if there's more than would fit
+            let mut dots = right.next().unwrap();
+            dots.ch = '…';
+            cells.push(dots);
         }
 
         screen[0]
             .iter_mut()
-            .zip(
-                line.iter()
-                    .flat_map(|t| t.text.chars().map(|c| Cell::of(c).fmt_of(t)))
-                    .chain(iter::repeat(Cell::BLANK)),
-            )
+            .zip(cells.into_iter().chain(iter::repeat(blank)))
             .for_each(|(cell, char)| *cell = char);
     }
 }
@@ -306,14 +557,28 @@ pub enum TextInputResult<'ti> {
     Submit(String),
 }
 
-impl<'s, 'ti> RawAttachment<'s> for &'ti mut TextInput {
+impl<'s, 'ti> Attachment<'s> for &'ti mut TextInput {
     type Output = TextInputResult<'ti>;
-    fn raw_attach(self, input: Action, screen: ScreenView<'s>) -> Self::Output {
+    fn attach(self, mut region: Region<'s>) -> Self::Output {
+        if region.is_empty() {
+            return TextInputResult::Nothing;
+        }
+        let focused = region.focusable(&self.prompt);
+        let theme = region.theme();
+        let (input, screen) = region.raw_pieces();
         // handle input and update state accordingly
-        let res = self.input(input);
+        let res = if focused {
+            self.input(input)
+        } else {
+            Some(TextInputResult::Nothing)
+        };
 
         // and now render!
-        self.render(screen);
+        if self.multiline {
+            self.render_multiline(screen, theme);
+        } else {
+            self.render(screen, theme);
+        }
 
         // avoid multiple mutable references (there's a better way, I'm sure, but I don't know it oops)
         res.unwrap_or_else(|| TextInputResult::Autocomplete {
@@ -334,7 +599,7 @@ mod test {
         },
         Region,
     };
-    use tuig_iosys::{Screen, XY};
+    use tuig_iosys::{text1, Screen};
 
     use super::*;
 
@@ -351,8 +616,8 @@ mod test {
             res
         }};
         ($s:ident, $ti:ident, key $k:expr $( => $( $res:tt )* )? ) => {
-            feed!($s, $ti, event Action::KeyPress { key: $k } $( => $( $res )* )?);
-            feed!($s, $ti, event Action::KeyRelease { key: $k } => Nothing);
+            feed!($s, $ti, event Action::press($k) $( => $( $res )* )?);
+            feed!($s, $ti, event Action::release($k) => Nothing);
         };
         ($s:ident, $ti:ident, chars $l:expr) => {
             for ch in $l.chars() {
@@ -365,6 +630,35 @@ mod test {
         }
     }
 
+    #[test]
+    fn empty_region_is_noop() {
+        for size in [XY(0, 2), XY(2, 0), XY(0, 0)] {
+            for multiline in [false, true] {
+                let mut s = Screen::new(size);
+                let mut ti = TextInput::new("> ", 0);
+                ti.multiline = multiline;
+                let r = Region::new(&mut s, Action::press(Key::Char('a')));
+                assert_eq!(r.attach(&mut ti), TextInputResult::Nothing);
+                let r = Region::new(&mut s, Action::press(Key::Enter));
+                assert_eq!(r.attach(&mut ti), TextInputResult::Nothing);
+            }
+        }
+    }
+
+    #[test]
+    fn narrow_region_shows_prompt() {
+        let mut s = Screen::new(XY(3, 1));
+        let mut ti = TextInput::new("> ", 0);
+        for ch in "abc".chars() {
+            Region::new(&mut s, Action::press(Key::Char(ch))).attach(&mut ti);
+        }
+        let row: alloc::string::String = s[0].iter().map(|c| c.ch).collect();
+        assert_eq!(row, ">  ");
+        let mut s = Screen::new(XY(1, 1));
+        Region::new(&mut s, Action::press(Key::Left)).attach(&mut ti);
+        assert_eq!(s[0][0].ch, '>');
+    }
+
     #[test]
     fn empty_renders_nothing() {
         make_screen!(s(15, 1), r(0, 0, *, *));
@@ -383,7 +677,7 @@ mod test {
 
     #[test]
     fn text_rendered_on_keypress() {
-        make_screen!(s(15, 1), r(0, 0, *, *, Action::KeyPress { key: Key::Char('z') }));
+        make_screen!(s(15, 1), r(0, 0, *, *, Action::press(Key::Char('z'))));
         let mut ti = TextInput::new("> ", 0);
         r.attach(&mut ti);
         screen_assert!(s: fmt 0, 0, "> z", fmt 3, 0, " " underline, fmt 4, 0, "           ");
@@ -592,7 +886,7 @@ mod test {
         // first: make sure we know what it should look like
         screen_assert!(s: fmt 0, 0, "> …9", fmt 4, 0, "a" underline, fmt 5, 0, "bcdefghij…");
         // autocomplete: should insert `ABCDEFGHIJ_____`, which gets cut off
-        match feed!(s, ti, event Action::KeyPress { key: Key::Tab }) {
+        match feed!(s, ti, event Action::press(Key::Tab)) {
             TextInputResult::Autocomplete { res, .. } => *res = "ABCDEFGHIJ_____".into(),
             _ => panic!("tab did not trigger TextInputResult::Autocomplete"),
         }
@@ -615,7 +909,7 @@ mod test {
         // first: make sure we know what it should look like
         screen_assert!(s: fmt 0, 0, "> …9", fmt 4, 0, "a" underline, fmt 5, 0, "bcdefghij…");
         // autocomplete: should insert `ABCDEFGHIJ`, which just barely fits, with normal text cut off after
-        match feed!(s, ti, event Action::KeyPress { key: Key::Tab }) {
+        match feed!(s, ti, event Action::press(Key::Tab)) {
             TextInputResult::Autocomplete { res, .. } => *res = "ABCDEFGHIJ".into(),
             _ => panic!("tab did not trigger TextInputResult::Autocomplete"),
         }
@@ -633,7 +927,7 @@ mod test {
         let mut ti = TextInput::new("> ", 0);
         const TEXT: &str = "0123456789abcdefghijklmnopqrst";
         feed!(s, ti, chars TEXT);
-        feed!(s, ti, event Action::KeyPress { key: Key::Enter } => Submit(TEXT.into()));
+        feed!(s, ti, event Action::press(Key::Enter) => Submit(TEXT.into()));
         screen_assert!(s: fmt 0, 0, "> ", fmt 2, 0, " " underline, fmt 3, 0, "            ");
     }
 
@@ -646,10 +940,167 @@ mod test {
         for _ in 0..5 {
             feed!(s, ti, key Key::Left);
         }
-        feed!(s, ti, event Action::KeyPress { key: Key::Enter } => Submit(TEXT.into()));
+        feed!(s, ti, event Action::press(Key::Enter) => Submit(TEXT.into()));
         screen_assert!(s: fmt 0, 0, "> ", fmt 2, 0, " " underline, fmt 3, 0, "            ");
     }
 
+    #[test]
+    fn paste_inserts_literally() {
+        make_screen!(s(15, 1));
+        let mut ti = TextInput::new("> ", 0);
+        feed!(s, ti, chars "ad");
+        feed!(s, ti, key Key::Left);
+        feed!(s, ti, event Action::Paste("b\nc".into()) => Nothing);
+        assert_eq!(ti.line, "ab\ncd");
+        assert_eq!(ti.cursor, 4);
+        screen_assert!(s: fmt 0, 0, "> ab c", fmt 6, 0, "d" underline);
+        feed!(s, ti, event Action::press(Key::Enter) => Submit("ab\ncd".into()));
+    }
+
+    #[test]
+    fn multiline_enter_inserts_newline() {
+        make_screen!(s(15, 3));
+        let mut ti = TextInput::new("> ", 0).multiline(true);
+        feed!(s, ti, chars "ab\ncd");
+        assert_eq!(ti.line, "ab\ncd");
+        screen_assert!(s:
+            fmt 0, 0, "> ab           ",
+            fmt 0, 1, "  cd",
+            fmt 4, 1, " " underline,
+            fmt 0, 2, "               ",
+        );
+    }
+
+    #[test]
+    fn multiline_submit_key_submits_everything() {
+        make_screen!(s(15, 3));
+        let mut ti = TextInput::new("> ", 0).multiline(true);
+        feed!(s, ti, chars "ab\ncd");
+        feed!(s, ti, key Key::Up);
+        let submit = Action::press(Key::Enter).with_mods(Modifiers::CTRL);
+        feed!(s, ti, event submit => Submit("ab\ncd".into()));
+        assert_eq!(ti.line, "");
+    }
+
+    #[test]
+    fn multiline_custom_submit_key() {
+        make_screen!(s(15, 3));
+        let mut ti = TextInput::new("> ", 0)
+            .multiline(true)
+            .submit_on(Key::F(2), Modifiers::NONE);
+        feed!(s, ti, chars "a\nb");
+        feed!(s, ti, event Action::press(Key::F(2)) => Submit("a\nb".into()));
+    }
+
+    #[test]
+    fn multiline_up_down_keep_virtual_column() {
+        make_screen!(s(15, 3));
+        let mut ti = TextInput::new("", 0).multiline(true);
+        feed!(s, ti, chars "abcdef\nx\nabcdef");
+        feed!(s, ti, key Key::Left);
+        feed!(s, ti, key Key::Left);
+        assert_eq!(ti.cursor, 13);
+        feed!(s, ti, key Key::Up);
+        assert_eq!(ti.cursor, 8);
+        feed!(s, ti, key Key::Up);
+        assert_eq!(ti.cursor, 4);
+        feed!(s, ti, key Key::Down);
+        feed!(s, ti, key Key::Down);
+        assert_eq!(ti.cursor, 13);
+    }
+
+    #[test]
+    fn multiline_home_end_stay_on_line() {
+        make_screen!(s(15, 3));
+        let mut ti = TextInput::new("", 0).multiline(true);
+        feed!(s, ti, chars "abc\ndef\nghi");
+        feed!(s, ti, key Key::Up);
+        feed!(s, ti, key Key::Home);
+        assert_eq!(ti.cursor, 4);
+        feed!(s, ti, key Key::End);
+        assert_eq!(ti.cursor, 7);
+    }
+
+    #[test]
+    fn multiline_scrolls_to_cursor() {
+        make_screen!(s(10, 2));
+        let mut ti = TextInput::new("", 0).multiline(true);
+        feed!(s, ti, chars "1\n2\n3\n4");
+        screen_assert!(s: fmt 0, 0, "3         ", fmt 0, 1, "4", fmt 1, 1, " " underline);
+        feed!(s, ti, key Key::Up);
+        feed!(s, ti, key Key::Up);
+        screen_assert!(s: fmt 0, 0, "2", fmt 0, 1, "3         ");
+    }
+
+    #[test]
+    fn multiline_long_lines_truncated() {
+        make_screen!(s(5, 2));
+        let mut ti = TextInput::new("", 0).multiline(true);
+        feed!(s, ti, chars "abcdefgh\nx");
+        screen_assert!(s: fmt 0, 0, "abcd…");
+    }
+
+    #[test]
+    fn masked_renders_mask_but_submits_text() {
+        make_screen!(s(15, 1));
+        let mut ti = TextInput::new("> ", 0).masked(TextInput::MASK);
+        feed!(s, ti, chars "hunter2");
+        screen_assert!(s: fmt 0, 0, "> •••••••", fmt 9, 0, " " underline);
+        feed!(s, ti, event Action::press(Key::Enter) => Submit("hunter2".into()));
+    }
+
+    #[test]
+    fn masked_cursor_uses_real_positions() {
+        make_screen!(s(15, 1));
+        let mut ti = TextInput::new("", 0).masked('*');
+        feed!(s, ti, chars "héllo");
+        feed!(s, ti, key Key::Home);
+        feed!(s, ti, key Key::Right);
+        feed!(s, ti, event Action::Paste("ab".into()) => Nothing);
+        assert_eq!(ti.line, "habéllo");
+        screen_assert!(s: fmt 0, 0, "***", fmt 3, 0, "*" underline, fmt 4, 0, "***");
+    }
+
+    #[test]
+    fn masked_overflow_clips() {
+        make_screen!(s(8, 1));
+        let mut ti = TextInput::new("", 0).masked('*');
+        feed!(s, ti, chars "0123456789");
+        screen_assert!(s: fmt 0, 0, "…******", fmt 7, 0, " " underline);
+    }
+
+    #[test]
+    fn reveal_shows_real_text() {
+        make_screen!(s(15, 1));
+        let mut ti = TextInput::new("> ", 0).masked(TextInput::MASK);
+        feed!(s, ti, chars "abc");
+        assert!(ti.toggle_reveal());
+        feed!(s, ti, event Action::Redraw => Nothing);
+        screen_assert!(s: fmt 0, 0, "> abc");
+        assert!(!ti.toggle_reveal());
+        feed!(s, ti, event Action::Redraw => Nothing);
+        screen_assert!(s: fmt 0, 0, "> •••");
+    }
+
+    #[test]
+    fn masked_multiline() {
+        make_screen!(s(6, 2));
+        let mut ti = TextInput::new("", 0).multiline(true).masked('*');
+        feed!(s, ti, chars "ab\nc");
+        screen_assert!(s: fmt 0, 0, "**    ", fmt 0, 1, "*", fmt 1, 1, " " underline);
+    }
+
+    #[test]
+    fn unfocused_ignores_typing() {
+        make_screen!(s(15, 1));
+        let mut ti = TextInput::new("> ", 0);
+        let mut focus = crate::FocusState::new();
+        focus.focus("elsewhere");
+        let region = Region::new(&mut s, Action::press(Key::Char('a'))).with_focus(&mut focus);
+        assert_eq!(region.attach(&mut ti), TextInputResult::Nothing);
+        assert_eq!(ti.line, "");
+    }
+
     #[test]
     fn press_tab_triggers_autocomplete() {
         make_screen!(s(15, 1));
@@ -659,7 +1110,7 @@ mod test {
         feed!(s, ti, key Key::Left);
         feed!(s, ti, key Key::Left);
         // line should now be abcd_fg
-        match feed!(s, ti, event Action::KeyPress { key: Key::Tab }) {
+        match feed!(s, ti, event Action::press(Key::Tab)) {
             TextInputResult::Autocomplete { text, res } => {
                 assert_eq!(text, "abcd");
                 assert_eq!(res, "");
@@ -677,7 +1128,7 @@ mod test {
         feed!(s, ti, key Key::Left);
         feed!(s, ti, key Key::Left);
         // line should now be abcd_fg
-        match feed!(s, ti, event Action::KeyPress { key: Key::Tab }) {
+        match feed!(s, ti, event Action::press(Key::Tab)) {
             TextInputResult::Autocomplete { res, .. } => *res = "mlem".into(),
             _ => panic!("tab did not trigger TextInputResult::Autocomplete"),
         }
@@ -692,6 +1143,35 @@ mod test {
         );
     }
 
+    #[test]
+    fn draws_with_theme() {
+        use tuig_iosys::fmt::{Color, Format, Formatted};
+        let theme = Theme::DEFAULT
+            .fg(Format {
+                fg: Color::Green,
+                ..Format::NONE
+            })
+            .bg(Format {
+                bg: Color::Blue,
+                ..Format::NONE
+            });
+        let mut s = Screen::new(XY(8, 1));
+        let mut ti = TextInput::new("> ", 0);
+        for input in [Action::press(Key::Char('a')), Action::Redraw] {
+            Region::new(&mut s, input)
+                .with_theme(&theme)
+                .attach(&mut ti);
+        }
+        assert_eq!(s[0][0].ch, '>');
+        assert_eq!(s[0][0].get_fmt().fg, Color::Green);
+        assert_eq!(s[0][2].ch, 'a');
+        assert_eq!(s[0][2].get_fmt().fg, Color::Green);
+        // the cursor and the empty space after it
+        assert!(s[0][3].get_fmt().underline);
+        assert_eq!(s[0][3].get_fmt().bg, Color::Blue);
+        assert_eq!(s[0][7].get_fmt().bg, Color::Blue);
+    }
+
     #[test]
     fn autocomplete_goes_away_after_keypress() {
         make_screen!(s(15, 1));
@@ -700,7 +1180,7 @@ mod test {
         feed!(s, ti, key Key::Left);
         feed!(s, ti, key Key::Left);
         feed!(s, ti, key Key::Left);
-        match feed!(s, ti, event Action::KeyPress { key: Key::Tab }) {
+        match feed!(s, ti, event Action::press(Key::Tab)) {
             TextInputResult::Autocomplete { res, .. } => *res = "mlem".into(),
             _ => panic!("tab did not trigger TextInputResult::Autocomplete"),
         }
@@ -719,7 +1199,7 @@ mod test {
             fmt 10, 0, "efg  "
         );
         // type a char to watch the autocomplete go away
-        feed!(s, ti, event Action::KeyPress { key: Key::Char('z') });
+        feed!(s, ti, event Action::press(Key::Char('z')));
         screen_assert!(s:
             fmt 0, 0, "> abcdz", fmt 7, 0, "e" underline, fmt 8, 0, "fg     "
         );
@@ -810,8 +1290,8 @@ mod test {
         feed!(s, ti, key Key::Up);
         feed!(s, ti, key Key::Up);
         // submit, and ensure we get the relevant text
-        feed!(s, ti, event Action::KeyPress { key: Key::Enter } => Submit("abc".into()));
-        feed!(s, ti, event Action::KeyRelease { key: Key::Enter });
+        feed!(s, ti, event Action::press(Key::Enter) => Submit("abc".into()));
+        feed!(s, ti, event Action::release(Key::Enter));
         // ensure the screen is as it should be
         screen_assert!(s: fmt 0, 0, "> ", fmt 2, 0, " " underline);
         // up should show us "1234" because we didn't store "abc" but we did move down
@@ -821,4 +1301,34 @@ mod test {
         feed!(s, ti, key Key::Up);
         screen_assert!(s: fmt 0, 0, "> abc", fmt 5, 0, " " underline);
     }
+
+    #[test]
+    fn left_right_move_by_grapheme() {
+        make_screen!(s(15, 1));
+        let mut ti = TextInput::new("> ", 0);
+        feed!(s, ti, chars "ae\u{0301}\u{1F1EF}\u{1F1F5}b");
+        feed!(s, ti, key Key::Left);
+        feed!(s, ti, key Key::Left);
+        assert_eq!(&ti.line[ti.cursor..], "\u{1F1EF}\u{1F1F5}b");
+        feed!(s, ti, key Key::Left);
+        assert_eq!(&ti.line[ti.cursor..], "e\u{0301}\u{1F1EF}\u{1F1F5}b");
+        feed!(s, ti, key Key::Right);
+        feed!(s, ti, key Key::Right);
+        assert_eq!(&ti.line[ti.cursor..], "b");
+    }
+
+    #[test]
+    fn backspace_and_delete_remove_graphemes() {
+        make_screen!(s(15, 1));
+        let mut ti = TextInput::new("> ", 0);
+        feed!(s, ti, chars "xe\u{0301}y\u{1F469}\u{200D}\u{1F4BB}z");
+        feed!(s, ti, key Key::Left);
+        feed!(s, ti, key Key::Backspace);
+        assert_eq!(ti.line, "xe\u{0301}yz");
+        feed!(s, ti, key Key::Home);
+        feed!(s, ti, key Key::Right);
+        feed!(s, ti, key Key::Delete);
+        assert_eq!(ti.line, "xyz");
+        assert_eq!(ti.cursor, 1);
+    }
 }
@@ -1,16 +1,17 @@
-use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+
+use alloc::vec::Vec;
 use tuig_iosys::{
-    fmt::{Cell, Formatted, FormattedExt, Text},
-    text, text1, Action, XY,
+    fmt::{self, char_width, Cell, FormattedExt, Text, Wrap, WrapOpts},
+    MouseButton, XY,
 };
 
-use crate::ScreenView;
+use crate::{Region, ScreenView};
 
-use super::RawAttachment;
+use super::Attachment;
 
-fn breakable(ch: char) -> bool {
-    ch.is_whitespace()
-}
+/// Identifies a clickable span of a [`Textbox`]'s text. See [`Textbox::span`].
+pub type SpanId = usize;
 
 /// Ancillary data which might be useful
 #[derive(PartialEq, Eq, Clone)]
@@ -19,8 +20,18 @@ pub struct TextboxData {
     pub lines: usize,
     /// How many lines on the screen the textbox occupied, accounting for the requested height, the
     pub height: usize,
-    /// How far down from the top the displayed text started.
+    /// How far down from the top the displayed text started, in lines after wrapping.
+    ///
+    /// This is the scroll position actually used, after clamping, so pair it with [`Self::lines`] to draw a
+    /// scrollbar.
     pub scroll: usize,
+    /// How many lines, after wrapping, are scrolled out of view above or below, i.e. `lines - height`.
+    pub hidden_lines: usize,
+    /// Whether any of the text didn't fit: either there are [`Self::hidden_lines`], or a line shown was cut off at
+    /// the right edge, e.g. by [`Wrap::None`]. Useful for deciding whether to draw a "more" indicator.
+    pub overflow: bool,
+    /// If the input was a left click on one of the textbox's [spans](Textbox::span), that span's ID.
+    pub clicked_span: Option<SpanId>,
 }
 
 impl TextboxData {
@@ -28,22 +39,73 @@ impl TextboxData {
         height: 0,
         lines: 0,
         scroll: 0,
+        hidden_lines: 0,
+        overflow: false,
+        clicked_span: None,
     };
 }
 
+/// The display width of each paragraph in some text, i.e. each line before wrapping.
+fn paragraph_widths(chunks: &[Text]) -> Vec<usize> {
+    let mut widths = alloc::vec![0];
+    for chunk in chunks {
+        let mut parts = chunk.text.split('\n');
+        if let Some(first) = parts.next() {
+            *widths.last_mut().unwrap() += fmt::str_width(first);
+        }
+        widths.extend(parts.map(fmt::str_width));
+    }
+    widths
+}
+
+/// How a [`Textbox`] positions each line horizontally.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Align {
+    /// Start each line at the left edge.
+    #[default]
+    Left,
+    /// Center each line, with any odd column left over on the right.
+    Center,
+    /// End each line at the right edge.
+    Right,
+}
+
+/// How a [`Textbox`] positions its lines vertically, when there are fewer than fit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum VAlign {
+    /// Start at the top.
+    #[default]
+    Top,
+    /// Center the lines, with any odd row left over on the bottom.
+    Middle,
+    /// End at the bottom.
+    Bottom,
+}
+
 /// A box of text which can be attached to a [`Region`](crate::Region).
 ///
 /// Textboxes automatically handle:
 ///
-/// - Word wrapping to fit in their region
-/// - Indentation, including distinct first line indentation
+/// - Wrapping to fit in their region, by word by default or as configured with [`Self::wrap`] (using
+///   [`fmt::wrap`], if you want the same wrapping without rendering)
+/// - Indentation, including distinct first line indentation, and [tab stops](Self::tab_width)
 /// - Scrolling to a desired height, relative to the top or bottom
+/// - Aligning lines [horizontally](Self::align) and [vertically](Self::valign)
+/// - Finding which [span](Self::span) of the text was clicked, wherever it ended up after all that
+///
+/// Unlike the other built-in attachments, a textbox doesn't use the region's [`Theme`](crate::Theme): the text is
+/// drawn in exactly the formatting it was given, and the rest of the region is left alone.
 pub struct Textbox {
     pub(in super::super) chunks: Vec<Text>,
     pub(in super::super) scroll: usize,
     pub(in super::super) scroll_bottom: bool,
     pub(in super::super) indent: usize,
     pub(in super::super) first_indent: Option<usize>,
+    pub(in super::super) wrap: Wrap,
+    pub(in super::super) align: Align,
+    pub(in super::super) valign: Option<VAlign>,
+    pub(in super::super) tab_width: usize,
+    pub(in super::super) spans: Vec<(Range<usize>, SpanId)>,
 }
 
 impl Textbox {
@@ -55,18 +117,49 @@ impl Textbox {
             scroll_bottom: false,
             indent: 0,
             first_indent: None,
+            wrap: Wrap::Word,
+            align: Align::Left,
+            valign: None,
+            tab_width: fmt::DEFAULT_TAB_WIDTH,
+            spans: Vec::new(),
         }
     }
 
+    /// Make a range of the chunks of text clickable, e.g. a username or a link, identified by some ID.
+    ///
+    /// The range is indices into the text the textbox was created with, so `1..2` is just the second chunk, no matter
+    /// how it ends up wrapped. When the textbox is attached with a left click anywhere on that text, including a
+    /// hyphen from breaking it, its ID is returned in [`TextboxData::clicked_span`]. If spans overlap, the first one
+    /// added wins.
+    ///
+    /// ```no_run
+    /// # use tuig_iosys::text;
+    /// # use tuig_ui::{Region, attachments::Textbox};
+    /// # let region = Region::empty(tuig_iosys::Action::Redraw);
+    /// const BOB: usize = 7;
+    /// let res = region.attach(Textbox::new(text!("<", blue "bob", "> hi!")).span(1..2, BOB));
+    /// if res.clicked_span == Some(BOB) {
+    ///     // show bob's profile
+    /// }
+    /// ```
+    pub fn span(mut self, chunks: Range<usize>, id: SpanId) -> Self {
+        self.spans.push((chunks, id));
+        self
+    }
+
     tuig_pm::setters! {
         /// Set the scroll position of the textbox, i.e. how many lines from the top or bottom should be hidden.
         ///
+        /// This counts lines *after* wrapping, the same way [`TextboxData::lines`] does, so one long paragraph
+        /// might take several steps to scroll past. If it's more than that, it's clamped so the last (or first,
+        /// when scrolling from the bottom) line is still visible; [`TextboxData::scroll`] has the real value.
+        ///
         /// Defaults to 0, i.e. not scrolling at all. Anything that doesn't fit is simply not visible.
         scroll(amt: usize) => scroll = amt,
         /// Set whether the scroll position should be relative to the top or bottom.
         ///
         /// Scrolling from the bottom will also align the bottom of the text with the bottom of the textbox, rather
-        /// than aligning the tops.
+        /// than aligning the tops, unless [`Self::valign`] says otherwise.
         ///
         /// Defaults to false, i.e. by default scrolling is from the top.
         scroll_bottom(v: bool) => scroll_bottom = v,
@@ -78,155 +171,158 @@ impl Textbox {
         ///
         /// Defaults to being the same as the indent.
         first_indent(amt: usize) => first_indent = Some(amt),
+        /// How to handle lines that are too long to fit. [`TextboxData::lines`] counts the lines after wrapping.
+        ///
+        /// Defaults to [`Wrap::Word`].
+        wrap(mode: Wrap) => wrap = mode,
+        /// How to position each line within the width of the textbox.
+        ///
+        /// Lines are positioned by their display width, not counting trailing spaces, so a wrapped line like
+        /// `"some "` is centered as `"some"`. Indents count as part of the line.
+        ///
+        /// Defaults to [`Align::Left`].
+        align(v: Align) => align = v,
+        /// How to position the lines within the height of the textbox, when there aren't enough to fill it.
+        ///
+        /// Defaults to [`VAlign::Top`], or [`VAlign::Bottom`] when [scrolling from the bottom](Self::scroll_bottom).
+        valign(v: VAlign) => valign = Some(v),
+        /// How many columns apart tab stops are, counting from the left edge of the textbox. See
+        /// [`WrapOpts::tab_width`] for how tabs interact with wrapping.
+        ///
+        /// Defaults to [`fmt::DEFAULT_TAB_WIDTH`].
+        tab_width(v: usize) => tab_width = v.max(1),
     }
 
     /// Render this textbox to a [`ScreenView`], and return information about the render.
     ///
-    /// This is functionally equivalent to just directly [`Region::attach`][crate::Region::attach]ing the textbox,
-    /// but you may find it useful if e.g. you want the text to depend on the input being handled in that region.
-    pub fn render_to(mut self, mut sv: ScreenView) -> TextboxData {
-        if sv.size() == XY(0, 0) {
+    /// This is functionally equivalent to just directly [`Region::attach`]ing the textbox, except that since the view
+    /// has no input, no [span](Self::span) is ever clicked. You may find it useful if e.g. you want the text to
+    /// depend on the input being handled in that region.
+    pub fn render_to(self, sv: ScreenView) -> TextboxData {
+        self.render(sv, None)
+    }
+
+    /// Render, noting which span is under `click`, relative to the view's top-left corner, if there is one.
+    fn render(self, mut sv: ScreenView, click: Option<XY>) -> TextboxData {
+        if sv.size().x() == 0 || sv.size().y() == 0 {
             return TextboxData::EMPTY;
         }
 
-        let first_indent = self.first_indent.unwrap_or(self.indent);
         let width = sv.size().x();
-        let mut height = sv.size().y();
-
-        assert!(width > self.indent);
-        assert!(width > first_indent);
-
-        // break the chunks into paragraphs on newlines
-        let mut paragraphs = alloc::vec![];
-        let mut cur_para = alloc::vec![];
-        for mut chunk in std::mem::take(&mut self.chunks) {
-            while let Some((line, rest)) = chunk.text.split_once('\n') {
-                cur_para.push(chunk.with_text(line.into()));
-                paragraphs.push(cur_para);
-                cur_para = alloc::vec![];
-                chunk.text = rest.into();
-            }
-            if !chunk.text.is_empty() {
-                cur_para.push(chunk);
-            }
-        }
-        paragraphs.push(cur_para);
-
-        // space out and word-wrap those paragraphs into lines
-        let mut lines = alloc::vec![];
-        for para in paragraphs {
-            let mut line: Vec<Text> = text!["{0:1$}"("", first_indent)];
-            let mut pos = first_indent;
-            let mut line_start = true;
-            for mut chunk in para {
-                // the code flow in this for loop is too complex to add this =false at the end, so we make do
-                let was_line_start = line_start;
-                line_start = false;
-                // while there's too much to fit on the next line all at once
-                while pos + chunk.text.len() > width {
-                    // how much space can we fit things into?
-                    let space_left = width - pos;
-                    // the bit of text that will be put at the end of this line
-                    let line_end: String;
-                    // the rest of the text, which wraps to following lines
-                    let rest: String;
-                    if let Some(idx) = chunk.text[..space_left + 1].rfind(breakable) {
-                        // we have a breakable character in time; we break there
-                        let pre = &chunk.text[..idx];
-                        let post = &chunk.text[idx + 1..];
-                        line_end = pre.into();
-                        rest = post.into();
-                    } else if !was_line_start {
-                        // no breakable character, but we're not at the start of the line, so let's try
-                        // ending the line here and getting to the next one
-                        line_end = String::new();
-                        rest = chunk.text;
-                    } else if space_left > 1 {
-                        // break the word with a hyphen, since there's space for it
-                        let (pre, post) = chunk.text.split_at(space_left - 1);
-                        line_end = alloc::format!("{}-", pre);
-                        rest = post.into();
-                    } else if space_left == 1 {
-                        // no room for a hyphen, so just pull one letter off
-                        let (pre, post) = chunk.text.split_at(1);
-                        line_end = pre.into();
-                        rest = post.into();
-                    } else {
-                        // at the start of a line, but 0 space left -- the asserts above should have
-                        // prevented this!
-                        unreachable!("indent or first indent is larger than width")
-                    }
-                    // set up the chunk for next iteration
-                    chunk.text = rest;
-                    // tack on the end of the line, if it's not empty
-                    if !line_end.is_empty() {
-                        let rem_space = width - (pos + line_end.len());
-                        line.push(chunk.with_text(line_end));
-                        // then make sure the formatting continues into the next line
-                        if rem_space > 0 {
-                            line.push(text1!("{0:1$}"("", rem_space)).bg(chunk.get_fmt().bg));
-                        }
-                    }
-                    // actually terminate the line and start the next one
-                    lines.push(line);
-                    line = text!["{0:1$}"("", self.indent)];
-                    pos = self.indent;
-                    line_start = true;
-                }
-                // now we can fit the rest on this one line
-                pos += chunk.text.len();
-                line.push(chunk);
-            }
-            lines.push(line);
-        }
+        let height = sv.size().y();
 
-        let x = 0;
-        let mut y = 0;
+        let mut opts = WrapOpts::new()
+            .mode(self.wrap)
+            .indent(self.indent)
+            .tab_width(self.tab_width);
+        if let Some(amt) = self.first_indent {
+            opts = opts.first_indent(amt);
+        }
+        let lines = fmt::wrap_indexed(&self.chunks, width, opts);
 
-        let start;
-        if self.scroll_bottom {
+        // there's always at least one line, even if it's blank, so this keeps at least one visible
+        let scroll = self.scroll.min(lines.len() - 1);
+        let (start, end) = if self.scroll_bottom {
             // we want [height] lines, starting [scroll] away from the bottom
-            let end = lines.len() - self.scroll;
-            start = end.saturating_sub(height);
-            // make sure the text fills from the bottom instead of the top
-            let real_height = end - start;
-            y += height - real_height;
-            height = real_height;
+            let end = lines.len() - scroll;
+            (end.saturating_sub(height), end)
         } else {
             // we want [height] lines, starting [scroll] away from the top
-            start = self.scroll;
+            (scroll, lines.len().min(scroll + height))
+        };
+        let slack = height - (end - start);
+        let default_valign = match self.scroll_bottom {
+            true => VAlign::Bottom,
+            false => VAlign::Top,
+        };
+        let top = match self.valign.unwrap_or(default_valign) {
+            VAlign::Top => 0,
+            VAlign::Middle => slack / 2,
+            VAlign::Bottom => slack,
         };
 
-        let mut data = TextboxData {
-            lines: lines.len(),
-            height: 0,
-            scroll: start,
+        // without wrapping, each paragraph is one line, cut off at the edge
+        let mut cut_off = self.wrap == Wrap::None && {
+            let indent = self.first_indent.unwrap_or(self.indent);
+            paragraph_widths(&fmt::expand_tabs(&self.chunks, indent, self.tab_width))
+                .iter()
+                .skip(start)
+                .take(end - start)
+                .any(|w| indent + w > width)
         };
+        let total = lines.len();
+        let hidden_lines = total - (end - start);
         let mut cells = alloc::vec![];
-        for line in lines.into_iter().skip(start).take(height) {
-            cells.extend(
-                line.iter()
-                    .flat_map(|t| t.text.chars().map(|c| Cell::of(c).fmt_of(t))),
-            );
+        // which chunk each cell came from, so clicks can be mapped back to spans
+        let mut sources = alloc::vec![];
+        let mut clicked_span = None;
+        let shown = lines.into_iter().skip(start).take(end - start);
+        for (y, line) in (top..).zip(shown) {
+            for (source, chunk) in &line {
+                for ch in chunk.text.chars() {
+                    match char_width(ch) {
+                        0 => (),
+                        1 => cells.push(Cell::of(ch).fmt_of(chunk)),
+                        _ => {
+                            cells.push(Cell::of(ch).fmt_of(chunk));
+                            cells.push(Cell::of(Cell::CONTINUATION).fmt_of(chunk));
+                        }
+                    }
+                    sources.resize(cells.len(), *source);
+                }
+            }
+            // a character wider than the whole textbox can still end up here, so make sure we don't overflow
+            if cells.len() > width {
+                cut_off = true;
+                cells.truncate(width);
+                if cells[width - 1].width() == 2 {
+                    cells[width - 1].ch = ' ';
+                }
+            }
+            if self.align != Align::Left {
+                let used = cells.iter().rposition(|c| c.ch != ' ').map_or(0, |i| i + 1);
+                cells.truncate(used);
+            }
+            let x = match self.align {
+                Align::Left => 0,
+                Align::Center => (width - cells.len()) / 2,
+                Align::Right => width - cells.len(),
+            };
             sv[y][x..x + cells.len()].clone_from_slice(&cells);
+            if let Some(XY(cx, cy)) = click {
+                if cy == y && cx >= x && cx < x + cells.len() {
+                    clicked_span = sources[cx - x].and_then(|src| {
+                        let mut spans = self.spans.iter();
+                        spans.find(|(r, _)| r.contains(&src)).map(|(_, id)| *id)
+                    });
+                }
+            }
             cells.clear();
-            y += 1;
-            data.height += 1;
+            sources.clear();
+        }
+        TextboxData {
+            lines: total,
+            height: end - start,
+            scroll: start,
+            hidden_lines,
+            overflow: hidden_lines > 0 || cut_off,
+            clicked_span,
         }
-        data
     }
 }
 
-impl<'s> RawAttachment<'s> for Textbox {
+impl<'s> Attachment<'s> for Textbox {
     type Output = TextboxData;
-    fn raw_attach(self, _: Action, screen: ScreenView<'s>) -> Self::Output {
-        self.render_to(screen)
+    fn attach(self, mut region: Region<'s>) -> Self::Output {
+        let click = region.clicked(MouseButton::Left);
+        let (_, sv) = region.raw_pieces();
+        self.render(sv, click)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use tuig_iosys::Screen;
+    use tuig_iosys::{text, text1, Action, Screen, XY};
 
     use crate::{
         attachments::test_utils::{
@@ -237,6 +333,21 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn empty_region_renders_nothing() {
+        for size in [XY(0, 3), XY(3, 0), XY(0, 0)] {
+            let mut s = Screen::new(size);
+            let r = Region::new(&mut s, Action::Redraw);
+            assert!(r.is_empty());
+            let res = r.attach(
+                Textbox::new(text!("some words"))
+                    .scroll(5)
+                    .align(Align::Center),
+            );
+            assert!(res == TextboxData::EMPTY);
+        }
+    }
+
     #[test]
     fn blank_textbox_renders_nothing() {
         make_screen!(sc(50, 30), r(0, 0, *, *));
@@ -263,6 +374,7 @@ mod test {
         assert_eq!(res.height, 1);
         assert_eq!(res.lines, 1);
         assert_eq!(res.scroll, 0);
+        assert!(!res.overflow);
     }
 
     #[test]
@@ -381,6 +493,8 @@ mod test {
         assert_eq!(res.height, 4);
         assert_eq!(res.lines, 6);
         assert_eq!(res.scroll, 2);
+        assert_eq!(res.hidden_lines, 2);
+        assert!(res.overflow);
     }
 
     #[test]
@@ -405,4 +519,291 @@ mod test {
         assert_eq!(res.lines, 6);
         assert_eq!(res.scroll, 1);
     }
+
+    #[test]
+    fn textbox_scroll_clamps_to_last_line() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        let res = r.attach(
+            Textbox::new(text!(
+                "these are some words which will eveeeentually be wrapped!"
+            ))
+            .scroll(100),
+        );
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 1..,
+            fmt 40, 0, "wrapped!",
+        );
+        assert_eq!(res.height, 1);
+        assert_eq!(res.lines, 6);
+        assert_eq!(res.scroll, 5);
+    }
+
+    #[test]
+    fn textbox_scroll_bottom_clamps_to_first_line() {
+        make_screen!(sc(50, 30), r(40, 0, 10, 4));
+        let res = r.attach(
+            Textbox::new(text!(
+                "these are some words which will eveeeentually be wrapped!"
+            ))
+            .scroll(100)
+            .scroll_bottom(true),
+        );
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 4..,
+            blank 40.., ..3,
+            fmt 40, 3, "these are ",
+        );
+        assert_eq!(res.height, 1);
+        assert_eq!(res.lines, 6);
+        assert_eq!(res.scroll, 0);
+    }
+
+    #[test]
+    fn textbox_wraps_by_display_width() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        let res = r.text(text!("漢字 and かな mixed 한국어입니다"));
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 4..,
+            fmt 40, 0, "漢\0字\0 and  ",
+            fmt 40, 1, "か\0な\0 mixed",
+            fmt 40, 2, "한\0국\0어\0입\0-",
+            fmt 40, 3, "니\0다\0",
+        );
+        assert_eq!(res.height, 4);
+        assert_eq!(res.lines, 4);
+    }
+
+    #[test]
+    fn textbox_char_wrap_fills_lines() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        let res = r.attach(
+            Textbox::new(text!(
+                "these are some words which will eveeeentually be wrapped!"
+            ))
+            .wrap(Wrap::Char),
+        );
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 6..,
+            fmt 40, 0, "these are ",
+            fmt 40, 1, "some words",
+            fmt 40, 2, " which wil",
+            fmt 40, 3, "l eveeeent",
+            fmt 40, 4, "ually be w",
+            fmt 40, 5, "rapped!",
+        );
+        assert_eq!(res.lines, 6);
+    }
+
+    #[test]
+    fn textbox_char_wrap_mixed_width() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        let res = r.attach(Textbox::new(text!("ab漢字かなmixed한국어")).wrap(Wrap::Char));
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 3..,
+            fmt 40, 0, "ab漢\0字\0か\0な\0",
+            fmt 40, 1, "mixed한\0국\0 ",
+            fmt 40, 2, "어\0",
+        );
+        assert_eq!(res.lines, 3);
+    }
+
+    #[test]
+    fn textbox_word_wrap_mixed_width() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        let res = r.attach(Textbox::new(text!("ab 漢字かな mixed 한국어")).wrap(Wrap::Word));
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 4..,
+            fmt 40, 0, "ab        ",
+            fmt 40, 1, "漢\0字\0か\0な\0  ",
+            fmt 40, 2, "mixed     ",
+            fmt 40, 3, "한\0국\0어\0",
+        );
+        assert_eq!(res.lines, 4);
+    }
+
+    #[test]
+    fn textbox_word_wrap_ideographic_space() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        let res = r.attach(Textbox::new(text!("日本語の\u{3000}テキストです")).wrap(Wrap::Word));
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 3..,
+            fmt 40, 0, "日\0本\0語\0の\0  ",
+            fmt 40, 1, "テ\0キ\0ス\0ト\0- ",
+            fmt 40, 2, "で\0す\0",
+        );
+        assert_eq!(res.lines, 3);
+    }
+
+    #[test]
+    fn textbox_no_wrap_clips() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        let res = r.attach(
+            Textbox::new(text!(
+                "these are some words\nshort\n", red "漢字かな", "mixed漢字"
+            ))
+            .wrap(Wrap::None),
+        );
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 3..,
+            fmt 40, 0, "these are ",
+            fmt 40, 1, "short",
+            fmt 40, 2, "漢\0字\0か\0な\0" red, fmt 48, 2, "mi",
+        );
+        assert_eq!(res.lines, 3);
+        assert_eq!(res.hidden_lines, 0);
+        assert!(res.overflow);
+    }
+
+    #[test]
+    fn textbox_align_center_and_right() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        r.attach(Textbox::new(text!("some words ", red "in a")).align(Align::Center));
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 2..,
+            blank 40..43, 1..=1, blank 47.., 1..=1,
+            fmt 40, 0, "some words",
+            fmt 43, 1, "in a" red,
+        );
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        r.attach(Textbox::new(text!("some words ", red "in a")).align(Align::Right));
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 2..,
+            blank 40..46, 1..=1,
+            fmt 40, 0, "some words",
+            fmt 46, 1, "in a" red,
+        );
+        // trailing spaces from wrapping don't count
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        r.attach(Textbox::new(text!("these are some")).align(Align::Right));
+        screen_assert!(sc:
+            blank ..41, ..,
+            blank .., 2..,
+            blank ..46, 1..=1,
+            fmt 41, 0, "these are",
+            fmt 46, 1, "some",
+        );
+    }
+
+    #[test]
+    fn textbox_align_uses_display_width() {
+        make_screen!(sc(50, 30), r(40, 0, *, *));
+        r.attach(Textbox::new(text!("漢字")).align(Align::Center));
+        screen_assert!(sc:
+            blank ..43, ..,
+            blank .., 1..,
+            blank 47.., ..,
+            fmt 43, 0, "漢\0字\0",
+        );
+    }
+
+    #[test]
+    fn textbox_valign_places_short_text() {
+        make_screen!(sc(50, 30), r(40, 0, 10, 5));
+        let res = r.attach(Textbox::new(text!("these are some words")).valign(VAlign::Middle));
+        screen_assert!(sc:
+            blank .., ..1,
+            blank .., 3..,
+            blank ..40, ..,
+            fmt 40, 1, "these are ",
+            fmt 40, 2, "some words",
+        );
+        assert_eq!(res.height, 2);
+        make_screen!(sc(50, 30), r(40, 0, 10, 5));
+        r.attach(Textbox::new(text!("these are some words")).valign(VAlign::Bottom));
+        screen_assert!(sc:
+            blank .., ..3,
+            blank .., 5..,
+            blank ..40, ..,
+            fmt 40, 3, "these are ",
+            fmt 40, 4, "some words",
+        );
+    }
+
+    #[test]
+    fn textbox_valign_overrides_scroll_bottom() {
+        make_screen!(sc(50, 30), r(40, 0, 10, 5));
+        r.attach(
+            Textbox::new(text!("these are some words"))
+                .scroll_bottom(true)
+                .valign(VAlign::Top),
+        );
+        screen_assert!(sc:
+            blank .., 2..,
+            blank ..40, ..,
+            fmt 40, 0, "these are ",
+            fmt 40, 1, "some words",
+        );
+    }
+
+    fn click(x: usize, y: usize) -> Action {
+        Action::MousePress {
+            pos: XY(x, y),
+            button: MouseButton::Left,
+        }
+    }
+
+    #[test]
+    fn clicks_map_through_wrapping_to_spans() {
+        let spanned = || {
+            Textbox::new(text!("hi ", blue "@someone", " ok"))
+                .span(1..2, 5)
+                .span(0..3, 9)
+        };
+        // lays out as "hi ", "@someone", "ok"
+        let cases = [
+            (XY(5, 2), Some(5)),
+            (XY(2, 1), Some(9)),
+            (XY(3, 3), Some(9)),
+            (XY(9, 1), None),
+            (XY(3, 4), None),
+        ];
+        for (pos, span) in cases {
+            make_screen!(sc(20, 5), r(2, 1, 10, 4, click(pos.x(), pos.y())));
+            let res = r.attach(spanned());
+            assert_eq!(res.clicked_span, span, "clicking {:?}", pos);
+        }
+        // and it has to be a left click
+        let right = Action::MousePress {
+            pos: XY(5, 2),
+            button: MouseButton::Right,
+        };
+        make_screen!(sc(20, 5), r(2, 1, 10, 4, right));
+        assert_eq!(r.attach(spanned()).clicked_span, None);
+    }
+
+    #[test]
+    fn clicks_follow_alignment() {
+        for (x, span) in [(8, None), (9, Some(1)), (2, None)] {
+            make_screen!(sc(10, 1), r(0, 0, *, *, click(x, 0)));
+            let tb = Textbox::new(text!("a", red "b")).span(1..2, 1);
+            assert_eq!(r.attach(tb.align(Align::Right)).clicked_span, span);
+        }
+    }
+
+    #[test]
+    fn textbox_tabs_line_up() {
+        make_screen!(sc(50, 30), r(40, 0, 10, 3));
+        let res = r.attach(
+            Textbox::new(text!("a\tb\tc\nde\tfg\th"))
+                .tab_width(3)
+                .wrap(Wrap::None),
+        );
+        screen_assert!(sc:
+            blank ..40, ..,
+            blank .., 2..,
+            fmt 40, 0, "a  b  c",
+            fmt 40, 1, "de fg h",
+        );
+        assert!(!res.overflow);
+    }
 }
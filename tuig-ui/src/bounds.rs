@@ -3,13 +3,26 @@ use core::{fmt, ops::Range};
 use tuig_iosys::{Action, XY};
 
 /// Boundaries of something, i.e. a position and size.
+///
+/// A [`Region`](crate::Region)'s bounds are where it is on the screen, in absolute screen coordinates, which you can
+/// get with [`Region::bounds`](crate::Region::bounds). They're just numbers, so there's nothing stopping you from
+/// making any `Bounds` you like, but the ones from a region always:
+///
+/// - Fit entirely within the screen (or are [empty](Self::empty))
+/// - Don't overlap those of any other region made from the same parent, [layers](crate::Region::layer) aside
+///
+/// The `split_*` methods preserve both of those: the two halves never overlap, and together they cover exactly the
+/// original bounds.
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub struct Bounds {
+    /// The top-left corner.
     pub pos: XY,
+    /// The width and height.
     pub size: XY,
 }
 
 impl Bounds {
+    /// Create bounds from the position of the top-left corner and the size.
     pub fn new(x: usize, y: usize, w: usize, h: usize) -> Self {
         Self {
             pos: XY(x, y),
@@ -18,8 +31,10 @@ impl Bounds {
     }
 
     /// Cut off the leftmost `amt` columns. Returns `(left, rest)`.
+    ///
+    /// This, and the other `split_*` methods, panic if `amt` is more than the width (or height) there is to split.
     pub fn split_left(&self, amt: usize) -> (Bounds, Bounds) {
-        assert!(amt > 0 && amt <= self.size.x());
+        assert!(amt <= self.size.x());
         let left = Bounds {
             pos: self.pos,
             size: XY(amt, self.size.y()),
@@ -33,7 +48,7 @@ impl Bounds {
 
     /// Cut off the rightmost `amt` columns. Returns `(right, rest)`. (**Not `(rest, right)`!**)
     pub fn split_right(&self, amt: usize) -> (Bounds, Bounds) {
-        assert!(amt > 0 && amt <= self.size.x());
+        assert!(amt <= self.size.x());
         let inverse = self.size.x() - amt;
         let (left, rest) = self.split_left(inverse);
         (rest, left)
@@ -41,7 +56,7 @@ impl Bounds {
 
     /// Cut off the topmost `amt` columns. Returns `(top, rest)`.
     pub fn split_top(&self, amt: usize) -> (Bounds, Bounds) {
-        assert!(amt > 0 && amt <= self.size.y());
+        assert!(amt <= self.size.y());
         let top = Bounds {
             pos: self.pos,
             size: XY(self.size.x(), amt),
@@ -55,13 +70,14 @@ impl Bounds {
 
     /// Cut off the bottommost `amt` columns. Returns `(bottom, rest)`. (**Not `(rest, bottom)`!**)
     pub fn split_bottom(&self, amt: usize) -> (Bounds, Bounds) {
-        assert!(amt > 0 && amt <= self.size.y());
+        assert!(amt <= self.size.y());
         let inverse = self.size.y() - amt;
         let (top, rest) = self.split_top(inverse);
         (rest, top)
     }
 
-    fn contains(&self, pos: XY) -> bool {
+    /// Whether a position is inside these bounds.
+    pub fn contains(&self, pos: XY) -> bool {
         self.xs().contains(&pos.x()) && self.ys().contains(&pos.y())
     }
 
@@ -0,0 +1,268 @@
+use core::{
+    cell::RefCell,
+    hash::{Hash, Hasher},
+};
+use std::collections::hash_map::DefaultHasher;
+
+use alloc::vec::Vec;
+use tuig_iosys::{Action, Key};
+
+struct FocusInner {
+    /// The ID of whatever currently has focus
+    focused: Option<u64>,
+    /// The focusable IDs registered on the previous frame, in order
+    last: Vec<u64>,
+    /// The focusable IDs registered so far on this frame, in order
+    current: Vec<u64>,
+    /// The focusable ID that got clicked on this frame, which will get focus on the next one
+    clicked: Option<u64>,
+}
+
+/// Tracks which attachment has keyboard focus, and moves it around with Tab and Shift+Tab.
+///
+/// Focusable attachments call [`Region::focusable`](crate::Region::focusable) with some ID every frame. Whichever
+/// region has the focused ID gets told it's focused, which attachments like [`Button`](crate::attachments::Button) and
+/// [`TextInput`](crate::attachments::TextInput) use to decide whether to respond to keyboard input. Focus moves:
+///
+/// - To the next focusable ID on Tab, wrapping around at the end
+/// - To the previous one on Shift+Tab, likewise
+/// - To whatever gets clicked on, starting on the next frame
+/// - To the first focusable ID registered, if nothing has focus or the focused ID wasn't registered last frame
+///
+/// Tab only moves focus if there was more than one focusable ID on the previous frame, so a lone [`TextInput`] can
+/// still use it for autocompletion. When it does move focus, the Tab isn't passed to any attachments.
+///
+/// [`Adapter`](crate::Adapter) tracks focus for you. To do it yourself, keep a `FocusState` around between frames,
+/// and call [`Region::with_focus`](crate::Region::with_focus) on each frame's root region.
+///
+/// [`TextInput`]: crate::attachments::TextInput
+pub struct FocusState {
+    inner: RefCell<FocusInner>,
+}
+
+fn hash_id(id: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    id.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl FocusState {
+    /// Create a new focus tracker, with nothing focused.
+    pub fn new() -> Self {
+        Self {
+            inner: RefCell::new(FocusInner {
+                focused: None,
+                last: Vec::new(),
+                current: Vec::new(),
+                clicked: None,
+            }),
+        }
+    }
+
+    /// Give focus to a specific ID, as would be passed to [`Region::focusable`](crate::Region::focusable).
+    pub fn focus(&mut self, id: impl Hash) {
+        self.inner.get_mut().focused = Some(hash_id(id));
+    }
+
+    /// Take focus away from everything. On the next frame, the first focusable thing will get it.
+    pub fn unfocus(&mut self) {
+        self.inner.get_mut().focused = None;
+    }
+
+    /// Whether there's anything to move focus between, i.e. whether focus is worth showing.
+    pub(crate) fn navigable(&self) -> bool {
+        self.inner.borrow().last.len() > 1
+    }
+
+    /// Start a new frame with a given input, returning whether that input was used to move focus.
+    pub(crate) fn begin(&mut self, input: &Action) -> bool {
+        let inner = self.inner.get_mut();
+        core::mem::swap(&mut inner.last, &mut inner.current);
+        inner.current.clear();
+        if let Some(id) = inner.clicked.take() {
+            inner.focused = Some(id);
+        }
+        if let Some(f) = inner.focused {
+            if !inner.last.is_empty() && !inner.last.contains(&f) {
+                inner.focused = None;
+            }
+        }
+        let backward = match input {
+            Action::KeyPress {
+                key: Key::Tab,
+                mods,
+                ..
+            } if !(mods.ctrl || mods.alt || mods.super_) => mods.shift,
+            _ => return false,
+        };
+        let count = inner.last.len();
+        if count < 2 {
+            return false;
+        }
+        let next = match inner
+            .focused
+            .and_then(|f| inner.last.iter().position(|&id| id == f))
+        {
+            Some(idx) if backward => (idx + count - 1) % count,
+            Some(idx) => (idx + 1) % count,
+            None if backward => count - 1,
+            None => 0,
+        };
+        inner.focused = Some(inner.last[next]);
+        true
+    }
+
+    /// Register a focusable ID for this frame, with the input its region got, returning whether it's focused.
+    pub(crate) fn register(&self, id: impl Hash, input: &Action) -> bool {
+        let id = hash_id(id);
+        let mut inner = self.inner.borrow_mut();
+        inner.current.push(id);
+        // scrolling over something isn't choosing it
+        if matches!(input, Action::MousePress { button, .. } if !button.is_scroll()) {
+            inner.clicked = Some(id);
+        }
+        if inner.focused.is_none() {
+            inner.focused = Some(id);
+        }
+        inner.focused == Some(id)
+    }
+}
+
+impl Default for FocusState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tuig_iosys::{Action, Key, Modifiers, MouseButton, Screen, XY};
+
+    use crate::{cols, Region};
+
+    use super::FocusState;
+
+    /// Run one frame of three focusables side by side, returning which were focused and what input each got.
+    fn frame(focus: &mut FocusState, input: Action) -> [(bool, Action); 3] {
+        let mut screen = Screen::new(XY(3, 1));
+        let root = Region::new(&mut screen, input).with_focus(focus);
+        let regions = root.split(cols!(1 1 1)).unwrap();
+        let mut i = 0;
+        regions.map(|mut r| {
+            i += 1;
+            let focused = r.focusable(i);
+            (focused, r.attach(|input, _| input))
+        })
+    }
+
+    fn focused(res: &[(bool, Action); 3]) -> [bool; 3] {
+        [res[0].0, res[1].0, res[2].0]
+    }
+
+    #[test]
+    fn first_is_focused_by_default() {
+        let mut focus = FocusState::new();
+        assert_eq!(
+            focused(&frame(&mut focus, Action::Redraw)),
+            [true, false, false]
+        );
+    }
+
+    #[test]
+    fn tab_moves_forward_and_wraps() {
+        let mut focus = FocusState::new();
+        frame(&mut focus, Action::Redraw);
+        let tab = Action::press(Key::Tab);
+        assert_eq!(
+            focused(&frame(&mut focus, tab.clone())),
+            [false, true, false]
+        );
+        assert_eq!(
+            focused(&frame(&mut focus, tab.clone())),
+            [false, false, true]
+        );
+        assert_eq!(focused(&frame(&mut focus, tab)), [true, false, false]);
+    }
+
+    #[test]
+    fn shift_tab_moves_backward() {
+        let mut focus = FocusState::new();
+        frame(&mut focus, Action::Redraw);
+        let tab = Action::press(Key::Tab).with_mods(Modifiers::SHIFT);
+        assert_eq!(focused(&frame(&mut focus, tab)), [false, false, true]);
+    }
+
+    #[test]
+    fn navigating_tab_is_swallowed() {
+        let mut focus = FocusState::new();
+        frame(&mut focus, Action::Redraw);
+        let res = frame(&mut focus, Action::press(Key::Tab));
+        assert!(res.iter().all(|(_, input)| *input == Action::Redraw));
+    }
+
+    #[test]
+    fn tab_passes_through_with_one_focusable() {
+        let mut focus = FocusState::new();
+        let run = |focus: &mut FocusState, input| {
+            let mut screen = Screen::new(XY(3, 1));
+            let mut root = Region::new(&mut screen, input).with_focus(focus);
+            assert!(root.focusable("only"));
+            root.attach(|input, _| input)
+        };
+        run(&mut focus, Action::Redraw);
+        let tab = Action::press(Key::Tab);
+        assert_eq!(run(&mut focus, tab.clone()), tab);
+    }
+
+    #[test]
+    fn click_moves_focus() {
+        let mut focus = FocusState::new();
+        frame(&mut focus, Action::Redraw);
+        let click = Action::MousePress {
+            pos: XY(2, 0),
+            button: MouseButton::Left,
+        };
+        assert_eq!(focused(&frame(&mut focus, click)), [true, false, false]);
+        assert_eq!(
+            focused(&frame(&mut focus, Action::Redraw)),
+            [false, false, true]
+        );
+    }
+
+    #[test]
+    fn scroll_doesnt_move_focus() {
+        let mut focus = FocusState::new();
+        frame(&mut focus, Action::Redraw);
+        for button in [MouseButton::ScrollUp, MouseButton::ScrollDown] {
+            frame(
+                &mut focus,
+                Action::MousePress {
+                    pos: XY(2, 0),
+                    button,
+                },
+            );
+            assert_eq!(
+                focused(&frame(&mut focus, Action::Redraw)),
+                [true, false, false]
+            );
+        }
+    }
+
+    #[test]
+    fn losing_focused_id_refocuses_first() {
+        let mut focus = FocusState::new();
+        focus.focus(7);
+        frame(&mut focus, Action::Redraw);
+        assert_eq!(
+            focused(&frame(&mut focus, Action::Redraw)),
+            [true, false, false]
+        );
+    }
+
+    #[test]
+    fn untracked_regions_are_always_focused() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut root = Region::new(&mut screen, Action::Redraw);
+        assert!(root.focusable(1));
+    }
+}
@@ -1,10 +1,14 @@
-use tuig_iosys::{Action, Key};
+use tuig_iosys::{Action, Key, XY};
 
 /// Tracks and presents the current state of inputs, based on past ones.
 ///
 /// You must manually feed this each input you receive. If you miss any, it might fall out of sync with reality,
 /// though because events are absolute (i.e. press/release rather than toggle), there's no risk of the classic
 /// "exactly inverted state" desync.
+///
+/// For what's happening in the one action being handled right now, e.g. whether a particular key was just pressed,
+/// see the predicates on [`Region`](crate::Region), like [`Region::key_pressed`](crate::Region::key_pressed) and
+/// [`Region::clicked`](crate::Region::clicked). Those also check the mouse is inside the region.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct InputState {
     /// Whether either Shift key is currently being held
@@ -16,6 +20,8 @@ pub struct InputState {
     /// Whether either Super (Windows) key is currently being held
     // (`super` is a keyword)
     pub super_: bool,
+    /// Where the mouse was last seen, if it's been seen at all
+    pub mouse: Option<XY>,
 }
 
 impl InputState {
@@ -51,14 +57,29 @@ impl InputState {
     }
 
     /// Handle an action, returning whether this was affected (i.e. whether it was a modifier being touched)
+    ///
+    /// This also records the position of any mouse event in [`Self::mouse`], though that doesn't count as being
+    /// affected.
     pub fn action(&mut self, action: &Action) -> bool {
+        if let Some(pos) = action.position() {
+            self.mouse = Some(pos);
+        }
         match action {
-            Action::KeyPress { key } => self.press(key),
-            Action::KeyRelease { key } => self.release(key),
+            Action::KeyPress { key, .. } => self.press(key),
+            Action::KeyRelease { key, .. } => self.release(key),
             _ => false,
         }
     }
 
+    /// Whether the mouse was last seen inside a region, i.e. whether it's hovering over it.
+    ///
+    /// [`Region::is_hovered`](crate::Region::is_hovered) does the same thing for a region given this state's mouse
+    /// position with [`Region::with_mouse`](crate::Region::with_mouse), but also accounts for any layers covering it.
+    /// That's generally more convenient while attaching.
+    pub fn is_hovered(&self, region: &crate::Region) -> bool {
+        self.mouse.map_or(false, |pos| region.contains(pos))
+    }
+
     /// Whether Ctrl, Alt, or Super are held, i.e. whether a hotkey is being used, vs. normal typing.
     ///
     /// This is mostly to clean up some code in text input, simplifying and sharing conditionals.
@@ -98,7 +119,7 @@ mod test {
             fn [< press_ $side _ $enum _action >]() {
                 let mut ms = InputState::default();
                 assert!(!ms.hotkeying(), "hotkeying on by default");
-                assert!(ms.action(&Action::KeyPress { key: Key::[<$side $enum>] }), "key should be handled");
+                assert!(ms.action(&Action::press(Key::[<$side $enum>])), "key should be handled");
                 assert!(ms.$field, "{} not set after key press", ms.$field);
                 assert_eq!(ms.hotkeying(), $hotkey, "hotkeying in wrong state afterwards")
             }
@@ -109,7 +130,7 @@ mod test {
                     $field: true,
                     ..Default::default()
                 };
-                assert!(ms.action(&Action::KeyRelease { key: Key::[<$side $enum>] }), "key should be handled");
+                assert!(ms.action(&Action::release(Key::[<$side $enum>])), "key should be handled");
                 assert!(!ms.$field, "{} set after key release", ms.$field);
             }
         )* } };
@@ -141,8 +162,18 @@ mod test {
     testignored! {
         other_press_ignored: press(&Key::Char('f')),
         other_release_ignored: release(&Key::Char('f')),
-        other_press_action_ignored: action(&Action::KeyPress { key: Key::Char('f') }),
-        other_release_action_ignored: action(&Action::KeyRelease { key: Key::Char('f') }),
+        other_press_action_ignored: action(&Action::press(Key::Char('f'))),
+        other_release_action_ignored: action(&Action::release(Key::Char('f'))),
         other_action_ignored: action(&Action::Redraw),
     }
+
+    #[test]
+    fn mouse_events_track_position() {
+        let mut ms = InputState::default();
+        let moved = Action::MouseMove { pos: XY(3, 4) };
+        assert!(!ms.action(&moved), "mouse movement counted as a modifier");
+        assert_eq!(ms.mouse, Some(XY(3, 4)));
+        ms.action(&Action::press(Key::Char('f')));
+        assert_eq!(ms.mouse, Some(XY(3, 4)));
+    }
 }
@@ -0,0 +1,354 @@
+use core::cell::RefCell;
+
+use alloc::{rc::Rc, vec::Vec};
+use tuig_iosys::{fmt::Cell, Screen, XY};
+
+use crate::{view::Drawn, Bounds, ScreenView};
+
+/// The `ch` that marks a cell in a layer as not drawn to, letting whatever's underneath show through.
+///
+/// This is a Unicode noncharacter, so it should never show up in any text that's actually meant to be displayed.
+const TRANSPARENT: char = '\u{FFFF}';
+
+struct LayersInner {
+    /// One screen per layer, all the same size. Only the first `used` are part of the current frame; the rest are
+    /// kept around so their allocations can be reused.
+    screens: Vec<Screen>,
+    /// Which cells of each screen have been drawn to, so input can be routed without reading the screens while
+    /// views into them are still alive.
+    drawn: Vec<Drawn>,
+    /// The layer each layer was created from, indexed by `id - 1`. (0 is the base screen.)
+    parents: Vec<usize>,
+    used: usize,
+    size: XY,
+}
+
+/// The off-screen storage backing [`Region::layer`](crate::Region::layer).
+///
+/// Each layer is a full-size [`Screen`] which starts out transparent. When you're done rendering, call
+/// [`Self::composite`] to draw all of them, bottom to top, onto the base screen. [`Adapter`](crate::Adapter) does all
+/// of this for you; you only need to use `Layers` directly if you're managing your own [`Screen`]s.
+///
+/// A `Layers` can be reused across frames -- [`Region::layered`](crate::Region::layered) clears it out -- and it's
+/// good to, since it'll reuse the screens' allocations.
+pub struct Layers {
+    inner: RefCell<LayersInner>,
+}
+
+impl Layers {
+    /// Create an empty layer stack.
+    pub fn new() -> Self {
+        Self {
+            inner: RefCell::new(LayersInner {
+                screens: Vec::new(),
+                drawn: Vec::new(),
+                parents: Vec::new(),
+                used: 0,
+                size: XY(0, 0),
+            }),
+        }
+    }
+
+    /// Whether any layers were created on the last frame, i.e. whether [`Self::composite`] would do anything.
+    pub fn is_empty(&self) -> bool {
+        self.inner.borrow().used == 0
+    }
+
+    /// Forget about all the layers from the last frame, and prepare to make new ones of a certain size.
+    pub(crate) fn reset(&mut self, size: XY) {
+        let inner = self.inner.get_mut();
+        inner.used = 0;
+        inner.parents.clear();
+        inner.size = size;
+    }
+
+    /// Create a new, transparent layer above `parent`, returning its ID and a view into `bounds` of it.
+    pub(crate) fn push(&self, parent: usize, bounds: Bounds) -> (usize, ScreenView<'_>) {
+        let mut inner = self.inner.borrow_mut();
+        let (idx, size) = (inner.used, inner.size);
+        if idx == inner.screens.len() {
+            inner.screens.push(Screen::new(size));
+        } else {
+            inner.screens[idx].resize(size);
+        }
+        inner.screens[idx].fill_all(Cell::of(TRANSPARENT));
+        let cells = size.x() * size.y();
+        if idx == inner.drawn.len() {
+            inner.drawn.push(Rc::from(Vec::new()));
+        }
+        // reuse the last frame's record if nothing else is holding onto it, like a view that was never dropped
+        match Rc::get_mut(&mut inner.drawn[idx]) {
+            Some(drawn) if drawn.len() == cells => {
+                drawn.iter_mut().for_each(|c| *c.get_mut() = false)
+            }
+            _ => inner.drawn[idx] = (0..cells).map(|_| core::cell::Cell::new(false)).collect(),
+        }
+        let drawn = inner.drawn[idx].clone();
+        inner.parents.push(parent);
+        inner.used += 1;
+        let screen: *mut Screen = &mut inner.screens[idx];
+        // SAFETY: Every layer gets its own screen, so this can't overlap any other view of any other layer. The
+        // `ScreenView` only holds onto the screen's cell buffer, not the `Screen` itself, so it doesn't matter if
+        // `screens` later gets reallocated; and the cells can't be reallocated until `reset`, which needs `&mut self`
+        // and therefore won't happen until the view is gone. `bounds` is within the screen because it's within the
+        // base screen, which is the same size.
+        let sv = unsafe { ScreenView::new(&mut *screen, bounds) }.recording(drawn);
+        (idx + 1, sv)
+    }
+
+    /// Whether the layer `id`, or anything layered above it, has been drawn to at `pos`.
+    pub(crate) fn covers(&self, id: usize, pos: XY) -> bool {
+        let inner = self.inner.borrow();
+        inner.covers(id, pos)
+    }
+
+    /// Draw every layer from the last frame onto `onto`, from the bottom up.
+    ///
+    /// Within each layer, only the cells that were actually drawn to get copied over. Everything else is left as it
+    /// was underneath.
+    ///
+    /// `onto` should be the same size as the screen the layers were created for. If it isn't, nothing is drawn.
+    pub fn composite(&self, onto: &mut Screen) {
+        let inner = self.inner.borrow();
+        if onto.size() != inner.size {
+            return;
+        }
+        inner.composite_above(0, onto);
+    }
+}
+
+impl LayersInner {
+    /// The IDs of the layers created directly above `parent`, from the bottommost to the topmost.
+    ///
+    /// The first layer created is the topmost, so it processes input first, as the region it was made from does.
+    fn children(&self, parent: usize) -> impl Iterator<Item = usize> + '_ {
+        (1..=self.used)
+            .rev()
+            .filter(move |&id| self.parents[id - 1] == parent)
+    }
+
+    fn covers(&self, id: usize, pos: XY) -> bool {
+        // the screens themselves can't be read here, since the layers' views might still be alive
+        if self.drawn[id - 1][pos.y() * self.size.x() + pos.x()].get() {
+            return true;
+        }
+        self.children(id).any(|child| self.covers(child, pos))
+    }
+
+    fn composite_above(&self, parent: usize, onto: &mut Screen) {
+        for id in self.children(parent) {
+            let layer = &self.screens[id - 1];
+            for y in 0..self.size.y() {
+                composite_row(&layer[y], &mut onto[y]);
+            }
+            self.composite_above(id, onto);
+        }
+    }
+}
+
+/// Copy the drawn cells of `src` onto `dst`, blanking any wide characters below that got cut in half.
+fn composite_row(src: &[Cell], dst: &mut [Cell]) {
+    let drawn = |x: usize| src.get(x).map_or(false, |c| c.ch != TRANSPARENT);
+    for x in 0..src.len() {
+        if !drawn(x) {
+            continue;
+        }
+        if dst[x].is_continuation() && x > 0 && !drawn(x - 1) {
+            dst[x - 1].ch = ' ';
+        }
+        if dst[x].width() == 2 && !drawn(x + 1) {
+            if let Some(next) = dst.get_mut(x + 1) {
+                next.ch = ' ';
+            }
+        }
+        dst[x] = src[x].clone();
+    }
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tuig_iosys::{fmt::Cell, Action, Key, MouseButton, Screen, XY};
+
+    use crate::{Region, ScreenView};
+
+    use super::Layers;
+
+    fn row(screen: &Screen, y: usize) -> alloc::string::String {
+        screen[y].iter().map(|c| c.ch).collect()
+    }
+
+    fn click(x: usize, y: usize) -> Action {
+        Action::MousePress {
+            pos: XY(x, y),
+            button: MouseButton::Left,
+        }
+    }
+
+    /// Draw `ch` in one cell and return the input that arrived
+    fn dot(ch: char, at: XY) -> impl FnOnce(Action, ScreenView) -> Action {
+        move |input, mut sv: ScreenView| {
+            sv[at] = Cell::of(ch);
+            input
+        }
+    }
+
+    #[test]
+    fn layer_draws_over_base() {
+        let mut screen = Screen::new(XY(4, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, Action::Redraw);
+        root.layer(false).attach(dot('!', XY(1, 0)));
+        root.fill(Cell::of('.'));
+        assert_eq!(row(&screen, 0), "....");
+        layers.composite(&mut screen);
+        assert_eq!(row(&screen, 0), ".!..");
+    }
+
+    #[test]
+    fn first_layer_is_topmost() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, Action::Redraw);
+        let mut top = root.layer(false);
+        top.layer(false).attach(dot('c', XY(2, 0)));
+        top.attach(|_, mut sv: ScreenView| {
+            sv[XY(1, 0)] = Cell::of('a');
+            sv[XY(2, 0)] = Cell::of('a');
+        });
+        root.layer(false)
+            .attach(|_, mut sv: ScreenView| sv.fill(Cell::of('b')));
+        root.fill(Cell::of('.'));
+        layers.composite(&mut screen);
+        assert_eq!(row(&screen, 0), "bac");
+    }
+
+    #[test]
+    fn opaque_layer_takes_all_input() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, click(2, 0));
+        let top = root.layer(false).attach(dot('!', XY(0, 0)));
+        assert_eq!(top, click(2, 0));
+        assert_eq!(root.attach(|i, _| i), Action::Redraw);
+    }
+
+    #[test]
+    fn pass_through_click_on_layer_is_consumed() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, click(0, 0));
+        assert_eq!(root.layer(true).attach(dot('!', XY(0, 0))), click(0, 0));
+        assert_eq!(root.attach(|i, _| i), Action::Redraw);
+    }
+
+    #[test]
+    fn pass_through_click_elsewhere_falls_through() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, click(2, 0));
+        assert_eq!(root.layer(true).attach(dot('!', XY(0, 0))), click(2, 0));
+        assert_eq!(root.attach(|i, _| i), click(2, 0));
+    }
+
+    #[test]
+    fn pass_through_checks_nested_layers() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, click(2, 0));
+        let mut mid = root.layer(true);
+        mid.layer(true).attach(dot('!', XY(2, 0)));
+        mid.attach(dot('?', XY(0, 0)));
+        assert_eq!(root.attach(|i, _| i), Action::Redraw);
+    }
+
+    #[test]
+    fn pass_through_works_with_layer_still_alive() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, click(0, 0));
+        let (drawn, rest) = root.layer(true).split_left(1);
+        drawn.attach(dot('!', XY(0, 0)));
+        // part of the layer hasn't been attached to yet, and the base region still knows what got clicked
+        assert_eq!(root.attach(|i, _| i), Action::Redraw);
+        rest.fill(Cell::of('?'));
+        layers.composite(&mut screen);
+        assert_eq!(row(&screen, 0), "!??");
+    }
+
+    #[test]
+    fn pass_through_keys_fall_through() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut layers = Layers::new();
+        let input = Action::press(Key::Char('x'));
+        let mut root = Region::layered(&mut screen, &mut layers, input.clone());
+        root.layer(true)
+            .attach(|_, mut sv: ScreenView| sv.fill(Cell::of('#')));
+        assert_eq!(root.attach(|i, _| i), input);
+    }
+
+    #[test]
+    fn split_region_layers_cover_their_bounds() {
+        let mut screen = Screen::new(XY(4, 1));
+        let mut layers = Layers::new();
+        let root = Region::layered(&mut screen, &mut layers, Action::Redraw);
+        let (_, mut right) = root.split_left(2);
+        right
+            .layer(false)
+            .attach(|_, mut sv: ScreenView| sv.fill(Cell::of('#')));
+        right.fill(Cell::of('.'));
+        layers.composite(&mut screen);
+        assert_eq!(&row(&screen, 0)[2..], "##");
+    }
+
+    #[test]
+    fn composite_blanks_split_wide_chars() {
+        let mut screen = Screen::new(XY(4, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, Action::Redraw);
+        root.layer(false).attach(dot('!', XY(1, 0)));
+        root.attach(|_, mut sv: ScreenView| {
+            sv[XY(0, 0)] = Cell::of('中');
+            sv[XY(1, 0)] = Cell::of(Cell::CONTINUATION);
+        });
+        layers.composite(&mut screen);
+        assert_eq!(row(&screen, 0), " !  ");
+    }
+
+    #[test]
+    fn reset_reuses_layers() {
+        let mut screen = Screen::new(XY(2, 1));
+        let mut layers = Layers::new();
+        let mut root = Region::layered(&mut screen, &mut layers, Action::Redraw);
+        root.layer(false).attach(dot('!', XY(0, 0)));
+        let root = Region::layered(&mut screen, &mut layers, Action::Redraw);
+        root.fill(Cell::of('.'));
+        assert!(layers.is_empty());
+        layers.composite(&mut screen);
+        assert_eq!(row(&screen, 0), "..");
+    }
+
+    #[test]
+    fn covered_regions_arent_hovered() {
+        let mut screen = Screen::new(XY(3, 1));
+        let mut layers = Layers::new();
+        let root = Region::layered(&mut screen, &mut layers, Action::Redraw);
+        let mut root = root.with_mouse(Some(XY(0, 0)));
+        let mut top = root.layer(true);
+        assert!(top.is_hovered());
+        top.attach(dot('!', XY(0, 0)));
+        assert!(!root.is_hovered());
+
+        let root = Region::layered(&mut screen, &mut layers, Action::Redraw);
+        let mut root = root.with_mouse(Some(XY(2, 0)));
+        root.layer(true).attach(dot('!', XY(0, 0)));
+        assert!(root.is_hovered());
+        root.layer(false);
+        assert!(!root.is_hovered());
+    }
+}
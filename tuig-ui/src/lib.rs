@@ -23,11 +23,17 @@ pub use adapter::Adapter;
 pub mod attachments;
 pub use attachments::{Attachment, RawAttachment};
 mod bounds;
-pub(crate) use bounds::Bounds;
+pub use bounds::Bounds;
+mod focus;
+pub use focus::FocusState;
 mod inputstate;
 pub use inputstate::InputState;
+mod layers;
+pub use layers::Layers;
 mod region;
 pub use region::Region;
+mod theme;
+pub use theme::Theme;
 mod view;
 pub use tuig_iosys::Screen;
 pub use view::ScreenView;
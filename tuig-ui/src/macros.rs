@@ -8,7 +8,16 @@ pub use tuig_pm as pm;
 /// cols!(15 * 24)
 /// ```
 ///
-/// You can provide an integer, for a fixed-width column, or `*` (exactly once) to say "fill up all available space".
+/// You can provide an integer, for a fixed-width column, or `*` to say "fill up all available space". If there's more
+/// than one `*`, the space left over after the fixed-width columns and separators is split between them evenly. To
+/// split it unevenly, give the stars weights in parentheses:
+///
+/// ```rust,ignore
+/// cols!(15 *(2) *(1))
+/// ```
+///
+/// That takes 15 columns off the left, then gives two thirds of the rest to the middle column and one third to the
+/// right. A plain `*` has a weight of 1. If the space doesn't divide evenly, the leftover columns go to the first star.
 ///
 /// Between width items you can pass a string, e.g.:
 ///
@@ -55,3 +64,48 @@ macro_rules! rows {
         $crate::macros::pm::splitter!($crate::splitters::statics::Rows @ $( $i )*)
     };
 }
+
+/// Create a splitter for a [`Region`](super::Region) which divides it into columns sized as percentages of its width.
+///
+/// The syntax is the same as for [`cols!`], except that the integers are percentages instead of column counts:
+///
+/// ```rust,ignore
+/// pct!(25 "|" *)
+/// ```
+///
+/// That gives the left column a quarter of the region's width, rounded down, and the rest to the right column, minus
+/// the separator. Percentages are of the *whole* region, separators included, and any columns lost to rounding go to
+/// the `*`s, just like the space left over in [`cols!`].
+///
+/// The widths are worked out from the region's size when it's split, so since layouts are redone every frame, they
+/// naturally reflow when the screen gets resized. It returns the same `Result<[Region; N], Region>` as [`cols!`],
+/// which is an `Err` if the percentages and separators add up to more than the whole width.
+#[macro_export]
+macro_rules! pct {
+    ($( $i:tt )*) => {
+        $crate::macros::pm::splitter!($crate::splitters::percent::Percent @ $( $i )*)
+    };
+}
+
+/// Create a splitter for a [`Region`](super::Region) which divides it into a grid of cells.
+///
+/// This takes two parenthesized groups: first the columns, then the rows, each with the same syntax as [`cols!`] and
+/// [`rows!`] respectively. For example, a calculator keypad might be:
+///
+/// ```rust,ignore
+/// grid!((* "|" * "|" * "|" *) (* "-" * "-" * "-" *))
+/// ```
+///
+/// Column separators are repeated vertically, like in [`cols!`]. Row separators are repeated horizontally across
+/// the whole width, one line per character, so a `"-"` between rows draws a full grid line.
+///
+/// When splitting, this returns a `Result<[[Region; COLS]; ROWS], Region>`, indexed by row and then by column. As
+/// with [`cols!`], it only fails if there's not enough room, and then it returns the original region.
+#[macro_export]
+macro_rules! grid {
+    ( ( $( $c:tt )* ) ( $( $r:tt )* ) ) => {{
+        #[allow(deprecated)]
+        let grid = $crate::splitters::grid::Grid::new($crate::cols!($( $c )*), $crate::rows!($( $r )*));
+        grid
+    }};
+}
@@ -1,25 +1,31 @@
 use alloc::vec::Vec;
 use tuig_iosys::{
     fmt::{Cell, Text},
-    Action, Screen, XY,
+    Action, Key, MouseButton, Screen, XY,
 };
 
 use super::{
     attachments::{Attachment, Textbox, TextboxData},
     splitters::Splitter,
-    Bounds, ScreenView,
+    Bounds, FocusState, Layers, ScreenView, Theme,
 };
 
 macro_rules! split_fn {
-    ( $lt:lifetime: $( $name:ident ),* $(,)? ) => { paste::paste! { $(
-        #[allow(dead_code)] // might as well have all of them, even if unused
-        pub(crate) fn [<split_ $name>](mut self, amt: usize) -> (Region<$lt>, Region<$lt>) {
+    ( $lt:lifetime: $( $name:ident = $what:literal $unit:literal ),* $(,)? ) => { paste::paste! { $(
+        #[doc = concat!("Cut off the ", $what, " `amt` ", $unit, ", returning `(", stringify!($name), ", rest)`.")]
+        ///
+        /// This is the basic building block for [`Splitter`]s, which is how the built-in ones are written. Like
+        /// [`Bounds`]'s `split_*` methods, it panics if there isn't enough room.
+        pub fn [<split_ $name>](mut self, amt: usize) -> (Region<$lt>, Region<$lt>) {
             let chunk = self.[<split_ $name _mut>](amt);
             (chunk, self)
         }
 
-        #[allow(dead_code)] // might as well have all of them, even if unused
-        pub(crate) fn [<split_ $name _mut >](&mut self, amt: usize) -> Region<$lt> {
+        #[doc = concat!("Cut off the ", $what, " `amt` ", $unit, " and return them, leaving the rest in `self`.")]
+        ///
+        #[doc = concat!("This is the in-place version of [`Self::split_", stringify!($name), "`], with the same panics.")]
+        pub fn [<split_ $name _mut >](&mut self, amt: usize) -> Region<$lt> {
+            self.settle();
             let (chunk, rest) = self.bounds.[<split_ $name>](amt);
             let chunk_input = chunk.filter(&self.input);
             // SAFETY: `chunk` and `rest` are guaranteed to be non-overlapping by the `bounds.split_*` methods
@@ -29,7 +35,17 @@ macro_rules! split_fn {
             self.sv = rest_sv;
             self.input = rest.filter(&self.input);
             self.bounds = rest;
-            Region { sv: chunk_sv, input: chunk_input, bounds: chunk }
+            Region {
+                sv: chunk_sv,
+                input: chunk_input,
+                bounds: chunk,
+                layers: self.layers,
+                layer: self.layer,
+                pending: Vec::new(),
+                focus: self.focus,
+                mouse: self.mouse,
+                theme: self.theme,
+            }
         }
     )* } }
 }
@@ -49,10 +65,24 @@ macro_rules! split_fn {
 ///
 /// You can split regions and add attachments in whatever order you like, so e.g. you can change how you display bits
 /// depending on the state of user input, and refresh things on the same frame you get input.
+///
+/// If you need things to overlap, like popups over the rest of the UI, see [`Self::layer`].
 pub struct Region<'s> {
     sv: ScreenView<'s>,
     pub(crate) input: Action,
     bounds: Bounds,
+    /// Where to put new layers, if anywhere
+    layers: Option<&'s Layers>,
+    /// Which layer this region is drawing to; 0 is the base screen
+    layer: usize,
+    /// Pass-through layers made from this region, which might still need to take `input`
+    pending: Vec<usize>,
+    /// Where to register focusable things, if anywhere
+    focus: Option<&'s FocusState>,
+    /// The last known mouse position, unless something's covering this region there
+    mouse: Option<XY>,
+    /// What the built-in attachments should look like, if not the default
+    theme: Option<&'s Theme>,
 }
 
 impl<'s> Region<'s> {
@@ -64,13 +94,198 @@ impl<'s> Region<'s> {
         let bounds = Bounds::new(0, 0, screen.size().x(), screen.size().y());
         Self {
             sv: unsafe { ScreenView::new(screen, bounds) },
-            input,
             bounds,
+            layers: None,
+            layer: 0,
+            pending: Vec::new(),
+            focus: None,
+            mouse: input.position(),
+            theme: None,
+            input,
+        }
+    }
+
+    /// Create a new `Region` like [`Self::new`], which can also create [layers](Self::layer).
+    ///
+    /// `layers` is cleared out, then filled with whatever layers get made while rendering. Once you're done with the
+    /// regions, call [`Layers::composite`] to draw them on top of the `screen`.
+    pub fn layered(screen: &'s mut Screen, layers: &'s mut Layers, input: Action) -> Self {
+        layers.reset(screen.size());
+        Self {
+            layers: Some(&*layers),
+            ..Self::new(screen, input)
+        }
+    }
+
+    /// Track keyboard focus for this region and everything made from it with a [`FocusState`].
+    ///
+    /// This should be called on the root region of each frame, before it's split or attached to, with the same
+    /// `FocusState` every time. If the input was Tab or Shift+Tab, and that moved the focus, the region's input is
+    /// replaced with an [`Action::Redraw`].
+    pub fn with_focus(mut self, focus: &'s mut FocusState) -> Self {
+        if focus.begin(&self.input) {
+            self.input = Action::Redraw;
+        }
+        self.focus = Some(focus);
+        self
+    }
+
+    /// Set where the mouse was last seen, for [`Self::is_hovered`].
+    ///
+    /// By default that's only known if this region's input is a mouse event. [`Adapter`](crate::Adapter) keeps track
+    /// of it between inputs, using an [`InputState`](crate::InputState), and sets it for you. If you're making your
+    /// own regions, call this on the root region of each frame, before it's split or attached to.
+    pub fn with_mouse(mut self, pos: Option<XY>) -> Self {
+        self.mouse = pos;
+        self
+    }
+
+    /// Set the [`Theme`] for this region and everything made from it, which the built-in attachments draw with.
+    ///
+    /// Without this, they use [`Theme::DEFAULT`].
+    pub fn with_theme(mut self, theme: &'s Theme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+
+    /// The [`Theme`] this region's attachments should draw with.
+    pub fn theme(&self) -> &'s Theme {
+        self.theme.unwrap_or(&Theme::DEFAULT)
+    }
+
+    /// Whether the mouse is over this region, and not over any layer covering it.
+    ///
+    /// Unlike mouse events, which only go to the region they happen in, this works no matter what the current input
+    /// is, as long as the last mouse position is known; see [`Self::with_mouse`].
+    pub fn is_hovered(&mut self) -> bool {
+        self.settle();
+        self.mouse.map_or(false, |pos| self.bounds.contains(pos))
+    }
+
+    /// If this region's input is a mouse event inside it, where, relative to the region's top-left corner.
+    ///
+    /// Unlike [`Self::is_hovered`], this only looks at the current input, so it's `None` for anything but mouse
+    /// events.
+    pub fn mouse_pos(&mut self) -> Option<XY> {
+        self.settle();
+        let pos = self.input.position()?;
+        if self.bounds.contains(pos) {
+            Some(pos - self.bounds.pos)
+        } else {
+            None
+        }
+    }
+
+    /// If this region's input is a press of `button` inside it, where, relative to the region's top-left corner.
+    ///
+    /// Like [`Self::mouse_released`], [`Self::scrolled`], [`Self::key_pressed`], and [`Self::key_released`], this
+    /// only reflects the one action this region is handling right now, not anything accumulated from earlier ones.
+    /// For that, e.g. which modifiers are held, use an [`InputState`](crate::InputState).
+    pub fn clicked(&mut self, button: MouseButton) -> Option<XY> {
+        let pos = self.mouse_pos()?;
+        match &self.input {
+            Action::MousePress { button: b, .. } if *b == button => Some(pos),
+            _ => None,
+        }
+    }
+
+    /// If this region's input is a release of `button` inside it, where, relative to the region's top-left corner.
+    ///
+    /// The press might have been somewhere else entirely; see [`DragTracker`](tuig_iosys::DragTracker) if that
+    /// matters.
+    pub fn mouse_released(&mut self, button: MouseButton) -> Option<XY> {
+        let pos = self.mouse_pos()?;
+        match &self.input {
+            Action::MouseRelease { button: b, .. } if *b == button => Some(pos),
+            _ => None,
+        }
+    }
+
+    /// If this region's input is the mouse wheel scrolling over it, which way: -1 for up, 1 for down.
+    pub fn scrolled(&mut self) -> Option<isize> {
+        self.mouse_pos()?;
+        match &self.input {
+            Action::MousePress {
+                button: MouseButton::ScrollUp,
+                ..
+            } => Some(-1),
+            Action::MousePress {
+                button: MouseButton::ScrollDown,
+                ..
+            } => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Whether this region's input is a press of `key`, with any modifiers, including repeats.
+    ///
+    /// This doesn't account for focus, so if only one region should react to the key, check [`Self::focusable`]
+    /// too.
+    pub fn key_pressed(&self, key: Key) -> bool {
+        matches!(&self.input, Action::KeyPress { key: k, .. } if *k == key)
+    }
+
+    /// Whether this region's input is a release of `key`, with any modifiers.
+    pub fn key_released(&self, key: Key) -> bool {
+        matches!(&self.input, Action::KeyRelease { key: k, .. } if *k == key)
+    }
+
+    /// Whether this region contains a position on the screen.
+    pub(crate) fn contains(&self, pos: XY) -> bool {
+        self.bounds.contains(pos)
+    }
+
+    /// Register this region as focusable with some stable ID, returning whether it currently has focus.
+    ///
+    /// The ID can be anything hashable, but it should stay the same across frames, and be different from the other
+    /// focusable regions' IDs. Focus moves between them in the order they're registered; see [`FocusState`] for
+    /// details. Attachments should generally ignore keyboard input unless they're focused.
+    ///
+    /// If focus isn't being tracked (see [`Self::with_focus`]) this always returns `true`, so attachments relying on
+    /// it will behave like focus doesn't exist.
+    pub fn focusable(&mut self, id: impl core::hash::Hash) -> bool {
+        self.settle();
+        match self.focus {
+            Some(focus) => focus.register(id, &self.input),
+            None => true,
+        }
+    }
+
+    /// Whether focus is actually being tracked, i.e. whether [`Self::focusable`] can ever say no.
+    ///
+    /// Keys that activate something, like Enter on a button, should only count when this is true, or every such
+    /// attachment on the screen would react to the same keypress.
+    pub(crate) fn focus_tracked(&self) -> bool {
+        self.focus.is_some()
+    }
+
+    /// Whether showing that this region is focused is meaningful, i.e. whether focus can be moved to anything else.
+    pub(crate) fn focus_visible(&self) -> bool {
+        self.focus.map_or(false, |f| f.navigable())
+    }
+
+    /// Take any input the pass-through layers above this region consumed.
+    fn settle(&mut self) {
+        let layers = match self.layers {
+            Some(l) => l,
+            None => return,
+        };
+        for id in self.pending.drain(..) {
+            if let Some(pos) = self.input.position() {
+                if layers.covers(id, pos) {
+                    self.input = Action::Redraw;
+                }
+            }
+            if let Some(pos) = self.mouse {
+                if layers.covers(id, pos) {
+                    self.mouse = None;
+                }
+            }
         }
     }
 
-    #[cfg(test)]
-    pub(crate) fn bounds(&self) -> &Bounds {
+    /// Where this region is on the screen, in absolute screen coordinates.
+    pub fn bounds(&self) -> &Bounds {
         &self.bounds
     }
 
@@ -79,21 +294,107 @@ impl<'s> Region<'s> {
         self.bounds.size
     }
 
-    split_fn!('s: left, right, top, bottom);
+    /// Whether this region has no area, i.e. it's zero columns wide or zero rows tall.
+    ///
+    /// That's common when laying out a very small screen. Attaching anything to an empty region is a no-op: nothing
+    /// gets drawn, no input is handled, and nothing becomes [focusable](Self::focusable).
+    pub fn is_empty(&self) -> bool {
+        self.size().x() == 0 || self.size().y() == 0
+    }
+
+    split_fn!('s:
+        left = "leftmost" "columns",
+        right = "rightmost" "columns",
+        top = "topmost" "rows",
+        bottom = "bottommost" "rows",
+    );
+
+    /// Split off the leftmost `left_cols` columns, returning `[left, rest]`.
+    ///
+    /// This is a simpler alternative to [`cols!`](crate::cols) for when you only need one cut. If the region isn't
+    /// that wide, it's returned unchanged as the `Err`.
+    // same shape as the splitters' output, which hands the region back on failure
+    #[allow(clippy::result_large_err)]
+    pub fn split_h(self, left_cols: usize) -> Result<[Region<'s>; 2], Region<'s>> {
+        if left_cols > self.size().x() {
+            return Err(self);
+        }
+        let (left, rest) = self.split_left(left_cols);
+        Ok([left, rest])
+    }
+
+    /// Split off the topmost `top_rows` rows, returning `[top, rest]`.
+    ///
+    /// This is a simpler alternative to [`rows!`](crate::rows) for when you only need one cut. If the region isn't
+    /// that tall, it's returned unchanged as the `Err`.
+    #[allow(clippy::result_large_err)]
+    pub fn split_v(self, top_rows: usize) -> Result<[Region<'s>; 2], Region<'s>> {
+        if top_rows > self.size().y() {
+            return Err(self);
+        }
+        let (top, rest) = self.split_top(top_rows);
+        Ok([top, rest])
+    }
 
     /// Split the region into one or more children.
     ///
     /// The child regions never overlap each other, and never extend beyond the bounds of the parent. If you want to
-    /// overlap, wait for [#61](https://github.com/nic-hartley/tuig/issues/61) to be completed.
+    /// overlap, use [`Self::layer`].
     ///
     /// This consumes the parent and returns the child regions. It doesn't modify anything in-place. If you don't use
     /// the children, why even bother doing the split?
     #[must_use = "child regions can't be used if you discard them -- why split?"]
-    pub fn split<S: Splitter<'s>>(self, splitter: S) -> S::Output {
+    pub fn split<S: Splitter<'s>>(mut self, splitter: S) -> S::Output {
+        self.settle();
         splitter.split(self)
     }
 
-    pub(crate) fn raw_pieces(self) -> (Action, ScreenView<'s>) {
+    /// Create a new region covering the same area as this one, but drawn on top of it.
+    ///
+    /// The layer starts out transparent, so anything you don't draw on it leaves this region's content on the screen.
+    /// It gets this region's input *before* this region does, so to get input routed top-first, you should make and
+    /// attach to your layers before you attach anything to the region underneath. Following that, the first layer
+    /// made from a region is the topmost, and layers made from a layer go on top of it.
+    ///
+    /// If `pass_through` is false, the layer takes all the input, and this region only gets [`Action::Redraw`]s. If
+    /// it's true, this region also gets any input that the layer didn't consume, which is anything that isn't a mouse
+    /// event on a cell the layer (or a layer on top of it) drew to.
+    ///
+    /// Layers only actually get displayed if this region came from [`Self::layered`], which the [`Adapter`] uses. If
+    /// not, the returned region is [empty](Self::empty), but input is still split up the same way.
+    ///
+    /// [`Adapter`]: crate::Adapter
+    pub fn layer(&mut self, pass_through: bool) -> Region<'s> {
+        self.settle();
+        let input = self.input.clone();
+        let layer = match self.layers {
+            Some(layers) => {
+                let (id, sv) = layers.push(self.layer, self.bounds);
+                self.pending.push(id);
+                Region {
+                    sv,
+                    input,
+                    bounds: self.bounds,
+                    layers: self.layers,
+                    layer: id,
+                    pending: Vec::new(),
+                    focus: self.focus,
+                    mouse: self.mouse,
+                    theme: self.theme,
+                }
+            }
+            None => Region::empty(input),
+        };
+        if !pass_through {
+            self.pending.clear();
+            self.input = Action::Redraw;
+            self.mouse = None;
+        }
+        layer
+    }
+
+    pub(crate) fn raw_pieces(mut self) -> (Action, ScreenView<'s>) {
+        self.settle();
         (self.input, self.sv)
     }
 
@@ -132,6 +433,12 @@ impl Region<'static> {
             sv: ScreenView::empty(),
             input,
             bounds: Bounds::empty(),
+            layers: None,
+            layer: 0,
+            pending: Vec::new(),
+            focus: None,
+            mouse: None,
+            theme: None,
         }
     }
 }
@@ -141,3 +448,165 @@ impl<'s> core::fmt::Debug for Region<'s> {
         self.bounds.fmt(f)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use tuig_iosys::{Action, Key, MouseButton, Screen, XY};
+
+    use crate::{bounds::Bounds, Region};
+
+    /// Get the 3x2 region at (2, 1) in a 6x4 screen, with some input.
+    fn inner(s: &mut Screen, input: Action) -> Region<'_> {
+        let r = Region::new(s, input);
+        let [_, r] = r.split_v(1).unwrap();
+        let [r, _] = r.split_v(2).unwrap();
+        let [_, r] = r.split_h(2).unwrap();
+        let [r, _] = r.split_h(3).unwrap();
+        r
+    }
+
+    fn press(x: usize, y: usize) -> Action {
+        Action::MousePress {
+            pos: XY(x, y),
+            button: MouseButton::Left,
+        }
+    }
+
+    #[test]
+    fn mouse_pos_is_local() {
+        let mut s = Screen::new(XY(6, 4));
+        let inside = [
+            (2, 1, XY(0, 0)),
+            (4, 1, XY(2, 0)),
+            (2, 2, XY(0, 1)),
+            (4, 2, XY(2, 1)),
+        ];
+        for (x, y, local) in inside {
+            assert_eq!(inner(&mut s, press(x, y)).mouse_pos(), Some(local));
+            let moved = Action::MouseMove { pos: XY(x, y) };
+            assert_eq!(inner(&mut s, moved).mouse_pos(), Some(local));
+        }
+    }
+
+    #[test]
+    fn mouse_pos_none_outside() {
+        let mut s = Screen::new(XY(6, 4));
+        for (x, y) in [
+            (1, 1),
+            (5, 1),
+            (2, 0),
+            (2, 3),
+            (1, 2),
+            (5, 2),
+            (4, 0),
+            (4, 3),
+        ] {
+            assert_eq!(inner(&mut s, press(x, y)).mouse_pos(), None);
+        }
+        assert_eq!(inner(&mut s, Action::Redraw).mouse_pos(), None);
+    }
+
+    #[test]
+    fn clicked_checks_button() {
+        let mut s = Screen::new(XY(6, 4));
+        let right = Action::MousePress {
+            pos: XY(3, 2),
+            button: MouseButton::Right,
+        };
+        assert_eq!(
+            inner(&mut s, right.clone()).clicked(MouseButton::Right),
+            Some(XY(1, 1))
+        );
+        assert_eq!(inner(&mut s, right).clicked(MouseButton::Left), None);
+        let release = Action::MouseRelease {
+            pos: XY(3, 2),
+            button: MouseButton::Left,
+        };
+        assert_eq!(inner(&mut s, release).clicked(MouseButton::Left), None);
+        assert_eq!(inner(&mut s, press(5, 2)).clicked(MouseButton::Left), None);
+    }
+
+    #[test]
+    fn release_and_scroll_are_local() {
+        let mut s = Screen::new(XY(6, 4));
+        let release = |x, y| Action::MouseRelease {
+            pos: XY(x, y),
+            button: MouseButton::Left,
+        };
+        let scroll = |x, y, button| Action::MousePress {
+            pos: XY(x, y),
+            button,
+        };
+        assert_eq!(
+            inner(&mut s, release(3, 2)).mouse_released(MouseButton::Left),
+            Some(XY(1, 1))
+        );
+        assert_eq!(
+            inner(&mut s, release(0, 0)).mouse_released(MouseButton::Left),
+            None
+        );
+        assert_eq!(
+            inner(&mut s, press(3, 2)).mouse_released(MouseButton::Left),
+            None
+        );
+        let up = scroll(2, 1, MouseButton::ScrollUp);
+        assert_eq!(inner(&mut s, up).scrolled(), Some(-1));
+        let down = scroll(4, 2, MouseButton::ScrollDown);
+        assert_eq!(inner(&mut s, down).scrolled(), Some(1));
+        let outside = scroll(5, 3, MouseButton::ScrollDown);
+        assert_eq!(inner(&mut s, outside).scrolled(), None);
+        assert_eq!(inner(&mut s, press(3, 2)).scrolled(), None);
+    }
+
+    #[test]
+    fn key_predicates_match_one_action() {
+        let mut s = Screen::new(XY(6, 4));
+        let region = inner(&mut s, Action::press(Key::Char('x')).with_repeat(true));
+        assert!(region.key_pressed(Key::Char('x')));
+        assert!(!region.key_pressed(Key::Char('y')));
+        assert!(!region.key_released(Key::Char('x')));
+        let region = inner(&mut s, Action::release(Key::Char('x')));
+        assert!(region.key_released(Key::Char('x')));
+        assert!(!region.key_pressed(Key::Char('x')));
+    }
+
+    #[test]
+    fn split_h_cuts_columns() {
+        let mut s = Screen::new(XY(10, 3));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [left, rest] = r.split_h(4).expect("should have had enough space");
+        assert_eq!(left.bounds(), &Bounds::new(0, 0, 4, 3));
+        assert_eq!(rest.bounds(), &Bounds::new(4, 0, 6, 3));
+    }
+
+    #[test]
+    fn split_v_cuts_rows() {
+        let mut s = Screen::new(XY(10, 3));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [top, rest] = r.split_v(3).expect("should have had enough space");
+        assert_eq!(top.bounds(), &Bounds::new(0, 0, 10, 3));
+        assert_eq!(rest.bounds(), &Bounds::new(0, 3, 10, 0));
+    }
+
+    #[test]
+    fn splits_too_big_return_self() {
+        let mut s = Screen::new(XY(10, 3));
+        let r = Region::new(&mut s, Action::Redraw);
+        let r = r.split_h(11).expect_err("shouldn't have had space");
+        let r = r.split_v(4).expect_err("shouldn't have had space");
+        assert_eq!(r.bounds(), &Bounds::new(0, 0, 10, 3));
+    }
+
+    #[test]
+    fn split_h_routes_input() {
+        let mut s = Screen::new(XY(10, 3));
+        let click = Action::MousePress {
+            pos: XY(6, 1),
+            button: MouseButton::Left,
+        };
+        let r = Region::new(&mut s, click.clone());
+        let [left, rest] = r.split_h(4).expect("should have had enough space");
+        assert_eq!(left.input, Action::Redraw);
+        assert_eq!(rest.input, click);
+    }
+}
@@ -0,0 +1,127 @@
+use tuig_iosys::fmt::Cell;
+
+use crate::Region;
+
+use super::{
+    statics::{Cols, Rows},
+    Splitter,
+};
+
+/// Splits a region into a grid of `R` rows by `C` columns. Create with [`grid!`](crate::grid), not directly.
+pub struct Grid<const C: usize, const R: usize> {
+    cols: Cols<C>,
+    rows: Rows<R>,
+}
+
+impl<const C: usize, const R: usize> Grid<C, R> {
+    #[deprecated = "use grid!() instead of Grid::new directly"]
+    pub fn new(cols: Cols<C>, rows: Rows<R>) -> Self {
+        Self { cols, rows }
+    }
+
+    /// Fill a row separator, one full-width line per character.
+    fn fill_sep(r: &mut Region<'_>, sep: &str) {
+        for ch in sep.chars() {
+            r.split_top_mut(1).fill(Cell::of(ch));
+        }
+    }
+}
+
+impl<'s, const C: usize, const R: usize> Splitter<'s> for Grid<C, R> {
+    type Output = Result<[[Region<'s>; C]; R], Region<'s>>;
+    fn split(self, mut parent: Region<'s>) -> Self::Output {
+        let heights = match self.rows.lengths(parent.size().y()) {
+            Some(hs) if self.cols.lengths(parent.size().x()).is_some() => hs,
+            _ => return Err(parent),
+        };
+
+        Self::fill_sep(&mut parent, self.rows.preseparator);
+
+        Ok(core::array::from_fn(|i| {
+            let row = if heights[i] == 0 {
+                Region::empty(parent.input.clone())
+            } else {
+                parent.split_top_mut(heights[i])
+            };
+            Self::fill_sep(&mut parent, self.rows.separators[i]);
+            match row.split(self.cols) {
+                Ok(cells) => cells,
+                // only happens for zero-height rows, which can't be split
+                Err(row) => core::array::from_fn(|_| Region::empty(row.input.clone())),
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::string::String;
+    use tuig_iosys::{fmt::Cell, Action, Screen, XY};
+
+    use crate::{bounds::Bounds, grid, Region};
+
+    fn row(screen: &Screen, y: usize) -> String {
+        screen[y].iter().map(|c| c.ch).collect()
+    }
+
+    #[test]
+    fn plain_grid_bounds() {
+        let mut s = Screen::new(XY(6, 4));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [[a, b], [c, d]] = r
+            .split(grid!((2 *) (* 1)))
+            .expect("should have had enough space");
+        assert_eq!(a.bounds(), &Bounds::new(0, 0, 2, 3));
+        assert_eq!(b.bounds(), &Bounds::new(2, 0, 4, 3));
+        assert_eq!(c.bounds(), &Bounds::new(0, 3, 2, 1));
+        assert_eq!(d.bounds(), &Bounds::new(2, 3, 4, 1));
+    }
+
+    #[test]
+    fn grid_with_separators() {
+        let mut s = Screen::new(XY(5, 3));
+        let r = Region::new(&mut s, Action::Redraw);
+        let cells = r
+            .split(grid!((2 "|" *) (1 "-" *)))
+            .expect("should have had enough space");
+        for (i, cell) in cells.into_iter().flatten().enumerate() {
+            cell.fill(Cell::of(char::from_digit(i as u32, 10).unwrap()));
+        }
+        assert_eq!(row(&s, 0), "00|11");
+        assert_eq!(row(&s, 1), "-----");
+        assert_eq!(row(&s, 2), "22|33");
+    }
+
+    #[test]
+    fn grid_too_wide_fails() {
+        let mut s = Screen::new(XY(5, 5));
+        let r = Region::new(&mut s, Action::Redraw);
+        let r = r
+            .split(grid!((3 3) (*)))
+            .expect_err("shouldn't have had space");
+        assert_eq!(r.bounds(), &Bounds::new(0, 0, 5, 5));
+    }
+
+    #[test]
+    fn grid_too_tall_fails() {
+        let mut s = Screen::new(XY(5, 5));
+        let r = Region::new(&mut s, Action::Redraw);
+        let r = r
+            .split(grid!((*) (2 "-" 3)))
+            .expect_err("shouldn't have had space");
+        assert_eq!(r.bounds(), &Bounds::new(0, 0, 5, 5));
+    }
+
+    #[test]
+    fn grid_empty_star_row() {
+        let mut s = Screen::new(XY(4, 2));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [[a, b], [c, d]] = r
+            .split(grid!((* 2) (2 *)))
+            .expect("should have had enough space");
+        assert_eq!(a.bounds(), &Bounds::new(0, 0, 2, 2));
+        assert_eq!(b.bounds(), &Bounds::new(2, 0, 2, 2));
+        assert_eq!(c.size(), XY(0, 0));
+        assert_eq!(d.size(), XY(0, 0));
+    }
+}
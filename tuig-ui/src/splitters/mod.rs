@@ -1,12 +1,33 @@
 use super::Region;
 
+pub mod grid;
+pub mod percent;
 pub mod statics;
 
 /// Common trait implemented by all the things you can pass to [`Region::split`].
 ///
-/// This trait isn't really meant to be implemented externally, but you can if you want. If you really need custom
-/// splitting behavior, you might have some trouble with useful methods on `Region` being private -- consider dropping
-/// an issue/PR with suggestions to improve the API, as it's currently not very good.
+/// You can implement it yourself for custom layouts. The built-in splitters are written with [`Region::split_left`]
+/// and friends (or the `_mut` versions), which carve a region up without ever letting the pieces overlap, plus
+/// [`Region::size`] and [`Region::bounds`] to decide where to cut. For example, splitting into two halves:
+///
+/// ```
+/// # use tuig_ui::{splitters::Splitter, Region};
+/// struct Halves;
+///
+/// impl<'r> Splitter<'r> for Halves {
+///     type Output = [Region<'r>; 2];
+///     fn split(self, parent: Region<'r>) -> Self::Output {
+///         let half = parent.size().x() / 2;
+///         let (left, right) = parent.split_left(half);
+///         [left, right]
+///     }
+/// }
+///
+/// # let mut screen = tuig_ui::Screen::new(tuig_iosys::XY(9, 1));
+/// # let region = Region::new(&mut screen, tuig_iosys::Action::Redraw);
+/// let [left, right] = region.split(Halves);
+/// assert_eq!((left.size().x(), right.size().x()), (4, 5));
+/// ```
 pub trait Splitter<'r> {
     type Output;
     fn split(self, parent: Region<'r>) -> Self::Output;
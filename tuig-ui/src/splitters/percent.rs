@@ -0,0 +1,96 @@
+use crate::Region;
+
+use super::{statics::Cols, Splitter};
+
+/// Splits a region into columns whose widths are percentages of its width. Create with [`pct!`](crate::pct), not
+/// directly.
+#[derive(Clone, Copy)]
+pub struct Percent<const N: usize> {
+    percents: [usize; N],
+    weights: [usize; N],
+    preseparator: &'static str,
+    separators: [&'static str; N],
+}
+
+impl<const N: usize> Percent<N> {
+    #[deprecated = "use pct!() instead of Percent::new directly"]
+    pub fn new(
+        percents: [usize; N],
+        weights: [usize; N],
+        pre: &'static str,
+        seps: [&'static str; N],
+    ) -> Self {
+        Self {
+            percents,
+            weights,
+            preseparator: pre,
+            separators: seps,
+        }
+    }
+
+    /// The equivalent fixed-width [`Cols`] for a region `total` columns wide.
+    fn cols(&self, total: usize) -> Cols<N> {
+        let widths = self.percents.map(|p| total * p / 100);
+        #[allow(deprecated)]
+        Cols::new(widths, self.weights, self.preseparator, self.separators)
+    }
+}
+
+impl<'s, const N: usize> Splitter<'s> for Percent<N> {
+    type Output = Result<[Region<'s>; N], Region<'s>>;
+    fn split(self, parent: Region<'s>) -> Self::Output {
+        let cols = self.cols(parent.size().x());
+        cols.split(parent)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tuig_iosys::{Action, Screen, XY};
+
+    use crate::{bounds::Bounds, pct, Region};
+
+    #[test]
+    fn percent_of_width() {
+        let mut s = Screen::new(XY(40, 2));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [side, main] = r.split(pct!(25 *)).expect("should have had enough space");
+        assert_eq!(side.bounds(), &Bounds::new(0, 0, 10, 2));
+        assert_eq!(main.bounds(), &Bounds::new(10, 0, 30, 2));
+    }
+
+    #[test]
+    fn rounding_slack_goes_to_star() {
+        let mut s = Screen::new(XY(7, 1));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [a, b, c] = r
+            .split(pct!(50 * 20))
+            .expect("should have had enough space");
+        assert_eq!(a.size(), XY(3, 1));
+        assert_eq!(b.size(), XY(3, 1));
+        assert_eq!(c.size(), XY(1, 1));
+    }
+
+    #[test]
+    fn reflows_with_size() {
+        for width in [8, 20, 100] {
+            let mut s = Screen::new(XY(width, 1));
+            let r = Region::new(&mut s, Action::Redraw);
+            let [left, rest] = r
+                .split(pct!(50 "|" *))
+                .expect("should have had enough space");
+            assert_eq!(left.size(), XY(width / 2, 1));
+            assert_eq!(rest.size(), XY(width - width / 2 - 1, 1));
+        }
+    }
+
+    #[test]
+    fn separators_can_overflow() {
+        let mut s = Screen::new(XY(4, 1));
+        let r = Region::new(&mut s, Action::Redraw);
+        let r = r
+            .split(pct!(100 " | " *))
+            .expect_err("shouldn't have had space");
+        assert_eq!(r.bounds(), &Bounds::new(0, 0, 4, 1));
+    }
+}
@@ -15,25 +15,57 @@ macro_rules! split_static {
         // literally just x and y for macro hygeine
         $x:ident, $y:ident
     ) => { paste::paste! {
+        #[derive(Clone, Copy)]
         pub struct $struct<const N: usize> {
             sizes: [usize; N],
+            /// The weight of each star, or 0 for fixed sizes
+            weights: [usize; N],
             // TODO: &'static [fmt::Cell] separators?
-            preseparator: &'static str,
-            separators: [&'static str; N],
+            pub(super) preseparator: &'static str,
+            pub(super) separators: [&'static str; N],
         }
 
         impl<const N: usize> $struct<N> {
             #[deprecated = concat!(
                 "use ", stringify!($macro), "!() instead of ", stringify!($struct), "::new directly"
             )]
-            pub fn new(ws: [usize; N], pre: &'static str, seps: [&'static str; N]) -> Self {
+            pub fn new(
+                ws: [usize; N],
+                weights: [usize; N],
+                pre: &'static str,
+                seps: [&'static str; N],
+            ) -> Self {
                 Self {
                     sizes: ws,
+                    weights,
                     preseparator: pre,
                     separators: seps,
                 }
             }
 
+            /// Figure out how long each split will be, given the total space available, or `None` if it won't fit.
+            ///
+            /// Fixed sizes are taken out first, then whatever's left is split between the stars according to their
+            /// weights, with any remainder from rounding going to the first star.
+            pub(super) fn lengths(&self, total: usize) -> Option<[usize; N]> {
+                let fixed =
+                    self.sizes.iter().sum::<usize>() +
+                    self.separators.iter().map(|s| s.len()).sum::<usize>() +
+                    self.preseparator.len();
+                let leftover = total.checked_sub(fixed)?;
+                let total_weight = self.weights.iter().sum::<usize>();
+                if total_weight == 0 {
+                    return Some(self.sizes);
+                }
+                let shares = self.weights.map(|w| leftover * w / total_weight);
+                let remainder = leftover - shares.iter().sum::<usize>();
+                let mut res: [usize; N] = core::array::from_fn(|i| self.sizes[i] + shares[i]);
+                // UNWRAP: total_weight > 0, so at least one weight is nonzero
+                let first = self.weights.iter().position(|&w| w > 0).unwrap();
+                res[first] += remainder;
+                Some(res)
+            }
+
             fn fill_sep(r: &mut Region<'_>, sep: &str) {
                 if sep.is_empty() {
                     return;
@@ -54,23 +86,15 @@ macro_rules! split_static {
         impl<'s, const N: usize> Splitter<'s> for $struct<N> {
             type Output = Result<[Region<'s>; N], Region<'s>>;
             fn split(self, mut parent: Region<'s>) -> Self::Output {
-                let total_size =
-                    self.sizes.iter().map(|&s| if s == usize::MAX { 0 } else { s }).sum::<usize>() +
-                    self.separators.iter().map(|s| s.len()).sum::<usize>() +
-                    self.preseparator.len();
-                let star_width = match parent.size().$along().checked_sub(total_size) {
-                    Some(w) => w,
+                let widths = match self.lengths(parent.size().$along()) {
+                    Some(ws) => ws,
                     None => return Err(parent),
                 };
 
                 Self::fill_sep(&mut parent, self.preseparator);
 
                 Ok(core::array::from_fn(|i| {
-                    let width = if self.sizes[i] == usize::MAX {
-                        star_width
-                    } else {
-                        self.sizes[i]
-                    };
+                    let width = widths[i];
                     let res = if width == 0 {
                         Region::empty(parent.input.clone())
                     } else {
@@ -91,7 +115,7 @@ split_static!(Rows, rows, top, bottom, y, x, x, y);
 
 #[cfg(test)]
 mod test {
-    use crate::{bounds::Bounds, cols, Region};
+    use crate::{bounds::Bounds, cols, rows, Region};
 
     use alloc::string::String;
     use tuig_iosys::{fmt::Cell, Action, Screen, XY};
@@ -356,4 +380,67 @@ mod test {
         r.split(cols!(50 * "c"))
             .expect_err("should not have had enough space");
     }
+
+    #[test]
+    fn split_equal_stars() {
+        let mut s = Screen::new(XY(50, 50));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [a, b] = r.split(cols!(* *)).expect("should have had enough space");
+        assert_eq!(a.bounds(), &bounds(0, 0, 25, 50));
+        assert_eq!(b.bounds(), &bounds(25, 0, 25, 50));
+    }
+
+    #[test]
+    fn split_weighted_stars() {
+        let mut s = Screen::new(XY(50, 50));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [a, b] = r
+            .split(rows!(*(2) * (3)))
+            .expect("should have had enough space");
+        assert_eq!(a.bounds(), &bounds(0, 0, 50, 20));
+        assert_eq!(b.bounds(), &bounds(0, 20, 50, 30));
+    }
+
+    #[test]
+    fn split_weighted_remainder_goes_left() {
+        let mut s = Screen::new(XY(50, 50));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [a, b, c] = r.split(cols!(* * *)).expect("should have had enough space");
+        assert_eq!(a.bounds(), &bounds(0, 0, 18, 50));
+        assert_eq!(b.bounds(), &bounds(18, 0, 16, 50));
+        assert_eq!(c.bounds(), &bounds(34, 0, 16, 50));
+    }
+
+    #[test]
+    fn split_mixed_fixed_and_weighted() {
+        let mut s = Screen::new(XY(50, 50));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [a, b, c, d] = r
+            .split(cols!(5 *(2) " | " 9 *(1)))
+            .expect("should have had enough space");
+        assert_eq!(a.bounds(), &bounds(0, 0, 5, 50));
+        assert_eq!(b.bounds(), &bounds(5, 0, 22, 50));
+        assert_eq!(c.bounds(), &bounds(30, 0, 9, 50));
+        assert_eq!(d.bounds(), &bounds(39, 0, 11, 50));
+    }
+
+    #[test]
+    fn split_weighted_with_no_space_left() {
+        let mut s = Screen::new(XY(50, 50));
+        let r = Region::new(&mut s, Action::Redraw);
+        let [a, b, c] = r
+            .split(cols!(*(2) 50 *))
+            .expect("should have had enough space");
+        assert_eq!(a.size(), XY(0, 0));
+        assert_eq!(b.bounds(), &bounds(0, 0, 50, 50));
+        assert_eq!(c.size(), XY(0, 0));
+    }
+
+    #[test]
+    fn split_weighted_fixed_too_wide_fails() {
+        let mut s = Screen::new(XY(50, 50));
+        let r = Region::new(&mut s, Action::Redraw);
+        r.split(cols!(*(2) 30 *(1) 21))
+            .expect_err("should not have had enough space");
+    }
 }
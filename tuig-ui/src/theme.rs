@@ -0,0 +1,71 @@
+use tuig_iosys::fmt::{Color, Format};
+
+/// The formatting the built-in attachments draw with, so the UI can be reskinned all at once.
+///
+/// Set it on the root region with [`Region::with_theme`], or on an [`Adapter`] with [`Adapter::with_theme`], and
+/// every region made from that one uses it. Where none is set, regions use [`Self::DEFAULT`], which is what the
+/// built-ins have always looked like. The one exception is [`Textbox`], which draws its text exactly as formatted.
+///
+/// More fields might be added later, so to make one, start from [`Self::DEFAULT`] and use the setters.
+///
+/// [`Region::with_theme`]: crate::Region::with_theme
+/// [`Adapter`]: crate::Adapter
+/// [`Adapter::with_theme`]: crate::Adapter::with_theme
+/// [`Textbox`]: crate::attachments::Textbox
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Theme {
+    /// Ordinary content, like the label of a button that isn't highlighted.
+    pub fg: Format,
+    /// The empty space in and around ordinary content.
+    pub bg: Format,
+    /// Whatever's highlighted, e.g. because it's focused or hovered.
+    pub accent: Format,
+    /// Whatever's selected or being activated, like a button on the frame it's clicked.
+    pub selection: Format,
+    /// Things that can't be interacted with right now, like a [`TextInput`]'s autocompletion before it's accepted.
+    ///
+    /// [`TextInput`]: crate::attachments::TextInput
+    pub disabled: Format,
+}
+
+impl Theme {
+    /// White on black, with black on bright white to highlight.
+    pub const DEFAULT: Self = Self {
+        fg: Format::NONE,
+        bg: Format::NONE,
+        accent: Format {
+            fg: Color::Black,
+            bg: Color::BrightWhite,
+            ..Format::NONE
+        },
+        selection: Format {
+            fg: Color::Black,
+            bg: Color::White,
+            ..Format::NONE
+        },
+        disabled: Format {
+            fg: Color::BrightBlack,
+            ..Format::NONE
+        },
+    };
+
+    tuig_pm::setters! {
+        /// Set [`Self::fg`].
+        fg(v: Format) => fg = v,
+        /// Set [`Self::bg`].
+        bg(v: Format) => bg = v,
+        /// Set [`Self::accent`].
+        accent(v: Format) => accent = v,
+        /// Set [`Self::selection`].
+        selection(v: Format) => selection = v,
+        /// Set [`Self::disabled`].
+        disabled(v: Format) => disabled = v,
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
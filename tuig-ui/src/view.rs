@@ -4,17 +4,30 @@ use core::{
     ptr::NonNull,
 };
 
-use alloc::slice;
+use alloc::{rc::Rc, slice};
 use tuig_iosys::{fmt::Cell, Screen, XY};
 
 use super::Bounds;
 
+/// Which cells of a screen have been written to through a view, indexed the same way as the screen's cells. Every
+/// view split from one that's recording shares the same record.
+///
+/// This lives outside the screen so it can be checked while views into the screen are still around.
+pub(crate) type Drawn = Rc<[core::cell::Cell<bool>]>;
+
 /// A mutable view into a region of a `Screen`.
 ///
 /// You don't directly get `ScreenView`s; they're given to you through [`Region::attach`][crate::Region::attach] and
 /// [`RawAttachment::raw_attach`][super::RawAttachment::raw_attach]. You can use them to directly draw to a screen's
 /// textgrid, but bounded in a certain region, so that multiple attachments can be alive at once without causing
 /// lifetime issues or deadlocks or slowdowns from mutexes.
+///
+/// This is the mutable counterpart to [`tuig_iosys::ScreenView`], which you can get from [`Screen::view`] any time
+/// the screen isn't being drawn to.
+///
+/// A `ScreenView` can only ever reach the cells inside its bounds. Rows are sliced to the view's width, and anything
+/// past the edges is either `None` or a panic, depending on the method. So whatever an attachment does with the view
+/// it's given, nothing outside of its region can change.
 pub struct ScreenView<'s> {
     /// Ties the lifetimes together
     _sc: PhantomData<&'s Screen>,
@@ -24,6 +37,8 @@ pub struct ScreenView<'s> {
     full_size: XY,
     /// The boundaries of this particular `ScreenView` within the screen
     bounds: Bounds,
+    /// Where to note down the cells handed out mutably, if anywhere
+    drawn: Option<Drawn>,
 }
 
 impl<'s> ScreenView<'s> {
@@ -35,6 +50,7 @@ impl<'s> ScreenView<'s> {
             buf: None,
             full_size: XY(0, 0),
             bounds: Bounds::empty(),
+            drawn: None,
         }
     }
 
@@ -60,6 +76,22 @@ impl<'s> ScreenView<'s> {
             buf: Some(unsafe { NonNull::new_unchecked(screen.cells_mut().as_mut_ptr()) }),
             full_size: screen.size(),
             bounds,
+            drawn: None,
+        }
+    }
+
+    /// Note down every cell this view (or any view split from it) hands out mutably in `drawn`, which must be at
+    /// least as long as the screen's cells.
+    pub(crate) fn recording(mut self, drawn: Drawn) -> Self {
+        debug_assert!(drawn.len() >= self.full_size.x() * self.full_size.y());
+        self.drawn = Some(drawn);
+        self
+    }
+
+    /// Note down that `len` cells from `offset` were handed out mutably.
+    fn mark(&self, offset: usize, len: usize) {
+        if let Some(drawn) = &self.drawn {
+            drawn[offset..offset + len].iter().for_each(|c| c.set(true));
         }
     }
 
@@ -75,6 +107,7 @@ impl<'s> ScreenView<'s> {
             bounds: sb,
             buf: self.buf,
             full_size: self.full_size,
+            drawn: self.drawn.clone(),
         })
     }
 
@@ -118,6 +151,7 @@ impl<'s> ScreenView<'s> {
     pub fn cell_mut(&mut self, pos: XY) -> Option<&mut Cell> {
         let buf = self.buf?;
         let offset = self.offset(pos)?;
+        self.mark(offset, 1);
         // SAFETY: See [`Self::offset`] docs. Mutable references are safe because this method is `&mut self`, which
         // means Rust is preventing aliased references.
         unsafe { Some(&mut *buf.as_ptr().add(offset)) }
@@ -158,6 +192,7 @@ impl<'s> ScreenView<'s> {
         // we don't rely on "wrapping around".
         let start = unsafe { buf.as_ptr().add(offset) };
         let len = self.bounds.size.x();
+        self.mark(offset, len);
         // SAFETY: `bounds` from different instances are guaranteed (from `Self::new`) to be exclusive between them,
         // so there can't be any overlap that way. And the use of `&mut self` ensures that this object  won't be used
         // to get multiple row references simultaneously (except as Rust allows) so there's no risk of bad aliasing.
@@ -1,6 +1,7 @@
 /// The `Agent` trait and its `ControlFlow`
 use core::fmt;
 use std::{
+    any::Any,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -15,7 +16,10 @@ use std::time::Instant;
 
 use crate::{Message, Replies};
 
-/// See [`ControlFlow::Handle`].
+/// A handle which another agent, or the [`Game`](crate::Game), can use to wake a sleeping agent up early.
+///
+/// Get one from [`ControlFlow::wait`], [`ControlFlow::wait_until`], or [`ControlFlow::wait_for`]. It's cheap to
+/// clone, and `Send + Sync`, so you can hand copies off to whatever needs to do the waking.
 #[derive(Clone)]
 pub struct WaitHandle(Arc<AtomicBool>);
 
@@ -25,7 +29,12 @@ impl WaitHandle {
         WaitHandle(Arc::new(AtomicBool::new(false)))
     }
 
-    /// Notify the waiting agent that it can wake up.
+    /// Notify the waiting agent that it can wake up, so it'll react again in the next round (or so), no matter how long
+    /// it meant to sleep.
+    ///
+    /// This can race with the agent waking up on its own, e.g. because its time ran out. That's fine: if it's
+    /// already awake, this does nothing. It also does nothing once the agent goes to sleep again with a new
+    /// [`ControlFlow`], since that gets a fresh handle.
     pub fn wake(&self) {
         self.0.store(true, Ordering::Release);
     }
@@ -73,6 +82,8 @@ pub enum ControlFlow {
     Handle(WaitHandle),
     /// Sleep until the given time.
     Time(Instant),
+    /// Sleep until the given time, or until the handle gets woken up, whichever comes first.
+    TimeOrHandle(Instant, WaitHandle),
 }
 
 impl ControlFlow {
@@ -82,12 +93,29 @@ impl ControlFlow {
         (Self::Handle(wh.clone()), wh)
     }
 
+    /// Create a new [`ControlFlow::TimeOrHandle`], sleeping until the given time unless something wakes it first.
+    pub fn wait_until(time: Instant) -> (Self, WaitHandle) {
+        let wh = WaitHandle::new();
+        (Self::TimeOrHandle(time, wh.clone()), wh)
+    }
+
+    /// Create a new [`ControlFlow::TimeOrHandle`], sleeping for a given duration from now unless something wakes it
+    /// first.
+    pub fn wait_for(amt: Duration) -> (Self, WaitHandle) {
+        Self::wait_until(Instant::now() + amt)
+    }
+
     /// Create a new [`ControlFlow::Time`], sleeping until the given time.
+    ///
+    /// This is best-effort: the agent wakes up in the first round after `time`, which may be a little late if the
+    /// runner is busy, but never early. [`Self::sleep_for`] is the same thing, relative to now.
     pub fn sleep_until(time: Instant) -> Self {
         Self::Time(time)
     }
 
     /// Create a new [`ControlFlow::Time`], sleeping for a given duration from now.
+    ///
+    /// Like [`Self::sleep_until`], it's best-effort, and the agent may wake slightly late.
     pub fn sleep_for(amt: Duration) -> Self {
         Self::Time(Instant::now() + amt)
     }
@@ -99,10 +127,120 @@ impl ControlFlow {
             ControlFlow::Kill => false,
             ControlFlow::Handle(wh) => wh.is_woken(),
             ControlFlow::Time(when) => &Instant::now() > when,
+            ControlFlow::TimeOrHandle(when, wh) => wh.is_woken() || &Instant::now() > when,
         }
     }
 }
 
+/// What the runner should do when an agent panics. See [`Runner::on_agent_panic`](crate::Runner::on_agent_panic).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum PanicPolicy {
+    /// Let the panic go, which crashes the game, the same as a panic anywhere else would.
+    #[default]
+    Abort,
+    /// Catch the panic and kill the agent that panicked, then carry on with the rest as usual.
+    ///
+    /// Anything the agent queued or spawned in the call that panicked is thrown away. The panic is still reported
+    /// through the [panic hook](std::panic::set_hook), which prints it to stderr by default.
+    KillAgent,
+}
+
+/// How many agents were in what state at the end of a round. See [`Replies::_agent_stats`].
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct AgentStats {
+    /// How many agents returned [`ControlFlow::Continue`], or were sleeping but are ready to wake up.
+    pub awake: usize,
+    /// How many agents are waiting on a [`ControlFlow::Handle`] or [`ControlFlow::Time`].
+    pub sleeping: usize,
+    /// How many agents were removed, because they returned [`ControlFlow::Kill`] or were waiting on a handle that
+    /// nothing else could wake.
+    pub killed: usize,
+    /// How many agents spawned that round were dropped for going past the [spawn budget](crate::Runner::spawn_budget).
+    pub over_budget: usize,
+}
+
+/// Lets an [`Agent`] trait object be turned back into its concrete type, with [`downcast_ref`](Any#method.downcast_ref).
+///
+/// This is implemented automatically for every `'static` type, so you never need to implement it yourself. It's
+/// separate from `Agent` only because a default method can't do the same conversion.
+///
+/// Since that includes `Box`, calling `as_any` on a `Box<dyn Agent<_>>` gets you the box, not the agent. Call it on
+/// a `&dyn Agent<_>` instead (e.g. with `as_ref()`), or use [`downcast_ref`](trait.Agent.html#method.downcast_ref),
+/// which works on either.
+pub trait AsAny: Any {
+    /// Get this value as a `&dyn Any`, to [downcast](Any#method.downcast_ref) it.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// An agent in the system, which can react to messages of a specific type by spawning more agents or messages.
+///
+/// See [the crate root][crate#architecture] for details.
+///
+/// Agents must be `'static`, i.e. they can't borrow anything that doesn't live for the whole program. They're held
+/// onto by the runner for as long as they're alive, which might be the rest of the game, so this would be the case
+/// anyway. It's made explicit so that you can use [`AsAny::as_any`] to get back the concrete type of an agent, e.g.
+/// to inspect its state in a test:
+///
+/// ```
+/// # use tuig::{Agent, AsAny};
+/// struct Counter(u32);
+/// impl Agent<()> for Counter {}
+///
+/// let agent: Box<dyn Agent<()>> = Box::new(Counter(3));
+/// assert_eq!(agent.downcast_ref::<Counter>().map(|c| c.0), Some(3));
+/// assert!(agent.as_ref().as_any().is::<Counter>());
+/// ```
+pub trait Agent<M: Message>: AsAny + Send + Sync {
+    /// Called once on (re)start, to queue any starting events/ControlFlow as necessary. This will always be called
+    /// before `react`.
+    ///
+    /// By default, does nothing and returns [`ControlFlow::Continue`] to allow [`Self::react`] to be called, under
+    /// the assumption that your interesting code sits there.
+    #[cfg_attr(coverage, no_coverage)]
+    fn start(&mut self, _replies: &mut Replies<M>) -> ControlFlow {
+        ControlFlow::Continue
+    }
+
+    /// React to the events of a round, indicating when the agent should be called next and optionally queueing some
+    /// more events.
+    ///
+    /// By default, does nothing and returns [`ControlFlow::Kill`], under the assumption that you'd have implemented
+    /// `react` if you wanted your agent to stay alive and do things.
+    #[cfg_attr(coverage, no_coverage)]
+    fn react(&mut self, _msg: &M, _replies: &mut Replies<M>) -> ControlFlow {
+        ControlFlow::Kill
+    }
+
+    /// How early in each round this agent should run, relative to the others. Higher priorities go first.
+    ///
+    /// Within a round, agents react, and their replies are applied, in order of priority. Agents with the same
+    /// priority go in the order they were spawned. This only orders things *within* a round: a high-priority agent's
+    /// queued messages still won't be seen until the next round, and it doesn't get to see that round any sooner.
+    ///
+    /// This should stay the same for the whole life of the agent; it's only checked when the agent is spawned.
+    ///
+    /// By default, returns 0.
+    #[cfg_attr(coverage, no_coverage)]
+    fn priority(&self) -> i32 {
+        0
+    }
+}
+
+impl<M: Message> dyn Agent<M> {
+    /// Get a reference to this agent as its concrete type, if that's what it is.
+    ///
+    /// This is a shortcut for `self.as_any().downcast_ref()`.
+    pub fn downcast_ref<T: Agent<M>>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+}
+
 #[cfg(test)]
 mod cf_test {
     use std::time::Duration;
@@ -148,29 +286,29 @@ mod cf_test {
         MockClock::advance(Duration::from_millis(60));
         assert!(cf.is_ready());
     }
-}
 
-/// An agent in the system, which can react to messages of a specific type by spawning more agents or messages.
-///
-/// See [the crate root][crate#architecture] for details.
-pub trait Agent<M: Message>: Send + Sync {
-    /// Called once on (re)start, to queue any starting events/ControlFlow as necessary. This will always be called
-    /// before `react`.
-    ///
-    /// By default, does nothing and returns [`ControlFlow::Continue`] to allow [`Self::react`] to be called, under
-    /// the assumption that your interesting code sits there.
-    #[cfg_attr(coverage, no_coverage)]
-    fn start(&mut self, _replies: &mut Replies<M>) -> ControlFlow {
-        ControlFlow::Continue
+    #[test]
+    fn wait_for_readies_after_time() {
+        let (cf, _wh) = ControlFlow::wait_for(Duration::from_millis(100));
+        assert!(!cf.is_ready());
+        MockClock::advance(Duration::from_millis(120));
+        assert!(cf.is_ready());
     }
 
-    /// React to the events of a round, indicating when the agent should be called next and optionally queueing some
-    /// more events.
-    ///
-    /// By default, does nothing and returns [`ControlFlow::Kill`], under the assumption that you'd have implemented
-    /// `react` if you wanted your agent to stay alive and do things.
-    #[cfg_attr(coverage, no_coverage)]
-    fn react(&mut self, _msg: &M, _replies: &mut Replies<M>) -> ControlFlow {
-        ControlFlow::Kill
+    #[test]
+    fn wait_for_readies_early_on_wake() {
+        let (cf, wh) = ControlFlow::wait_for(Duration::from_secs(3600));
+        assert!(!cf.is_ready());
+        wh.clone().wake();
+        assert!(cf.is_ready());
+        // waking twice is harmless
+        wh.wake();
+        assert!(cf.is_ready());
+    }
+
+    #[test]
+    fn wait_handle_is_shareable() {
+        fn assert_shareable<T: Clone + Send + Sync>() {}
+        assert_shareable::<super::WaitHandle>();
     }
 }
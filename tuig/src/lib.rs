@@ -23,8 +23,14 @@
 //!     It's very widely compatible, because you literally don't need any 3D hardware for it to work.
 //!
 //! You have to pick exactly one runner, but you can choose more than one IO system. [`Runner::load_run`] will try to
-//! intelligently pick "the best it can" given the ones you've turned on, but if you very reasonably disagree, or if
-//! you want a third-party backend, you can load your preferred system and use [`Runner::run`] instead.
+//! intelligently pick "the best it can" given the ones you've turned on, but if you very reasonably disagree, you can
+//! narrow it down with [`Runner::prefer`], or players can with the `TUIG_BACKEND` environment variable. If you want a
+//! third-party backend, you can load your preferred system and use [`Runner::run`] instead.
+//!
+//! There are also a couple of extras, which you can turn on as you need them:
+//!
+//! -   `metrics`: Lets the game see how long each part of the main loop is taking, with [`Replies::frame_times`].
+//! -   `test_extras`: **Test-only** functions for introspecting the engine's state, all named starting with `_`.
 //!
 //! # Architecture
 //!
@@ -46,23 +52,39 @@
 //! in lockstep. They don't even all necessarily see the same rounds! You'll occasionally see them mentioned because
 //! that's how the engine works internally, and it explains why certain things happen or don't. But in short: Don't
 //! count on queued messages or spawned agents to be *immediate*, just vaguely "soon".
+//!
+//! What agents *can* count on is that each round looks the same to all of them. If they need to read some global
+//! state, rather than each keeping their own copy, give it to [`Runner::shared_state`]: every agent in a round sees
+//! the same [`SharedState`] snapshot, with updates from the round's messages applied up front, so none of them ever
+//! see it half-updated by another.
 
 mod agent;
 #[cfg(doc)]
 pub mod docs;
 mod game;
 mod message;
+mod metrics;
+mod replay;
 mod runner;
+mod state;
 mod util;
 
 pub use {
-    agent::{Agent, ControlFlow, WaitHandle},
+    agent::{Agent, AsAny, ControlFlow, PanicPolicy, WaitHandle},
     game::Game,
     message::{Message, Replies},
+    replay::{ReplayLog, Round},
     runner::Runner,
+    state::SharedState,
     tuig_iosys as io,
 };
 
+#[cfg(feature = "test_extras")]
+pub use agent::AgentStats;
+
+#[cfg(feature = "metrics")]
+pub use metrics::FrameTimes;
+
 /// A shortcut for basic games which only need to be started on the default, [`load!`](tuig_iosys::load!)ed IO system.
 ///
 /// If you need more control, e.g. selecting a specific backend or queueing initial agents, use [`Runner`]. Many real
@@ -1,8 +1,11 @@
 //! Messages are the primary backbone of the engine's communication. Replies are how they're queued.
 
-use std::fmt;
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
 
-use crate::Agent;
+use crate::{agent::AgentStats, metrics::FrameTimes, Agent, SharedState};
 
 /// A message that [`Agent`]s and [`Game`](crate::Game)s will be passing around.
 ///
@@ -10,8 +13,9 @@ use crate::Agent;
 /// should do the same. Usually you'll implement this in an enum. If you *want* a trait object, use `Message` as a
 /// supertrait for yours.
 ///
-/// This trait requires `Clone`, `Send`, and `Sync`, to ensure it can be properly shared across all threads.
-pub trait Message: Clone + Send + Sync {
+/// This trait requires `Clone`, `Send`, and `Sync`, to ensure it can be properly shared across all threads. It also
+/// requires `'static`, like [`Agent`], since agents are generic over their message type.
+pub trait Message: Clone + Send + Sync + 'static {
     /// The message to send agents when there aren't any other messages queued for processing, to ensure every awake
     /// agent processes at least one message per round. Will **not** be sent if there are any other messages!
     ///
@@ -21,7 +25,7 @@ pub trait Message: Clone + Send + Sync {
 
 /// Primarly so you can use basic integer types as messages in tests like `mass-messages`, automatically implement
 /// `Message` for `Default` types. `Message::tick()` returns `Default::default()`.
-impl<T: Clone + Send + Sync + Default> Message for T {
+impl<T: Clone + Send + Sync + Default + 'static> Message for T {
     fn tick() -> Self {
         Self::default()
     }
@@ -33,6 +37,18 @@ impl<T: Clone + Send + Sync + Default> Message for T {
 pub struct Replies<M: Message> {
     pub(crate) agents: Vec<Box<dyn Agent<M>>>,
     pub(crate) messages: Vec<M>,
+    pub(crate) delayed: Vec<(Instant, M)>,
+    /// Whether the game asked to pause (`true`) or resume (`false`) the agents
+    pub(crate) pause: Option<bool>,
+    /// The window title the game asked for, if it asked for one
+    pub(crate) title: Option<String>,
+    /// The agents' states as of the last round, for the game to look at
+    pub(crate) stats: AgentStats,
+    /// The snapshot of the shared state for this round
+    pub(crate) state: SharedState,
+    /// How long the runner's last loop took, for the game to look at
+    #[cfg_attr(not(feature = "metrics"), allow(unused))]
+    pub(crate) frames: FrameTimes,
 }
 
 impl<M: Message> Default for Replies<M> {
@@ -40,6 +56,12 @@ impl<M: Message> Default for Replies<M> {
         Self {
             agents: Default::default(),
             messages: Default::default(),
+            delayed: Default::default(),
+            pause: None,
+            title: None,
+            stats: AgentStats::default(),
+            state: SharedState::default(),
+            frames: FrameTimes::default(),
         }
     }
 }
@@ -49,6 +71,7 @@ impl<M: Message> fmt::Debug for Replies<M> {
         f.debug_struct(std::any::type_name::<Self>())
             .field("agents", &self.agents.len())
             .field("messages", &self.messages.len())
+            .field("delayed", &self.delayed.len())
             .finish()
     }
 }
@@ -73,6 +96,20 @@ impl<M: Message> Replies<M> {
         self
     }
 
+    /// [`Self::spawn`] an agent, and [`Self::queue_all`] some messages to go along with it, in one go.
+    ///
+    /// The messages go out in the same round that the agent is spawned into, so they're queued *before* its
+    /// [`Agent::start`] runs: everything reacting that round sees them (including the new agent itself), and anything
+    /// the agent does in `start` comes a round later. Without this, you'd have to have the agent queue them in
+    /// `start`, which takes an extra round to come out.
+    pub fn spawn_with(
+        &mut self,
+        agent: impl Agent<M> + 'static,
+        initial: impl IntoIterator<Item = M>,
+    ) -> &mut Self {
+        self.spawn(agent).queue_all(initial)
+    }
+
     /// Queues up a message to be sent out in the next round.
     ///
     /// Queued messages are guaranteed to be processed in the next round after this one. So:
@@ -89,6 +126,84 @@ impl<M: Message> Replies<M> {
         self.messages.extend(msgs);
         self
     }
+
+    /// The snapshot of the game's [shared state](crate::Runner::shared_state) for this round.
+    ///
+    /// Agents all see the same one for the whole round, with that round's messages already applied. The
+    /// [`Game`](crate::Game) sees the one from the last round run. If the runner wasn't given any shared state, it's
+    /// empty, and [`SharedState::get`] always returns `None`.
+    pub fn state(&self) -> &SharedState {
+        &self.state
+    }
+
+    /// How many agents were alive at the end of the last round, counting sleeping ones.
+    ///
+    /// This is only filled in for the [`Game`](crate::Game); in agents' replies, it's always 0. It's a snapshot, so
+    /// it doesn't count anything spawned since then, and with the `run_rayon` runner, it may be slightly stale.
+    pub fn agent_count(&self) -> usize {
+        self.stats.awake + self.stats.sleeping
+    }
+
+    /// How long each part of the runner's last loop took, and how many frames it's been drawing per second.
+    ///
+    /// Like [`Self::agent_count`], this is only filled in for the [`Game`](crate::Game), and it's a snapshot of the
+    /// last complete loop, not the one in progress.
+    #[cfg(feature = "metrics")]
+    pub fn frame_times(&self) -> FrameTimes {
+        self.frames
+    }
+
+    /// Pause the simulation: stop running rounds of messages until [`Self::resume`] is called.
+    ///
+    /// While paused, the [`Game`](crate::Game) still gets [attached](crate::Game::attach) to handle input and render,
+    /// but agents don't react to anything and the game isn't sent any messages. Anything queued or spawned waits
+    /// until the simulation resumes. No rounds means no [tick](Message::tick)s are generated either. Time stands
+    /// still for agents: when it resumes, [`ControlFlow::sleep_for`](crate::ControlFlow::sleep_for) and
+    /// [`Self::queue_after`] timers pick up where they left off, rather than all going off at once.
+    ///
+    /// This is only meant for the `Game`, e.g. to freeze things while a menu is open. Agents can't pause or resume,
+    /// so it does nothing in their replies.
+    pub fn pause(&mut self) -> &mut Self {
+        self.pause = Some(true);
+        self
+    }
+
+    /// Resume the simulation after [`Self::pause`]. Does nothing if it isn't paused.
+    pub fn resume(&mut self) -> &mut Self {
+        self.pause = Some(false);
+        self
+    }
+
+    /// Change the title of the game's display, e.g. the window title or the terminal's tab title.
+    ///
+    /// It's only sent to the [`IoSystem`](tuig_iosys::IoSystem) when it actually changes, so it's fine to set it
+    /// every time the game is attached. Backends without a title ignore it, as described in
+    /// [`IoSystem::set_title`](tuig_iosys::IoSystem::set_title).
+    ///
+    /// Like [`Self::pause`], this is only meant for the [`Game`](crate::Game), and it does nothing in agents'
+    /// replies.
+    pub fn set_title(&mut self, title: impl Into<String>) -> &mut Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Queues up a message to be sent out once some time has passed, without needing an agent to wait around for it.
+    ///
+    /// The timing is best-effort. The runner only checks for due messages once per round, so the message goes out in
+    /// the first round after the delay is up, which might be as much as one [`input_tick`] late, or more if the game
+    /// is lagging. It's never early, though. Messages that come due at the same time go out in the order they were
+    /// queued.
+    ///
+    /// A delay so long that the time it's up can't even be represented (e.g. [`Duration::MAX`]) would never come
+    /// due, so the message is just dropped.
+    ///
+    /// [`input_tick`]: crate::Runner::input_tick
+    pub fn queue_after(&mut self, delay: Duration, msg: M) -> &mut Self {
+        if let Some(at) = Instant::now().checked_add(delay) {
+            self.delayed.push((at, msg));
+        }
+        self
+    }
 }
 
 #[cfg(feature = "test_extras")]
@@ -100,8 +215,24 @@ impl<M: Message> Replies<M> {
         &self.messages
     }
 
+    /// A **test-only** function, listing the messages that have been [`Self::queue_after`]ed, with when they're due.
+    pub fn _delayed(&self) -> &[(Instant, M)] {
+        &self.delayed
+    }
+
     /// A **test-only** function, listing the agents that have been [`Self::spawn`]ed.
     pub fn _agents(&self) -> &[Box<dyn Agent<M>>] {
         &self.agents
     }
+
+    /// A **test-only** function, returning the title last [`Self::set_title`], if any.
+    pub fn _title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// A **test-only** function, breaking down [`Self::agent_count`] by what the agents were doing at the end of the
+    /// last round, plus how many were removed that round. The same caveats apply.
+    pub fn _agent_stats(&self) -> AgentStats {
+        self.stats
+    }
 }
@@ -0,0 +1,129 @@
+//! Timing the runner's main loop, for debug overlays and the like.
+
+#![cfg_attr(not(feature = "__run"), allow(unused))]
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// How long each part of the runner's last full loop took, and roughly how often it's drawing. See
+/// [`Replies::frame_times`](crate::Replies::frame_times).
+///
+/// One loop is one batch of input, then one round of messages, and whatever waiting happens in between.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub struct FrameTimes {
+    /// Time spent [attaching](crate::Game::attach) the game to handle input.
+    pub input: Duration,
+    /// Time spent running the round of messages through the game and agents. With `run_rayon`, this is the wall
+    /// time of the whole round, not the sum of the time each agent took.
+    pub agents: Duration,
+    /// Time spent drawing to the `IoSystem`.
+    pub render: Duration,
+    /// The total time the loop took, including the parts above and any time spent waiting.
+    pub total: Duration,
+    /// How many frames were actually drawn in the last second. This counts up from 0 over the first second of the
+    /// game.
+    pub fps: f32,
+}
+
+/// Keeps track of the [`FrameTimes`] for the current loop and the last complete one.
+pub(crate) struct FrameClock {
+    /// When the current loop started
+    start: Instant,
+    /// The times so far for the current loop
+    current: FrameTimes,
+    /// The times for the last complete loop
+    last: FrameTimes,
+    /// When each frame in the last second was drawn, oldest first
+    draws: VecDeque<Instant>,
+}
+
+impl FrameClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            current: FrameTimes::default(),
+            last: FrameTimes::default(),
+            draws: VecDeque::new(),
+        }
+    }
+
+    /// The times for the last complete loop.
+    pub fn last(&self) -> FrameTimes {
+        self.last
+    }
+
+    /// End the current loop and start the next.
+    pub fn lap(&mut self) {
+        let now = Instant::now();
+        while self
+            .draws
+            .front()
+            .map_or(false, |&at| now.duration_since(at) > Duration::from_secs(1))
+        {
+            self.draws.pop_front();
+        }
+        self.current.total = now.duration_since(self.start);
+        self.current.fps = self.draws.len() as f32;
+        self.last = std::mem::take(&mut self.current);
+        self.start = now;
+    }
+
+    /// Note that some input was handled, starting at `since`.
+    pub fn input(&mut self, since: Instant) {
+        self.current.input += since.elapsed();
+    }
+
+    /// Run part of a round of messages, timing it.
+    pub fn agents<T>(&mut self, run: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let res = run();
+        self.current.agents += start.elapsed();
+        res
+    }
+
+    /// Note that a frame was drawn, starting at `since`.
+    pub fn drew(&mut self, since: Instant) {
+        let now = Instant::now();
+        self.current.render += now.duration_since(since);
+        self.draws.push_back(now);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::{FrameClock, FrameTimes};
+
+    #[test]
+    fn lap_moves_current_to_last() {
+        let mut clock = FrameClock::new();
+        clock.input(Instant::now() - Duration::from_millis(3));
+        clock.agents(|| ());
+        clock.drew(Instant::now() - Duration::from_millis(5));
+        clock.drew(Instant::now());
+        assert_eq!(clock.last(), FrameTimes::default());
+        clock.lap();
+        let last = clock.last();
+        assert!(last.input >= Duration::from_millis(3));
+        assert!(last.render >= Duration::from_millis(5));
+        assert!(last.total >= last.agents);
+        assert_eq!(last.fps, 2.0);
+        clock.lap();
+        assert_eq!(clock.last().input, Duration::ZERO);
+        assert_eq!(clock.last().fps, 2.0);
+    }
+
+    #[test]
+    fn old_frames_dropped_from_fps() {
+        let mut clock = FrameClock::new();
+        clock
+            .draws
+            .push_back(Instant::now() - Duration::from_secs(2));
+        clock.drew(Instant::now());
+        clock.lap();
+        assert_eq!(clock.last().fps, 1.0);
+    }
+}
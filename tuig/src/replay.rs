@@ -0,0 +1,260 @@
+//! Recording a run of the game and replaying it later, deterministically and single-threaded, for debugging.
+
+use std::{
+    mem,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tuig_iosys::{Action, Capabilities, CursorStyle, IoSystem, Screen, XY};
+
+use crate::Message;
+
+/// One round of a [`ReplayLog`]: the input the game got since the last round, then the messages the round processed.
+#[derive(Clone, Debug)]
+pub struct Round<M: Message> {
+    /// Every input action the `IoSystem` gave the game between the previous round and this one, in order.
+    pub inputs: Vec<Action>,
+    /// The messages the game and agents were fed this round. If this is empty, they got a [tick](Message::tick).
+    pub messages: Vec<M>,
+}
+
+/// A record of everything that came into a game while it was running, round by round. See [`Runner::record`].
+///
+/// Replaying it with [`Runner::replay`] re-runs those exact rounds, in order, on a single thread, no matter which
+/// runner recorded it. That makes it a reproducible harness for bugs that only show up under concurrency: whatever
+/// interleaving of messages was observed gets fed back serially, every time.
+///
+/// You can also build one by hand, with [`Self::input`] and [`Self::end_round`], to script a test.
+///
+/// [`Runner::record`]: crate::Runner::record
+/// [`Runner::replay`]: crate::Runner::replay
+#[derive(Clone, Debug)]
+pub struct ReplayLog<M: Message> {
+    pub(crate) size: XY,
+    rounds: Vec<Round<M>>,
+    pending: Vec<Action>,
+}
+
+impl<M: Message> ReplayLog<M> {
+    /// Create an empty log, for a screen of some size.
+    ///
+    /// When recording, the size is replaced by the `IoSystem`'s when the game starts.
+    pub fn new(size: XY) -> Self {
+        Self {
+            size,
+            rounds: vec![],
+            pending: vec![],
+        }
+    }
+
+    /// The size of the screen at the start of the game. Resizes after that are in the inputs.
+    pub fn size(&self) -> XY {
+        self.size
+    }
+
+    /// Every round logged so far, oldest first.
+    ///
+    /// The runner logs one last round when the game quits, with the inputs up to the quit but no messages.
+    pub fn rounds(&self) -> &[Round<M>] {
+        &self.rounds
+    }
+
+    /// Log some input to the game, which will be part of the next round ended.
+    pub fn input(&mut self, action: Action) -> &mut Self {
+        self.pending.push(action);
+        self
+    }
+
+    /// End a round, which processed some messages, along with all the input since the last one.
+    pub fn end_round(&mut self, messages: &[M]) -> &mut Self {
+        let inputs = mem::take(&mut self.pending);
+        self.rounds.push(Round {
+            inputs,
+            messages: messages.to_vec(),
+        });
+        self
+    }
+}
+
+/// What the runner holds onto while it's [recording](crate::Runner::record).
+pub(crate) struct Recorder<M: Message> {
+    log: Arc<Mutex<ReplayLog<M>>>,
+    /// Inputs seen by the [`Taped`] system, not yet moved into the log
+    inputs: Arc<Mutex<Vec<Action>>>,
+}
+
+impl<M: Message> Recorder<M> {
+    pub fn new(log: Arc<Mutex<ReplayLog<M>>>) -> Self {
+        Self {
+            log,
+            inputs: Default::default(),
+        }
+    }
+
+    /// Wrap an `IoSystem` so its inputs get logged, and log its size as the starting size.
+    pub fn tape<IO: IoSystem>(&self, inner: IO) -> Taped<IO> {
+        self.log.lock().unwrap().size = inner.size();
+        Taped {
+            inner,
+            inputs: self.inputs.clone(),
+        }
+    }
+
+    /// Log the end of a round, which processed these messages.
+    #[cfg_attr(not(feature = "__run"), allow(unused))]
+    pub fn end_round(&self, messages: &[M]) {
+        let inputs = mem::take(&mut *self.inputs.lock().unwrap());
+        let mut log = self.log.lock().unwrap();
+        for action in inputs {
+            log.input(action);
+        }
+        log.end_round(messages);
+    }
+}
+
+/// Wraps an [`IoSystem`], noting down every input it gives out for a [`Recorder`].
+pub(crate) struct Taped<IO: IoSystem> {
+    inner: IO,
+    inputs: Arc<Mutex<Vec<Action>>>,
+}
+
+impl<IO: IoSystem> Taped<IO> {
+    fn log(&self, action: &Action) {
+        self.inputs.lock().unwrap().push(action.clone());
+    }
+}
+
+impl<IO: IoSystem> IoSystem for Taped<IO> {
+    fn draw(&mut self, screen: &Screen) -> tuig_iosys::Result<()> {
+        self.inner.draw(screen)
+    }
+    fn size(&self) -> XY {
+        self.inner.size()
+    }
+    fn input(&mut self) -> tuig_iosys::Result<Action> {
+        let action = self.inner.input()?;
+        self.log(&action);
+        Ok(action)
+    }
+    fn poll_input(&mut self) -> tuig_iosys::Result<Option<Action>> {
+        let action = self.inner.poll_input()?;
+        if let Some(action) = &action {
+            self.log(action);
+        }
+        Ok(action)
+    }
+    fn input_until(&mut self, timeout: Duration) -> tuig_iosys::Result<Option<Action>> {
+        let action = self.inner.input_until(timeout)?;
+        if let Some(action) = &action {
+            self.log(action);
+        }
+        Ok(action)
+    }
+    fn drain_input(&mut self) -> tuig_iosys::Result<Vec<Action>> {
+        let actions = self.inner.drain_input()?;
+        self.inputs.lock().unwrap().extend(actions.iter().cloned());
+        Ok(actions)
+    }
+    fn clipboard_get(&mut self) -> tuig_iosys::Result<Option<String>> {
+        self.inner.clipboard_get()
+    }
+    fn clipboard_set(&mut self, text: &str) -> tuig_iosys::Result<()> {
+        self.inner.clipboard_set(text)
+    }
+    fn capabilities(&self) -> Capabilities {
+        self.inner.capabilities()
+    }
+    fn set_title(&mut self, title: &str) {
+        self.inner.set_title(title)
+    }
+    fn set_cursor(&mut self, pos: Option<XY>) {
+        self.inner.set_cursor(pos)
+    }
+    fn set_cursor_style(&mut self, style: CursorStyle) {
+        self.inner.set_cursor_style(style)
+    }
+    fn zoom(&mut self, font_size: f32) -> tuig_iosys::Result<()> {
+        self.inner.zoom(font_size)
+    }
+    fn suspend(&mut self) -> tuig_iosys::Result<()> {
+        self.inner.suspend()
+    }
+    fn resume(&mut self) -> tuig_iosys::Result<()> {
+        self.inner.resume()
+    }
+    fn stop(&mut self) {
+        self.inner.stop()
+    }
+}
+
+/// An `IoSystem` with no real display or input, for replaying into. The size is shared, so the replay can follow
+/// the logged [`Action::Resized`]s.
+pub(crate) struct Headless(pub Arc<Mutex<XY>>);
+
+impl IoSystem for Headless {
+    fn draw(&mut self, _screen: &Screen) -> tuig_iosys::Result<()> {
+        Ok(())
+    }
+    fn size(&self) -> XY {
+        *self.0.lock().unwrap()
+    }
+    fn input(&mut self) -> tuig_iosys::Result<Action> {
+        // nothing should be asking; all the input comes from the log
+        Ok(Action::Closed)
+    }
+    fn poll_input(&mut self) -> tuig_iosys::Result<Option<Action>> {
+        Ok(None)
+    }
+    fn stop(&mut self) {}
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::VecDeque,
+        sync::{Arc, Mutex},
+    };
+
+    use tuig_iosys::{
+        backends::{NopSystem, Record},
+        Action, IoSystem, Key, XY,
+    };
+
+    use super::{Recorder, ReplayLog};
+
+    #[test]
+    fn recorder_groups_inputs_by_round() {
+        let log = Arc::new(Mutex::new(ReplayLog::<u32>::new(XY(0, 0))));
+        let rec = Recorder::new(log.clone());
+        let script = [Action::press(Key::Enter), Action::press(Key::Tab)];
+        let (nop, _) = NopSystem::new().unwrap();
+        let mut io = rec.tape(Record::new(
+            nop,
+            script.into_iter().collect::<VecDeque<_>>(),
+        ));
+        assert_eq!(log.lock().unwrap().size(), XY(80, 24));
+
+        io.poll_input().unwrap();
+        rec.end_round(&[1, 2]);
+        rec.end_round(&[]);
+        io.input().unwrap();
+        assert_eq!(io.poll_input().unwrap(), None);
+        rec.end_round(&[3]);
+
+        let log = log.lock().unwrap();
+        let rounds: Vec<_> = log
+            .rounds()
+            .iter()
+            .map(|r| (r.inputs.clone(), r.messages.clone()))
+            .collect();
+        assert_eq!(
+            rounds,
+            [
+                (vec![Action::press(Key::Enter)], vec![1, 2]),
+                (vec![], vec![]),
+                (vec![Action::press(Key::Tab)], vec![3]),
+            ]
+        );
+    }
+}
@@ -2,21 +2,45 @@
 
 #![cfg_attr(not(feature = "__run"), allow(unused))]
 
-use std::{mem, thread, time::Duration};
+use std::{
+    cmp::Reverse,
+    mem,
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
 
-use tuig_iosys::{IoRunner, IoSystem};
+use tuig_iosys::{backends::BackendKind, Action, IoRunner, IoSystem};
 use tuig_ui::{Adapter, Attachment, Region};
 
 use crate::{
-    agent::{Agent, ControlFlow},
+    agent::{Agent, AgentStats, ControlFlow, PanicPolicy},
     game::Game,
-    util::timing::Timer,
+    metrics::FrameClock,
+    replay::{Headless, Recorder, ReplayLog},
+    state::{SharedState, StateFn},
+    util::timing::{Delayed, Timer},
     Message, Replies,
 };
 
 struct AgentRunner<M: Message> {
     agents: Vec<(ControlFlow, Box<dyn Agent<M>>)>,
     replies: Replies<M>,
+    /// Messages from [`Replies::queue_after`] that aren't due yet
+    delayed: Delayed<M>,
+    /// What the agents were up to at the end of the last round
+    stats: AgentStats,
+    /// What to do when an agent panics
+    panics: PanicPolicy,
+    /// The shared state, if the game has one
+    state: Option<StateFn<M>>,
+    /// The snapshot of the shared state agents saw in the last round
+    snapshot: SharedState,
+    /// How many agents can be spawned in one round, if there's a limit
+    spawn_budget: Option<usize>,
+    /// Whether going over the spawn budget has been warned about yet
+    warned_budget: bool,
 }
 
 impl<M: Message> AgentRunner<M> {
@@ -24,42 +48,138 @@ impl<M: Message> AgentRunner<M> {
         Self {
             agents: Default::default(),
             replies: Default::default(),
+            delayed: Delayed::new(),
+            stats: AgentStats::default(),
+            panics: PanicPolicy::Abort,
+            state: None,
+            snapshot: SharedState::default(),
+            spawn_budget: None,
+            warned_budget: false,
         }
     }
 
-    /// Perform one round of message processing.
-    ///
-    /// `agents` and `messages` are both input and output:
-    ///
-    /// - `agents` and `messages` passed in are the agents/messages for this runner to run
-    /// - `agents` and `messages` coming out are the agents/messages that this round spawned
-    ///
-    /// Notably the vecs *will be cleared* and old messages *will not be available*!
-    #[cfg_attr(feature = "run_rayon", allow(unused))]
-    fn step(&mut self, messages: &mut Vec<M>, agents: &mut Vec<Box<dyn Agent<M>>>) {
-        self.agents.extend(
-            agents
-                .drain(..)
-                .map(|mut a| (a.start(&mut self.replies), a)),
-        );
+    /// Drop any agents spawned past the [spawn budget](Runner::spawn_budget) in the round being finished, keeping the
+    /// ones spawned first, and count them in [`Self::stats`]. Only the first round that goes over is warned about.
+    fn limit_spawns(&mut self, agents: &mut Vec<Box<dyn Agent<M>>>) {
+        self.stats.over_budget = 0;
+        match self.spawn_budget {
+            Some(max) if agents.len() > max => {
+                self.stats.over_budget = agents.len() - max;
+                if !self.warned_budget {
+                    eprintln!(
+                        "tuig: dropping {} agents spawned past the budget of {} per round (later drops aren't logged)",
+                        agents.len() - max,
+                        max
+                    );
+                    self.warned_budget = true;
+                }
+                agents.truncate(max);
+            }
+            _ => (),
+        }
+    }
 
-        if messages.is_empty() {
-            messages.push(M::tick());
+    /// Apply a round's messages to the shared state, if there is any, and take the snapshot agents see that round.
+    fn snapshot(&mut self, messages: &[M]) {
+        if let Some(state) = &mut self.state {
+            // let go of the old snapshots first, so the state isn't copied unless someone else kept one
+            self.snapshot = SharedState::default();
+            self.replies.state = SharedState::default();
+            self.snapshot = state.round(messages);
         }
+    }
 
-        for (cf, agent) in self.agents.iter_mut() {
-            if !cf.is_ready() {
-                continue;
+    /// Run an agent's `start` or `react`, following `policy` if it panics, and return its new `ControlFlow`.
+    ///
+    /// If the panic is caught, the agent is killed, and whatever it put into `replies` during `run` is dropped.
+    fn guard(
+        policy: PanicPolicy,
+        replies: &mut Replies<M>,
+        run: impl FnOnce(&mut Replies<M>) -> ControlFlow,
+    ) -> ControlFlow {
+        if policy == PanicPolicy::Abort {
+            return run(replies);
+        }
+        let marks = (
+            replies.agents.len(),
+            replies.messages.len(),
+            replies.delayed.len(),
+        );
+        // the agent that panicked gets dropped, so nobody sees its state afterwards; see `Runner::on_agent_panic`
+        match panic::catch_unwind(AssertUnwindSafe(|| run(&mut *replies))) {
+            Ok(cf) => cf,
+            Err(_) => {
+                replies.agents.truncate(marks.0);
+                replies.messages.truncate(marks.1);
+                replies.delayed.truncate(marks.2);
+                ControlFlow::Kill
             }
-            for msg in messages.iter() {
-                *cf = agent.react(msg, &mut self.replies);
+        }
+    }
+
+    /// Have an agent react to each of a round's messages, until it stops being ready, and return its new
+    /// `ControlFlow`. See [`Self::guard`] for what happens if it panics.
+    fn react(
+        policy: PanicPolicy,
+        agent: &mut Box<dyn Agent<M>>,
+        messages: &[M],
+        replies: &mut Replies<M>,
+    ) -> ControlFlow {
+        Self::guard(policy, replies, |replies| {
+            let mut cf = ControlFlow::Continue;
+            for msg in messages {
+                cf = agent.react(msg, replies);
                 if !cf.is_ready() {
                     break;
                 }
             }
+            cf
+        })
+    }
+
+    /// Schedule [delayed messages](Replies::queue_after), emptying out `delayed`.
+    fn schedule(&mut self, delayed: &mut Vec<(Instant, M)>) {
+        for (at, msg) in delayed.drain(..) {
+            self.delayed.schedule(at, msg);
         }
+    }
 
-        // filter out agents that will never wake up
+    /// Start newly spawned agents and add them to the running ones, keeping those [sorted by priority][prio].
+    ///
+    /// The new agents are started in priority order, too, so their `start` replies are queued in that order.
+    ///
+    /// [prio]: Agent::priority
+    fn start_agents(
+        policy: PanicPolicy,
+        running: &mut Vec<(ControlFlow, Box<dyn Agent<M>>)>,
+        new: &mut Vec<Box<dyn Agent<M>>>,
+        replies: &mut Replies<M>,
+    ) {
+        if new.is_empty() {
+            return;
+        }
+        // both sorts are stable, so equal priorities stay in spawn order
+        new.sort_by_key(|a| Reverse(a.priority()));
+        running.extend(new.drain(..).map(|mut a| {
+            let cf = Self::guard(policy, replies, |replies| a.start(replies));
+            (cf, a)
+        }));
+        running.sort_by_key(|(_, a)| Reverse(a.priority()));
+    }
+
+    /// Push back every timer -- agents sleeping until some time, and delayed messages -- by some amount of time.
+    fn delay_timers(&mut self, by: Duration) {
+        for (cf, _) in self.agents.iter_mut() {
+            if let ControlFlow::Time(when) | ControlFlow::TimeOrHandle(when, _) = cf {
+                *when += by;
+            }
+        }
+        self.delayed.delay_all(by);
+    }
+
+    /// Filter out agents that will never wake up, and update [`Self::stats`].
+    fn cull(&mut self) {
+        let before = self.agents.len();
         self.agents.retain(|(cf, _ag)| match cf {
             // never is_ready again
             ControlFlow::Kill => false,
@@ -68,6 +188,49 @@ impl<M: Message> AgentRunner<M> {
             // otherwise it might eventually wake up, keep it around
             _ => true,
         });
+        let awake = self.agents.iter().filter(|(cf, _)| cf.is_ready()).count();
+        self.stats = AgentStats {
+            awake,
+            sleeping: self.agents.len() - awake,
+            killed: before - self.agents.len(),
+            over_budget: 0,
+        };
+    }
+
+    /// Perform one round of message processing.
+    ///
+    /// `agents` and `messages` are both input and output:
+    ///
+    /// - `agents` and `messages` passed in are the agents/messages for this runner to run
+    /// - `agents` and `messages` coming out are the agents/messages that this round spawned, plus any delayed
+    ///   messages which have come due
+    ///
+    /// Notably the vecs *will be cleared* and old messages *will not be available*! `delayed` is only input, of
+    /// delayed messages queued outside of agents (i.e. by the `Game`), and it's emptied.
+    #[cfg_attr(feature = "run_rayon", allow(unused))]
+    fn step(
+        &mut self,
+        messages: &mut Vec<M>,
+        agents: &mut Vec<Box<dyn Agent<M>>>,
+        delayed: &mut Vec<(Instant, M)>,
+    ) {
+        self.schedule(delayed);
+
+        if messages.is_empty() {
+            messages.push(M::tick());
+        }
+        self.snapshot(messages);
+        self.replies.state = self.snapshot.clone();
+
+        Self::start_agents(self.panics, &mut self.agents, agents, &mut self.replies);
+
+        for (cf, agent) in self.agents.iter_mut() {
+            if cf.is_ready() {
+                *cf = Self::react(self.panics, agent, messages, &mut self.replies);
+            }
+        }
+
+        self.cull();
 
         // we're done with the old messages now
         messages.clear();
@@ -75,6 +238,12 @@ impl<M: Message> AgentRunner<M> {
         mem::swap(&mut self.replies.messages, messages);
         // ditto but for agents (no clear needed because we drained earlier)
         mem::swap(&mut self.replies.agents, agents);
+        self.limit_spawns(agents);
+        let mut new_delayed = mem::take(&mut self.replies.delayed);
+        self.schedule(&mut new_delayed);
+        // hand the (now empty) allocation back for next round
+        self.replies.delayed = new_delayed;
+        self.delayed.release(Instant::now(), messages);
     }
 
     /// Perform one round of message processing, using rayon.
@@ -82,58 +251,66 @@ impl<M: Message> AgentRunner<M> {
     /// `agents` and `messages` are both input and output:
     ///
     /// - `agents` and `messages` passed in are the agents/messages for this runner to run
-    /// - `agents` and `messages` coming out are the agents that this round spawned
+    /// - `agents` and `messages` coming out are the agents that this round spawned, plus any delayed messages which
+    ///   have come due
     ///
-    /// Notably the vecs *will be cleared* and old messages *will not be available*!
+    /// Notably the vecs *will be cleared* and old messages *will not be available*! `delayed` is as in
+    /// [`Self::step`].
     #[cfg(feature = "run_rayon")]
-    fn step_rayon(&mut self, messages: &mut Vec<M>, agents: &mut Vec<Box<dyn Agent<M>>>) {
+    fn step_rayon(
+        &mut self,
+        messages: &mut Vec<M>,
+        agents: &mut Vec<Box<dyn Agent<M>>>,
+        delayed: &mut Vec<(Instant, M)>,
+    ) {
+        self.schedule(delayed);
         use rayon::prelude::{IntoParallelRefMutIterator, ParallelIterator};
 
-        let mut replies = Replies::default();
-        self.agents
-            .extend(agents.drain(..).map(|mut a| (a.start(&mut replies), a)));
-
         if messages.is_empty() {
             messages.push(M::tick());
         }
+        self.snapshot(messages);
+
+        let mut replies = Replies {
+            state: self.snapshot.clone(),
+            ..Default::default()
+        };
+        Self::start_agents(self.panics, &mut self.agents, agents, &mut replies);
 
+        let policy = self.panics;
+        let state = &self.snapshot;
         let agent_replies = self
             .agents
             .par_iter_mut()
             .map(|(cf, agent)| {
-                let mut replies = Replies::default();
-                if !cf.is_ready() {
-                    return replies;
-                }
-                for msg in messages.iter() {
-                    *cf = agent.react(msg, &mut replies);
-                    if !cf.is_ready() {
-                        break;
-                    }
+                let mut replies = Replies {
+                    state: state.clone(),
+                    ..Default::default()
+                };
+                if cf.is_ready() {
+                    *cf = Self::react(policy, agent, messages, &mut replies);
                 }
                 replies
             })
+            // rayon combines results in order, so the replies stay in priority order
             .reduce(Replies::default, |mut old, new| {
                 old.agents.extend(new.agents);
                 old.messages.extend(new.messages);
+                old.delayed.extend(new.delayed);
                 old
             });
         replies.agents.extend(agent_replies.agents);
         replies.messages.extend(agent_replies.messages);
+        replies.delayed.extend(agent_replies.delayed);
 
-        // filter out agents that will never wake up
-        self.agents.retain(|(cf, _ag)| match cf {
-            // never is_ready again
-            ControlFlow::Kill => false,
-            // if there's only one reference, it's the one in this handle
-            ControlFlow::Handle(h) => h.references() > 1,
-            // otherwise it might eventually wake up, keep it around
-            _ => true,
-        });
+        self.cull();
 
         // no attempt to reuse allocations because we can't anyway in parallel
         *messages = replies.messages;
         *agents = replies.agents;
+        self.limit_spawns(agents);
+        self.schedule(&mut replies.delayed);
+        self.delayed.release(Instant::now(), messages);
     }
 }
 
@@ -152,6 +329,16 @@ struct GameRunner<G: Game, IO: IoSystem> {
     game: G,
     /// The IO adapter for the UI stuff
     adapter: Adapter<IO>,
+    /// The last [pause or resume](Replies::pause) the game asked for, if it hasn't been handled yet
+    pause_request: Option<bool>,
+    /// When the agents were paused, if they are
+    paused_since: Option<Instant>,
+    /// The last title sent to the `IoSystem`
+    title: Option<String>,
+    /// Whether the game's been attached since the last [`Self::render`]
+    attached: bool,
+    /// How long each part of the main loop is taking
+    clock: FrameClock,
 }
 
 impl<G: Game, IO: IoSystem> GameRunner<G, IO> {
@@ -159,7 +346,27 @@ impl<G: Game, IO: IoSystem> GameRunner<G, IO> {
         Self {
             game,
             adapter: Adapter::new(iosys).with_cap(60), // TODO: let the game pick
+            pause_request: None,
+            paused_since: None,
+            title: None,
+            attached: false,
+            clock: FrameClock::new(),
+        }
+    }
+
+    /// Handle any pause or resume the game asked for, returning whether the agents are paused.
+    ///
+    /// On resuming, all of the agents' timers get pushed back by however long they were paused.
+    fn paused(&mut self, ar: &mut AgentRunner<G::Message>) -> bool {
+        match (self.pause_request.take(), self.paused_since) {
+            (Some(true), None) => self.paused_since = Some(Instant::now()),
+            (Some(false), Some(since)) => {
+                ar.delay_timers(since.elapsed());
+                self.paused_since = None;
+            }
+            _ => (),
         }
+        self.paused_since.is_some()
     }
 
     /// Feed a list of messages to the associated `Game`.
@@ -167,13 +374,16 @@ impl<G: Game, IO: IoSystem> GameRunner<G, IO> {
     /// Returns whether a stop was requested.
     #[inline]
     fn feed(&mut self, messages: &[G::Message]) {
-        if messages.is_empty() {
-            self.game.message(&G::Message::tick());
-        } else {
-            for msg in messages {
-                self.game.message(msg);
+        let game = &mut self.game;
+        self.clock.agents(|| {
+            if messages.is_empty() {
+                game.message(&G::Message::tick());
+            } else {
+                for msg in messages {
+                    game.message(msg);
+                }
             }
-        };
+        });
     }
 
     /// Do a step of IO with the associated `IoSystem` and `Game`, re-rendering to the stored [`Screen`].
@@ -185,48 +395,114 @@ impl<G: Game, IO: IoSystem> GameRunner<G, IO> {
         &mut self,
         messages: &mut Vec<G::Message>,
         agents: &mut Vec<Box<dyn Agent<G::Message>>>,
+        delayed: &mut Vec<(Instant, G::Message)>,
+        ar: &AgentRunner<G::Message>,
+    ) -> bool {
+        let start = Instant::now();
+        let stop = self.with_replies(messages, agents, delayed, ar, |gr, replies| {
+            let mut drawn = false;
+            while let Ok(Some(stop)) = gr.adapter.poll_input(AttachGame(&mut gr.game, replies)) {
+                drawn = true;
+                if stop {
+                    return true;
+                }
+            }
+            !drawn && gr.adapter.refresh(AttachGame(&mut gr.game, replies))
+        });
+        self.clock.input(start);
+        stop
+    }
+
+    /// Feed the game one specific input, as though it came from the `IoSystem`, without drawing.
+    ///
+    /// Returns whether a stop was requested.
+    #[must_use]
+    fn feed_input(
+        &mut self,
+        input: Action,
+        messages: &mut Vec<G::Message>,
+        agents: &mut Vec<Box<dyn Agent<G::Message>>>,
+        delayed: &mut Vec<(Instant, G::Message)>,
+        ar: &AgentRunner<G::Message>,
+    ) -> bool {
+        self.with_replies(messages, agents, delayed, ar, |gr, replies| {
+            gr.adapter.feed(AttachGame(&mut gr.game, replies), input)
+        })
+    }
+
+    /// Run something with a [`Replies`] made of the given vecs, and the agents' stats and shared state, then put
+    /// whatever's in it back.
+    ///
+    /// If `run` returns `true`, i.e. a stop was requested, nothing is put back.
+    fn with_replies(
+        &mut self,
+        messages: &mut Vec<G::Message>,
+        agents: &mut Vec<Box<dyn Agent<G::Message>>>,
+        delayed: &mut Vec<(Instant, G::Message)>,
+        ar: &AgentRunner<G::Message>,
+        run: impl FnOnce(&mut Self, &mut Replies<G::Message>) -> bool,
     ) -> bool {
-        let mut drawn = false;
         let mut replies = Replies {
             agents: mem::take(agents),
             messages: mem::take(messages),
+            delayed: mem::take(delayed),
+            pause: None,
+            title: None,
+            stats: ar.stats,
+            state: ar.snapshot.clone(),
+            frames: self.clock.last(),
         };
-
-        while let Ok(Some(stop)) = self
-            .adapter
-            .poll_input(AttachGame(&mut self.game, &mut replies))
-        {
-            drawn = true;
-            if stop {
-                return true;
-            }
-        }
-        if !drawn
-            && self
-                .adapter
-                .refresh(AttachGame(&mut self.game, &mut replies))
-        {
+        self.attached = true;
+        if run(self, &mut replies) {
             return true;
         }
         *agents = replies.agents;
         *messages = replies.messages;
+        *delayed = replies.delayed;
+        if let Some(p) = replies.pause {
+            self.pause_request = Some(p);
+        }
+        if replies.title.is_some() && replies.title != self.title {
+            self.title = replies.title;
+            self.adapter
+                .set_title(self.title.as_deref().unwrap_or_default());
+        }
         false
     }
 
     /// Render the stored [`Screen`] to the real screen. This will automatically only render if the screen contents
     /// have been tainted (e.g. by a [`Response::Redraw`] or [`Action::Redraw`]) and the render timer says it's time.
+    ///
+    /// It also only renders if the game has been attached since the last call, so however many times the loop asks,
+    /// the `IoSystem` is drawn to at most once per batch of input. Besides saving work, that matters because the
+    /// [`Adapter`] hands back an older screen to render into after drawing, which shouldn't be drawn itself.
     fn render(&mut self) {
-        self.adapter.draw().expect("Failed to draw to the screen")
+        if !mem::take(&mut self.attached) {
+            return;
+        }
+        let start = Instant::now();
+        self.adapter.draw().expect("Failed to draw to the screen");
+        self.clock.drew(start);
     }
 }
 
 /// Handles starting up and running a `Game` and all its agents.
+///
+/// Whichever runner is used, the loop alternates between handing the game every input that's come in since last time
+/// -- or a [`Redraw`](tuig_iosys::Action::Redraw) if there wasn't any -- and drawing the result. [`IoSystem::draw`] is
+/// called at most once between those batches, no matter how many inputs were in one, so a burst of input doesn't
+/// turn into a burst of (possibly torn) frames.
 #[must_use]
 pub struct Runner<G: Game + 'static> {
     messages: Vec<G::Message>,
     agents: Vec<Box<dyn Agent<G::Message>>>,
     game: G,
     input_tick: f32,
+    record: Option<Recorder<G::Message>>,
+    panics: PanicPolicy,
+    state: Option<StateFn<G::Message>>,
+    prefer: Option<Vec<BackendKind>>,
+    spawn_budget: Option<usize>,
 }
 
 impl<G: Game + 'static> Runner<G> {
@@ -237,9 +513,31 @@ impl<G: Game + 'static> Runner<G> {
             messages: vec![],
             agents: vec![],
             input_tick: 0.1,
+            record: None,
+            panics: PanicPolicy::Abort,
+            state: None,
+            prefer: None,
+            spawn_budget: None,
         }
     }
 
+    /// How many agents have been [`Self::spawn`]ed to run at startup.
+    ///
+    /// Once the game is running, the [`Game`] can check how many agents are alive with [`Replies::agent_count`].
+    pub fn agent_count(&self) -> usize {
+        self.agents.len()
+    }
+
+    /// A **test-only** function, iterating over the agents that have been [`Self::spawn`]ed to run at startup, in
+    /// spawn order.
+    ///
+    /// Downcast them with [`AsAny::as_any`](crate::AsAny::as_any) to inspect their state. Once the game is running,
+    /// the live agents belong to the runner; look at the ones spawned from a round with [`Replies::_agents`].
+    #[cfg(feature = "test_extras")]
+    pub fn _agents(&self) -> impl Iterator<Item = &dyn Agent<G::Message>> {
+        self.agents.iter().map(|a| a.as_ref())
+    }
+
     /// Set an agent to be running at game startup, to process the first round of messages.
     pub fn spawn(mut self, agent: impl Agent<G::Message> + 'static) -> Self {
         self.agents.push(Box::new(agent));
@@ -266,6 +564,128 @@ impl<G: Game + 'static> Runner<G> {
         self
     }
 
+    /// Choose what happens when an agent panics in [`Agent::start`] or [`Agent::react`].
+    ///
+    /// By default, it's [`PanicPolicy::Abort`]: the panic crashes the game, like any other. In a long-running game,
+    /// it may be better to use [`PanicPolicy::KillAgent`], isolating the buggy agent rather than ending the session.
+    ///
+    /// Agents don't need to be [`UnwindSafe`](std::panic::UnwindSafe) for this. An agent that panics is dropped, so
+    /// whatever half-updated state it had is never seen again. But anything it shares with other agents or the game,
+    /// e.g. through an `Arc<Mutex<_>>`, might have been left inconsistent, so be as careful with that as you would be
+    /// with any other shared state and [`catch_unwind`](std::panic::catch_unwind). The game itself panicking still
+    /// crashes it, whatever the policy.
+    pub fn on_agent_panic(mut self, policy: PanicPolicy) -> Self {
+        self.panics = policy;
+        self
+    }
+
+    /// Choose which backends [`Self::load_run`] tries, and in what order, instead of letting it pick.
+    ///
+    /// Only these backends are tried, so e.g. `&[BackendKind::Crossterm]` keeps a headless server from trying to
+    /// open a window. If any of them isn't compiled in, `load_run` panics saying which feature to turn on, rather
+    /// than quietly skipping it.
+    ///
+    /// If this isn't called, the `TUIG_BACKEND` environment variable is checked instead, as a comma-separated list
+    /// of backend names in the same format [`BackendKind`] parses, like `crossterm,softbuffer`. If that's not set
+    /// either, `load_run` goes back to its usual order.
+    pub fn prefer(mut self, kinds: &[BackendKind]) -> Self {
+        self.prefer = Some(kinds.to_vec());
+        self
+    }
+
+    /// Give the agents some global state to read from, as a [`SharedState`] snapshot in their [`Replies::state`].
+    ///
+    /// It starts as `initial`. At the start of each round, before any agent [starts](Agent::start) or
+    /// [reacts](Agent::react), `apply` is called with each of the round's messages, in order, including the
+    /// [tick](Message::tick) if that's all there is. Then everyone reacting that round sees the same snapshot, no
+    /// matter how the agents are spread across threads. Since the state only changes through messages, anything an
+    /// agent wants to change about it, it has to queue, and the change shows up the round those messages go out.
+    ///
+    /// The [`Game`] sees the snapshot from the last round, too, in the `Replies` it's given in [`Game::attach`].
+    /// `apply` runs on the runner's thread, in between rounds, so keep it quick.
+    pub fn shared_state<S: Clone + Send + Sync + 'static>(
+        mut self,
+        initial: S,
+        apply: impl FnMut(&mut S, &G::Message) + Send + 'static,
+    ) -> Self {
+        self.state = Some(StateFn::new(initial, apply));
+        self
+    }
+
+    /// Limit how many new agents can be spawned by agents in a single round, as a safety valve against a bug that
+    /// spawns more and more of them until the game runs out of memory.
+    ///
+    /// Once a round's agents have all reacted, anything they spawned past the first `max_per_round` is dropped. The
+    /// first time that happens, a warning is printed to stderr; after that, the only sign is
+    /// [`AgentStats::over_budget`], so the log isn't flooded every round. Agents spawned first are the ones kept, in
+    /// the same order they'd be started in: by the priority of the agent that spawned them, then the order they were
+    /// spawned. Agents spawned by the [`Game`], or with [`Self::spawn`], don't count.
+    ///
+    /// By default, there's no limit.
+    pub fn spawn_budget(mut self, max_per_round: usize) -> Self {
+        self.spawn_budget = Some(max_per_round);
+        self
+    }
+
+    /// Record every round of messages, and all the input in between, into a [`ReplayLog`] as the game runs.
+    ///
+    /// The log is shared so you can get at it after [`Self::run`] returns, or from a panic hook if the bug you're
+    /// chasing is a crash. Feed it to [`Self::replay`] to re-run the game exactly as it was observed.
+    pub fn record(mut self, log: Arc<Mutex<ReplayLog<G::Message>>>) -> Self {
+        self.record = Some(Recorder::new(log));
+        self
+    }
+
+    /// Replay a [`ReplayLog`] from [`Self::record`], deterministically and on a single thread, no matter which
+    /// runner recorded it.
+    ///
+    /// This should be called on a `Runner` set up the same way as the recorded one, i.e. with the same game state and
+    /// the same agents [spawned](Self::spawn). Then, for each logged round:
+    ///
+    /// - The game is attached with each of the round's inputs, in order. Nothing is drawn anywhere.
+    /// - The game and agents are fed the round's logged messages.
+    ///
+    /// Agents spawned along the way are kept, but any messages queued are dropped, since the log already has the ones
+    /// that were actually sent, in the order they actually arrived. That's what makes it deterministic. Things that
+    /// depend on wall-clock time, like [sleeping](ControlFlow::sleep_for) agents, [pausing](Replies::pause), or
+    /// anything the game does when it's redrawn without input, aren't reproduced exactly.
+    ///
+    /// It exits once the log runs out, or when [`Game::attach`] asks to quit, and returns the [`Game`].
+    pub fn replay(self, log: &ReplayLog<G::Message>) -> G {
+        let Self {
+            game,
+            mut agents,
+            panics,
+            state,
+            spawn_budget,
+            ..
+        } = self;
+        let size = Arc::new(Mutex::new(log.size()));
+        let mut ar = AgentRunner::new();
+        ar.panics = panics;
+        ar.state = state;
+        ar.spawn_budget = spawn_budget;
+        let mut gr = GameRunner::new(game, Headless(size.clone()));
+        let (mut messages, mut delayed) = (vec![], vec![]);
+
+        for round in log.rounds() {
+            for input in &round.inputs {
+                if let Action::Resized { size: new } = input {
+                    *size.lock().unwrap() = *new;
+                }
+                if gr.feed_input(input.clone(), &mut messages, &mut agents, &mut delayed, &ar) {
+                    return gr.game;
+                }
+            }
+            messages.clone_from(&round.messages);
+            delayed.clear();
+            gr.feed(&messages);
+            ar.step(&mut messages, &mut agents, &mut delayed);
+            messages.clear();
+        }
+        gr.game
+    }
+
     #[cfg(feature = "run_orig")]
     fn run_orig(self, iosys: impl IoSystem + 'static, mut iorun: impl IoRunner) -> G {
         let Self {
@@ -273,17 +693,27 @@ impl<G: Game + 'static> Runner<G> {
             mut messages,
             mut agents,
             input_tick,
+            record,
+            panics,
+            state,
+            spawn_budget,
+            ..
         } = self;
 
         let thread = thread::spawn(move || {
             let mut ar = AgentRunner::new();
+            ar.panics = panics;
+            ar.state = state;
+            ar.spawn_budget = spawn_budget;
             let mut gr = GameRunner::new(game, iosys);
             let mut input_timer = Timer::new(input_tick);
+            let mut delayed = vec![];
 
             'mainloop: loop {
+                gr.clock.lap();
                 loop {
                     gr.render();
-                    if gr.attach(&mut messages, &mut agents) {
+                    if gr.attach(&mut messages, &mut agents, &mut delayed, &ar) {
                         break 'mainloop;
                     }
                     if input_timer.tick_ready() {
@@ -292,8 +722,18 @@ impl<G: Game + 'static> Runner<G> {
                     thread::sleep(input_timer.remaining().min(Duration::from_millis(2)));
                 }
                 gr.render();
+                if gr.paused(&mut ar) {
+                    continue;
+                }
+                if let Some(rec) = &record {
+                    rec.end_round(&messages);
+                }
                 gr.feed(&messages);
-                ar.step(&mut messages, &mut agents);
+                gr.clock
+                    .agents(|| ar.step(&mut messages, &mut agents, &mut delayed));
+            }
+            if let Some(rec) = &record {
+                rec.end_round(&[]);
             }
             gr.adapter.stop();
             gr.game
@@ -309,19 +749,29 @@ impl<G: Game + 'static> Runner<G> {
             mut messages,
             mut agents,
             input_tick,
+            record,
+            panics,
+            state,
+            spawn_budget,
+            ..
         } = self;
 
         let mut ar = AgentRunner::new();
+        ar.panics = panics;
+        ar.state = state;
+        ar.spawn_budget = spawn_budget;
         let mut gr = GameRunner::new(game, iosys);
         let mut input_timer = Timer::new(input_tick);
+        let mut delayed = vec![];
 
         'mainloop: loop {
+            gr.clock.lap();
             loop {
                 gr.render();
                 if iorun.step() {
                     break 'mainloop;
                 }
-                if gr.attach(&mut messages, &mut agents) {
+                if gr.attach(&mut messages, &mut agents, &mut delayed, &ar) {
                     break 'mainloop;
                 }
                 if input_timer.tick_ready() {
@@ -330,8 +780,18 @@ impl<G: Game + 'static> Runner<G> {
                 thread::sleep(input_timer.remaining().min(Duration::from_millis(2)));
             }
             gr.render();
+            if gr.paused(&mut ar) {
+                continue;
+            }
+            if let Some(rec) = &record {
+                rec.end_round(&messages);
+            }
             gr.feed(&messages);
-            ar.step(&mut messages, &mut agents);
+            gr.clock
+                .agents(|| ar.step(&mut messages, &mut agents, &mut delayed));
+        }
+        if let Some(rec) = &record {
+            rec.end_round(&[]);
         }
         gr.adapter.stop();
         iorun.run();
@@ -347,16 +807,26 @@ impl<G: Game + 'static> Runner<G> {
                 mut messages,
                 mut agents,
                 input_tick,
+                record,
+                panics,
+                state,
+                spawn_budget,
+                ..
             } = self;
 
             let mut ar = AgentRunner::new();
+            ar.panics = panics;
+            ar.state = state;
+            ar.spawn_budget = spawn_budget;
             let mut gr = GameRunner::new(game, iosys);
             let mut input_timer = Timer::new(input_tick);
+            let mut delayed = vec![];
 
             'mainloop: loop {
+                gr.clock.lap();
                 loop {
                     gr.render();
-                    if gr.attach(&mut messages, &mut agents) {
+                    if gr.attach(&mut messages, &mut agents, &mut delayed, &ar) {
                         break 'mainloop;
                     }
                     if input_timer.tick_ready() {
@@ -365,8 +835,18 @@ impl<G: Game + 'static> Runner<G> {
                     thread::sleep(input_timer.remaining().min(Duration::from_millis(2)));
                 }
                 gr.render();
+                if gr.paused(&mut ar) {
+                    continue;
+                }
+                if let Some(rec) = &record {
+                    rec.end_round(&messages);
+                }
                 gr.feed(&messages);
-                ar.step_rayon(&mut messages, &mut agents);
+                gr.clock
+                    .agents(|| ar.step_rayon(&mut messages, &mut agents, &mut delayed));
+            }
+            if let Some(rec) = &record {
+                rec.end_round(&[]);
             }
             gr.adapter.stop();
             send.send(gr.game).unwrap();
@@ -379,8 +859,18 @@ impl<G: Game + 'static> Runner<G> {
     ///
     /// This function only exits when [`Game::message`] or [`Game::attach`] returns [`Response::Quit`]. It returns the
     /// [`Game`], primarily for testing purposes.
-    #[allow(unreachable_code)] // for `cargo check --all-features`
     pub fn run(self, iosys: impl IoSystem + 'static, iorun: impl IoRunner) -> G {
+        match &self.record {
+            Some(rec) => {
+                let iosys = rec.tape(iosys);
+                self.run_with(iosys, iorun)
+            }
+            None => self.run_with(iosys, iorun),
+        }
+    }
+
+    #[allow(unreachable_code)] // for `cargo check --all-features`
+    fn run_with(self, iosys: impl IoSystem + 'static, iorun: impl IoRunner) -> G {
         use crate::util::macros::feature_switch;
 
         feature_switch!(
@@ -394,10 +884,630 @@ impl<G: Game + 'static> Runner<G> {
     ///
     /// This **must** be run on the main thread. Ideally, you'd run it from `main` directly.
     ///
+    /// The backends tried can be overridden with [`Self::prefer`] or the `TUIG_BACKEND` environment variable.
+    ///
     /// This function only exits when [`Game::message`] or [`Game::attach`] returns [`Response::Quit`]. It returns the
-    /// [`Game`], primarily for testing purposes. If loading fails, it panics.
+    /// [`Game`], primarily for testing purposes. If loading fails, it panics, listing why each backend failed.
     #[cfg(feature = "__io")]
-    pub fn load_run(self) -> G {
-        tuig_iosys::load!(self.run).unwrap()
+    pub fn load_run(mut self) -> G {
+        let env = std::env::var("TUIG_BACKEND").ok();
+        let res = match backend_order(self.prefer.take(), env.as_deref()) {
+            Err(e) => Err(e),
+            Ok(None) => tuig_iosys::load!(self.run),
+            Ok(Some(order)) => {
+                let names: Vec<_> = order.iter().map(|k| k.feature()).collect();
+                tuig_iosys::load_ordered!(&names, self.run)
+            }
+        };
+        res.unwrap_or_else(|e| panic!("{}", e))
+    }
+}
+
+/// Figure out which backends [`Runner::load_run`] should try, from [`Runner::prefer`] or else `TUIG_BACKEND`, or
+/// `None` for the default order. Any named backend that isn't compiled in is an error.
+#[cfg_attr(not(feature = "__io"), allow(unused))]
+fn backend_order(
+    prefer: Option<Vec<BackendKind>>,
+    env: Option<&str>,
+) -> tuig_iosys::Result<Option<Vec<BackendKind>>> {
+    let order = match (prefer, env) {
+        (Some(order), _) => order,
+        (None, Some(list)) if !list.trim().is_empty() => list
+            .split(',')
+            .map(|name| name.parse())
+            .collect::<Result<_, tuig_iosys::Error>>()
+            .map_err(|e| format!("in TUIG_BACKEND: {}", e))?,
+        (None, _) => return Ok(None),
+    };
+    if let Some(missing) = order.iter().find(|k| !k.is_compiled()) {
+        return Err(format!(
+            "backend {:?} was asked for, but tuig wasn't built with the `io_{}` feature",
+            missing,
+            missing.feature()
+        )
+        .into());
+    }
+    Ok(Some(order))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use mock_instant::MockClock;
+    use tuig_iosys::{fmt::Cell, Action, IoSystem, Key, Screen, XY};
+    use tuig_ui::{Region, ScreenView};
+
+    use crate::{Agent, ControlFlow, Game, PanicPolicy, ReplayLog, Replies, Runner};
+
+    use super::{backend_order, AgentRunner, GameRunner, StateFn};
+
+    /// Replies to every round with its ID, at some priority
+    struct Prio(i32, u32);
+
+    impl Agent<u32> for Prio {
+        fn start(&mut self, replies: &mut Replies<u32>) -> ControlFlow {
+            replies.queue(self.1 + 100);
+            ControlFlow::Continue
+        }
+
+        fn react(&mut self, _msg: &u32, replies: &mut Replies<u32>) -> ControlFlow {
+            replies.queue(self.1);
+            ControlFlow::Continue
+        }
+
+        fn priority(&self) -> i32 {
+            self.0
+        }
+    }
+
+    fn spawn(agents: &[(i32, u32)]) -> Vec<Box<dyn Agent<u32>>> {
+        agents
+            .iter()
+            .map(|&(p, id)| Box::new(Prio(p, id)) as Box<dyn Agent<u32>>)
+            .collect()
+    }
+
+    #[test]
+    fn default_priority_keeps_spawn_order() {
+        let mut ar = AgentRunner::new();
+        let (mut messages, mut agents) = (vec![], spawn(&[(0, 1), (0, 2), (0, 3)]));
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        assert_eq!(messages, [101, 102, 103, 1, 2, 3]);
+    }
+
+    #[test]
+    fn higher_priority_replies_first() {
+        let mut ar = AgentRunner::new();
+        let (mut messages, mut agents) = (vec![], spawn(&[(0, 1), (5, 2), (-1, 3), (5, 4)]));
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        assert_eq!(messages, [102, 104, 101, 103, 2, 4, 1, 3]);
+    }
+
+    #[test]
+    fn later_spawns_slot_into_priority_order() {
+        let mut ar = AgentRunner::new();
+        let (mut messages, mut agents) = (vec![], spawn(&[(0, 1), (-5, 2)]));
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        messages.clear();
+        agents = spawn(&[(0, 3), (10, 4)]);
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        assert_eq!(messages, [104, 103, 4, 1, 3, 2]);
+    }
+
+    /// Queues a delayed message on start, then dies
+    struct Later(Duration, u32);
+
+    impl Agent<u32> for Later {
+        fn start(&mut self, replies: &mut Replies<u32>) -> ControlFlow {
+            replies.queue_after(self.0, self.1);
+            ControlFlow::Kill
+        }
+    }
+
+    #[test]
+    fn delayed_messages_released_when_due() {
+        let mut ar = AgentRunner::new();
+        let mut messages = vec![];
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![
+            Box::new(Later(Duration::ZERO, 1)),
+            Box::new(Later(Duration::from_secs(3600), 2)),
+        ];
+        let mut delayed = vec![(Instant::now(), 3)];
+        ar.step(&mut messages, &mut agents, &mut delayed);
+        assert!(delayed.is_empty());
+        assert_eq!(messages, [3, 1]);
+        assert_eq!(ar.delayed.len(), 1);
+        ar.step(&mut messages, &mut agents, &mut delayed);
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn delays_too_long_to_represent_are_dropped() {
+        let mut ar = AgentRunner::new();
+        let mut messages = vec![];
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![
+            Box::new(Later(Duration::MAX, 1)),
+            Box::new(Later(Duration::ZERO, 2)),
+        ];
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        assert_eq!(messages, [2]);
+        assert_eq!(ar.delayed.len(), 0);
+    }
+
+    /// Spawns a [`Prio`] with an initial message on start, then dies
+    struct Spawner;
+
+    impl Agent<u32> for Spawner {
+        fn start(&mut self, replies: &mut Replies<u32>) -> ControlFlow {
+            replies.spawn_with(Prio(0, 5), [42]);
+            ControlFlow::Kill
+        }
+    }
+
+    #[test]
+    fn spawn_with_messages_come_before_start() {
+        let mut ar = AgentRunner::new();
+        let mut messages = vec![];
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![Box::new(Spawner)];
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        assert_eq!(messages, [42]);
+        assert_eq!(agents.len(), 1);
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        // the new agent's start reply, then its reaction to the initial message
+        assert_eq!(messages, [105, 5]);
+    }
+
+    #[cfg(feature = "test_extras")]
+    #[test]
+    fn startup_agents_downcast() {
+        let runner = super::Runner::new(Notes::default())
+            .spawn(Prio(3, 1))
+            .spawn(Prio(-1, 2));
+        let found: Vec<_> = runner
+            ._agents()
+            .filter_map(|a| a.downcast_ref::<Prio>())
+            .map(|p| (p.0, p.1))
+            .collect();
+        assert_eq!(found, [(3, 1), (-1, 2)]);
+        assert!(runner._agents().all(|a| !a.as_any().is::<Notes>()));
+    }
+
+    /// Notes down everything it sees, spawns a [`Prio`] on Enter, and quits on Escape
+    #[derive(Default)]
+    struct Notes(Vec<String>);
+
+    impl Game for Notes {
+        type Message = u32;
+
+        fn message(&mut self, message: &u32) {
+            self.0.push(format!("msg {}", message));
+        }
+
+        fn attach(&mut self, into: Region<'_>, replies: &mut Replies<u32>) -> bool {
+            let size = into.size();
+            let input = into.attach(|input, _| input);
+            self.0.push(format!("{:?} at {:?}", input, size));
+            match input {
+                Action::KeyPress {
+                    key: Key::Enter, ..
+                } => {
+                    replies.spawn(Prio(0, 7)).queue(99);
+                }
+                Action::KeyPress {
+                    key: Key::Escape, ..
+                } => return true,
+                _ => (),
+            }
+            false
+        }
+    }
+
+    #[test]
+    fn replay_feeds_logged_rounds() {
+        let mut log = ReplayLog::new(XY(4, 2));
+        log.input(Action::press(Key::Enter))
+            .end_round(&[3])
+            .input(Action::Resized { size: XY(6, 1) })
+            .end_round(&[])
+            .input(Action::press(Key::Escape))
+            .end_round(&[])
+            .end_round(&[5]);
+        let game = Runner::new(Notes::default()).replay(&log);
+        assert_eq!(
+            game.0,
+            [
+                format!("{:?} at {:?}", Action::press(Key::Enter), XY(4, 2)),
+                // the 99 queued and the 107 and 7 from the agent aren't in the log, so it never sees them
+                "msg 3".into(),
+                format!("{:?} at {:?}", Action::Resized { size: XY(6, 1) }, XY(6, 1)),
+                "msg 0".into(),
+                format!("{:?} at {:?}", Action::press(Key::Escape), XY(6, 1)),
+            ]
+        );
+    }
+
+    /// Sets the title to whatever character was typed, and draws it
+    struct Titled;
+
+    impl Game for Titled {
+        type Message = u32;
+
+        fn message(&mut self, _message: &u32) {}
+
+        fn attach(&mut self, into: Region<'_>, replies: &mut Replies<u32>) -> bool {
+            if let Action::KeyPress {
+                key: Key::Char(ch), ..
+            } = into.attach(|input, mut sv: ScreenView| {
+                if let Action::KeyPress {
+                    key: Key::Char(ch), ..
+                } = input
+                {
+                    sv.fill(Cell::of(ch));
+                }
+                input
+            }) {
+                replies.set_title(ch);
+            }
+            false
+        }
+    }
+
+    /// Remembers every title it's given, and the first character of every screen drawn
+    struct Spy(Arc<Mutex<Vec<String>>>);
+
+    impl IoSystem for Spy {
+        fn draw(&mut self, screen: &Screen) -> tuig_iosys::Result<()> {
+            let ch = screen.cells().first().map_or('?', |c| c.ch);
+            self.0.lock().unwrap().push(format!("draw {}", ch));
+            Ok(())
+        }
+        fn size(&self) -> XY {
+            XY(1, 1)
+        }
+        fn input(&mut self) -> tuig_iosys::Result<Action> {
+            Ok(Action::Closed)
+        }
+        fn poll_input(&mut self) -> tuig_iosys::Result<Option<Action>> {
+            Ok(None)
+        }
+        fn set_title(&mut self, title: &str) {
+            self.0.lock().unwrap().push(format!("title {}", title));
+        }
+        fn stop(&mut self) {}
+    }
+
+    #[test]
+    fn title_only_sent_when_changed() {
+        let titles = Arc::new(Mutex::new(vec![]));
+        let mut gr = GameRunner::new(Titled, Spy(titles.clone()));
+        for ch in "aab a".chars() {
+            let input = Action::press(Key::Char(ch));
+            let stop = gr.feed_input(
+                input,
+                &mut vec![],
+                &mut vec![],
+                &mut vec![],
+                &AgentRunner::new(),
+            );
+            assert!(!stop);
+        }
+        assert_eq!(
+            *titles.lock().unwrap(),
+            ["title a", "title b", "title  ", "title a"]
+        );
+        let _ = gr.feed_input(
+            Action::Redraw,
+            &mut vec![],
+            &mut vec![],
+            &mut vec![],
+            &AgentRunner::new(),
+        );
+        assert_eq!(titles.lock().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn render_draws_once_per_attach() {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut gr = GameRunner::new(Titled, Spy(log.clone()));
+        let feed = |gr: &mut GameRunner<_, _>, ch| {
+            let input = Action::press(Key::Char(ch));
+            let _ = gr.feed_input(
+                input,
+                &mut vec![],
+                &mut vec![],
+                &mut vec![],
+                &AgentRunner::new(),
+            );
+        };
+        // wait out the FPS cap between renders, so only the attach tracking can be skipping draws
+        let cap = Duration::from_millis(20);
+        feed(&mut gr, 'a');
+        gr.render();
+        std::thread::sleep(cap);
+        gr.render();
+        feed(&mut gr, 'b');
+        feed(&mut gr, 'c');
+        std::thread::sleep(cap);
+        gr.render();
+        std::thread::sleep(cap);
+        gr.render();
+        let draws: Vec<_> = log
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| l.starts_with("draw"))
+            .cloned()
+            .collect();
+        assert_eq!(draws, ["draw a", "draw c"]);
+    }
+
+    /// Sleeps on start, then replies with its ID
+    struct Sleepy(u32);
+
+    impl Agent<u32> for Sleepy {
+        fn start(&mut self, _replies: &mut Replies<u32>) -> ControlFlow {
+            ControlFlow::sleep_for(Duration::from_millis(100))
+        }
+
+        fn react(&mut self, _msg: &u32, replies: &mut Replies<u32>) -> ControlFlow {
+            replies.queue(self.0);
+            ControlFlow::Kill
+        }
+    }
+
+    /// Run a round with a [`Sleepy`] and a [`Later`], delay the timers by `by`, then run another round once both
+    /// would have been due, returning the messages it put out.
+    fn delayed_round(by: Duration) -> (Vec<u32>, AgentRunner<u32>) {
+        let mut ar = AgentRunner::new();
+        let mut messages = vec![];
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![
+            Box::new(Sleepy(1)),
+            Box::new(Later(Duration::from_millis(10), 2)),
+        ];
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        assert!(messages.is_empty());
+        ar.delay_timers(by);
+        // agents sleep on the mockable clock, but delayed messages are released by the real one, so wait on both
+        MockClock::advance(Duration::from_millis(200));
+        std::thread::sleep(Duration::from_millis(20));
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        (messages, ar)
+    }
+
+    #[test]
+    fn delaying_timers_pushes_back_sleeps_and_messages() {
+        assert_eq!(delayed_round(Duration::ZERO).0, [1, 2]);
+        let (messages, ar) = delayed_round(Duration::from_secs(3600));
+        assert!(messages.is_empty());
+        assert_eq!(ar.delayed.len(), 1);
+        assert!(!ar.agents[0].0.is_ready());
+    }
+
+    #[test]
+    fn stats_count_agent_states() {
+        let mut ar = AgentRunner::new();
+        let mut messages = vec![];
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![
+            Box::new(Prio(0, 1)),
+            Box::new(Sleepy(2)),
+            Box::new(Later(Duration::ZERO, 3)),
+        ];
+        ar.step(&mut messages, &mut agents, &mut vec![]);
+        let stats = ar.stats;
+        assert_eq!((stats.awake, stats.sleeping, stats.killed), (1, 1, 1));
+    }
+
+    /// Queues a message, then panics when it sees `on`
+    struct Crashy(u32);
+
+    impl Agent<u32> for Crashy {
+        fn start(&mut self, replies: &mut Replies<u32>) -> ControlFlow {
+            replies.queue(self.0 + 100);
+            if self.0 == 0 {
+                panic!("crashing on start");
+            }
+            ControlFlow::Continue
+        }
+
+        fn react(&mut self, msg: &u32, replies: &mut Replies<u32>) -> ControlFlow {
+            replies.queue(self.0).spawn(Prio(0, 9));
+            if *msg == self.0 {
+                panic!("crashing on {}", msg);
+            }
+            ControlFlow::Continue
+        }
+    }
+
+    /// Run two rounds with some [`Crashy`] agents, in parallel or not
+    fn crashy_rounds(parallel: bool) {
+        let step = |ar: &mut AgentRunner<u32>, messages: &mut _, agents: &mut _| {
+            #[cfg(feature = "run_rayon")]
+            if parallel {
+                return ar.step_rayon(messages, agents, &mut vec![]);
+            }
+            assert!(!parallel, "parallel rounds need run_rayon");
+            ar.step(messages, agents, &mut vec![])
+        };
+        let mut ar = AgentRunner::new();
+        ar.panics = PanicPolicy::KillAgent;
+        let mut messages = vec![];
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![
+            Box::new(Crashy(0)),
+            Box::new(Crashy(5)),
+            Box::new(Prio(0, 1)),
+        ];
+        step(&mut ar, &mut messages, &mut agents);
+        assert_eq!(messages, [105, 101, 5, 1]);
+        assert_eq!(agents.len(), 1);
+        assert_eq!((ar.stats.awake, ar.stats.killed), (2, 1));
+
+        messages = vec![5];
+        agents.clear();
+        step(&mut ar, &mut messages, &mut agents);
+        assert_eq!(messages, [1]);
+        assert!(agents.is_empty());
+        assert_eq!((ar.stats.awake, ar.stats.killed), (1, 1));
+    }
+
+    #[test]
+    fn panicking_agents_killed() {
+        crashy_rounds(false);
+    }
+
+    #[cfg(feature = "run_rayon")]
+    #[test]
+    fn panicking_agents_killed_in_parallel() {
+        crashy_rounds(true);
+    }
+
+    /// Notes down how many messages the shared state has seen whenever it reacts, then replies with its ID
+    struct Reader(u32, Arc<Mutex<Vec<(u32, usize)>>>);
+
+    impl Agent<u32> for Reader {
+        fn react(&mut self, _msg: &u32, replies: &mut Replies<u32>) -> ControlFlow {
+            let seen = replies.state().get::<Vec<u32>>().map_or(0, Vec::len);
+            self.1.lock().unwrap().push((self.0, seen));
+            replies.queue(self.0);
+            ControlFlow::Continue
+        }
+    }
+
+    /// Run two rounds with some [`Reader`]s, in parallel or not
+    fn shared_state_rounds(parallel: bool) {
+        let log = Arc::new(Mutex::new(vec![]));
+        let mut ar = AgentRunner::new();
+        ar.state = Some(StateFn::new(vec![], |s: &mut Vec<u32>, m: &u32| s.push(*m)));
+        let mut messages = vec![];
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![
+            Box::new(Reader(1, log.clone())),
+            Box::new(Reader(2, log.clone())),
+        ];
+        for _ in 0..2 {
+            #[cfg(feature = "run_rayon")]
+            if parallel {
+                ar.step_rayon(&mut messages, &mut agents, &mut vec![]);
+                continue;
+            }
+            assert!(!parallel, "parallel rounds need run_rayon");
+            ar.step(&mut messages, &mut agents, &mut vec![]);
+        }
+        // the tick, then both replies (each agent reacts to both); never just one of them
+        let mut log = log.lock().unwrap().clone();
+        log.sort();
+        assert_eq!(log, [(1, 1), (1, 3), (1, 3), (2, 1), (2, 3), (2, 3)]);
+        assert_eq!(ar.snapshot.get::<Vec<u32>>(), Some(&vec![0, 1, 2]));
+    }
+
+    #[test]
+    fn shared_state_consistent_within_round() {
+        shared_state_rounds(false);
+    }
+
+    #[cfg(feature = "run_rayon")]
+    #[test]
+    fn shared_state_consistent_in_parallel() {
+        shared_state_rounds(true);
+    }
+
+    #[test]
+    fn backend_order_prefers_builder_then_env() {
+        use tuig_iosys::backends::BackendKind;
+        let compiled: Vec<_> = BackendKind::ALL
+            .iter()
+            .copied()
+            .filter(|k| k.is_compiled())
+            .collect();
+        let names: Vec<_> = compiled.iter().map(|k| k.feature()).collect();
+        let env = names.join(", ");
+        assert_eq!(backend_order(None, None).unwrap(), None);
+        assert_eq!(backend_order(None, Some(" ")).unwrap(), None);
+        // with nothing compiled in, the list is empty, like it isn't set at all
+        let expected = Some(compiled.clone()).filter(|c| !c.is_empty());
+        assert_eq!(backend_order(None, Some(&env)).unwrap(), expected);
+        assert_eq!(
+            backend_order(Some(compiled.clone()), Some("bogus")).unwrap(),
+            Some(compiled)
+        );
+        let err = backend_order(None, Some("bogus")).unwrap_err();
+        assert!(err.to_string().contains("TUIG_BACKEND"), "{}", err);
+        if let Some(&missing) = BackendKind::ALL.iter().find(|k| !k.is_compiled()) {
+            let err = backend_order(Some(vec![missing]), None).unwrap_err();
+            assert!(err.to_string().contains("io_"), "{}", err);
+        }
+    }
+
+    /// Spawns a [`Prio`] for each of its IDs every time it reacts
+    struct Breeder(Vec<u32>);
+
+    impl Agent<u32> for Breeder {
+        fn react(&mut self, _msg: &u32, replies: &mut Replies<u32>) -> ControlFlow {
+            for &id in &self.0 {
+                replies.spawn(Prio(0, id));
+            }
+            ControlFlow::Continue
+        }
+    }
+
+    /// Run a round with some [`Breeder`]s and a spawn budget, in parallel or not, returning the IDs of the agents kept
+    fn budgeted_round(parallel: bool) -> Vec<u32> {
+        budgeted_rounds(parallel, 1).0
+    }
+
+    /// Run `rounds` rounds with [`Breeder`]s and a spawn budget, returning the IDs of the agents kept in the last
+    /// round along with the runner, for its stats
+    fn budgeted_rounds(parallel: bool, rounds: usize) -> (Vec<u32>, AgentRunner<u32>) {
+        let mut ar = AgentRunner::new();
+        ar.spawn_budget = Some(3);
+        let mut messages = vec![];
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![
+            Box::new(Breeder(vec![1, 2])),
+            Box::new(Breeder(vec![3, 4, 5])),
+        ];
+        let mut kept = vec![];
+        for _ in 0..rounds {
+            #[cfg(feature = "run_rayon")]
+            if parallel {
+                ar.step_rayon(&mut messages, &mut agents, &mut vec![]);
+            }
+            if !parallel {
+                ar.step(&mut messages, &mut agents, &mut vec![]);
+            }
+            kept = agents
+                .drain(..)
+                .map(|a| a.downcast_ref::<Prio>().unwrap().1)
+                .collect();
+        }
+        (kept, ar)
+    }
+
+    #[test]
+    fn spawn_budget_drops_overflow_in_order() {
+        assert_eq!(budgeted_round(false), [1, 2, 3]);
+    }
+
+    #[cfg(feature = "run_rayon")]
+    #[test]
+    fn spawn_budget_drops_overflow_in_parallel() {
+        assert_eq!(budgeted_round(true), [1, 2, 3]);
+    }
+
+    #[test]
+    fn spawn_budget_counts_every_round_but_warns_once() {
+        let (kept, ar) = budgeted_rounds(false, 3);
+        assert_eq!(kept, [1, 2, 3]);
+        assert_eq!(ar.stats.over_budget, 2);
+        assert!(ar.warned_budget);
+    }
+
+    #[test]
+    #[should_panic = "crashing on start"]
+    fn panics_abort_by_default() {
+        let mut ar = AgentRunner::new();
+        let mut agents: Vec<Box<dyn Agent<u32>>> = vec![Box::new(Crashy(0))];
+        ar.step(&mut vec![], &mut agents, &mut vec![]);
     }
 }
@@ -0,0 +1,83 @@
+//! A read-only snapshot of the game's global state, shared by every agent in a round.
+
+use std::{any::Any, fmt, sync::Arc};
+
+use crate::Message;
+
+/// A snapshot of the game's global state, the same for everyone reacting in a round. Get it from
+/// [`Replies::state`](crate::Replies::state).
+///
+/// The state itself is whatever type you pass to [`Runner::shared_state`](crate::Runner::shared_state), along with
+/// a function that applies messages to it. At the start of each round, before any agent reacts, the runner applies
+/// that round's messages -- the ones the agents are about to see -- and hands out the result. So the snapshot never
+/// changes partway through a round, no matter how many threads are reacting at once, and it always reflects every
+/// message an agent is about to see. Agents change it the same way they change anything else: by queueing messages,
+/// which build the next round's state.
+///
+/// It's cheap to clone, since it's just an [`Arc`], so an agent can hold onto an old snapshot if it wants to compare.
+/// The runner only copies the state itself when a round changes it while someone's still holding the last one.
+#[derive(Clone, Default)]
+pub struct SharedState(Option<Arc<dyn Any + Send + Sync>>);
+
+impl SharedState {
+    /// Get the state, if there is one and it's a `T`.
+    ///
+    /// This returns `None` if the runner wasn't given any [shared state](crate::Runner::shared_state), or if it was
+    /// given some other type.
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.0.as_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Debug for SharedState {
+    #[cfg_attr(coverage, no_coverage)]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedState(...)")
+    }
+}
+
+type Apply<M> = Box<dyn FnMut(&[M]) -> SharedState + Send>;
+
+/// Applies a round's messages to the state and takes a new snapshot of it.
+pub(crate) struct StateFn<M: Message>(Apply<M>);
+
+impl<M: Message> StateFn<M> {
+    pub fn new<S: Clone + Send + Sync + 'static>(
+        initial: S,
+        mut apply: impl FnMut(&mut S, &M) + Send + 'static,
+    ) -> Self {
+        let mut state = Arc::new(initial);
+        Self(Box::new(move |messages| {
+            if !messages.is_empty() {
+                // only clones if an old snapshot is still around somewhere
+                let state = Arc::make_mut(&mut state);
+                for msg in messages {
+                    apply(state, msg);
+                }
+            }
+            let snapshot: Arc<dyn Any + Send + Sync> = state.clone();
+            SharedState(Some(snapshot))
+        }))
+    }
+
+    /// Apply a round's messages and get the snapshot for that round.
+    pub fn round(&mut self, messages: &[M]) -> SharedState {
+        (self.0)(messages)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SharedState, StateFn};
+
+    #[test]
+    fn snapshots_stay_put() {
+        let mut state = StateFn::new(vec![], |v: &mut Vec<u32>, m: &u32| v.push(*m));
+        let first = state.round(&[1, 2]);
+        let second = state.round(&[3]);
+        assert_eq!(first.get::<Vec<u32>>(), Some(&vec![1, 2]));
+        assert_eq!(second.get::<Vec<u32>>(), Some(&vec![1, 2, 3]));
+        assert_eq!(second.get::<u32>(), None);
+        assert_eq!(SharedState::default().get::<Vec<u32>>(), None);
+    }
+}
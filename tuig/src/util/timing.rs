@@ -4,7 +4,10 @@
 // under exactly what conditions any given function is expected to be used.
 #![allow(unused)]
 
-use std::time::{Duration, Instant};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
 
 /// Keeps track of time between relatively steady pulses.
 ///
@@ -52,3 +55,70 @@ impl Timer {
         }
     }
 }
+
+/// Messages waiting to be sent at some point in the future, kept in the order they're due.
+pub struct Delayed<M> {
+    queue: VecDeque<(Instant, M)>,
+}
+
+impl<M> Delayed<M> {
+    pub fn new() -> Self {
+        Self {
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Schedule a message to be released at a certain time. Messages due at the same time are released in the order
+    /// they were scheduled.
+    pub fn schedule(&mut self, at: Instant, msg: M) {
+        let idx = self.queue.partition_point(|(t, _)| *t <= at);
+        self.queue.insert(idx, (at, msg));
+    }
+
+    /// Move every message that's due by `now` into `into`, in order.
+    pub fn release(&mut self, now: Instant, into: &mut Vec<M>) {
+        while self.queue.front().map_or(false, |(t, _)| *t <= now) {
+            // UNWRAP: just checked there's a front
+            into.push(self.queue.pop_front().unwrap().1);
+        }
+    }
+
+    /// Push back every waiting message by some amount of time.
+    pub fn delay_all(&mut self, by: Duration) {
+        for (t, _) in self.queue.iter_mut() {
+            *t += by;
+        }
+    }
+
+    /// How many messages are still waiting.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::Delayed;
+
+    #[test]
+    fn releases_in_due_order() {
+        let start = Instant::now();
+        let ms = |n| start + Duration::from_millis(n);
+        let mut d = Delayed::new();
+        d.schedule(ms(30), 'c');
+        d.schedule(ms(10), 'a');
+        d.schedule(ms(20), 'b');
+        d.schedule(ms(10), 'A');
+        let mut out = vec![];
+        d.release(ms(5), &mut out);
+        assert!(out.is_empty());
+        d.release(ms(20), &mut out);
+        assert_eq!(out, ['a', 'A', 'b']);
+        assert_eq!(d.len(), 1);
+        d.release(ms(100), &mut out);
+        assert_eq!(out, ['a', 'A', 'b', 'c']);
+        assert_eq!(d.len(), 0);
+    }
+}